@@ -0,0 +1,307 @@
+// Archive member path checks
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// An archive member whose path would be written outside the extraction
+/// directory: absolute, or climbing out with `..`. Archives with one are
+/// refused outright rather than partly unpacked.
+#[derive(Debug, Clone)]
+pub struct UnsafeArchiveEntry {
+    pub member: String,
+    pub reason: &'static str,
+}
+
+impl std::fmt::Display for UnsafeArchiveEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Refusing to extract {}: {}", self.member, self.reason)
+    }
+}
+
+impl std::error::Error for UnsafeArchiveEntry {}
+
+/// Where the member `name` goes, relative to the extraction directory.
+/// `.` and empty components are dropped, so the archive's own root (`./`)
+/// comes out empty; zips made on Windows may use backslashes, so those
+/// count as separators too.
+pub fn member_path(name: &str) -> Result<PathBuf, UnsafeArchiveEntry> {
+    let unsafe_entry = |reason| UnsafeArchiveEntry {
+        member: name.to_string(),
+        reason,
+    };
+    let normalized = name.replace('\\', "/");
+    if normalized.starts_with('/') || normalized.as_bytes().get(1) == Some(&b':') {
+        return Err(unsafe_entry("it has an absolute path"));
+    }
+
+    let mut path = PathBuf::new();
+    for part in Path::new(&normalized).components() {
+        match part {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err(unsafe_entry("its path leads out of the extraction directory")),
+            Component::RootDir | Component::Prefix(_) => return Err(unsafe_entry("it has an absolute path")),
+        }
+    }
+    Ok(path)
+}
+
+/// [`member_path`] for a file, which has to have a name
+pub fn file_path(name: &str) -> Result<PathBuf, UnsafeArchiveEntry> {
+    let path = member_path(name)?;
+    if path.as_os_str().is_empty() {
+        return Err(UnsafeArchiveEntry {
+            member: name.to_string(),
+            reason: "it has no file name",
+        });
+    }
+    Ok(path)
+}
+
+/// Unpack every directory and file of a zip into `dest_dir`, which should
+/// be freshly created, skipping symlinks like [`unpack_tar`] does.
+pub fn unpack_zip<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>, dest_dir: &Path) -> Result<()> {
+    for i in 0..archive.len() {
+        let mut member = archive.by_index(i).context("Failed to read archive")?;
+        let name = member.name().to_string();
+        if member.is_symlink() {
+            log::warn!("Skipping link {} in the archive", name);
+            continue;
+        }
+
+        if member.is_dir() {
+            let output = dest_dir.join(member_path(&name)?);
+            std::fs::create_dir_all(&output)
+                .with_context(|| format!("Failed to create {}", output.display()))?;
+            continue;
+        }
+        let output = dest_dir.join(file_path(&name)?);
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = File::create(&output)
+            .with_context(|| format!("Failed to create {}", output.display()))?;
+        std::io::copy(&mut member, &mut file)
+            .with_context(|| format!("Failed to extract {}", name))?;
+    }
+    Ok(())
+}
+
+/// Unpack every directory and regular file of a tar stream into
+/// `dest_dir`, which should be freshly created. Symlinks and hard links
+/// are skipped, so nothing written can point outside it; images never
+/// need them.
+pub fn unpack_tar<R: Read>(archive: &mut tar::Archive<R>, dest_dir: &Path) -> Result<()> {
+    for member in archive.entries().context("Failed to read archive")? {
+        let mut member = member.context("Failed to read archive")?;
+        let name = member.path()?.to_string_lossy().into_owned();
+        let entry_type = member.header().entry_type();
+
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            log::warn!("Skipping link {} in the archive", name);
+            continue;
+        }
+        if !entry_type.is_dir() && !entry_type.is_file() {
+            log::debug!("Skipping {} in the archive", name);
+            continue;
+        }
+
+        if entry_type.is_dir() {
+            let output = dest_dir.join(member_path(&name)?);
+            std::fs::create_dir_all(&output)
+                .with_context(|| format!("Failed to create {}", output.display()))?;
+            continue;
+        }
+        let output = dest_dir.join(file_path(&name)?);
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = File::create(&output)
+            .with_context(|| format!("Failed to create {}", output.display()))?;
+        std::io::copy(&mut member, &mut file)
+            .with_context(|| format!("Failed to extract {}", name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Names that would land outside the extraction directory, written the
+    /// way Unix and Windows tools write them
+    const UNSAFE_NAMES: &[&str] = &[
+        "../boot.img",
+        "images/../../boot.img",
+        "/etc/boot.img",
+        "..\\boot.img",
+        "\\boot.img",
+        "C:\\boot.img",
+        "C:/boot.img",
+    ];
+
+    /// A fresh, empty directory to unpack into, inside one holding nothing
+    /// else, so anything escaping it shows up as a sibling
+    fn scratch(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("sidestep-archive-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&root);
+        let dest = root.join("out");
+        std::fs::create_dir_all(&dest).unwrap();
+        dest
+    }
+
+    /// A tar of `(name, type, link target)` members, with the names written
+    /// byte for byte so the builder's own path checks don't step in
+    fn tar_of(members: &[(&str, tar::EntryType, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for &(name, entry_type, link) in members {
+            let data: &[u8] = if entry_type.is_file() { b"image" } else { b"" };
+            let mut header = tar::Header::new_gnu();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.as_old_mut().linkname[..link.len()].copy_from_slice(link.as_bytes());
+            header.set_entry_type(entry_type);
+            header.set_mode(0o644);
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append(&header, data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    /// A zip of `(name, symlink target)` members, files when there's no
+    /// target
+    fn zip_of(members: &[(&str, Option<&str>)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for &(name, link) in members {
+            match link {
+                Some(target) => writer.add_symlink(name, target, options).unwrap(),
+                None => {
+                    writer.start_file(name, options).unwrap();
+                    writer.write_all(b"image").unwrap();
+                }
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn unpack_tar_bytes(bytes: &[u8], dest: &Path) -> Result<()> {
+        unpack_tar(&mut tar::Archive::new(bytes), dest)
+    }
+
+    fn unpack_zip_bytes(bytes: Vec<u8>, dest: &Path) -> Result<()> {
+        unpack_zip(
+            &mut zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap(),
+            dest,
+        )
+    }
+
+    fn assert_refused(result: Result<()>, dest: &Path, name: &str) {
+        let error = result.expect_err(name);
+        assert!(
+            error.downcast_ref::<UnsafeArchiveEntry>().is_some(),
+            "{}: {:#}",
+            name,
+            error
+        );
+        let root = dest.parent().unwrap();
+        assert!(!root.join("boot.img").exists(), "{} escaped", name);
+        assert_eq!(std::fs::read_dir(dest).unwrap().count(), 0, "{}", name);
+    }
+
+    #[test]
+    fn names_leading_outside_are_refused() {
+        for name in UNSAFE_NAMES {
+            assert!(member_path(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn names_inside_are_normalized() {
+        assert_eq!(
+            member_path("./images/boot.img").unwrap(),
+            Path::new("images/boot.img")
+        );
+        assert_eq!(
+            member_path("images\\boot.img").unwrap(),
+            Path::new("images/boot.img")
+        );
+        assert_eq!(member_path("./").unwrap(), Path::new(""));
+        assert!(file_path("./").is_err());
+    }
+
+    #[test]
+    fn tars_with_unsafe_members_are_refused() {
+        for (i, name) in UNSAFE_NAMES.iter().enumerate() {
+            let dest = scratch(&format!("tar-unsafe-{}", i));
+            let archive = tar_of(&[(name, tar::EntryType::Regular, "")]);
+
+            assert_refused(unpack_tar_bytes(&archive, &dest), &dest, name);
+            let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+        }
+    }
+
+    #[test]
+    fn zips_with_unsafe_members_are_refused() {
+        for (i, name) in UNSAFE_NAMES.iter().enumerate() {
+            let dest = scratch(&format!("zip-unsafe-{}", i));
+            let archive = zip_of(&[(name, None)]);
+
+            assert_refused(unpack_zip_bytes(archive, &dest), &dest, name);
+            let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+        }
+    }
+
+    #[test]
+    fn tar_links_are_skipped() {
+        let dest = scratch("tar-links");
+        let archive = tar_of(&[
+            ("images/boot.img", tar::EntryType::Regular, ""),
+            ("images/passwd", tar::EntryType::Symlink, "/etc/passwd"),
+            ("images/outside", tar::EntryType::Symlink, "../../.."),
+            ("images/hard.img", tar::EntryType::Link, "images/boot.img"),
+        ]);
+
+        unpack_tar_bytes(&archive, &dest).unwrap();
+
+        assert_eq!(
+            std::fs::read(dest.join("images/boot.img")).unwrap(),
+            b"image"
+        );
+        for link in ["passwd", "outside", "hard.img"] {
+            let path = dest.join("images").join(link);
+            assert!(std::fs::symlink_metadata(&path).is_err(), "{}", link);
+        }
+        let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+    }
+
+    #[test]
+    fn zip_symlinks_are_skipped() {
+        // Zips have no hard links; a symlink is the only kind they hold
+        let dest = scratch("zip-links");
+        let archive = zip_of(&[
+            ("images/boot.img", None),
+            ("images/passwd", Some("/etc/passwd")),
+            ("images/outside", Some("../../..")),
+        ]);
+
+        unpack_zip_bytes(archive, &dest).unwrap();
+
+        assert_eq!(
+            std::fs::read(dest.join("images/boot.img")).unwrap(),
+            b"image"
+        );
+        for link in ["passwd", "outside"] {
+            let path = dest.join("images").join(link);
+            assert!(std::fs::symlink_metadata(&path).is_err(), "{}", link);
+        }
+        let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+    }
+}
@@ -0,0 +1,103 @@
+// Pre-install partition backup
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::Adb;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MANIFEST_NAME: &str = "backup.json";
+
+/// Record written next to the partition images once a backup succeeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub codename: String,
+    pub serial: String,
+    /// Seconds since the Unix epoch
+    pub created: u64,
+    pub partitions: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub partition: String,
+    pub file: String,
+    pub size: u64,
+}
+
+/// Saves IMEI/DRM-critical partitions (persist, modemst*, Sony TA, ...)
+/// to `~/.local/share/sidestep/backups/<codename>/<serial>/`.
+pub struct PartitionBackup {
+    serial: String,
+    codename: String,
+    partitions: Vec<String>,
+}
+
+impl PartitionBackup {
+    pub fn new(serial: String, codename: String, partitions: Vec<String>) -> Self {
+        Self {
+            serial,
+            codename,
+            partitions,
+        }
+    }
+
+    /// Where backups for a given device are kept.
+    pub fn backup_dir(codename: &str, serial: &str) -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join("sidestep")
+            .join("backups")
+            .join(codename)
+            .join(serial)
+    }
+
+    /// The manifest of a previous successful backup of this device, if any.
+    pub fn existing(codename: &str, serial: &str) -> Option<BackupManifest> {
+        let path = Self::backup_dir(codename, serial).join(MANIFEST_NAME);
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Pull every partition, then write the manifest. The manifest is only
+    /// written when all partitions were saved, so a partial backup is never
+    /// recorded as taken.
+    pub async fn run(&self) -> Result<PathBuf> {
+        let adb = Adb::new();
+        let dir = Self::backup_dir(&self.codename, &self.serial);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let mut entries = Vec::new();
+        for partition in &self.partitions {
+            let file = format!("{}.img", partition);
+            let size = adb
+                .pull_partition(&self.serial, partition, &dir.join(&file))
+                .await?;
+            log::info!("Saved {} ({} bytes)", partition, size);
+            entries.push(BackupEntry {
+                partition: partition.clone(),
+                file,
+                size,
+            });
+        }
+
+        let manifest = BackupManifest {
+            codename: self.codename.clone(),
+            serial: self.serial.clone(),
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            partitions: entries,
+        };
+        std::fs::write(
+            dir.join(MANIFEST_NAME),
+            serde_json::to_string_pretty(&manifest)?,
+        )
+        .context("Failed to write backup manifest")?;
+
+        Ok(dir)
+    }
+}
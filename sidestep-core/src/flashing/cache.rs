@@ -0,0 +1,52 @@
+// Image cache
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Root of Sidestep's download cache (`~/.cache/sidestep`)
+pub fn cache_root() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("sidestep")
+}
+
+/// Where an installer keeps its downloads, e.g. `~/.cache/sidestep/mobian`
+pub fn installer_dir(name: &str) -> PathBuf {
+    cache_root().join(name)
+}
+
+/// Total size in bytes of the files under `dir`. Missing or unreadable
+/// entries count as empty.
+pub fn disk_usage(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => disk_usage(&entry.path()),
+            Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Delete `dir` and everything in it, returning how many bytes were freed.
+///
+/// Only paths inside [`cache_root`] are accepted, so a bad caller can't
+/// wipe anything else.
+pub fn remove(dir: &Path) -> Result<u64> {
+    if !dir.starts_with(cache_root()) {
+        anyhow::bail!("Refusing to delete {} outside the image cache", dir.display());
+    }
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let freed = disk_usage(dir);
+    std::fs::remove_dir_all(dir)
+        .with_context(|| format!("Failed to delete {}", dir.display()))?;
+    log::info!("Removed cached images in {} ({} bytes)", dir.display(), freed);
+    Ok(freed)
+}
@@ -0,0 +1,306 @@
+// Current build details of a distro channel, for browsing before install
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::github_release::GithubReleaseSource;
+use crate::flashing::lineageos::LineageosInstaller;
+use crate::flashing::postmarketos::{self, PostmarketosInstaller};
+use crate::flashing::ubports::SYSTEM_IMAGE_SERVER;
+use crate::models::Device;
+use crate::models::distro_config::{ChannelConfig, DistroConfig};
+use crate::models::system_image::SystemImageIndex;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a lookup is reused before asking the server again
+const CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Browsing shouldn't sit on a lookup that's never going to answer
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static CACHE: Lazy<Mutex<HashMap<String, (Instant, ChannelDetails)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// What a distro's server says about the build a channel would install
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelDetails {
+    /// Version or build date, as the distro names its builds
+    pub version: Option<String>,
+    /// Total download size of the build
+    pub size_bytes: Option<u64>,
+}
+
+impl ChannelDetails {
+    /// "Version 20.0 · 1.2 GB" style summary; empty when nothing is known.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref version) = self.version {
+            parts.push(version.clone());
+        }
+        if let Some(bytes) = self.size_bytes {
+            parts.push(crate::utils::format::format_size(bytes));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Most builds offered when picking one within a channel
+const RECENT_BUILDS: usize = 10;
+
+/// One build a channel can install, for pinning the install to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelBuild {
+    /// What the installer is pinned with: the system-image version for
+    /// Ubuntu Touch, the release asset name for Droidian
+    pub id: String,
+    pub label: String,
+    /// `YYYY-MM-DD`, when the server says
+    pub date: Option<String>,
+    pub size_bytes: Option<u64>,
+}
+
+impl ChannelBuild {
+    /// "2024-07-03 · 1.2 GB" style summary; empty when nothing is known.
+    pub fn summary(&self) -> String {
+        ChannelDetails {
+            version: self.date.clone(),
+            size_bytes: self.size_bytes,
+        }
+        .summary()
+    }
+}
+
+/// Whether `distro_id`'s installer can be pinned to a build from
+/// [`list_builds`]
+pub fn can_choose_build(distro_id: &str) -> bool {
+    matches!(distro_id, "ubuntutouch" | "ubports" | "droidian")
+}
+
+/// The recent builds of `channel`, newest first. Distros that can't be
+/// pinned to a build return none.
+pub async fn list_builds(distro_id: &str, channel: &ChannelConfig) -> Result<Vec<ChannelBuild>> {
+    let lookup = async {
+        match distro_id {
+            "ubuntutouch" | "ubports" => ubports_builds(channel).await,
+            "droidian" => droidian_builds(channel).await,
+            _ => Ok(Vec::new()),
+        }
+    };
+    tokio::time::timeout(REQUEST_TIMEOUT, lookup)
+        .await
+        .with_context(|| format!("Listing builds of {}/{} timed out", distro_id, channel.id))?
+}
+
+/// Look up the current build of `channel`, reusing an answer from the last
+/// half hour. Distros without an index to ask return empty details.
+pub async fn channel_details(
+    device: &Device,
+    distro: &DistroConfig,
+    channel: &ChannelConfig,
+) -> Result<ChannelDetails> {
+    let key = format!("{}/{}/{}", device.codename, distro.id, channel.id);
+    if let Some((fetched, details)) = CACHE.lock().unwrap().get(&key)
+        && fetched.elapsed() < CACHE_TTL
+    {
+        return Ok(details.clone());
+    }
+
+    let lookup = async {
+        match distro.id.as_str() {
+            "ubuntutouch" | "ubports" => ubports(channel).await,
+            "lineageos" => lineageos(channel).await,
+            "droidian" => droidian(channel).await,
+            "postmarketos" => postmarketos(device, distro, channel).await,
+            _ => Ok(ChannelDetails::default()),
+        }
+    };
+    let details = tokio::time::timeout(REQUEST_TIMEOUT, lookup)
+        .await
+        .with_context(|| format!("Looking up {} timed out", key))??;
+    log::debug!("{}: {:?}", key, details);
+    CACHE
+        .lock()
+        .unwrap()
+        .insert(key, (Instant::now(), details.clone()));
+    Ok(details)
+}
+
+async fn ubports(channel: &ChannelConfig) -> Result<ChannelDetails> {
+    let Some(ref path) = channel.path else {
+        return Ok(ChannelDetails::default());
+    };
+    let index = system_image_index(path).await?;
+    Ok(match index.latest_full() {
+        Some(entry) => ChannelDetails {
+            version: Some(format!("Version {}", entry.version)),
+            size_bytes: Some(entry.files.iter().map(|f| f.size).sum()),
+        },
+        None => ChannelDetails::default(),
+    })
+}
+
+async fn ubports_builds(channel: &ChannelConfig) -> Result<Vec<ChannelBuild>> {
+    let Some(ref path) = channel.path else {
+        return Ok(Vec::new());
+    };
+    let index = system_image_index(path).await?;
+    Ok(index
+        .full_images()
+        .into_iter()
+        .take(RECENT_BUILDS)
+        .map(|entry| ChannelBuild {
+            id: entry.version.to_string(),
+            label: format!("Version {}", entry.version),
+            date: entry.build_date(),
+            size_bytes: Some(entry.files.iter().map(|f| f.size).sum()),
+        })
+        .collect())
+}
+
+async fn system_image_index(path: &str) -> Result<SystemImageIndex> {
+    let url = format!("{}/{}/index.json", SYSTEM_IMAGE_SERVER, path.trim_end_matches('/'));
+    let text = client()?
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    serde_json::from_str(&text).context("Failed to parse system-image index.json")
+}
+
+async fn lineageos(channel: &ChannelConfig) -> Result<ChannelDetails> {
+    let Some(ref api_url) = channel.release_url else {
+        return Ok(ChannelDetails::default());
+    };
+    let build = LineageosInstaller::new(String::new(), api_url.clone(), false)
+        .fetch_latest_build()
+        .await?;
+    Ok(ChannelDetails {
+        version: Some(format!("Version {}", build.version)),
+        size_bytes: Some(build.files.iter().map(|f| f.size).sum()),
+    })
+}
+
+async fn droidian(channel: &ChannelConfig) -> Result<ChannelDetails> {
+    let (Some(source), Some(pattern)) = (
+        GithubReleaseSource::for_channel(channel),
+        channel.artifact_match.as_deref(),
+    ) else {
+        return Ok(ChannelDetails::default());
+    };
+    let (release, asset) = source.find_asset(pattern).await?;
+    Ok(ChannelDetails {
+        version: Some(release.tag_name),
+        size_bytes: Some(asset.size),
+    })
+}
+
+async fn droidian_builds(channel: &ChannelConfig) -> Result<Vec<ChannelBuild>> {
+    let (Some(source), Some(pattern)) = (
+        GithubReleaseSource::for_channel(channel),
+        channel.artifact_match.as_deref(),
+    ) else {
+        return Ok(Vec::new());
+    };
+    let assets = source.list_assets(pattern, RECENT_BUILDS).await?;
+    Ok(assets
+        .into_iter()
+        .map(|(release, asset)| ChannelBuild {
+            date: asset.build_date(),
+            size_bytes: Some(asset.size),
+            label: release.tag_name,
+            id: asset.name,
+        })
+        .collect())
+}
+
+/// The newest build directory for the first interface, sized from the
+/// server's Content-Length for its images.
+async fn postmarketos(
+    device: &Device,
+    distro: &DistroConfig,
+    channel: &ChannelConfig,
+) -> Result<ChannelDetails> {
+    let (Some(base_url), Some(interface)) = (
+        distro.base_url.as_deref(),
+        distro.interfaces.as_ref().and_then(|i| i.first()),
+    ) else {
+        return Ok(ChannelDetails::default());
+    };
+    let device_name = postmarketos::device_name(device);
+    let installer = PostmarketosInstaller::new(
+        String::new(),
+        base_url.to_string(),
+        channel.id.clone(),
+        interface.id.clone(),
+        device_name.clone(),
+    );
+
+    let listing_url = format!(
+        "{}{}/{}/{}/",
+        base_url, channel.id, device_name, interface.id
+    );
+    let build_dir = installer.discover_latest_build(&listing_url).await?;
+    let build_url = format!("{}{}/", listing_url, build_dir);
+
+    // Builds shipped as one multi-image archive have no separate images to size
+    let mut size_bytes = None;
+    if distro.archive_partitions.is_empty() {
+        let (boot, _, rootfs, _) = installer.discover_images(&build_url).await?;
+        let client = client()?;
+        let boot_size = remote_size(&client, &format!("{}{}", build_url, boot)).await;
+        let rootfs_size = remote_size(&client, &format!("{}{}", build_url, rootfs)).await;
+        size_bytes = boot_size.zip(rootfs_size).map(|(b, r)| b + r);
+    }
+
+    // Build directories are named YYYYMMDD-HHMM
+    let date = build_dir.trim_end_matches('/');
+    let version = match (date.get(0..4), date.get(4..6), date.get(6..8)) {
+        (Some(y), Some(m), Some(d)) => format!("Built {}-{}-{}", y, m, d),
+        _ => date.to_string(),
+    };
+    Ok(ChannelDetails {
+        version: Some(version),
+        size_bytes,
+    })
+}
+
+/// True when `err` came from not reaching the server at all (no network,
+/// DNS failure, a proxy that won't connect, or a timeout) rather than from
+/// what it answered.
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|cause| {
+            cause.is::<tokio::time::error::Elapsed>()
+                || cause
+                    .downcast_ref::<reqwest::Error>()
+                    .is_some_and(|e| e.is_connect() || e.is_timeout())
+        })
+}
+
+pub(crate) fn client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()?)
+}
+
+/// Size of `url` from a HEAD request, when the server reports one
+async fn remote_size(client: &reqwest::Client, url: &str) -> Option<u64> {
+    let response = client.head(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,437 @@
+// Checksum verifier
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// A hash function distributions publish image digests with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "SHA256",
+            Self::Sha512 => "SHA512",
+            Self::Blake3 => "BLAKE3",
+            Self::Md5 => "MD5",
+        }
+    }
+
+    /// The algorithm a checksum file is named for, as in `SHA512SUMS`,
+    /// `image.img.sha256sum` or `B3SUMS`
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.rsplit('/').next().unwrap_or(name).to_ascii_lowercase();
+        if name.contains("sha512") {
+            Some(Self::Sha512)
+        } else if name.contains("sha256") {
+            Some(Self::Sha256)
+        } else if name.contains("blake3") || name.contains("b3sum") || name.ends_with(".b3") {
+            Some(Self::Blake3)
+        } else if name.contains("md5") {
+            Some(Self::Md5)
+        } else {
+            None
+        }
+    }
+
+    /// The algorithm a hex digest of this length comes from. BLAKE3 digests
+    /// are as long as SHA256 ones and can only be told apart by a hint.
+    pub fn from_digest(digest: &str) -> Option<Self> {
+        match digest.trim().len() {
+            32 => Some(Self::Md5),
+            64 => Some(Self::Sha256),
+            128 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    /// `hint` when there is one, otherwise whatever the digest's length
+    /// suggests, falling back to SHA256
+    pub fn resolve(hint: Option<Self>, digest: &str) -> Self {
+        hint.or_else(|| Self::from_digest(digest)).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A file's digest didn't match the published one
+#[derive(Debug, Clone)]
+pub struct ChecksumMismatch {
+    pub file: String,
+    pub algorithm: ChecksumAlgorithm,
+    pub expected: String,
+    pub computed: String,
+}
+
+impl std::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Checksum mismatch for {}: expected {} {}, computed {}",
+            self.file, self.algorithm, self.expected, self.computed
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// A sums file has no line for the file being checked
+#[derive(Debug, Clone)]
+pub struct ChecksumNotFound {
+    pub file: String,
+}
+
+impl std::fmt::Display for ChecksumNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The checksum file has no entry for {}", self.file)
+    }
+}
+
+impl std::error::Error for ChecksumNotFound {}
+
+/// Undo `sha256sum`'s escaping of names with backslashes or newlines
+fn unescape_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            ('\\', Some('n')) => {
+                result.push('\n');
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// One `<hash> <marker><name>` line, where the marker is a space for text
+/// mode and `*` for binary mode. A leading backslash flags an escaped name.
+fn parse_sums_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim_end_matches('\r');
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (hash, rest) = line.split_once(' ')?;
+    if hash.is_empty() || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let name = rest.strip_prefix([' ', '*']).unwrap_or(rest);
+    let name = if escaped { unescape_name(name) } else { name.to_string() };
+    let name = name.strip_prefix("./").map(str::to_string).unwrap_or(name);
+    (!name.is_empty()).then(|| (name, hash.to_lowercase()))
+}
+
+/// The state of one of the supported hash functions
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+    Md5(md5::Md5),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Sha512 => Self::Sha512(Sha512::new()),
+            ChecksumAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+            ChecksumAlgorithm::Md5 => Self::Md5(md5::Md5::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Sha512(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            Self::Md5(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Self::Sha256(hasher) => hex::encode(hasher.finalize()),
+            Self::Sha512(hasher) => hex::encode(hasher.finalize()),
+            Self::Blake3(hasher) => hex::encode(hasher.finalize().as_bytes()),
+            Self::Md5(hasher) => hex::encode(hasher.finalize()),
+        }
+    }
+}
+
+/// Verifies file checksums
+pub struct ChecksumVerifier;
+
+impl ChecksumVerifier {
+    /// Calculate SHA256 hash of a file
+    pub fn sha256(path: &Path) -> Result<String> {
+        Self::hash(path, ChecksumAlgorithm::Sha256)
+    }
+
+    /// Hash a file with `algorithm`, as lowercase hex
+    pub fn hash(path: &Path, algorithm: ChecksumAlgorithm) -> Result<String> {
+        log::debug!("Calculating {} for {}", algorithm, path.display());
+
+        let file = File::open(path)
+            .context("Failed to open file for checksum")?;
+        let mut reader = BufReader::new(file);
+        let mut hasher = Hasher::new(algorithm);
+        let mut buffer = [0u8; 64 * 1024];
+
+        loop {
+            let bytes_read = reader.read(&mut buffer)
+                .context("Failed to read file")?;
+
+            if bytes_read == 0 {
+                break;
+            }
+
+            hasher.update(&buffer[..bytes_read]);
+        }
+
+        let hex_hash = hasher.finalize_hex();
+
+        log::debug!("{}: {}", algorithm, hex_hash);
+        Ok(hex_hash)
+    }
+
+    /// Check the MD5 Samsung appends to an Odin `.tar.md5`: a `hash  name`
+    /// line after the tar's last 512-byte block, covering everything before
+    /// it. Returns `false` for an archive without one.
+    pub fn check_tar_md5(path: &Path) -> Result<bool> {
+        let mut file = File::open(path).context("Failed to open file for checksum")?;
+        let len = file.metadata().context("Failed to read file")?.len();
+        let payload_len = len / 512 * 512;
+        if payload_len == len {
+            return Ok(false);
+        }
+
+        let mut trailer = String::new();
+        file.seek(SeekFrom::Start(payload_len)).context("Failed to read file")?;
+        file.by_ref()
+            .take(1024)
+            .read_to_string(&mut trailer)
+            .context("The archive's MD5 line isn't text")?;
+        let Some((_, expected)) = parse_sums_line(trailer.trim()) else {
+            anyhow::bail!("{} ends in something other than an MD5 line", path.display());
+        };
+
+        file.seek(SeekFrom::Start(0)).context("Failed to read file")?;
+        let mut reader = BufReader::new(file.take(payload_len));
+        let mut hasher = Hasher::new(ChecksumAlgorithm::Md5);
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let bytes_read = reader.read(&mut buffer).context("Failed to read file")?;
+            if bytes_read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..bytes_read]);
+        }
+
+        let computed = hasher.finalize_hex();
+        if !computed.eq_ignore_ascii_case(&expected) {
+            return Err(ChecksumMismatch {
+                file: path
+                    .file_name()
+                    .map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned()),
+                algorithm: ChecksumAlgorithm::Md5,
+                expected: expected.to_lowercase(),
+                computed,
+            }
+            .into());
+        }
+        log::info!("MD5 verified for {}", path.display());
+        Ok(true)
+    }
+
+    /// Every entry of a `SHA256SUMS`-style file, by file name. Lines that
+    /// aren't checksum entries, such as comments, are skipped.
+    pub fn parse_sums(contents: &str) -> HashMap<String, String> {
+        contents.lines().filter_map(parse_sums_line).collect()
+    }
+
+    /// The digest `contents`, a sums file listing many files, gives for
+    /// `target_filename`. Entries under a directory match by their last
+    /// component when nothing matches the name exactly.
+    pub fn from_sums_file(contents: &str, target_filename: &str) -> Result<String> {
+        let sums = Self::parse_sums(contents);
+        let target = target_filename.rsplit('/').next().unwrap_or(target_filename);
+        sums.get(target_filename)
+            .or_else(|| sums.get(target))
+            .or_else(|| {
+                sums.iter()
+                    .find(|(name, _)| name.rsplit('/').next() == Some(target))
+                    .map(|(_, hash)| hash)
+            })
+            .cloned()
+            .ok_or_else(|| {
+                ChecksumNotFound {
+                    file: target_filename.to_string(),
+                }
+                .into()
+            })
+    }
+
+    /// Verify a file against an expected hash, with the algorithm told by
+    /// the hash's length
+    pub fn verify(path: &Path, expected_hash: &str) -> Result<bool> {
+        Self::verify_with(path, expected_hash, None)
+    }
+
+    /// Verify a file against an expected hash made with `algorithm`, or
+    /// the one its length suggests when that's `None`
+    pub fn verify_with(
+        path: &Path,
+        expected_hash: &str,
+        algorithm: Option<ChecksumAlgorithm>,
+    ) -> Result<bool> {
+        match Self::check(path, expected_hash, algorithm) {
+            Ok(()) => Ok(true),
+            Err(e) if e.downcast_ref::<ChecksumMismatch>().is_some() => {
+                log::error!("{}", e);
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Like [`verify_with`](Self::verify_with), but a mismatch is a
+    /// [`ChecksumMismatch`] error carrying both digests
+    pub fn check(
+        path: &Path,
+        expected_hash: &str,
+        algorithm: Option<ChecksumAlgorithm>,
+    ) -> Result<()> {
+        let expected = expected_hash.trim();
+        let algorithm = ChecksumAlgorithm::resolve(algorithm, expected);
+        let computed = Self::hash(path, algorithm)?;
+        if !computed.eq_ignore_ascii_case(expected) {
+            return Err(ChecksumMismatch {
+                file: path
+                    .file_name()
+                    .map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned()),
+                algorithm,
+                expected: expected.to_lowercase(),
+                computed,
+            }
+            .into());
+        }
+        log::info!("Checksum verified for {}", path.display());
+        Ok(())
+    }
+
+    /// Verify files against a checksum map. `algorithm` applies to every
+    /// entry; without it each digest's length decides.
+    pub fn verify_all(
+        files_dir: &Path,
+        checksums: &std::collections::HashMap<String, String>,
+        algorithm: Option<ChecksumAlgorithm>,
+    ) -> Result<Vec<(String, bool)>> {
+        let mut results = Vec::new();
+
+        for (filename, expected) in checksums {
+            let path = files_dir.join(filename);
+            if path.exists() {
+                let ok = Self::verify_with(&path, expected, algorithm)?;
+                results.push((filename.clone(), ok));
+            } else {
+                log::warn!("File not found for checksum: {}", filename);
+                results.push((filename.clone(), false));
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOT: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+    const ROOTFS: &str = "60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752";
+
+    #[test]
+    fn every_entry_of_a_sums_file_is_read() {
+        let sums = format!(
+            "# built 2026-01-01\n{}  boot.img\n{}  rootfs.img\r\n\nnot a checksum line\n",
+            BOOT, ROOTFS
+        );
+
+        let parsed = ChecksumVerifier::parse_sums(&sums);
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed["boot.img"], BOOT);
+        assert_eq!(parsed["rootfs.img"], ROOTFS);
+        assert_eq!(
+            ChecksumVerifier::from_sums_file(&sums, "rootfs.img").unwrap(),
+            ROOTFS
+        );
+    }
+
+    #[test]
+    fn binary_mode_entries_drop_their_marker() {
+        let sums = format!(
+            "{} *boot.img\n{}  rootfs.img\n",
+            BOOT.to_uppercase(),
+            ROOTFS
+        );
+
+        assert_eq!(
+            ChecksumVerifier::from_sums_file(&sums, "boot.img").unwrap(),
+            BOOT
+        );
+    }
+
+    #[test]
+    fn entries_under_a_directory_match_by_name() {
+        let sums = format!("{}  ./boot.img\n{}  images/rootfs.img\n", BOOT, ROOTFS);
+
+        assert_eq!(
+            ChecksumVerifier::from_sums_file(&sums, "boot.img").unwrap(),
+            BOOT
+        );
+        assert_eq!(
+            ChecksumVerifier::from_sums_file(&sums, "rootfs.img").unwrap(),
+            ROOTFS
+        );
+        assert_eq!(
+            ChecksumVerifier::from_sums_file(&sums, "images/rootfs.img").unwrap(),
+            ROOTFS
+        );
+    }
+
+    #[test]
+    fn a_file_the_sums_dont_list_is_not_found() {
+        let sums = format!("{}  boot.img\n{}  rootfs.img\n", BOOT, ROOTFS);
+
+        let error = ChecksumVerifier::from_sums_file(&sums, "recovery.img").unwrap_err();
+
+        let missing = error.downcast_ref::<ChecksumNotFound>().unwrap();
+        assert_eq!(missing.file, "recovery.img");
+    }
+}
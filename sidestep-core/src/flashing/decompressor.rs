@@ -0,0 +1,975 @@
+// Image decompressor
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::archive;
+use crate::flashing::downloader::{DownloadCancelHandle, DownloadCancelled};
+use crate::flashing::github_release::artifact_matches;
+use crate::hardware::{CancelToken, InstallCancelled};
+use crate::models::distro_config::ArchiveEntry;
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Progress callback type, called with how much of the input has been
+/// worked through and its total. For a compressed image these are bytes
+/// of the compressed file, since the unpacked size isn't known in advance.
+pub type ProgressCallback = Box<dyn Fn(u64, u64) + Send + Sync>;
+
+/// A compressed image failed the format's own integrity checks (xz block
+/// CRCs, gzip CRC32/length trailer), usually because the download was
+/// truncated or damaged.
+#[derive(Debug, Clone)]
+pub struct CorruptImage {
+    pub file: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for CorruptImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Image appears corrupted: {} ({})", self.file, self.reason)
+    }
+}
+
+impl std::error::Error for CorruptImage {}
+
+/// An image is compressed in a format Sidestep can't unpack
+#[derive(Debug, Clone)]
+pub struct UnsupportedFormat {
+    pub file: String,
+    pub format: String,
+}
+
+impl std::fmt::Display for UnsupportedFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is compressed with {}, which Sidestep can't unpack", self.file, self.format)
+    }
+}
+
+impl std::error::Error for UnsupportedFormat {}
+
+/// How an image file is compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Xz,
+    Gzip,
+    Zstd,
+    /// Recognised, but no decoder is built in
+    Unsupported(&'static str),
+    None,
+}
+
+impl Compression {
+    /// Leading bytes of each format's stream
+    const MAGIC: &'static [(&'static [u8], Compression)] = &[
+        (&[0xFD, b'7', b'z', b'X', b'Z', 0x00], Compression::Xz),
+        (&[0x1F, 0x8B], Compression::Gzip),
+        (&[0x28, 0xB5, 0x2F, 0xFD], Compression::Zstd),
+        (b"BZh", Compression::Unsupported("bzip2")),
+        (&[0x04, 0x22, 0x4D, 0x18], Compression::Unsupported("LZ4")),
+        (&[0x5D, 0x00, 0x00], Compression::Unsupported("LZMA")),
+    ];
+
+    /// Guess from a file name's extension alone
+    pub fn from_name(name: &str) -> Self {
+        let extension = name.rsplit_once('.').map_or("", |(_, ext)| ext).to_ascii_lowercase();
+        match extension.as_str() {
+            "xz" => Compression::Xz,
+            "gz" => Compression::Gzip,
+            "zst" | "zstd" => Compression::Zstd,
+            "bz2" => Compression::Unsupported("bzip2"),
+            "lz4" => Compression::Unsupported("LZ4"),
+            "lzma" => Compression::Unsupported("LZMA"),
+            _ => Compression::None,
+        }
+    }
+
+    /// Detect from the file's magic bytes, falling back to its extension
+    /// when they match no known format
+    pub fn detect(path: &Path) -> Result<Self> {
+        let mut header = [0u8; 6];
+        let mut file = File::open(path).context("Failed to open input file")?;
+        let mut read = 0;
+        while read < header.len() {
+            match file.read(&mut header[read..])? {
+                0 => break,
+                n => read += n,
+            }
+        }
+        let header = &header[..read];
+        let detected = Self::MAGIC
+            .iter()
+            .find(|(magic, _)| header.starts_with(magic))
+            .map(|(_, compression)| *compression);
+        Ok(detected.unwrap_or_else(|| Self::from_name(&file_name(path))))
+    }
+}
+
+/// The unpacked contents of an image, read straight from the compressed
+/// file. Corruption surfaces as a read error part way through.
+pub struct DecompressedStream {
+    inner: Box<dyn Read + Send>,
+    size: Option<u64>,
+}
+
+impl DecompressedStream {
+    /// How many bytes the stream will yield, when the format records it
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    pub fn into_reader(self) -> Box<dyn Read + Send> {
+        self.inner
+    }
+}
+
+impl Read for DecompressedStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// The unpacked size of an .xz file, added up from the index at the end of
+/// each of its streams. `None` if the file doesn't end the way xz files do.
+pub fn xz_unpacked_size(path: &Path) -> Result<Option<u64>> {
+    use std::io::{Seek, SeekFrom};
+
+    fn read_at(file: &mut File, offset: u64, len: u64) -> std::io::Result<Vec<u8>> {
+        let mut buffer = vec![0u8; len as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// xz's variable-length integers: 7 bits a byte, low bits first
+    fn varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in 0..9 {
+            let byte = *bytes.get(*pos)?;
+            *pos += 1;
+            value |= u64::from(byte & 0x7F) << (shift * 7);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    const HEADER_SIZE: u64 = 12;
+    const FOOTER_SIZE: u64 = 12;
+
+    let mut file = File::open(path).context("Failed to open input file")?;
+    let mut end = file.metadata()?.len();
+    let mut total = 0u64;
+    while end > 0 {
+        // Streams may be followed by zero padding in whole 32-bit words
+        while end >= 4 && read_at(&mut file, end - 4, 4)? == [0; 4] {
+            end -= 4;
+        }
+        if end < HEADER_SIZE + FOOTER_SIZE {
+            return Ok(None);
+        }
+        let footer = read_at(&mut file, end - FOOTER_SIZE, FOOTER_SIZE)?;
+        if &footer[10..12] != b"YZ" {
+            return Ok(None);
+        }
+        let backward_size = u32::from_le_bytes([footer[4], footer[5], footer[6], footer[7]]);
+        let index_size = (u64::from(backward_size) + 1) * 4;
+        if index_size + HEADER_SIZE + FOOTER_SIZE > end {
+            return Ok(None);
+        }
+        let index = read_at(&mut file, end - FOOTER_SIZE - index_size, index_size)?;
+        if index[0] != 0 {
+            return Ok(None);
+        }
+
+        let mut pos = 1;
+        let Some(records) = varint(&index, &mut pos) else {
+            return Ok(None);
+        };
+        let mut blocks_size = 0u64;
+        for _ in 0..records {
+            let (Some(unpadded), Some(uncompressed)) = (varint(&index, &mut pos), varint(&index, &mut pos))
+            else {
+                return Ok(None);
+            };
+            blocks_size += unpadded.div_ceil(4) * 4;
+            total += uncompressed;
+        }
+
+        let stream_size = HEADER_SIZE + blocks_size + index_size + FOOTER_SIZE;
+        if stream_size > end {
+            return Ok(None);
+        }
+        end -= stream_size;
+    }
+    Ok(Some(total))
+}
+
+/// Where an image unpacks to when no output path is given: the input
+/// without its compression extension
+fn default_output(input_path: &Path) -> PathBuf {
+    if Compression::from_name(&file_name(input_path)) == Compression::None {
+        let mut name = input_path.as_os_str().to_os_string();
+        name.push(".unpacked");
+        PathBuf::from(name)
+    } else {
+        input_path.with_extension("")
+    }
+}
+
+/// Discard what was written and the bad input, so a retry downloads the
+/// image again rather than reusing the cached copy.
+fn corrupt(input: &Path, output: &Path, reason: impl Into<String>) -> anyhow::Error {
+    let _ = std::fs::remove_file(output);
+    let _ = std::fs::remove_file(input);
+    let error = CorruptImage {
+        file: input
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| input.display().to_string()),
+        reason: reason.into(),
+    };
+    log::error!("{}", error);
+    anyhow::Error::new(error)
+}
+
+/// Stop a decompression: remove the partial output but keep the compressed
+/// input, which is still a good cached download.
+fn cancelled(input: &Path, output: &Path) -> anyhow::Error {
+    let _ = std::fs::remove_file(output);
+    log::info!("Decompression of {} cancelled", input.display());
+    anyhow::Error::new(DownloadCancelled)
+}
+
+/// Run an unpack on the blocking pool, setting the handle it watches once
+/// the install's `cancel` is. For callers that hold only a [`CancelToken`];
+/// the unpack still removes its partial output, and the run ends as
+/// [`InstallCancelled`].
+pub(crate) async fn unpack_cancellable<T: Send + 'static>(
+    cancel: &CancelToken,
+    unpack: impl FnOnce(&DownloadCancelHandle) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    cancel.check()?;
+    let handle = DownloadCancelHandle::new();
+    let watched = handle.clone();
+    let mut task = tokio::task::spawn_blocking(move || unpack(&watched));
+    tokio::select! {
+        result = &mut task => result.context("Unpacking task failed")?,
+        () = cancel.cancelled() => {
+            handle.cancel();
+            let _ = task.await;
+            Err(InstallCancelled.into())
+        }
+    }
+}
+
+/// Decompresses .xz, .gz and .zst images.
+///
+/// A `cancel` handle is checked between chunks, so a cancelled run stops
+/// within one 64 KiB write and returns [`DownloadCancelled`].
+pub struct Decompressor;
+
+impl Decompressor {
+    /// Decompress a file, detecting the format from its magic bytes and
+    /// then its extension. Files in neither a known format nor with a
+    /// compression extension are returned as they are; formats without a
+    /// decoder fail with [`UnsupportedFormat`].
+    pub fn decompress(
+        input_path: &Path,
+        output_path: Option<&Path>,
+        on_progress: Option<ProgressCallback>,
+        cancel: Option<&DownloadCancelHandle>,
+    ) -> Result<PathBuf> {
+        match Compression::detect(input_path)? {
+            Compression::Xz => Self::decompress_xz(input_path, output_path, on_progress, cancel),
+            Compression::Gzip => Self::decompress_gz(input_path, output_path, on_progress, cancel),
+            Compression::Zstd => Self::decompress_zstd(input_path, output_path, on_progress, cancel),
+            Compression::Unsupported(format) => Err(UnsupportedFormat {
+                file: file_name(input_path),
+                format: format.to_string(),
+            }
+            .into()),
+            Compression::None => {
+                // Not compressed, just return the input path
+                log::debug!("File {} is not compressed", input_path.display());
+                Ok(input_path.to_path_buf())
+            }
+        }
+    }
+
+    /// Open `input_path` for reading its unpacked contents without writing
+    /// them anywhere. Only xz records its unpacked size up front; streams
+    /// of other formats report no size. Uncompressed files are read as
+    /// they are.
+    pub fn stream(input_path: &Path) -> Result<DecompressedStream> {
+        let compression = Compression::detect(input_path)?;
+        let file = File::open(input_path).context("Failed to open input file")?;
+        let reader = BufReader::new(file);
+        let (inner, size): (Box<dyn Read + Send>, Option<u64>) = match compression {
+            Compression::Xz => (
+                Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+                xz_unpacked_size(input_path)?,
+            ),
+            Compression::Gzip => (Box::new(GzDecoder::new(reader)), None),
+            Compression::Zstd => (
+                Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+                None,
+            ),
+            Compression::Unsupported(format) => {
+                return Err(UnsupportedFormat {
+                    file: file_name(input_path),
+                    format: format.to_string(),
+                }
+                .into());
+            }
+            Compression::None => {
+                let size = std::fs::metadata(input_path)?.len();
+                (Box::new(reader), Some(size))
+            }
+        };
+        Ok(DecompressedStream { inner, size })
+    }
+
+    /// Decompress an .xz file.
+    ///
+    /// liblzma checks each block's integrity check and fails on a stream
+    /// that ends early; both are reported as [`CorruptImage`].
+    pub fn decompress_xz(
+        input_path: &Path,
+        output_path: Option<&Path>,
+        on_progress: Option<ProgressCallback>,
+        cancel: Option<&DownloadCancelHandle>,
+    ) -> Result<PathBuf> {
+        log::info!("Decompressing XZ: {}", input_path.display());
+
+        let output = output_path
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| default_output(input_path));
+
+        let input_file = File::open(input_path)
+            .context("Failed to open input file")?;
+        let input_size = input_file.metadata()?.len();
+        let reader = BufReader::new(input_file);
+
+        // Multi-stream so images compressed in parallel (pixz, xz -T) decode fully
+        let mut decoder = xz2::read::XzDecoder::new_multi_decoder(reader);
+        let mut reported = 0;
+        let mut output_file = File::create(&output)
+            .context("Failed to create output file")?;
+
+        let mut buffer = [0u8; 64 * 1024]; // 64KB buffer
+        let mut total_written: u64 = 0;
+
+        loop {
+            if cancel.is_some_and(|c| c.is_cancelled()) {
+                drop(output_file);
+                return Err(cancelled(input_path, &output));
+            }
+
+            let bytes_read = match decoder.read(&mut buffer) {
+                Ok(n) => n,
+                Err(e) => return Err(corrupt(input_path, &output, e.to_string())),
+            };
+
+            if bytes_read == 0 {
+                break;
+            }
+
+            output_file.write_all(&buffer[..bytes_read])
+                .context("Error writing decompressed data")?;
+
+            total_written += bytes_read as u64;
+
+            if let Some(ref callback) = on_progress {
+                report_input(callback, decoder.total_in(), input_size, &mut reported);
+            }
+        }
+
+        if decoder.total_in() < input_size {
+            return Err(corrupt(
+                input_path,
+                &output,
+                format!("stream ended after {} of {} bytes", decoder.total_in(), input_size),
+            ));
+        }
+
+        log::info!("Decompressed {} bytes to {}", total_written, output.display());
+        Ok(output)
+    }
+
+    /// Decompress a .gz file.
+    ///
+    /// The gzip trailer's CRC32 and length are checked once the stream
+    /// ends; a missing or mismatching trailer is reported as [`CorruptImage`].
+    pub fn decompress_gz(
+        input_path: &Path,
+        output_path: Option<&Path>,
+        on_progress: Option<ProgressCallback>,
+        cancel: Option<&DownloadCancelHandle>,
+    ) -> Result<PathBuf> {
+        log::info!("Decompressing GZ: {}", input_path.display());
+
+        let output = output_path
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| default_output(input_path));
+
+        let input_file = File::open(input_path)
+            .context("Failed to open input file")?;
+        let input_size = input_file.metadata()?.len();
+        let consumed = Arc::new(AtomicU64::new(0));
+        let reader = CountingReader {
+            inner: BufReader::new(input_file),
+            consumed: consumed.clone(),
+        };
+
+        let mut decoder = GzDecoder::new(reader);
+        let mut reported = 0;
+        let mut output_file = File::create(&output)
+            .context("Failed to create output file")?;
+
+        let mut buffer = [0u8; 64 * 1024];
+        let mut total_written: u64 = 0;
+
+        loop {
+            if cancel.is_some_and(|c| c.is_cancelled()) {
+                drop(output_file);
+                return Err(cancelled(input_path, &output));
+            }
+
+            let bytes_read = match decoder.read(&mut buffer) {
+                Ok(n) => n,
+                Err(e) => return Err(corrupt(input_path, &output, e.to_string())),
+            };
+
+            if bytes_read == 0 {
+                break;
+            }
+
+            output_file.write_all(&buffer[..bytes_read])
+                .context("Error writing decompressed data")?;
+
+            total_written += bytes_read as u64;
+
+            if let Some(ref callback) = on_progress {
+                report_input(callback, consumed.load(Ordering::Relaxed), input_size, &mut reported);
+            }
+        }
+
+        log::info!("Decompressed {} bytes to {}", total_written, output.display());
+        Ok(output)
+    }
+
+    /// Decompress a .zst file.
+    ///
+    /// Concatenated frames are all decoded. A frame cut off by the end of
+    /// the file, or failing its content checksum, is reported as
+    /// [`CorruptImage`].
+    pub fn decompress_zstd(
+        input_path: &Path,
+        output_path: Option<&Path>,
+        on_progress: Option<ProgressCallback>,
+        cancel: Option<&DownloadCancelHandle>,
+    ) -> Result<PathBuf> {
+        log::info!("Decompressing Zstandard: {}", input_path.display());
+
+        let output = output_path
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| default_output(input_path));
+
+        let input_file = File::open(input_path)
+            .context("Failed to open input file")?;
+        let input_size = input_file.metadata()?.len();
+        let consumed = Arc::new(AtomicU64::new(0));
+        let reader = BufReader::new(CountingReader {
+            inner: input_file,
+            consumed: consumed.clone(),
+        });
+
+        let mut decoder = match zstd::stream::read::Decoder::with_buffer(reader) {
+            Ok(decoder) => decoder,
+            Err(e) => return Err(corrupt(input_path, &output, e.to_string())),
+        };
+        let mut output_file = File::create(&output)
+            .context("Failed to create output file")?;
+
+        let mut buffer = [0u8; 64 * 1024];
+        let mut total_written: u64 = 0;
+        let mut reported = 0;
+
+        loop {
+            if cancel.is_some_and(|c| c.is_cancelled()) {
+                drop(output_file);
+                return Err(cancelled(input_path, &output));
+            }
+
+            let bytes_read = match decoder.read(&mut buffer) {
+                Ok(n) => n,
+                Err(e) => return Err(corrupt(input_path, &output, e.to_string())),
+            };
+
+            if bytes_read == 0 {
+                break;
+            }
+
+            output_file.write_all(&buffer[..bytes_read])
+                .context("Error writing decompressed data")?;
+
+            total_written += bytes_read as u64;
+
+            if let Some(ref callback) = on_progress {
+                report_input(callback, consumed.load(Ordering::Relaxed), input_size, &mut reported);
+            }
+        }
+
+        log::info!("Decompressed {} bytes to {}", total_written, output.display());
+        Ok(output)
+    }
+
+    /// Unpack the images `entries` names from a combined `.zip`, `.tar`,
+    /// `.tar.gz`, `.tar.xz`, `.tar.zst` or Odin `.tar.md5` archive into
+    /// `dest_dir`, returning each partition with the image to flash to it,
+    /// in `entries` order.
+    ///
+    /// Every required entry must match exactly one file in the archive and
+    /// no file may match two entries; files no entry names are skipped.
+    /// Images that are themselves compressed are unpacked too. Links are
+    /// never followed, and an archive with absolute or `..` member paths is
+    /// refused with [`archive::UnsafeArchiveEntry`].
+    pub fn extract_partitions(
+        archive_path: &Path,
+        dest_dir: &Path,
+        entries: &[ArchiveEntry],
+        on_progress: Option<ProgressCallback>,
+        cancel: Option<&DownloadCancelHandle>,
+    ) -> Result<Vec<(String, PathBuf)>> {
+        log::info!("Extracting partition images from {}", archive_path.display());
+
+        if dest_dir.exists() {
+            std::fs::remove_dir_all(dest_dir).context("Failed to clean previous extraction")?;
+        }
+        std::fs::create_dir_all(dest_dir).context("Failed to create extraction directory")?;
+
+        let archive_name = file_name(archive_path).to_lowercase();
+        let extracted = if archive_name.ends_with(".zip") {
+            extract_zip(archive_path, dest_dir, entries, on_progress.as_ref(), cancel)?
+        } else {
+            extract_tar(archive_path, dest_dir, entries, on_progress.as_ref(), cancel)?
+        };
+
+        let missing: Vec<&str> = entries
+            .iter()
+            .zip(&extracted)
+            .filter(|(entry, path)| !entry.optional && path.is_none())
+            .map(|(entry, _)| entry.file.as_str())
+            .collect();
+        if !missing.is_empty() {
+            let _ = std::fs::remove_dir_all(dest_dir);
+            anyhow::bail!(
+                "{} doesn't contain the expected {}",
+                file_name(archive_path),
+                missing.join(", ")
+            );
+        }
+
+        let mut images = Vec::new();
+        for (entry, path) in entries.iter().zip(extracted) {
+            let Some(path) = path else {
+                log::info!("No {} in the archive; not flashing {}", entry.file, entry.partition);
+                continue;
+            };
+            let image = Self::decompress(&path, None, None, cancel)?;
+            if image != path {
+                let _ = std::fs::remove_file(&path);
+            }
+            log::info!("{} → {}", image.display(), entry.partition);
+            images.push((entry.partition.clone(), image));
+        }
+        Ok(images)
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// The entry that claims the archive member `member`, if any. A member
+/// wanted by two entries, or an entry matching two members, is a config
+/// error rather than something to guess about.
+fn claim(
+    member: &str,
+    entries: &[ArchiveEntry],
+    extracted: &[Option<PathBuf>],
+) -> Result<Option<usize>> {
+    let base = member.rsplit('/').next().unwrap_or(member);
+    let wanted = |pattern: &str| {
+        if pattern.contains(['*', '?']) {
+            artifact_matches(pattern, base)
+        } else {
+            pattern == base
+        }
+    };
+    let matching: Vec<usize> = (0..entries.len()).filter(|&i| wanted(&entries[i].file)).collect();
+    match matching.as_slice() {
+        [] => Ok(None),
+        [index] => {
+            if let Some(ref earlier) = extracted[*index] {
+                anyhow::bail!(
+                    "{} matches both {} and {} in the archive",
+                    entries[*index].file,
+                    file_name(earlier),
+                    base
+                );
+            }
+            Ok(Some(*index))
+        }
+        _ => anyhow::bail!("{} in the archive matches more than one partition", base),
+    }
+}
+
+/// Where the member `name` is unpacked to: its file name alone, in
+/// `dest_dir`. A path that tries to leave it fails the whole extraction.
+fn member_output(name: &str, dest_dir: &Path) -> Result<PathBuf> {
+    match archive::file_path(name) {
+        Ok(path) => Ok(dest_dir.join(path.file_name().unwrap_or(path.as_os_str()))),
+        Err(e) => {
+            log::error!("{}", e);
+            let _ = std::fs::remove_dir_all(dest_dir);
+            Err(e.into())
+        }
+    }
+}
+
+/// Copy one archive member to `output`, stopping when cancelled. Read
+/// errors mean the archive itself is damaged.
+fn copy_member(
+    member: &mut dyn Read,
+    output: &Path,
+    archive_path: &Path,
+    dest_dir: &Path,
+    cancel: Option<&DownloadCancelHandle>,
+) -> Result<()> {
+    let mut output_file = File::create(output).context("Failed to create output file")?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        if cancel.is_some_and(|c| c.is_cancelled()) {
+            drop(output_file);
+            let _ = std::fs::remove_dir_all(dest_dir);
+            return Err(cancelled(archive_path, output));
+        }
+        let bytes_read = match member.read(&mut buffer) {
+            Ok(n) => n,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(dest_dir);
+                return Err(corrupt(archive_path, output, e.to_string()));
+            }
+        };
+        if bytes_read == 0 {
+            return Ok(());
+        }
+        output_file
+            .write_all(&buffer[..bytes_read])
+            .context("Error writing extracted image")?;
+    }
+}
+
+fn extract_zip(
+    archive_path: &Path,
+    dest_dir: &Path,
+    entries: &[ArchiveEntry],
+    on_progress: Option<&ProgressCallback>,
+    cancel: Option<&DownloadCancelHandle>,
+) -> Result<Vec<Option<PathBuf>>> {
+    let file = File::open(archive_path).context("Failed to open archive")?;
+    let mut archive = match zip::ZipArchive::new(file) {
+        Ok(archive) => archive,
+        Err(e) => return Err(corrupt(archive_path, dest_dir, e.to_string())),
+    };
+
+    let mut extracted = vec![None; entries.len()];
+    let count = archive.len();
+    for i in 0..count {
+        let mut member = match archive.by_index(i) {
+            Ok(member) => member,
+            Err(e) => return Err(corrupt(archive_path, dest_dir, e.to_string())),
+        };
+        if member.is_symlink() {
+            log::warn!("Skipping link {} in the archive", member.name());
+        } else if member.is_file() {
+            let name = member.name().to_string();
+            let output = member_output(&name, dest_dir)?;
+            match claim(&name, entries, &extracted)? {
+                Some(index) => {
+                    copy_member(&mut member, &output, archive_path, dest_dir, cancel)?;
+                    extracted[index] = Some(output);
+                }
+                None => log::debug!("Skipping {} in the archive", name),
+            }
+        }
+        if let Some(callback) = on_progress {
+            callback((i + 1) as u64, count as u64);
+        }
+    }
+    Ok(extracted)
+}
+
+fn extract_tar(
+    archive_path: &Path,
+    dest_dir: &Path,
+    entries: &[ArchiveEntry],
+    on_progress: Option<&ProgressCallback>,
+    cancel: Option<&DownloadCancelHandle>,
+) -> Result<Vec<Option<PathBuf>>> {
+    let file = File::open(archive_path).context("Failed to open archive")?;
+    let input_size = file.metadata()?.len().max(1);
+    let consumed = Arc::new(AtomicU64::new(0));
+    let counted = CountingReader {
+        inner: BufReader::new(file),
+        consumed: consumed.clone(),
+    };
+
+    let name = file_name(archive_path).to_lowercase();
+    let reader: Box<dyn Read> = if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+        Box::new(xz2::read::XzDecoder::new_multi_decoder(counted))
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Box::new(GzDecoder::new(counted))
+    } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+        match zstd::stream::read::Decoder::new(counted) {
+            Ok(decoder) => Box::new(decoder),
+            Err(e) => return Err(corrupt(archive_path, dest_dir, e.to_string())),
+        }
+    } else if name.ends_with(".tar") || name.ends_with(".tar.md5") {
+        // Samsung's .tar.md5 is a tar with an MD5 line after its end blocks
+        Box::new(counted)
+    } else {
+        anyhow::bail!("Unsupported archive format: {}", file_name(archive_path));
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    let members = match archive.entries() {
+        Ok(members) => members,
+        Err(e) => return Err(corrupt(archive_path, dest_dir, e.to_string())),
+    };
+
+    let mut extracted = vec![None; entries.len()];
+    for member in members {
+        let mut member = match member {
+            Ok(member) => member,
+            Err(e) => return Err(corrupt(archive_path, dest_dir, e.to_string())),
+        };
+        let entry_type = member.header().entry_type();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            log::warn!("Skipping link {} in the archive", member.path()?.display());
+            continue;
+        }
+        if !entry_type.is_file() {
+            continue;
+        }
+        let name = member.path()?.to_string_lossy().into_owned();
+        let output = member_output(&name, dest_dir)?;
+        match claim(&name, entries, &extracted)? {
+            Some(index) => {
+                copy_member(&mut member, &output, archive_path, dest_dir, cancel)?;
+                extracted[index] = Some(output);
+            }
+            None => log::debug!("Skipping {} in the archive", name),
+        }
+        if let Some(callback) = on_progress {
+            callback(consumed.load(Ordering::Relaxed).min(input_size), input_size);
+        }
+    }
+    Ok(extracted)
+}
+
+/// Report `consumed` of `input_size` compressed bytes, at most once per
+/// percent; callers see a steady trickle rather than one call per chunk
+fn report_input(callback: &ProgressCallback, consumed: u64, input_size: u64, reported: &mut u64) {
+    let input_size = input_size.max(1);
+    let consumed = consumed.min(input_size);
+    let percent = consumed * 100 / input_size;
+    if percent != *reported || consumed == input_size {
+        *reported = percent;
+        callback(consumed, input_size);
+    }
+}
+
+/// Counts how much of the compressed archive has been read, for progress
+/// through a tar stream that can't report its own position.
+struct CountingReader<R> {
+    inner: R,
+    consumed: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.consumed.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes that don't compress away, so a half-length file really cuts
+    /// the stream short
+    fn image_bytes() -> Vec<u8> {
+        let mut state: u32 = 0x2545_f491;
+        (0..512 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sidestep-decompress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    fn xz(data: &[u8]) -> Vec<u8> {
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 1);
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn gz(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zst(data: &[u8]) -> Vec<u8> {
+        zstd::stream::encode_all(data, 1).unwrap()
+    }
+
+    /// Write `compressed` cut to half its length and check it's refused as
+    /// corrupt, with neither it nor a partial output left behind
+    fn assert_truncated_is_corrupt(name: &str, compressed: Vec<u8>) {
+        let input = scratch(name);
+        std::fs::write(&input, &compressed[..compressed.len() / 2]).unwrap();
+        let output = default_output(&input);
+
+        let error = Decompressor::decompress(&input, None, None, None).unwrap_err();
+        assert!(
+            error.downcast_ref::<CorruptImage>().is_some(),
+            "{}: {:#}",
+            name,
+            error
+        );
+        assert!(!output.exists(), "{} left a partial output", name);
+        assert!(!input.exists(), "{} kept the bad download", name);
+    }
+
+    #[test]
+    fn truncated_xz_is_corrupt() {
+        assert_truncated_is_corrupt("truncated.img.xz", xz(&image_bytes()));
+    }
+
+    #[test]
+    fn truncated_gz_is_corrupt() {
+        assert_truncated_is_corrupt("truncated.img.gz", gz(&image_bytes()));
+    }
+
+    #[test]
+    fn truncated_zst_is_corrupt() {
+        assert_truncated_is_corrupt("truncated.img.zst", zst(&image_bytes()));
+    }
+
+    #[test]
+    fn whole_images_unpack_to_the_original() {
+        let data = image_bytes();
+        for (name, compressed) in [
+            ("whole.img.xz", xz(&data)),
+            ("whole.img.gz", gz(&data)),
+            ("whole.img.zst", zst(&data)),
+        ] {
+            let input = scratch(name);
+            std::fs::write(&input, compressed).unwrap();
+            let output = Decompressor::decompress(&input, None, None, None).unwrap();
+            assert_eq!(std::fs::read(&output).unwrap(), data, "{}", name);
+            let _ = std::fs::remove_file(&input);
+            let _ = std::fs::remove_file(&output);
+        }
+    }
+    #[test]
+    fn cancelling_part_way_stops_and_removes_the_output() {
+        let data = image_bytes();
+        for (name, compressed) in [
+            ("cancelled.img.xz", xz(&data)),
+            ("cancelled.img.gz", gz(&data)),
+            ("cancelled.img.zst", zst(&data)),
+        ] {
+            let input = scratch(name);
+            std::fs::write(&input, compressed).unwrap();
+            let cancel = DownloadCancelHandle::new();
+            let on_progress: ProgressCallback = {
+                let cancel = cancel.clone();
+                Box::new(move |_, _| cancel.cancel())
+            };
+
+            let error = Decompressor::decompress(&input, None, Some(on_progress), Some(&cancel))
+                .unwrap_err();
+            assert!(
+                error.downcast_ref::<DownloadCancelled>().is_some(),
+                "{}: {:#}",
+                name,
+                error
+            );
+            assert!(
+                !default_output(&input).exists(),
+                "{} left a partial output",
+                name
+            );
+            assert!(input.exists(), "{} dropped the cached download", name);
+            let _ = std::fs::remove_file(&input);
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_the_install_stops_an_unpack_promptly() {
+        let input = scratch("install-cancelled.img.xz");
+        std::fs::write(&input, xz(&image_bytes())).unwrap();
+        let cancel = CancelToken::new();
+
+        // Held at the first progress report until the cancel reaches the
+        // unpack, so it always lands part way
+        let path = input.clone();
+        let unpack = unpack_cancellable(&cancel, move |handle| {
+            let watched = handle.clone();
+            let on_progress: ProgressCallback = Box::new(move |_, _| {
+                let waiting = std::time::Instant::now();
+                while !watched.is_cancelled() && waiting.elapsed().as_secs() < 5 {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+            });
+            Decompressor::decompress(&path, None, Some(on_progress), Some(handle))
+        });
+        let started = std::time::Instant::now();
+        let cancel_soon = async {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            cancel.cancel();
+        };
+        let (result, ()) = tokio::join!(unpack, cancel_soon);
+
+        let error = result.unwrap_err();
+        assert!(
+            error.downcast_ref::<InstallCancelled>().is_some(),
+            "{:#}",
+            error
+        );
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(!default_output(&input).exists());
+        let _ = std::fs::remove_file(&input);
+    }
+}
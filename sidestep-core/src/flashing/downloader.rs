@@ -0,0 +1,1401 @@
+// Image downloader
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::checksum::{ChecksumAlgorithm, ChecksumMismatch, ChecksumVerifier};
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::io::Read;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::fs::File;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Notify;
+
+/// Progress callback type
+pub type ProgressCallback = Box<dyn Fn(u64, u64) + Send + Sync>;
+
+/// Transfers allowed at once across every downloader in the process.
+/// Most images come from community-funded mirrors, so this stays low.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 2;
+
+/// How many ranged requests a large download is split into by default
+pub const DEFAULT_DOWNLOAD_CHUNKS: usize = 4;
+
+/// Files smaller than this are fetched in one stream; splitting them
+/// buys nothing
+const MIN_CHUNKED_SIZE: u64 = 16 * 1024 * 1024;
+
+/// How many times a 429 or 503 is waited out before the download fails
+const MAX_THROTTLE_RETRIES: u32 = 3;
+
+/// Used when a throttling response has no `Retry-After` in seconds
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// Longer `Retry-After` values are capped to this
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// How long a host may take to accept the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a response may go without sending anything, headers or body,
+/// before the transfer counts as failed. Only silence counts, so a slow
+/// but steady download of a large image never trips it.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+static CONNECTIONS: ConnectionLimit = ConnectionLimit {
+    active: Mutex::new(0),
+    max: AtomicUsize::new(DEFAULT_MAX_CONNECTIONS),
+    freed: Notify::const_new(),
+};
+
+/// Cap the number of simultaneous transfers. Takes effect for transfers
+/// that haven't started yet; values below 1 are treated as 1.
+pub fn set_max_connections(max: usize) {
+    let max = max.max(1);
+    if CONNECTIONS.max.swap(max, Ordering::SeqCst) != max {
+        log::info!("Download connections limited to {}", max);
+    }
+    CONNECTIONS.freed.notify_waiters();
+}
+
+static RATE_LIMIT: RateLimit = RateLimit {
+    bytes_per_sec: AtomicU64::new(0),
+    bucket: Mutex::new(TokenBucket {
+        tokens: 0.0,
+        refilled: None,
+    }),
+};
+
+/// Cap the combined speed of every transfer in the process, chunks of a
+/// split download included. `None` lifts the limit.
+pub fn set_max_bytes_per_sec(limit: Option<u64>) {
+    let limit = limit.filter(|&bytes| bytes > 0);
+    if RATE_LIMIT.bytes_per_sec.swap(limit.unwrap_or(0), Ordering::SeqCst) != limit.unwrap_or(0) {
+        match limit {
+            Some(bytes) => log::info!(
+                "Downloads limited to {}/s",
+                crate::utils::format::format_size(bytes)
+            ),
+            None => log::info!("Download speed no longer limited"),
+        }
+    }
+    *RATE_LIMIT.bucket.lock().unwrap() = TokenBucket {
+        tokens: 0.0,
+        refilled: None,
+    };
+}
+
+/// A token bucket refilled at the rate limit and holding up to a second's
+/// worth. Transfers take what they received and wait off any overdraft,
+/// so a large chunk is paid for after the fact instead of being refused.
+struct RateLimit {
+    bytes_per_sec: AtomicU64,
+    bucket: Mutex<TokenBucket>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    refilled: Option<Instant>,
+}
+
+impl RateLimit {
+    /// Account for `bytes` just received, sleeping until they fit the limit
+    async fn take(&self, bytes: usize) {
+        let rate = self.bytes_per_sec.load(Ordering::SeqCst);
+        if rate == 0 {
+            return;
+        }
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let rate = rate as f64;
+            let elapsed = bucket.refilled.map_or(1.0, |then| (now - then).as_secs_f64());
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(rate) - bytes as f64;
+            bucket.refilled = Some(now);
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+struct ConnectionLimit {
+    active: Mutex<usize>,
+    max: AtomicUsize,
+    freed: Notify,
+}
+
+impl ConnectionLimit {
+    /// Wait for a free slot; it is given back when the guard drops.
+    async fn acquire(&'static self, url: &str) -> ConnectionSlot {
+        let mut logged = false;
+        loop {
+            let freed = self.freed.notified();
+            {
+                let mut active = self.active.lock().unwrap();
+                if *active < self.max.load(Ordering::SeqCst) {
+                    *active += 1;
+                    return ConnectionSlot(self);
+                }
+            }
+            if !logged {
+                log::info!("Waiting for a free download connection for {}", url);
+                logged = true;
+            }
+            freed.await;
+        }
+    }
+}
+
+struct ConnectionSlot(&'static ConnectionLimit);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        *self.0.active.lock().unwrap() -= 1;
+        self.0.freed.notify_waiters();
+    }
+}
+
+/// How long a 429 or 503 response asks us to wait. Only the delay-seconds
+/// form of `Retry-After` is understood; HTTP dates get the default.
+fn retry_after(response: &reqwest::Response) -> Duration {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+        .min(MAX_RETRY_AFTER)
+}
+
+/// Shared flag for aborting in-flight downloads from another thread.
+///
+/// This only stops HTTP transfers; it is separate from cancelling an install.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadCancelHandle(Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    /// Wakes transfers waiting on the network as soon as they're cancelled
+    notify: Notify,
+}
+
+impl DownloadCancelHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the handle is cancelled, for racing against a wait
+    pub async fn cancelled(&self) {
+        let notified = self.0.notify.notified();
+        tokio::pin!(notified);
+        // Registered before the check, so a cancel in between still wakes it
+        notified.as_mut().enable();
+        if !self.is_cancelled() {
+            notified.await;
+        }
+    }
+}
+
+/// Error returned when a transfer was stopped through its [`DownloadCancelHandle`]
+#[derive(Debug, Clone)]
+pub struct DownloadCancelled;
+
+impl std::fmt::Display for DownloadCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Download cancelled")
+    }
+}
+
+impl std::error::Error for DownloadCancelled {}
+
+/// A transfer failed on the server or network side: the connection,
+/// an HTTP error status or a stream cut off part way
+#[derive(Debug, Clone)]
+pub struct DownloadFailed {
+    pub url: String,
+    pub reason: String,
+    /// A 5xx, or the host couldn't be reached or stopped answering: the
+    /// same file may still come through from a mirror
+    pub transient: bool,
+}
+
+impl DownloadFailed {
+    fn from_request(url: &str, error: &reqwest::Error) -> Self {
+        Self {
+            url: url.to_string(),
+            reason: error.to_string(),
+            transient: error.is_connect() || error.is_timeout(),
+        }
+    }
+
+    fn from_status(url: &str, status: reqwest::StatusCode) -> Self {
+        Self {
+            url: url.to_string(),
+            reason: format!("server returned {}", status),
+            transient: status.is_server_error(),
+        }
+    }
+}
+
+/// Whether `error` is a failure a mirror might not share
+fn is_transient(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<DownloadFailed>()
+        .is_some_and(|failed| failed.transient)
+}
+
+impl std::fmt::Display for DownloadFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Download from {} failed: {}", self.url, self.reason)
+    }
+}
+
+impl std::error::Error for DownloadFailed {}
+
+/// The download directory's filesystem doesn't have room for what's about
+/// to be written to it
+#[derive(Debug, Clone)]
+pub struct InsufficientSpace {
+    pub path: PathBuf,
+    pub needed: u64,
+    pub available: u64,
+}
+
+impl std::fmt::Display for InsufficientSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Not enough disk space in {}: {} needed, {} free",
+            self.path.display(),
+            crate::utils::format::format_size(self.needed),
+            crate::utils::format::format_size(self.available)
+        )
+    }
+}
+
+impl std::error::Error for InsufficientSpace {}
+
+/// Bytes an unprivileged user can still write on the filesystem holding
+/// `path`, or its closest existing parent when it hasn't been created yet
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a plain
+    // struct statvfs() fills in; nothing is kept past the call.
+    let stat = unsafe {
+        let mut stat: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(c_path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+        stat
+    };
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Fail with [`InsufficientSpace`] unless `needed` more bytes fit in `dir`.
+/// Filesystems that can't be queried are given the benefit of the doubt.
+fn check_space(dir: &Path, needed: u64) -> Result<()> {
+    match available_space(dir) {
+        Some(available) if available < needed => Err(InsufficientSpace {
+            path: dir.to_path_buf(),
+            needed,
+            available,
+        }
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// A chunk came back without the byte range it asked for, so the server
+/// can't be trusted with a split download after all
+#[derive(Debug, Clone)]
+struct RangeIgnored;
+
+impl std::fmt::Display for RangeIgnored {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Server ignored the range request")
+    }
+}
+
+impl std::error::Error for RangeIgnored {}
+
+/// The bytes a GET asks for, `first` through `last` or to the end, and
+/// the `If-Range` validator they're only wanted under
+#[derive(Debug, Clone, Copy)]
+struct ByteRange<'a> {
+    first: u64,
+    last: Option<u64>,
+    if_range: Option<&'a str>,
+}
+
+/// Where an interrupted download of `url` came from, kept next to its
+/// `.part` file so the rest can be requested later. Only downloads whose
+/// server sent a validator can be resumed: without one there is no telling
+/// whether the file changed in between.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl PartialDownload {
+    fn from_response(url: &str, response: &reqwest::Response) -> Self {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            url: url.to_string(),
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    /// The `If-Range` value: a strong ETag, otherwise the modification date.
+    /// Weak ETags aren't allowed there.
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+/// The file a download of `dest` streams into until it's complete
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Where the [`PartialDownload`] for `dest` is kept
+fn validator_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part.json");
+    dest.with_file_name(name)
+}
+
+/// The first byte a `206 Partial Content` response covers, from
+/// `Content-Range: bytes 1000-1999/2000`
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    let range = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?;
+    range.strip_prefix("bytes ")?.split('-').next()?.trim().parse().ok()
+}
+
+/// Feed what's already in `path` to `hasher`, so a resumed download still
+/// hashes the whole file
+fn hash_existing(path: &Path, hasher: &mut Sha256) -> std::io::Result<()> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Downloads images from remote URLs
+pub struct ImageDownloader {
+    client: reqwest::Client,
+    download_dir: PathBuf,
+    cancel: DownloadCancelHandle,
+    chunks: usize,
+    /// The base URL the mirrors stand in for, without a trailing slash
+    mirrored_base: Option<String>,
+    mirrors: Vec<String>,
+}
+
+impl ImageDownloader {
+    pub fn new(download_dir: PathBuf) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            download_dir,
+            cancel: DownloadCancelHandle::new(),
+            chunks: DEFAULT_DOWNLOAD_CHUNKS,
+            mirrored_base: None,
+            mirrors: Vec::new(),
+        }
+    }
+
+    /// Abort transfers when `handle` is cancelled.
+    pub fn with_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Fail with [`InsufficientSpace`] before anything is downloaded when
+    /// the download directory can't fit `needed` bytes. Transfers check
+    /// their own size again as they start.
+    pub fn ensure_space(&self, needed: u64) -> Result<()> {
+        check_space(&self.download_dir, needed)
+    }
+
+    /// Fall back to `mirrors`, in order, for files under `base_url` when
+    /// its host fails with a 5xx or can't be reached. Each mirror serves
+    /// the same tree; `base_url` itself is skipped if it's listed.
+    pub fn with_mirrors(mut self, base_url: &str, mirrors: &[String]) -> Self {
+        let base = base_url.trim_end_matches('/');
+        self.mirrored_base = Some(base.to_string());
+        self.mirrors = mirrors
+            .iter()
+            .map(|mirror| mirror.trim_end_matches('/').to_string())
+            .filter(|mirror| mirror != base)
+            .collect();
+        self
+    }
+
+    /// `url` followed by the same path on each mirror
+    fn sources(&self, url: &str) -> Vec<String> {
+        let mut sources = vec![url.to_string()];
+        if let Some(path) = self
+            .mirrored_base
+            .as_deref()
+            .and_then(|base| url.strip_prefix(base))
+        {
+            sources.extend(self.mirrors.iter().map(|mirror| format!("{}{}", mirror, path)));
+        }
+        sources
+    }
+
+    /// Run `fetch` on `url`, then on its mirrors while the failures are
+    /// ones another host might not have. The last error is returned when
+    /// every source fails.
+    async fn with_fallback<T, F, Fut>(&self, url: &str, fetch: F) -> Result<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut sources = self.sources(url).into_iter().peekable();
+        loop {
+            let source = sources.next().expect("sources always holds url");
+            match fetch(source).await {
+                Err(e) if is_transient(&e) && sources.peek().is_some() => {
+                    let next = sources.peek().map(String::as_str).unwrap_or_default();
+                    log::warn!("{:#}; trying mirror {}", e, next);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Split large downloads into `chunks` ranged requests fetched at once.
+    /// Each chunk takes a connection of its own, so no more than the
+    /// connection limit run together. 1 downloads in a single stream.
+    pub fn with_chunks(mut self, chunks: usize) -> Self {
+        self.chunks = chunks.max(1);
+        self
+    }
+
+    /// Download a file only if it doesn't already exist with the correct checksum.
+    /// If `expected_sha256` is provided and a local file matches, the download is skipped.
+    /// Returns the local path either way.
+    pub async fn download_if_needed(
+        &self,
+        url: &str,
+        filename: &str,
+        expected_sha256: Option<&str>,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<PathBuf> {
+        let dest_path = self.download_dir.join(filename);
+
+        if self.reuse_cached(&dest_path, expected_sha256, None, &on_progress).await? {
+            return Ok(dest_path);
+        }
+
+        self.download_resumable(url, &dest_path, on_progress).await
+    }
+
+    /// Download a file while its checksum is fetched by `checksum`.
+    ///
+    /// The two run concurrently, so a missing or broken checksum file aborts
+    /// the transfer as soon as it fails instead of after the whole image has
+    /// arrived. The image is hashed as it streams in and compared with the
+    /// expected digest before this returns; a mismatch deletes the file.
+    ///
+    /// A cached copy can only be judged once the checksum is known, so in
+    /// that case the checksum is awaited first.
+    ///
+    /// `algorithm` is the distro's hint for its digests; without one the
+    /// digest's length decides, which can't tell BLAKE3 from SHA256.
+    ///
+    /// Returns the local path and the expected digest, `None` when the
+    /// checksum source had no entry for this file.
+    pub async fn download_verified<F>(
+        &self,
+        url: &str,
+        filename: &str,
+        checksum: F,
+        algorithm: Option<ChecksumAlgorithm>,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<(PathBuf, Option<String>)>
+    where
+        F: Future<Output = Result<Option<String>>>,
+    {
+        let dest_path = self.download_dir.join(filename);
+
+        let (digest, expected) = if dest_path.exists() {
+            let expected = checksum.await?;
+            if self.reuse_cached(&dest_path, expected.as_deref(), algorithm, &on_progress).await? {
+                return Ok((dest_path, expected));
+            }
+            let (_, digest) = self.download_hashed(url, &dest_path, on_progress).await?;
+            (digest, expected)
+        } else {
+            match futures_util::future::try_join(
+                self.download_hashed(url, &dest_path, on_progress),
+                checksum,
+            )
+            .await
+            {
+                Ok(((_, digest), expected)) => (digest, expected),
+                Err(e) => {
+                    // Don't leave a truncated image behind to be trusted as a cached copy
+                    let _ = tokio::fs::remove_file(&dest_path).await;
+                    return Err(e);
+                }
+            }
+        };
+
+        if let Some(ref expected) = expected {
+            // The transfer was hashed with SHA256; other digests need a
+            // second pass over the file
+            let result = match ChecksumAlgorithm::resolve(algorithm, expected) {
+                ChecksumAlgorithm::Sha256 if digest.eq_ignore_ascii_case(expected.trim()) => Ok(()),
+                ChecksumAlgorithm::Sha256 => Err(ChecksumMismatch {
+                    file: filename.to_string(),
+                    algorithm: ChecksumAlgorithm::Sha256,
+                    expected: expected.trim().to_lowercase(),
+                    computed: digest,
+                }
+                .into()),
+                algorithm => {
+                    let path = dest_path.clone();
+                    let expected = expected.clone();
+                    tokio::task::spawn_blocking(move || {
+                        ChecksumVerifier::check(&path, &expected, Some(algorithm))
+                    })
+                    .await?
+                }
+            };
+            if let Err(e) = result {
+                let _ = tokio::fs::remove_file(&dest_path).await;
+                return Err(e);
+            }
+            log::info!("Checksum verified for {}", filename);
+        }
+
+        Ok((dest_path, expected))
+    }
+
+    /// Whether an existing file at `dest_path` can stand in for a download:
+    /// it must match `expected` when one is known, or just be non-empty
+    /// when none is.
+    async fn reuse_cached(
+        &self,
+        dest_path: &Path,
+        expected: Option<&str>,
+        algorithm: Option<ChecksumAlgorithm>,
+        on_progress: &Option<ProgressCallback>,
+    ) -> Result<bool> {
+        if !dest_path.exists() {
+            return Ok(false);
+        }
+        let name = dest_path.file_name().unwrap_or_default().to_string_lossy();
+
+        match expected {
+            Some(expected) => {
+                // Have a checksum — validate the cached file
+                if let Ok(true) = ChecksumVerifier::verify_with(dest_path, expected, algorithm) {
+                    log::info!("Skipping download of {} — cached copy matches checksum", name);
+                    if let Some(cb) = on_progress {
+                        let size = tokio::fs::metadata(dest_path).await?.len();
+                        cb(size, size);
+                    }
+                    return Ok(true);
+                }
+                log::info!("Cached {} has wrong checksum, re-downloading", name);
+            }
+            None => {
+                // No checksum — trust the cached file if it exists and is non-empty
+                if let Ok(meta) = tokio::fs::metadata(dest_path).await
+                    && meta.len() > 0
+                {
+                    log::info!("Skipping download of {} — cached copy exists", name);
+                    if let Some(cb) = on_progress {
+                        cb(meta.len(), meta.len());
+                    }
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Download a file into the download directory with progress reporting
+    pub async fn download(
+        &self,
+        url: &str,
+        filename: &str,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<PathBuf> {
+        self.download_resumable(url, &self.download_dir.join(filename), on_progress)
+            .await
+    }
+
+    /// Download `url` to `dest`, continuing an earlier attempt that was cut
+    /// off instead of starting over.
+    ///
+    /// Data streams into `dest` with `.part` appended and is renamed once
+    /// complete. When a network error interrupts the transfer the partial
+    /// file is kept, and the next call asks for the remaining bytes with a
+    /// `Range` request guarded by the server's ETag or Last-Modified. If
+    /// the server answers with anything but `206 Partial Content` for that
+    /// range, or the file changed since, it is downloaded again in full.
+    ///
+    /// With nothing to resume, large files from servers that accept byte
+    /// ranges are fetched in [`with_chunks`](Self::with_chunks) parallel
+    /// pieces instead. A split download that's cut off starts over.
+    pub async fn download_resumable(
+        &self,
+        url: &str,
+        dest: &Path,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<PathBuf> {
+        let (path, _) = self.download_hashed(url, dest, on_progress).await?;
+        Ok(path)
+    }
+
+    /// The bytes already downloaded into `dest`'s `.part` file from `url`
+    /// and where they came from, when they can be resumed. A partial file
+    /// that can't be is removed.
+    async fn partial_download(&self, url: &str, dest: &Path) -> Option<(u64, PartialDownload)> {
+        let part_path = partial_path(dest);
+        let resumable = async {
+            let size = tokio::fs::metadata(&part_path).await.ok()?.len();
+            let content = tokio::fs::read_to_string(validator_path(dest)).await.ok()?;
+            let partial: PartialDownload = serde_json::from_str(&content).ok()?;
+            (size > 0 && partial.url == url && partial.if_range().is_some()).then_some((size, partial))
+        }
+        .await;
+        if resumable.is_none() {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            let _ = tokio::fs::remove_file(validator_path(dest)).await;
+        }
+        resumable
+    }
+
+    /// Download a file from `url` or one of its mirrors, returning its path
+    /// and the SHA-256 of the whole file
+    async fn download_hashed(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        on_progress: Option<ProgressCallback>,
+    ) -> Result<(PathBuf, String)> {
+        let on_progress = &on_progress;
+        self.with_fallback(url, |source| async move {
+            self.download_from(&source, dest_path, on_progress).await
+        })
+        .await
+    }
+
+    /// [`download_hashed`](Self::download_hashed) from `url` alone
+    async fn download_from(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        on_progress: &Option<ProgressCallback>,
+    ) -> Result<(PathBuf, String)> {
+        if self.chunks > 1
+            && !validator_path(dest_path).exists()
+            && let Some((size, validator)) = self.ranged_size(url).await
+        {
+            match self
+                .download_chunked(url, dest_path, size, validator.as_deref(), on_progress)
+                .await
+            {
+                Ok(()) => {
+                    let path = dest_path.to_path_buf();
+                    let digest = tokio::task::spawn_blocking(move || {
+                        ChecksumVerifier::sha256(&path)
+                    })
+                    .await??;
+                    return Ok((dest_path.to_path_buf(), digest));
+                }
+                Err(e) if e.is::<RangeIgnored>() => {
+                    log::info!("{} for {}; downloading it in one stream", e, url);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        self.download_stream(url, dest_path, on_progress).await
+    }
+
+    /// The size of `url` and its `If-Range` validator, when the server says
+    /// it serves byte ranges and the file is large enough to split
+    async fn ranged_size(&self, url: &str) -> Option<(u64, Option<String>)> {
+        let _slot = self.unless_cancelled(CONNECTIONS.acquire(url)).await.ok()?;
+        let head = tokio::time::timeout(READ_TIMEOUT, self.client.head(url).send());
+        let response = match self.unless_cancelled(head).await.ok()? {
+            Ok(Ok(response)) if response.status().is_success() => response,
+            Ok(Ok(response)) => {
+                log::debug!("HEAD {} answered {}", url, response.status());
+                return None;
+            }
+            Ok(Err(e)) => {
+                log::debug!("HEAD {} failed: {}", url, e);
+                return None;
+            }
+            Err(_) => {
+                log::debug!("HEAD {} got no response", url);
+                return None;
+            }
+        };
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        let accepts_ranges = header(reqwest::header::ACCEPT_RANGES)
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("bytes"));
+        // Read the header itself; a HEAD response has no body to size
+        let size: u64 = header(reqwest::header::CONTENT_LENGTH)?.trim().parse().ok()?;
+        if !accepts_ranges || size < MIN_CHUNKED_SIZE {
+            return None;
+        }
+        let validator = PartialDownload::from_response(url, &response)
+            .if_range()
+            .map(str::to_string);
+        Some((size, validator))
+    }
+
+    /// Fetch the `size` bytes of `url` as parallel ranged requests, each
+    /// written at its offset of a preallocated file
+    async fn download_chunked(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        size: u64,
+        validator: Option<&str>,
+        on_progress: &Option<ProgressCallback>,
+    ) -> Result<()> {
+        let filename = dest_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        log::info!("Downloading {} from {} in {} chunks", filename, url, self.chunks);
+
+        if let Some(dir) = dest_path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .context("Failed to create download directory")?;
+        }
+        if self.cancel.is_cancelled() {
+            return Err(DownloadCancelled.into());
+        }
+
+        check_space(dest_path.parent().unwrap_or(dest_path), size)?;
+
+        // No validator file: a half-finished split download can't be resumed
+        let part_path = partial_path(dest_path);
+        let file = File::create(&part_path)
+            .await
+            .context("Failed to create destination file")?;
+        file.set_len(size).await.context("Failed to allocate the download")?;
+        drop(file);
+
+        let chunk_size = size.div_ceil(self.chunks as u64);
+        let downloaded = AtomicU64::new(0);
+        let mut ranges = Vec::new();
+        let mut first = 0;
+        while first < size {
+            let last = (first + chunk_size).min(size) - 1;
+            ranges.push((first, last));
+            first = last + 1;
+        }
+        let chunks = ranges.into_iter().map(|(first, last)| {
+            let range = ByteRange {
+                first,
+                last: Some(last),
+                if_range: validator,
+            };
+            self.download_range(url, &part_path, range, &downloaded, size, on_progress)
+        });
+
+        if let Err(e) = futures_util::future::try_join_all(chunks).await {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            if e.is::<DownloadCancelled>() {
+                log::info!("Download of {} cancelled", filename);
+            }
+            return Err(e);
+        }
+
+        tokio::fs::rename(&part_path, dest_path)
+            .await
+            .context("Failed to move the finished download into place")?;
+        log::info!("Download complete: {}", dest_path.display());
+        Ok(())
+    }
+
+    /// One chunk of [`download_chunked`](Self::download_chunked): stream
+    /// `range` into `part_path` at its offset, adding to the shared
+    /// `downloaded` count for progress
+    async fn download_range(
+        &self,
+        url: &str,
+        part_path: &Path,
+        range: ByteRange<'_>,
+        downloaded: &AtomicU64,
+        total_size: u64,
+        on_progress: &Option<ProgressCallback>,
+    ) -> Result<()> {
+        let _slot = self.unless_cancelled(CONNECTIONS.acquire(url)).await?;
+        let response = self.get_range(url, Some(range)).await?;
+        if response.status() == reqwest::StatusCode::OK
+            || (response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+                && content_range_start(&response) != Some(range.first))
+        {
+            return Err(RangeIgnored.into());
+        }
+        if !response.status().is_success() {
+            return Err(DownloadFailed::from_status(url, response.status()).into());
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(part_path)
+            .await
+            .context("Failed to open the download")?;
+        file.seek(std::io::SeekFrom::Start(range.first)).await?;
+
+        let mut stream = response.bytes_stream();
+        let mut written: u64 = 0;
+        while let Some(chunk) = self.next_chunk(&mut stream).await? {
+            let chunk = chunk.map_err(|reason| DownloadFailed {
+                url: url.to_string(),
+                reason: format!("{} after {} bytes at offset {}", reason, written, range.first),
+                transient: true,
+            })?;
+            self.unless_cancelled(RATE_LIMIT.take(chunk.len())).await?;
+            file.write_all(&chunk)
+                .await
+                .context("Error writing to file")?;
+            written += chunk.len() as u64;
+
+            let so_far = downloaded.fetch_add(chunk.len() as u64, Ordering::SeqCst) + chunk.len() as u64;
+            if let Some(callback) = on_progress {
+                callback(so_far, total_size);
+            }
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Stream a file in one request, returning its path and the SHA-256 of
+    /// the whole file, computed chunk by chunk as the data arrives.
+    async fn download_stream(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        on_progress: &Option<ProgressCallback>,
+    ) -> Result<(PathBuf, String)> {
+        let filename = dest_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        log::info!("Downloading {} from {}", filename, url);
+
+        // Create download directory if needed
+        if let Some(dir) = dest_path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .context("Failed to create download directory")?;
+        }
+
+        if self.cancel.is_cancelled() {
+            return Err(DownloadCancelled.into());
+        }
+
+        let part_path = partial_path(dest_path);
+        let mut resume = self.partial_download(url, dest_path).await;
+
+        // Start the download
+        let _slot = self.unless_cancelled(CONNECTIONS.acquire(url)).await?;
+        let response = loop {
+            let range = resume.as_ref().map(|(offset, partial)| ByteRange {
+                first: *offset,
+                last: None,
+                if_range: partial.if_range(),
+            });
+            let response = self.get_range(url, range).await?;
+            // The partial file is already as long as the file, or longer
+            if range.is_some() && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                log::info!("Server can't resume {}, downloading it again", filename);
+                resume = None;
+                continue;
+            }
+            break response;
+        };
+        if self.cancel.is_cancelled() {
+            return Err(DownloadCancelled.into());
+        }
+        if !response.status().is_success() {
+            return Err(DownloadFailed::from_status(url, response.status()).into());
+        }
+
+        let offset = match resume {
+            Some((offset, _))
+                if response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+                    && content_range_start(&response) == Some(offset) =>
+            {
+                log::info!("Resuming {} at {} bytes", filename, offset);
+                offset
+            }
+            Some(_) => {
+                log::info!(
+                    "Server ignored the range request or {} changed; downloading it again",
+                    filename
+                );
+                0
+            }
+            None => 0,
+        };
+
+        let total_size = response.content_length().map_or(0, |length| offset + length);
+        log::debug!("Download size: {} bytes", total_size);
+        if let Some(remaining) = response.content_length() {
+            check_space(dest_path.parent().unwrap_or(dest_path), remaining)?;
+        }
+
+        // Remember where this came from so a cut-off transfer can be resumed
+        let partial = PartialDownload::from_response(url, &response);
+        if partial.if_range().is_some() {
+            tokio::fs::write(validator_path(dest_path), serde_json::to_string(&partial)?)
+                .await
+                .context("Failed to record the download's origin")?;
+        } else {
+            let _ = tokio::fs::remove_file(validator_path(dest_path)).await;
+        }
+
+        // Open the partial file, continuing it when resuming
+        let mut hasher = Sha256::new();
+        let mut file = if offset > 0 {
+            hash_existing(&part_path, &mut hasher).context("Failed to read the partial download")?;
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&part_path)
+                .await
+                .context("Failed to open the partial download")?
+        } else {
+            File::create(&part_path)
+                .await
+                .context("Failed to create destination file")?
+        };
+
+        // Stream the download
+        let mut stream = response.bytes_stream();
+        let mut downloaded: u64 = offset;
+
+        loop {
+            let chunk = match self.next_chunk(&mut stream).await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    drop(file);
+                    discard_partial(dest_path, &filename).await;
+                    return Err(e);
+                }
+            };
+
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(reason) => {
+                    file.flush().await.ok();
+                    drop(file);
+                    log::info!("Keeping {} bytes of {} to resume from", downloaded, filename);
+                    return Err(DownloadFailed {
+                        url: url.to_string(),
+                        reason: format!("{} after {} bytes", reason, downloaded),
+                        transient: true,
+                    }
+                    .into());
+                }
+            };
+            if let Err(e) = self.unless_cancelled(RATE_LIMIT.take(chunk.len())).await {
+                drop(file);
+                discard_partial(dest_path, &filename).await;
+                return Err(e);
+            }
+            file.write_all(&chunk)
+                .await
+                .context("Error writing to file")?;
+            hasher.update(&chunk);
+
+            downloaded += chunk.len() as u64;
+
+            if let Some(callback) = on_progress {
+                callback(downloaded, total_size);
+            }
+        }
+
+        file.flush().await?;
+        drop(file);
+        tokio::fs::rename(&part_path, dest_path)
+            .await
+            .context("Failed to move the finished download into place")?;
+        let _ = tokio::fs::remove_file(validator_path(dest_path)).await;
+        log::info!("Download complete: {}", dest_path.display());
+
+        Ok((dest_path.to_path_buf(), hex::encode(hasher.finalize())))
+    }
+
+    /// Wait for `wait` unless the download is cancelled first: a free
+    /// connection, the rate limit or a server's `Retry-After`
+    async fn unless_cancelled<T>(&self, wait: impl Future<Output = T>) -> Result<T> {
+        tokio::select! {
+            biased;
+            () = self.cancel.cancelled() => Err(DownloadCancelled.into()),
+            output = wait => Ok(output),
+        }
+    }
+
+    /// The next piece of a response body, `None` once it has all arrived.
+    ///
+    /// Fails with [`DownloadCancelled`] as soon as the handle is cancelled,
+    /// rather than after the next piece turns up. A read error, or nothing
+    /// for [`READ_TIMEOUT`], is returned as the inner error's reason.
+    async fn next_chunk<S, B>(&self, stream: &mut S) -> Result<Option<Result<B, String>>>
+    where
+        S: futures_util::Stream<Item = reqwest::Result<B>> + Unpin,
+    {
+        let next = tokio::select! {
+            biased;
+            () = self.cancel.cancelled() => return Err(DownloadCancelled.into()),
+            next = tokio::time::timeout(READ_TIMEOUT, stream.next()) => next,
+        };
+        Ok(match next {
+            Ok(Some(chunk)) => Some(chunk.map_err(|e| e.to_string())),
+            Ok(None) => None,
+            Err(_) => Some(Err(format!("no data for {}s", READ_TIMEOUT.as_secs()))),
+        })
+    }
+
+    /// Send a GET, waiting out 429 and 503 responses as their `Retry-After`
+    /// asks. After [`MAX_THROTTLE_RETRIES`] waits the last response is
+    /// returned for the caller to report.
+    ///
+    /// Fails with [`DownloadCancelled`] as soon as the handle is cancelled,
+    /// and with [`DownloadFailed`] when the request can't be sent or no
+    /// response comes within [`READ_TIMEOUT`].
+    async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        self.get_range(url, None).await
+    }
+
+    /// [`get`](Self::get), asking for only `range` when one is given
+    async fn get_range(&self, url: &str, range: Option<ByteRange<'_>>) -> Result<reqwest::Response> {
+        let mut retries = 0;
+        loop {
+            let mut request = self.client.get(url);
+            if let Some(range) = range {
+                let last = range.last.map(|last| last.to_string()).unwrap_or_default();
+                request = request.header(reqwest::header::RANGE, format!("bytes={}-{}", range.first, last));
+                if let Some(validator) = range.if_range {
+                    request = request.header(reqwest::header::IF_RANGE, validator);
+                }
+            }
+            let sent = tokio::select! {
+                biased;
+                () = self.cancel.cancelled() => return Err(DownloadCancelled.into()),
+                sent = tokio::time::timeout(READ_TIMEOUT, request.send()) => sent,
+            };
+            let response = match sent {
+                Ok(Ok(response)) => response,
+                Ok(Err(e)) => return Err(DownloadFailed::from_request(url, &e).into()),
+                Err(_) => {
+                    return Err(DownloadFailed {
+                        url: url.to_string(),
+                        reason: format!("no response for {}s", READ_TIMEOUT.as_secs()),
+                        transient: true,
+                    }
+                    .into());
+                }
+            };
+            let status = response.status();
+            let throttled = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::SERVICE_UNAVAILABLE;
+            if !throttled || retries == MAX_THROTTLE_RETRIES || self.cancel.is_cancelled() {
+                return Ok(response);
+            }
+
+            retries += 1;
+            let wait = retry_after(&response);
+            log::warn!(
+                "Throttled by {} ({}); retrying in {}s ({}/{})",
+                response.url().host_str().unwrap_or(url),
+                status,
+                wait.as_secs(),
+                retries,
+                MAX_THROTTLE_RETRIES
+            );
+            drop(response);
+            self.unless_cancelled(tokio::time::sleep(wait)).await?;
+        }
+    }
+
+    /// Download checksum file and parse it, from a mirror if need be
+    pub async fn download_checksums(&self, url: &str) -> Result<std::collections::HashMap<String, String>> {
+        Ok(ChecksumVerifier::parse_sums(&self.download_sums(url).await?))
+    }
+
+    /// Download a checksum file as it is, from a mirror if need be, for
+    /// [`ChecksumVerifier::from_sums_file`] to look a file up in
+    pub async fn download_sums(&self, url: &str) -> Result<String> {
+        self.with_fallback(url, |source| async move { self.fetch_sums(&source).await })
+            .await
+    }
+
+    /// Sums files are a few hundred bytes, so fetching one takes no
+    /// connection slot: it runs beside the image it checks instead of
+    /// behind it, and a bad checksum URL fails before the image is in.
+    async fn fetch_sums(&self, url: &str) -> Result<String> {
+        log::debug!("Downloading checksums from {}", url);
+
+        let response = self
+            .get(url)
+            .await
+            .context("Failed to download checksums")?;
+
+        if response.status().is_server_error() {
+            return Err(anyhow::Error::new(DownloadFailed::from_status(url, response.status()))
+                .context("Failed to download checksums"));
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Checksum server returned status {} for {}", response.status(), url);
+        }
+
+        Ok(response.text().await?)
+    }
+}
+
+/// Remove what a cancelled stream wrote to `dest_path`. A cancelled
+/// download starts over rather than resuming.
+async fn discard_partial(dest_path: &Path, filename: &str) {
+    let _ = tokio::fs::remove_file(partial_path(dest_path)).await;
+    let _ = tokio::fs::remove_file(validator_path(dest_path)).await;
+    log::info!("Download of {} cancelled", filename);
+}
+
+impl Default for ImageDownloader {
+    fn default() -> Self {
+        let download_dir = dirs::download_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join("sidestep");
+        Self::new(download_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A host that accepts connections and never answers them
+    fn silent_server() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let _held: Vec<_> = listener.incoming().collect();
+        });
+        format!("http://{}/image.img", address)
+    }
+
+    #[tokio::test]
+    async fn cancel_ends_a_request_waiting_on_the_server() {
+        let url = silent_server();
+        let cancel = DownloadCancelHandle::new();
+        let downloader =
+            ImageDownloader::new(std::env::temp_dir()).with_cancel_handle(cancel.clone());
+
+        let started = Instant::now();
+        let cancel_soon = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        };
+        let (result, ()) = tokio::join!(downloader.get(&url), cancel_soon);
+
+        let error = result.unwrap_err();
+        assert!(error.is::<DownloadCancelled>(), "{:#}", error);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    /// A host that answers every request with a 503 asking for a long wait
+    fn throttling_server() -> String {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.read(&mut [0u8; 1024]);
+                let _ = stream.write_all(
+                    b"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 300\r\nContent-Length: 0\r\n\r\n",
+                );
+            }
+        });
+        format!("http://{}/image.img", address)
+    }
+
+    #[tokio::test]
+    async fn cancel_ends_a_retry_after_wait() {
+        let url = throttling_server();
+        let cancel = DownloadCancelHandle::new();
+        let downloader =
+            ImageDownloader::new(std::env::temp_dir()).with_cancel_handle(cancel.clone());
+
+        let started = Instant::now();
+        let cancel_soon = async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            cancel.cancel();
+        };
+        let (result, ()) = tokio::join!(downloader.get(&url), cancel_soon);
+
+        let error = result.unwrap_err();
+        assert!(error.is::<DownloadCancelled>(), "{:#}", error);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    /// A host serving a large image that trickles out a byte at a time in
+    /// ranges, with no checksum file beside it
+    fn slow_image_server() -> String {
+        use std::io::{BufRead, BufReader, Write};
+        const SIZE: u64 = 64 * 1024 * 1024;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut stream = stream;
+                    loop {
+                        let mut request = String::new();
+                        let mut line = String::new();
+                        while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                            request.push_str(&line);
+                            line.clear();
+                        }
+                        if request.is_empty() {
+                            return;
+                        }
+                        if request.starts_with("HEAD") {
+                            let head = format!(
+                                "HTTP/1.1 200 OK\r\nAccept-Ranges: bytes\r\n\
+                                 Content-Length: {}\r\n\r\n",
+                                SIZE
+                            );
+                            let _ = stream.write_all(head.as_bytes());
+                        } else if request.contains(".sha256") {
+                            let _ = stream
+                                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+                        } else {
+                            let range = request
+                                .lines()
+                                .find_map(|line| {
+                                    line.to_ascii_lowercase()
+                                        .strip_prefix("range: bytes=")
+                                        .map(str::to_string)
+                                })
+                                .unwrap_or_default();
+                            let (first, last) = range.trim().split_once('-').unwrap_or(("0", ""));
+                            let first: u64 = first.parse().unwrap_or(0);
+                            let last: u64 = last.parse().unwrap_or(SIZE - 1);
+                            let head = format!(
+                                "HTTP/1.1 206 Partial Content\r\n\
+                                 Content-Range: bytes {}-{}/{}\r\n\
+                                 Content-Length: {}\r\n\r\n",
+                                first,
+                                last,
+                                SIZE,
+                                last - first + 1
+                            );
+                            let _ = stream.write_all(head.as_bytes());
+                            while stream.write_all(b"x").is_ok() {
+                                std::thread::sleep(Duration::from_millis(100));
+                            }
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        format!("http://{}/image.img", address)
+    }
+
+    #[tokio::test]
+    async fn a_missing_checksum_file_stops_the_download_early() {
+        let url = slow_image_server();
+        let dir = std::env::temp_dir().join(format!("sidestep-downloader-{}", std::process::id()));
+        let downloader = ImageDownloader::new(dir.clone()).with_chunks(DEFAULT_DOWNLOAD_CHUNKS);
+        // Looked up once the image has taken every connection it will get
+        let checksum = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            downloader
+                .download_sums(&format!("{}.sha256", url))
+                .await
+                .map(|_| None)
+        };
+
+        let started = Instant::now();
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            downloader.download_verified(&url, "image.img", checksum, None, None),
+        )
+        .await
+        .expect("the download waited for the image before checking the checksum");
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn cancelled_resolves_for_a_handle_cancelled_earlier() {
+        let cancel = DownloadCancelHandle::new();
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_secs(1), cancel.cancelled())
+            .await
+            .expect("an already cancelled handle doesn't wait");
+    }
+}
@@ -1,15 +1,19 @@
 // Droidian installer backend
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::flashing::checksum::ChecksumVerifier;
-use crate::flashing::downloader::ImageDownloader;
-use crate::flashing::progress::InstallProgress;
+use crate::flashing::cache;
+use crate::flashing::checksum::{ChecksumAlgorithm, ChecksumVerifier};
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::github_release::GithubReleaseSource;
+use crate::flashing::mode_wait;
+use crate::flashing::progress::{self, InstallProgress};
 use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
 use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
-use std::time::Duration;
 
 struct DroidianPartition {
     image_name: &'static str,
@@ -32,26 +36,65 @@ const SARGO_PARTITIONS: &[DroidianPartition] = &[
 /// Orchestrates Droidian installation
 pub struct DroidianInstaller {
     serial: String,
-    release_url: String,
+    source: GithubReleaseSource,
     artifact_pattern: String,
     download_dir: PathBuf,
+    download_only: bool,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
+    checksum_algorithm: Option<ChecksumAlgorithm>,
 }
 
 impl DroidianInstaller {
-    pub fn new(serial: String, release_url: String, artifact_pattern: String) -> Self {
-        let download_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("sidestep")
-            .join("droidian");
+    /// Starts from Android or straight from the bootloader
+    pub const REQUIRED_MODE: StartMode = StartMode::AdbOrFastboot;
+
+    pub fn new(serial: String, source: GithubReleaseSource, artifact_pattern: String) -> Self {
+        let download_dir = cache::installer_dir("droidian");
 
         Self {
             serial,
-            release_url,
+            source,
             artifact_pattern,
             download_dir,
+            download_only: false,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
+            checksum_algorithm: None,
         }
     }
 
+    /// Where downloaded images are cached
+    pub fn download_dir(&self) -> &Path {
+        &self.download_dir
+    }
+
+    /// Stop after downloading and verifying images, leaving them in the cache.
+    pub fn download_only(mut self, enabled: bool) -> Self {
+        self.download_only = enabled;
+        self
+    }
+
+    /// Hash function the distro publishes its digests with, for when
+    /// their length can't tell (BLAKE3 and SHA256 are both 64 hex digits)
+    pub fn checksum_algorithm(mut self, algorithm: Option<ChecksumAlgorithm>) -> Self {
+        self.checksum_algorithm = algorithm;
+        self
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
     /// Spawn the installer on a background thread, returning immediately.
     /// Progress is reported via the returned mpsc::Receiver.
     pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
@@ -66,7 +109,7 @@ impl DroidianInstaller {
             rt.block_on(async {
                 if let Err(e) = self.run(&sender).await {
                     log::error!("Droidian installation failed: {:#}", e);
-                    let _ = sender.send(InstallProgress::Error(format!("{:#}", e)));
+                    let _ = sender.send(InstallProgress::from_error(&e));
                 }
             });
         });
@@ -76,72 +119,65 @@ impl DroidianInstaller {
 
     /// Main installation flow (runs on background thread)
     async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
-        let downloader = ImageDownloader::new(self.download_dir.clone());
-        let adb = Adb::new();
-        let fastboot = Fastboot::new();
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone());
+        let adb = Adb::new().with_cancel(self.cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
 
-        // ── Step 1: Query GitHub API for latest release ──
+        // ── Step 1: Query GitHub API for the newest matching release ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Fetching release info...".into(),
         ));
         let (zip_url, zip_name, checksums_url) = self.fetch_release_info().await?;
         log::info!("Found release artifact: {}", zip_name);
 
-        // ── Step 2: Download SHA256SUMS ──
-        let _ = sender.send(InstallProgress::StatusChanged(
-            "Downloading checksums...".into(),
-        ));
-        let expected_hash = self
-            .download_and_parse_checksums(&downloader, &checksums_url, &zip_name)
-            .await?;
-
-        // ── Step 3: Download ZIP ──
+        // ── Step 2: Download ZIP, fetching SHA256SUMS alongside ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Downloading Droidian image...".into(),
         ));
-        let sender_clone = sender.clone();
-        let zip_path = downloader
-            .download_if_needed(
+        let (zip_path, expected_hash) = downloader
+            .download_verified(
                 &zip_url,
                 &zip_name,
-                expected_hash.as_deref(),
-                Some(Box::new(move |downloaded, total| {
-                    let _ = sender_clone.send(InstallProgress::DownloadProgress {
-                        downloaded,
-                        total,
-                        file_name: "Droidian image".into(),
-                    });
-                })),
+                self.download_and_parse_checksums(&downloader, &checksums_url, &zip_name),
+                self.checksum_algorithm
+                    .or_else(|| ChecksumAlgorithm::from_file_name(&checksums_url)),
+                progress::report_download(sender, "Droidian image", 1, 1),
             )
             .await
             .context("Failed to download Droidian ZIP")?;
 
-        // ── Step 4: Verify ZIP checksum ──
-        if let Some(ref hash) = expected_hash {
-            let _ = sender.send(InstallProgress::VerifyProgress {
-                verified: 0,
-                total: 1,
-                file_name: zip_name.clone(),
-            });
-            let ok = ChecksumVerifier::verify(&zip_path, hash)?;
-            if !ok {
-                anyhow::bail!("Checksum mismatch for {}", zip_name);
+        // ── Step 3: Checksum ──
+        // The digest was checked while downloading; all that's left is
+        // asking about a file the release didn't list.
+        match expected_hash {
+            Some(_) => {
+                let _ = sender.send(InstallProgress::VerifyProgress {
+                    verified: 1,
+                    total: 1,
+                    file_name: zip_name.clone(),
+                });
+            }
+            None => {
+                log::warn!("No checksum published for {}", zip_name);
+                progress::confirm_unverified(sender, &zip_name)?;
             }
-            let _ = sender.send(InstallProgress::VerifyProgress {
-                verified: 1,
-                total: 1,
-                file_name: zip_name.clone(),
-            });
         }
 
-        // ── Step 5: Extract ZIP ──
+        // Download-only mode stops here, leaving the verified images cached
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
+        // ── Step 4: Extract ZIP ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Extracting...".into(),
         ));
         let extract_dir = self.download_dir.join("extracted");
         self.extract_zip(&zip_path, &extract_dir)?;
 
-        // ── Step 6: Reboot to bootloader ──
+        // ── Step 5: Reboot to bootloader ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Rebooting to bootloader...".into(),
         ));
@@ -152,13 +188,13 @@ impl DroidianInstaller {
             );
         }
 
-        // ── Step 7: Wait for fastboot device ──
+        // ── Step 6: Wait for fastboot device ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Waiting for device in fastboot mode...".into(),
         ));
-        self.wait_for_fastboot(&fastboot).await?;
+        mode_wait::wait_for_fastboot(&fastboot, &self.serial, sender).await?;
 
-        // ── Step 8: Flash partitions ──
+        // ── Step 7: Flash partitions ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Flashing partitions...".into(),
         ));
@@ -192,7 +228,7 @@ impl DroidianInstaller {
             }
         }
 
-        // ── Step 9: Reboot ──
+        // ── Step 8: Reboot ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Rebooting device...".into(),
         ));
@@ -206,72 +242,17 @@ impl DroidianInstaller {
     // Sub-steps
     // ────────────────────────────────────────────────────────────────
 
-    /// Query GitHub API for the latest release, returning (zip_url, zip_name, checksums_url).
+    /// Find the newest release asset, returning (zip_url, zip_name, checksums_url).
     async fn fetch_release_info(&self) -> Result<(String, String, String)> {
-        let client = reqwest::Client::builder()
-            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
-            .build()?;
-
-        let resp = client
-            .get(&self.release_url)
-            .send()
-            .await
-            .context("Failed to query GitHub releases API")?;
+        let (release, zip_asset) = self.source.find_asset(&self.artifact_pattern).await?;
 
-        if !resp.status().is_success() {
-            anyhow::bail!(
-                "GitHub API returned status {} for {}",
-                resp.status(),
-                self.release_url
-            );
-        }
+        let checksums_url = release
+            .checksums_asset()
+            .context("No SHA256SUMS asset in release")?
+            .browser_download_url
+            .clone();
 
-        let text = resp.text().await.context("Failed to read GitHub release response")?;
-        let body: serde_json::Value =
-            serde_json::from_str(&text).context("Failed to parse GitHub release JSON")?;
-
-        let assets = body["assets"]
-            .as_array()
-            .context("No 'assets' array in release JSON")?;
-
-        // Find the ZIP matching our artifact pattern
-        let zip_asset = assets
-            .iter()
-            .find(|a| {
-                a["name"]
-                    .as_str()
-                    .is_some_and(|n: &str| n.contains(&self.artifact_pattern))
-            })
-            .context(format!(
-                "No asset matching '{}' in release",
-                self.artifact_pattern
-            ))?;
-
-        let zip_url = zip_asset["browser_download_url"]
-            .as_str()
-            .context("No browser_download_url for ZIP asset")?
-            .to_string();
-        let zip_name = zip_asset["name"]
-            .as_str()
-            .context("No name for ZIP asset")?
-            .to_string();
-
-        // Find the SHA256SUMS asset
-        let checksums_asset = assets
-            .iter()
-            .find(|a| {
-                a["name"]
-                    .as_str()
-                    .is_some_and(|n: &str| n.contains("SHA256SUMS"))
-            })
-            .context("No SHA256SUMS asset in release")?;
-
-        let checksums_url = checksums_asset["browser_download_url"]
-            .as_str()
-            .context("No browser_download_url for SHA256SUMS")?
-            .to_string();
-
-        Ok((zip_url, zip_name, checksums_url))
+        Ok((zip_asset.browser_download_url, zip_asset.name, checksums_url))
     }
 
     /// Download SHA256SUMS and extract the hash for the given zip filename.
@@ -281,8 +262,8 @@ impl DroidianInstaller {
         checksums_url: &str,
         zip_name: &str,
     ) -> Result<Option<String>> {
-        let checksums = downloader.download_checksums(checksums_url).await?;
-        Ok(checksums.get(zip_name).cloned())
+        let sums = downloader.download_sums(checksums_url).await?;
+        ChecksumVerifier::from_sums_file(&sums, zip_name).map(Some)
     }
 
     /// Extract a ZIP archive to the given directory.
@@ -300,35 +281,9 @@ impl DroidianInstaller {
         let mut archive = zip::ZipArchive::new(file)
             .context("Failed to read ZIP archive")?;
 
-        for i in 0..archive.len() {
-            let mut entry = archive.by_index(i)?;
-            let out_path = extract_dir.join(entry.name());
-
-            if entry.is_dir() {
-                std::fs::create_dir_all(&out_path)?;
-            } else {
-                if let Some(parent) = out_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
-                let mut outfile = std::fs::File::create(&out_path)?;
-                std::io::copy(&mut entry, &mut outfile)?;
-            }
-        }
+        crate::flashing::archive::unpack_zip(&mut archive, extract_dir)?;
 
         log::info!("Extracted ZIP to {}", extract_dir.display());
         Ok(())
     }
-
-    /// Poll fastboot devices until our device appears.
-    async fn wait_for_fastboot(&self, fastboot: &Fastboot) -> Result<()> {
-        for _ in 0..60 {
-            if let Ok(devices) = fastboot.devices().await {
-                if devices.iter().any(|d| d.serial == self.serial) {
-                    return Ok(());
-                }
-            }
-            tokio::time::sleep(Duration::from_secs(2)).await;
-        }
-        anyhow::bail!("Timed out waiting for device in fastboot mode")
-    }
 }
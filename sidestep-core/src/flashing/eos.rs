@@ -0,0 +1,482 @@
+// /e/OS installer backend
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::cache;
+use crate::flashing::checksum::ChecksumAlgorithm;
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::progress::{self, InstallProgress};
+use crate::flashing::recovery::RecoveryFlash;
+use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
+use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/// Orchestrates /e/OS installation via recovery sideload.
+///
+/// Flow:
+///   1. Scrape image index to find latest recovery + ROM for the selected channel
+///   2. Download recovery image + ROM zip with progress
+///   3. Verify SHA256 checksums
+///   4. Reboot to bootloader → flash recovery (boot on A/B devices)
+///   5. Reboot to recovery → wait for recovery
+///   6. Prompt user: Factory reset → Format data
+///   7. Prompt user: Apply update → Apply from ADB
+///   8. adb sideload ROM zip
+///   9. Prompt user: Reboot system now
+pub struct EosInstaller {
+    serial: String,
+    base_url: String,
+    codename: String,
+    channel: String,
+    download_dir: PathBuf,
+    download_only: bool,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
+    checksum_algorithm: Option<ChecksumAlgorithm>,
+    mirrors: Vec<String>,
+}
+
+impl EosInstaller {
+    /// Starts from Android or straight from the bootloader
+    pub const REQUIRED_MODE: StartMode = StartMode::AdbOrFastboot;
+
+    pub fn new(
+        serial: String,
+        base_url: String,
+        codename: String,
+        channel: String,
+    ) -> Self {
+        let download_dir = cache::installer_dir("eos");
+
+        Self {
+            serial,
+            base_url,
+            codename,
+            channel,
+            download_dir,
+            download_only: false,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
+            checksum_algorithm: None,
+            mirrors: Vec::new(),
+        }
+    }
+
+    /// Where downloaded images are cached
+    pub fn download_dir(&self) -> &Path {
+        &self.download_dir
+    }
+
+    /// Stop after downloading and verifying images, leaving them in the cache.
+    pub fn download_only(mut self, enabled: bool) -> Self {
+        self.download_only = enabled;
+        self
+    }
+
+    /// Hash function the distro publishes its digests with, for when
+    /// their length can't tell (BLAKE3 and SHA256 are both 64 hex digits)
+    pub fn checksum_algorithm(mut self, algorithm: Option<ChecksumAlgorithm>) -> Self {
+        self.checksum_algorithm = algorithm;
+        self
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Hosts serving the same tree as `base_url`, tried in order when it
+    /// fails while downloading
+    pub fn mirrors(mut self, mirrors: Vec<String>) -> Self {
+        self.mirrors = mirrors;
+        self
+    }
+
+    pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(async {
+                if let Err(e) = self.run(&sender).await {
+                    log::error!("/e/OS installation failed: {:#}", e);
+                    let _ = sender.send(InstallProgress::from_error(&e));
+                }
+            });
+        });
+
+        receiver
+    }
+
+    async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone())
+            .with_mirrors(&self.base_url, &self.mirrors);
+        let adb = Adb::new().with_cancel(self.cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
+
+        // ── Step 1: Scrape image index ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Fetching latest /e/OS build...".into(),
+        ));
+        let (recovery_url, recovery_name, rom_url, rom_name, sha256_url) =
+            self.find_latest_build().await?;
+
+        log::info!("/e/OS recovery: {}", recovery_name);
+        log::info!("/e/OS ROM: {}", rom_name);
+
+        // ── Step 2: Download recovery image ──
+        // Recovery images usually have a .sha256sum alongside, but not always
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Downloading /e/OS recovery...".into(),
+        ));
+        let recovery_checksum_url = format!("{}.sha256sum", recovery_url);
+        let recovery_checksum = async {
+            match self.fetch_sha256(&recovery_checksum_url).await {
+                Ok(hash) => Ok(Some(hash)),
+                Err(e) => {
+                    log::warn!("No checksum for recovery {}: {:#}", recovery_name, e);
+                    Ok(None)
+                }
+            }
+        };
+        let (recovery_path, recovery_sha256) = downloader
+            .download_verified(
+                &recovery_url,
+                &recovery_name,
+                recovery_checksum,
+                self.checksum_algorithm
+                    .or_else(|| ChecksumAlgorithm::from_file_name(&recovery_checksum_url)),
+                progress::report_download(sender, "Recovery image", 1, 2),
+            )
+            .await
+            .context("Failed to download /e/OS recovery")?;
+        if recovery_sha256.is_none() {
+            progress::confirm_unverified(sender, &recovery_name)?;
+        }
+
+        // ── Step 3: Download ROM zip ──
+        // The ROM checksum is required; fetching it alongside means a missing
+        // one stops the transfer right away.
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Downloading /e/OS ROM...".into(),
+        ));
+        let (rom_path, _) = downloader
+            .download_verified(
+                &rom_url,
+                &rom_name,
+                async { self.fetch_sha256(&sha256_url).await.map(Some) },
+                self.checksum_algorithm
+                    .or_else(|| ChecksumAlgorithm::from_file_name(&sha256_url)),
+                progress::report_download(sender, "/e/OS ROM", 2, 2),
+            )
+            .await
+            .context("Failed to download /e/OS ROM")?;
+
+        // ── Step 4: Checksums ──
+        // Digests were checked while the files streamed in
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: 1,
+            total: 1,
+            file_name: "Checksum verified".into(),
+        });
+
+        // Download-only mode stops here, leaving the verified images cached
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
+        // ── Steps 5–7: Flash the recovery and boot into it ──
+        let recovery = RecoveryFlash::new(&self.serial, &adb, &fastboot, sender);
+        recovery
+            .install(&recovery_path, "/e/OS recovery", (1, 2))
+            .await?;
+
+        // ── Step 8: Factory reset + Apply from ADB ──
+        // Tell the user everything they need to do, then wait for sideload
+        // mode — that way they have as long as they need.
+        recovery
+            .wait_for_sideload("On your phone:\n1. Select \"Factory reset\" → \"Format data/factory reset\" → confirm\n2. Go back, select \"Apply update\" → \"Apply from ADB\"")
+            .await?;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        // ── Step 9: Sideload ROM ──
+        let _ = sender.send(InstallProgress::FlashProgress {
+            current: 2,
+            total: 2,
+            description: "Sideloading /e/OS ROM...".into(),
+        });
+        let file_name = rom_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        adb.sideload(&self.serial, &rom_path, |percent| {
+            let _ = sender.send(InstallProgress::SideloadProgress {
+                percent,
+                file_name: file_name.clone(),
+            });
+        })
+            .await
+            .context("Failed to sideload /e/OS ROM")?;
+
+        // ── Done ──
+        let _ = sender.send(InstallProgress::WaitingForUserAction(
+            "Sideload complete! On your phone: Select \"Reboot system now\"".into(),
+        ));
+        let _ = sender.send(InstallProgress::Complete);
+        Ok(())
+    }
+
+    /// Scrape the /e/OS image index page to find the latest build files
+    /// for the selected channel (e.g. "a15", "a14", "t").
+    ///
+    /// Fails before anything is downloaded when the codename has no
+    /// directory on the server or the channel has no builds in it.
+    async fn find_latest_build(&self) -> Result<(String, String, String, String, String)> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .build()?;
+
+        let resp = client
+            .get(&self.base_url)
+            .send()
+            .await
+            .context("Failed to fetch /e/OS image index")?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            anyhow::bail!(
+                "/e/OS does not publish builds for '{}' ({} not found)",
+                self.codename,
+                self.base_url
+            );
+        }
+        if !resp.status().is_success() {
+            anyhow::bail!(
+                "/e/OS image server returned status {} for {}",
+                resp.status(),
+                self.base_url
+            );
+        }
+
+        let html = resp.text().await.context("Failed to read image index")?;
+        let (recovery_name, rom_name) = select_latest_build(&index_links(&html), &self.codename, &self.channel)?;
+
+        let base = self.base_url.trim_end_matches('/');
+        let rom_url = format!("{}/{}", base, rom_name);
+        let recovery_url = format!("{}/{}", base, recovery_name);
+        let sha256_url = format!("{}.sha256sum", rom_url);
+
+        Ok((recovery_url, recovery_name, rom_url, rom_name, sha256_url))
+    }
+
+    /// Fetch and parse a .sha256sum file (format: "hash  filename" or just "hash")
+    async fn fetch_sha256(&self, url: &str) -> Result<String> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .build()?;
+
+        let resp = client
+            .get(url)
+            .send()
+            .await
+            .context("Failed to download SHA256 checksum")?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("Checksum server returned status {} for {}", resp.status(), url);
+        }
+
+        let text = resp.text().await?;
+        let hash = text
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Empty SHA256 checksum file"))?
+            .to_string();
+
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("Malformed SHA256 checksum in {}", url);
+        }
+
+        Ok(hash)
+    }
+}
+
+/// Every href target in an HTML directory listing.
+///
+/// The /e/OS server emits unquoted hrefs (e.g. `<a href=filename>`) and the
+/// HTML is minified (many hrefs per line), so we scan for every "href=" and
+/// take the value delimited by a quote or `>`.
+pub(crate) fn index_links(html: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut remaining = html;
+    while let Some(pos) = remaining.find("href=") {
+        remaining = &remaining[pos + 5..];
+        let (link, advance) = if let Some(rest) = remaining.strip_prefix('"') {
+            match rest.find('"') {
+                Some(end) => (&rest[..end], 1 + end + 1),
+                None => continue,
+            }
+        } else if let Some(rest) = remaining.strip_prefix('\'') {
+            match rest.find('\'') {
+                Some(end) => (&rest[..end], 1 + end + 1),
+                None => continue,
+            }
+        } else {
+            match remaining.find('>') {
+                Some(end) => (&remaining[..end], end + 1),
+                None => continue,
+            }
+        };
+        links.push(link.to_string());
+        remaining = &remaining[advance..];
+    }
+    links
+}
+
+/// Pick the newest recovery and ROM for `codename` on `channel` from an
+/// index listing, returning `(recovery, rom)` file names.
+///
+/// Names look like `e-2.6.3-a14-20241220455735-community-FP4.zip`. The
+/// build date orders them, since version strings such as "2.10" vs "2.9"
+/// don't sort lexicographically.
+fn select_latest_build(files: &[String], codename: &str, channel: &str) -> Result<(String, String)> {
+    let codename_pattern = format!("-community-{}.", codename);
+    let channel_pattern = format!("-{}-", channel);
+
+    let roms: Vec<&String> = files
+        .iter()
+        .filter(|f| {
+            f.starts_with("e-")
+                && f.ends_with(".zip")
+                && !f.contains("recovery")
+                && f.contains(&codename_pattern)
+        })
+        .collect();
+
+    if roms.is_empty() {
+        anyhow::bail!("/e/OS has no builds for '{}' on this server", codename);
+    }
+
+    let latest = |candidates: Vec<&String>| -> Option<String> {
+        candidates
+            .into_iter()
+            .max_by_key(|name| (build_date(name, &channel_pattern), name.to_string()))
+            .cloned()
+    };
+
+    let rom_name = latest(roms.iter().copied().filter(|f| f.contains(&channel_pattern)).collect())
+        .ok_or_else(|| {
+            let mut channels: Vec<&str> = roms
+                .iter()
+                .filter_map(|f| f.strip_prefix("e-")?.split('-').nth(1))
+                .collect();
+            channels.sort();
+            channels.dedup();
+            anyhow::anyhow!(
+                "/e/OS has no '{}' build for '{}' (available: {})",
+                channel,
+                codename,
+                channels.join(", ")
+            )
+        })?;
+
+    let recovery_name = latest(
+        files
+            .iter()
+            .filter(|f| {
+                f.starts_with("recovery-")
+                    && !f.ends_with(".sha256sum")
+                    && !f.ends_with(".md5sum")
+                    && f.contains(&channel_pattern)
+                    && f.contains(&codename_pattern)
+            })
+            .collect(),
+    )
+    .ok_or_else(|| {
+        anyhow::anyhow!(
+            "/e/OS publishes a '{}' ROM for '{}' but no matching recovery",
+            channel,
+            codename
+        )
+    })?;
+
+    Ok((recovery_name, rom_name))
+}
+
+/// The digits right after the channel, e.g. "20241220455735".
+fn build_date(name: &str, channel_pattern: &str) -> String {
+    name.split_once(channel_pattern)
+        .map(|(_, rest)| rest.chars().take_while(|c| c.is_ascii_digit()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// https://images.ecloud.global/community/FP4/ as served, minified and
+    /// with unquoted hrefs
+    const FP4_INDEX: &str = "<html><head><title>Index of /community/FP4/</title></head><body><h1>Index of /community/FP4/</h1><hr><pre><a href=../>../</a>\n<a href=e-2.5-t-20241108446589-community-FP4.zip>e-2.5-t-20241108446589-community-FP4.zip</a> 08-Nov-2024 10:12 1395652408\n<a href=e-2.5-t-20241108446589-community-FP4.zip.sha256sum>e-2.5-t-20241108446589-community-FP4.zip.sha256sum</a> 08-Nov-2024 10:12 107\n<a href=e-2.6.3-a14-20241220455735-community-FP4.zip>e-2.6.3-a14-20241220455735-community-FP4.zip</a> 20-Dec-2024 18:40 1498724512\n<a href=e-2.6.3-a14-20241220455735-community-FP4.zip.sha256sum>e-2.6.3-a14-20241220455735-community-FP4.zip.sha256sum</a> 20-Dec-2024 18:40 111\n<a href=e-2.10-a14-20250512493811-community-FP4.zip>e-2.10-a14-20250512493811-community-FP4.zip</a> 12-May-2025 09:03 1512003840\n<a href=e-2.10-a14-20250512493811-community-FP4.zip.sha256sum>e-2.10-a14-20250512493811-community-FP4.zip.sha256sum</a> 12-May-2025 09:03 110\n<a href=recovery-e-2.5-t-20241108446589-community-FP4.img>recovery-e-2.5-t-20241108446589-community-FP4.img</a> 08-Nov-2024 10:12 100663296\n<a href=recovery-e-2.6.3-a14-20241220455735-community-FP4.img>recovery-e-2.6.3-a14-20241220455735-community-FP4.img</a> 20-Dec-2024 18:40 100663296\n<a href=recovery-e-2.10-a14-20250512493811-community-FP4.img>recovery-e-2.10-a14-20250512493811-community-FP4.img</a> 12-May-2025 09:03 100663296\n<a href=recovery-e-2.10-a14-20250512493811-community-FP4.img.sha256sum>recovery-e-2.10-a14-20250512493811-community-FP4.img.sha256sum</a> 12-May-2025 09:03 117\n</pre><hr></body></html>";
+
+    #[test]
+    fn unquoted_links_are_read_from_the_listing() {
+        let links = index_links(FP4_INDEX);
+        assert_eq!(links.first().map(String::as_str), Some("../"));
+        assert_eq!(links.len(), 11);
+        assert!(links.contains(&"e-2.5-t-20241108446589-community-FP4.zip".to_string()));
+    }
+
+    #[test]
+    fn latest_build_is_picked_by_date_not_version() {
+        let (recovery, rom) = select_latest_build(&index_links(FP4_INDEX), "FP4", "a14").unwrap();
+        assert_eq!(rom, "e-2.10-a14-20250512493811-community-FP4.zip");
+        assert_eq!(
+            recovery,
+            "recovery-e-2.10-a14-20250512493811-community-FP4.img"
+        );
+    }
+
+    #[test]
+    fn older_channel_still_resolves() {
+        let (recovery, rom) = select_latest_build(&index_links(FP4_INDEX), "FP4", "t").unwrap();
+        assert_eq!(rom, "e-2.5-t-20241108446589-community-FP4.zip");
+        assert_eq!(
+            recovery,
+            "recovery-e-2.5-t-20241108446589-community-FP4.img"
+        );
+    }
+
+    #[test]
+    fn unpublished_channel_lists_the_ones_there_are() {
+        let error = select_latest_build(&index_links(FP4_INDEX), "FP4", "a15").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "/e/OS has no 'a15' build for 'FP4' (available: a14, t)"
+        );
+    }
+
+    #[test]
+    fn unknown_codename_is_refused() {
+        let error = select_latest_build(&index_links(FP4_INDEX), "FP5", "a14").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "/e/OS has no builds for 'FP5' on this server"
+        );
+    }
+}
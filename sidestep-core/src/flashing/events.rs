@@ -0,0 +1,138 @@
+// Installer event bus
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::InstallProgress;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// How an installation run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallOutcome {
+    /// The distro was written to the device
+    Installed,
+    /// The images were only downloaded, into this directory
+    Downloaded(PathBuf),
+    /// The device was booted once without flashing
+    BootedTemporarily,
+    /// The user stopped the run, before anything was written or part way
+    /// through flashing
+    Cancelled,
+    /// The run failed; carries the message shown to the user
+    Failed(String),
+}
+
+impl InstallOutcome {
+    /// The outcome a progress message announces, if it is a terminal one.
+    pub fn from_progress(progress: &InstallProgress) -> Option<Self> {
+        match progress {
+            InstallProgress::Complete => Some(Self::Installed),
+            InstallProgress::DownloadOnlyComplete(dir) => Some(Self::Downloaded(dir.clone())),
+            InstallProgress::BootedTemporarily => Some(Self::BootedTemporarily),
+            InstallProgress::DownloadCancelled | InstallProgress::Cancelled => Some(Self::Cancelled),
+            InstallProgress::DeviceDisconnected(operation) => {
+                Some(Self::Failed(format!("Device disconnected while {}", operation)))
+            }
+            InstallProgress::InsufficientSpace { needed, available, .. } => Some(Self::Failed(format!(
+                "Not enough disk space: {} needed, {} free",
+                crate::utils::format::format_size(*needed),
+                crate::utils::format::format_size(*available)
+            ))),
+            InstallProgress::ChannelUnavailable { channel, .. } => {
+                Some(Self::Failed(format!("{} is no longer available", channel)))
+            }
+            InstallProgress::ImageCorrupted(msg)
+            | InstallProgress::DownloadFailed(msg)
+            | InstallProgress::Error(msg) => Some(Self::Failed(msg.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// What subscribers of an [`EventBus`] receive
+#[derive(Debug, Clone)]
+pub enum InstallEvent {
+    Progress(InstallProgress),
+    /// Always the last event of a run
+    Finished(InstallOutcome),
+}
+
+/// Fans one installer's messages out to any number of consumers.
+///
+/// Installers keep sending [`InstallProgress`] on their own channel;
+/// [`EventBus::forward`] relays it to every subscriber, so the GTK page, a
+/// log writer or a JSON emitter each read their own receiver without the
+/// installer knowing about them. Prompts that carry a reply sender reach
+/// every subscriber, and the first answer wins.
+#[derive(Debug, Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<InstallEvent>>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new receiver that gets every event published from now on
+    pub fn subscribe(&self) -> Receiver<InstallEvent> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Send `event` to every subscriber, forgetting those that hung up.
+    pub fn publish(&self, event: InstallEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /// Relay an installer's progress channel on a background thread until a
+    /// terminal message, then publish the outcome.
+    ///
+    /// An installer that goes away without one is reported as failed.
+    pub fn forward(&self, installer: Receiver<InstallProgress>) {
+        let bus = self.clone();
+        std::thread::spawn(move || {
+            for progress in installer.iter() {
+                let outcome = InstallOutcome::from_progress(&progress);
+                bus.publish(InstallEvent::Progress(progress));
+                if let Some(outcome) = outcome {
+                    bus.publish(InstallEvent::Finished(outcome));
+                    return;
+                }
+            }
+            bus.publish(InstallEvent::Finished(InstallOutcome::Failed(
+                "The installer stopped without reporting a result".to_string(),
+            )));
+        });
+    }
+
+    /// Write a log line for every status change and the final outcome.
+    pub fn log_events(&self) {
+        let events = self.subscribe();
+        std::thread::spawn(move || {
+            for event in events.iter() {
+                match event {
+                    InstallEvent::Progress(InstallProgress::StatusChanged(status)) => {
+                        log::info!("Install: {}", status);
+                    }
+                    InstallEvent::Progress(InstallProgress::FlashProgress {
+                        current,
+                        total,
+                        description,
+                    }) => {
+                        log::info!("Install step {}/{}: {}", current, total, description);
+                    }
+                    InstallEvent::Finished(outcome) => {
+                        log::info!("Install finished: {:?}", outcome);
+                        return;
+                    }
+                    InstallEvent::Progress(_) => {}
+                }
+            }
+        });
+    }
+}
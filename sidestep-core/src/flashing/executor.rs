@@ -0,0 +1,927 @@
+// Flash executor
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::checksum::ChecksumVerifier;
+use crate::flashing::decompressor::{Compression, Decompressor, ProgressCallback, unpack_cancellable};
+use crate::flashing::flash_state::FlashState;
+use crate::hardware::{
+    CancelToken, DeviceDisconnected, Fastboot, FlashStep, InstallCancelled, RebootWindow,
+};
+use crate::models::{Device, Distro, PartitionImage};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Callback for flash progress
+pub type FlashProgressCallback = Box<dyn Fn(&str, usize, usize) + Send + Sync>;
+
+/// Callback for progress within one partition's flash, read from fastboot's
+/// output: partition name and how far it has got
+pub type FlashStepCallback = Box<dyn Fn(&str, FlashStep) + Send + Sync>;
+
+/// Callback for a transfer that has gone quiet: partition name and silent time
+pub type StallCallback = Box<dyn Fn(&str, Duration) + Send + Sync>;
+
+/// Callback for a flash about to be tried again: partition name, the
+/// attempt that's starting (2 for the first retry), attempts allowed in
+/// total and why the last one failed
+pub type RetryCallback = Box<dyn Fn(&str, u32, u32, &str) + Send + Sync>;
+
+/// Callback for a compressed image unpacked to disk before its flash:
+/// partition name, then compressed bytes worked through and their total
+pub type UnpackCallback = Box<dyn Fn(&str, u64, u64) + Send + Sync>;
+
+/// An [`UnpackCallback`] shared with the unpacking thread, which outlives
+/// a borrow of the executor
+type SharedUnpackCallback = Arc<dyn Fn(&str, u64, u64) + Send + Sync>;
+
+/// How long fastboot may go without output before a flash counts as stalled
+pub const DEFAULT_STALL_THRESHOLD: Duration = Duration::from_secs(90);
+
+/// How many times a partition flash that failed transiently is tried again
+pub const DEFAULT_FLASH_RETRIES: u32 = 2;
+
+/// Wait before the first retry, doubled for each one after it
+const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// fastboot and bootloader messages, lower-cased, for failures a second
+/// attempt usually gets past: the USB link hiccuped or a reply came late
+const TRANSIENT_FLASH_ERRORS: &[&str] = &[
+    "timeout",
+    "timed out",
+    "write to device failed",
+    "read from device failed",
+    "status read failed",
+    "libusb_error_io",
+    "libusb_error_pipe",
+    "usb reset",
+    "protocol error",
+    "data transfer failure",
+];
+
+/// Refusals that come back the same however often the flash is repeated.
+/// Checked first, so "remote: 'not allowed in locked state'" isn't retried
+/// for also mentioning a transfer.
+const PERMANENT_FLASH_ERRORS: &[&str] = &[
+    "locked state",
+    "not allowed",
+    "flashing is not allowed",
+    "unknown partition",
+    "does not exist",
+    "no such partition",
+    "too large",
+    "not enough space",
+    "invalid sparse",
+    "signature",
+];
+
+/// One command sent while writing a partition
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WriteStep<'a> {
+    /// Unpack a compressed image to a file of its own, so one that's
+    /// corrupt or in a format without a decoder fails before anything is
+    /// erased
+    Prepare,
+    /// Refuse an image bigger than the partition while it's still intact
+    CheckFits(&'a str),
+    Erase(&'a str),
+    Flash(&'a str),
+}
+
+/// The commands that write one partition to each of `targets`, in order.
+/// A slot's erase comes right before its own flash rather than all erases
+/// up front, so a failure leaves at most the slot being written empty.
+/// Every slot is checked for room before the first erase. Without an
+/// erase there's nothing to protect, and compressed images are streamed to
+/// the device as they unpack, with the flash checking the size itself.
+fn write_steps(erase_first: bool, targets: &[String]) -> Vec<WriteStep<'_>> {
+    let mut steps = Vec::new();
+    if erase_first {
+        steps.push(WriteStep::Prepare);
+        steps.extend(targets.iter().map(|target| WriteStep::CheckFits(target)));
+    }
+    for target in targets {
+        if erase_first {
+            steps.push(WriteStep::Erase(target));
+        }
+        steps.push(WriteStep::Flash(target));
+    }
+    steps
+}
+
+/// The file a partition is written from. One unpacked for the write is
+/// removed once the write is over, however it ended.
+struct PreparedImage {
+    path: PathBuf,
+    unpacked: bool,
+}
+
+impl Drop for PreparedImage {
+    fn drop(&mut self) {
+        if self.unpacked {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// The device side of writing a partition, so the order of its commands
+/// can be checked without a device
+trait PartitionWriter {
+    /// Get `image` ready to write, unpacking a compressed one
+    async fn prepare(&self, image: &Path) -> Result<PreparedImage>;
+    async fn check_fits(&self, target: &str, image: &Path) -> Result<()>;
+    async fn erase(&self, target: &str) -> Result<()>;
+    async fn flash(&self, target: &str, image: &Path) -> Result<()>;
+}
+
+/// Run `steps` for `image` in order, stopping at the first that fails.
+/// Flashes after a [`WriteStep::Prepare`] write the prepared file.
+async fn run_write_steps<W: PartitionWriter>(
+    writer: &W,
+    steps: &[WriteStep<'_>],
+    image: &Path,
+) -> Result<()> {
+    let mut prepared: Option<PreparedImage> = None;
+    let mut erased = false;
+    for step in steps {
+        match *step {
+            WriteStep::Prepare => prepared = Some(writer.prepare(image).await?),
+            WriteStep::CheckFits(target) => {
+                let path = prepared.as_ref().map_or(image, |p| p.path.as_path());
+                writer.check_fits(target, path).await?;
+            }
+            WriteStep::Erase(target) => {
+                writer.erase(target).await.context("Failed to erase partition")?;
+                erased = true;
+            }
+            WriteStep::Flash(target) => {
+                let path = prepared.as_ref().map_or(image, |p| p.path.as_path());
+                log::info!("Flashing {} to partition {}", path.display(), target);
+                let result = writer.flash(target, path).await;
+                if result.is_err() && erased {
+                    log::error!(
+                        "Partition {} was erased but not rewritten; retry it as a whole",
+                        target
+                    );
+                }
+                result.context("Failed to flash partition")?;
+                erased = false;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether a failed flash is worth trying again
+fn is_transient(error: &anyhow::Error) -> bool {
+    if error.downcast_ref::<InstallCancelled>().is_some()
+        || error.downcast_ref::<DeviceMismatch>().is_some()
+    {
+        return false;
+    }
+    let message = format!("{:#}", error).to_lowercase();
+    if PERMANENT_FLASH_ERRORS.iter().any(|m| message.contains(m)) {
+        return false;
+    }
+    error.downcast_ref::<DeviceDisconnected>().is_some()
+        || TRANSIENT_FLASH_ERRORS.iter().any(|m| message.contains(m))
+}
+
+/// How long a reboot between the bootloader and fastbootd may take
+const MODE_SWITCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The device about to be flashed isn't the one the user confirmed
+#[derive(Debug, Clone)]
+pub struct DeviceMismatch(pub String);
+
+impl std::fmt::Display for DeviceMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Wrong device connected: {}. Nothing was flashed.", self.0)
+    }
+}
+
+impl std::error::Error for DeviceMismatch {}
+
+/// Identity of the device the user confirmed on the safety page
+struct ConfirmedDevice {
+    serial: String,
+    /// The codename and its aliases, any of which the bootloader may report
+    codenames: Vec<String>,
+}
+
+/// Executes the flashing sequence
+pub struct FlashExecutor {
+    fastboot: Fastboot,
+    stall_threshold: Duration,
+    on_stall: Option<StallCallback>,
+    on_step: Option<FlashStepCallback>,
+    retries: u32,
+    on_retry: Option<RetryCallback>,
+    on_unpack: Option<SharedUnpackCallback>,
+    all_slots: bool,
+    confirmed: Option<ConfirmedDevice>,
+    cancel: CancelToken,
+}
+
+impl FlashExecutor {
+    pub fn new() -> Self {
+        Self {
+            fastboot: Fastboot::new(),
+            stall_threshold: DEFAULT_STALL_THRESHOLD,
+            on_stall: None,
+            on_step: None,
+            retries: DEFAULT_FLASH_RETRIES,
+            on_retry: None,
+            on_unpack: None,
+            all_slots: false,
+            confirmed: None,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Override how long a flash may go without progress before warning.
+    pub fn with_stall_threshold(mut self, threshold: Duration) -> Self {
+        self.stall_threshold = threshold;
+        self
+    }
+
+    /// Called when a flash looks stalled. Non-fatal: the flash keeps running.
+    pub fn on_stall(mut self, callback: StallCallback) -> Self {
+        self.on_stall = Some(callback);
+        self
+    }
+
+    /// Called as fastboot reports sending and writing each chunk of an image
+    pub fn on_step(mut self, callback: FlashStepCallback) -> Self {
+        self.on_step = Some(callback);
+        self
+    }
+
+    /// Try a partition flash that failed transiently up to `retries` more
+    /// times. Zero fails on the first error.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Called before each retry of a partition flash
+    pub fn on_retry(mut self, callback: RetryCallback) -> Self {
+        self.on_retry = Some(callback);
+        self
+    }
+
+    /// Called as a compressed image that can't be streamed to the device
+    /// is unpacked next to itself
+    pub fn on_unpack(mut self, callback: UnpackCallback) -> Self {
+        self.on_unpack = Some(Arc::from(callback));
+        self
+    }
+
+    /// On A/B devices, write slotted partitions to both slots instead of
+    /// only the active one. Devices without A/B are flashed as before.
+    pub fn with_all_slots(mut self, all_slots: bool) -> Self {
+        self.all_slots = all_slots;
+        self
+    }
+
+    /// Stop between partitions, and kill the partition being written, once
+    /// `cancel` is set. The flash then fails with [`InstallCancelled`].
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.fastboot = self.fastboot.with_cancel(cancel.clone());
+        self.cancel = cancel;
+        self
+    }
+
+    /// Refuse to write anything unless `serial` is still connected and
+    /// reports `device`'s codename, so swapping phones after confirming
+    /// can't flash the wrong one.
+    pub fn confirmed_device(mut self, serial: &str, device: &Device) -> Self {
+        let mut codenames = vec![device.codename.clone()];
+        codenames.extend(device.aliases.iter().cloned());
+        self.confirmed = Some(ConfirmedDevice {
+            serial: serial.to_string(),
+            codenames,
+        });
+        self
+    }
+
+    /// Flash all partitions for a distro
+    ///
+    /// Every image is located before the first command is sent, so a missing
+    /// file aborts the install while the device is still untouched.
+    ///
+    /// Logical partitions are written from fastbootd; the device is moved
+    /// there on the first one and back to the bootloader once all partitions
+    /// are done.
+    ///
+    /// Each written partition is recorded in the device's [`FlashState`]
+    /// with its image's checksum, so running the same flash again after an
+    /// interruption skips the partitions it already finished. Images are
+    /// only hashed up front when there's such a state to compare them with;
+    /// otherwise each is hashed while it's being written.
+    pub async fn flash_distro(
+        &self,
+        serial: &str,
+        distro: &Distro,
+        images_dir: &Path,
+        on_progress: Option<FlashProgressCallback>,
+    ) -> Result<()> {
+        let total = distro.partitions.len();
+        let images = distro
+            .partitions
+            .iter()
+            .map(|partition| resolve_image(partition, images_dir))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.verify_confirmed_device(serial).await?;
+
+        let mut state = FlashState::load(serial);
+        let checksums = if state.is_empty() {
+            None
+        } else {
+            Some(hash_images(images.clone()).await?)
+        };
+        let skip = match checksums {
+            Some(ref checksums) => {
+                let planned: Vec<(&str, &str)> = distro
+                    .partitions
+                    .iter()
+                    .zip(checksums)
+                    .map(|(partition, sha256)| (partition.partition.as_str(), sha256.as_str()))
+                    .collect();
+                state.resume(&planned).unwrap_or_else(|e| {
+                    log::warn!("Couldn't read what was flashed before, flashing everything: {:#}", e);
+                    0
+                })
+            }
+            None => 0,
+        };
+
+        let mut entered_fastbootd = false;
+        for (index, (partition, image_path)) in distro.partitions.iter().zip(&images).enumerate() {
+            if let Some(ref callback) = on_progress {
+                callback(&partition.partition, index + 1, total);
+            }
+            if index < skip {
+                log::info!("{} was already flashed from this image, skipping", partition.partition);
+                continue;
+            }
+            if self.cancel.is_cancelled() {
+                log::info!("Install cancelled before {}", partition.partition);
+                return Err(InstallCancelled.into());
+            }
+
+            let name = self.partition_name(serial, partition).await;
+            if self.needs_fastbootd(serial, &name).await {
+                self.enter_fastbootd(serial).await?;
+                entered_fastbootd = true;
+            }
+            // Hashed while the partition is written, unless resuming did it
+            let hashing = {
+                let image = image_path.clone();
+                let known = checksums.as_ref().map(|checksums| checksums[index].clone());
+                tokio::task::spawn_blocking(move || {
+                    known.map_or_else(|| ChecksumVerifier::sha256(&image), Ok)
+                })
+            };
+            self.write_partition(serial, partition, &name, image_path).await?;
+            let sha256 = hashing.await.map_err(anyhow::Error::from).and_then(|hashed| hashed);
+            if let Err(e) = sha256.and_then(|sha256| state.mark_flashed(&partition.partition, &sha256)) {
+                log::warn!("Couldn't record that {} was flashed: {:#}", partition.partition, e);
+            }
+        }
+
+        if entered_fastbootd {
+            self.leave_fastbootd(serial).await?;
+        }
+        if let Err(e) = state.finish() {
+            log::warn!("Couldn't clear the flash state: {:#}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Flash a single partition
+    pub async fn flash_partition(
+        &self,
+        serial: &str,
+        partition: &PartitionImage,
+        images_dir: &Path,
+    ) -> Result<()> {
+        self.cancel.check()?;
+        let image_path = resolve_image(partition, images_dir)?;
+        self.verify_confirmed_device(serial).await?;
+        let name = self.partition_name(serial, partition).await;
+        if !self.needs_fastbootd(serial, &name).await {
+            return self.write_partition(serial, partition, &name, &image_path).await;
+        }
+
+        self.enter_fastbootd(serial).await?;
+        self.write_partition(serial, partition, &name, &image_path).await?;
+        self.leave_fastbootd(serial).await
+    }
+
+    /// Check the device against the confirmed one right before the first
+    /// write. A bootloader that doesn't report its product only gets the
+    /// serial checked.
+    async fn verify_confirmed_device(&self, serial: &str) -> Result<()> {
+        let Some(ref confirmed) = self.confirmed else {
+            return Ok(());
+        };
+
+        if serial != confirmed.serial {
+            return Err(DeviceMismatch(format!(
+                "confirmed {} but about to flash {}",
+                confirmed.serial, serial
+            ))
+            .into());
+        }
+
+        let connected = self
+            .fastboot
+            .devices()
+            .await
+            .context("Failed to list fastboot devices")?;
+        if !connected.iter().any(|d| d.serial == serial) {
+            return Err(DeviceMismatch(format!("{} is no longer connected", serial)).into());
+        }
+
+        let product = self.fastboot.getvar(serial, "product").await.unwrap_or_default();
+        let product = product.trim();
+        if product.is_empty() {
+            log::warn!("{} doesn't report its product; only the serial was checked", serial);
+        } else if !confirmed.codenames.iter().any(|c| c.eq_ignore_ascii_case(product)) {
+            return Err(DeviceMismatch(format!(
+                "expected {} but the device reports {}",
+                confirmed.codenames[0], product
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// The name to flash `partition` under: the first of its primary name
+    /// and aliases the bootloader reports. Without aliases nothing is
+    /// probed, and when none is reported the primary name is used.
+    async fn partition_name(&self, serial: &str, partition: &PartitionImage) -> String {
+        if partition.aliases.is_empty() {
+            return partition.partition.clone();
+        }
+
+        for name in std::iter::once(&partition.partition).chain(&partition.aliases) {
+            if self.fastboot.has_partition(serial, name).await.unwrap_or(false) {
+                if *name != partition.partition {
+                    log::info!(
+                        "{} has no {} partition; flashing its alias {}",
+                        serial,
+                        partition.partition,
+                        name
+                    );
+                }
+                return name.clone();
+            }
+        }
+
+        log::warn!(
+            "{} reports none of {}, {}; trying {}",
+            serial,
+            partition.partition,
+            partition.aliases.join(", "),
+            partition.partition
+        );
+        partition.partition.clone()
+    }
+
+    /// Whether `partition` is logical while the device is still in the
+    /// bootloader. Bootloaders that don't report `is-logical` are taken to
+    /// have no logical partitions.
+    async fn needs_fastbootd(&self, serial: &str, partition: &str) -> bool {
+        self.fastboot.is_logical(serial, partition).await.unwrap_or(false)
+            && !self.fastboot.is_fastbootd(serial).await.unwrap_or(false)
+    }
+
+    async fn enter_fastbootd(&self, serial: &str) -> Result<()> {
+        let _window = RebootWindow::open(serial, "fastbootd");
+        self.fastboot.reboot_fastbootd(serial, MODE_SWITCH_TIMEOUT).await
+    }
+
+    async fn leave_fastbootd(&self, serial: &str) -> Result<()> {
+        let _window = RebootWindow::open(serial, "the bootloader");
+        self.fastboot
+            .reboot_bootloader(serial)
+            .await
+            .context("Failed to return to the bootloader")?;
+        self.fastboot
+            .wait_for_mode(serial, false, MODE_SWITCH_TIMEOUT)
+            .await
+    }
+
+    /// Erase (when `erase_first` is set) and flash one partition under `name`.
+    ///
+    /// The two are a single unit: once the erase has succeeded the partition
+    /// is empty until the flash completes, so anything retrying a failed
+    /// partition must call this again rather than resuming at the flash.
+    async fn write_partition(
+        &self,
+        serial: &str,
+        partition: &PartitionImage,
+        name: &str,
+        image_path: &Path,
+    ) -> Result<()> {
+        let targets = if self.all_slots {
+            self.fastboot.slot_names(serial, name).await.unwrap_or_else(|e| {
+                log::warn!("Couldn't read the slots of {}, flashing the active one: {:#}", name, e);
+                vec![name.to_string()]
+            })
+        } else {
+            vec![name.to_string()]
+        };
+
+        let writer = DeviceWriter {
+            executor: self,
+            serial,
+            name,
+        };
+        let steps = write_steps(partition.erase_first, &targets);
+        run_write_steps(&writer, &steps, image_path).await
+    }
+
+    /// [`flash_image`](Self::flash_image), tried again with a growing pause
+    /// while it fails in a way [`is_transient`] expects to clear up
+    async fn flash_with_retries(&self, serial: &str, name: &str, image_path: &Path) -> Result<()> {
+        let attempts = self.retries + 1;
+        let mut attempt = 1;
+        loop {
+            let error = match self.flash_image(serial, name, image_path).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            if attempt >= attempts || !is_transient(&error) {
+                return Err(error);
+            }
+
+            let delay = RETRY_BACKOFF * 2u32.pow(attempt - 1);
+            let reason = format!("{:#}", error);
+            attempt += 1;
+            log::warn!(
+                "Flashing {} failed ({}); attempt {} of {} in {}s",
+                name,
+                reason,
+                attempt,
+                attempts,
+                delay.as_secs()
+            );
+            if let Some(ref callback) = self.on_retry {
+                callback(name, attempt, attempts, &reason);
+            }
+            tokio::select! {
+                () = tokio::time::sleep(delay) => {}
+                () = self.cancel.cancelled() => return Err(InstallCancelled.into()),
+            }
+        }
+    }
+
+    /// Flash `image_path` to `name`. A compressed image is unpacked straight
+    /// into the bootloader when the transport can take it in one piece,
+    /// and to a temporary file next to it otherwise.
+    async fn flash_image(&self, serial: &str, name: &str, image_path: &Path) -> Result<()> {
+        if Compression::detect(image_path)? == Compression::None {
+            return self.flash_file(serial, name, image_path).await;
+        }
+
+        let stream = Decompressor::stream(image_path)?;
+        if let Some(size) = stream.size() {
+            let label = image_path.display().to_string();
+            if self
+                .fastboot
+                .flash_stream(serial, name, &label, stream.into_reader(), size)
+                .await?
+            {
+                return Ok(());
+            }
+        }
+
+        let unpacked = self.unpack(name, image_path).await?;
+        self.flash_file(serial, name, &unpacked.path).await
+    }
+
+    /// `image_path` as a file ready to flash to `name`: as it is when it
+    /// isn't compressed, and unpacked next to itself otherwise
+    async fn unpack(&self, name: &str, image_path: &Path) -> Result<PreparedImage> {
+        if Compression::detect(image_path)? == Compression::None {
+            return Ok(PreparedImage {
+                path: image_path.to_path_buf(),
+                unpacked: false,
+            });
+        }
+
+        log::info!("Unpacking {} before flashing it", image_path.display());
+        let compressed = image_path.to_path_buf();
+        let on_progress = self.on_unpack.clone().map(|callback| {
+            let name = name.to_string();
+            Box::new(move |done, total| callback(&name, done, total)) as ProgressCallback
+        });
+        let path = unpack_cancellable(&self.cancel, move |cancel| {
+            Decompressor::decompress(&compressed, None, on_progress, Some(cancel))
+        })
+        .await?;
+        Ok(PreparedImage {
+            path,
+            unpacked: true,
+        })
+    }
+
+    /// Flash an image file, watching for a transfer that stops making progress
+    async fn flash_file(&self, serial: &str, name: &str, image_path: &Path) -> Result<()> {
+        self.fastboot
+            .flash_watched(
+                serial,
+                name,
+                image_path,
+                self.stall_threshold,
+                |silent| {
+                    if let Some(ref callback) = self.on_stall {
+                        callback(name, silent);
+                    }
+                },
+                |step| {
+                    if let Some(ref callback) = self.on_step {
+                        callback(name, step);
+                    }
+                },
+            )
+            .await
+    }
+
+    /// Reboot the device after flashing
+    pub async fn reboot(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting device");
+        self.fastboot.reboot(serial).await
+    }
+}
+
+impl Default for FlashExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The SHA-256 of each of `images`, worked out on a blocking thread
+async fn hash_images(images: Vec<PathBuf>) -> Result<Vec<String>> {
+    tokio::task::spawn_blocking(move || images.iter().map(|image| ChecksumVerifier::sha256(image)).collect())
+        .await?
+}
+
+/// [`PartitionWriter`] for one partition of the device at `serial`
+struct DeviceWriter<'a> {
+    executor: &'a FlashExecutor,
+    serial: &'a str,
+    /// The name the partition is flashed under, for progress
+    name: &'a str,
+}
+
+impl PartitionWriter for DeviceWriter<'_> {
+    async fn prepare(&self, image: &Path) -> Result<PreparedImage> {
+        self.executor.unpack(self.name, image).await
+    }
+
+    async fn check_fits(&self, target: &str, image: &Path) -> Result<()> {
+        self.executor
+            .fastboot
+            .check_image_fits(self.serial, target, image)
+            .await
+    }
+
+    async fn erase(&self, target: &str) -> Result<()> {
+        self.executor.fastboot.erase(self.serial, target).await
+    }
+
+    async fn flash(&self, target: &str, image: &Path) -> Result<()> {
+        self.executor.flash_with_retries(self.serial, target, image).await
+    }
+}
+
+/// Find the image for a partition on disk, preferring an already
+/// decompressed copy, and make sure it isn't empty.
+fn resolve_image(partition: &PartitionImage, images_dir: &Path) -> Result<PathBuf> {
+    let image_path = images_dir.join(&partition.image);
+    let unpacked = (Compression::from_name(&partition.image) != Compression::None)
+        .then(|| image_path.with_extension(""))
+        .filter(|unpacked| unpacked.exists());
+
+    let actual_path = match unpacked {
+        Some(unpacked) => unpacked,
+        None if image_path.exists() => image_path,
+        None => anyhow::bail!("Image not found: {}", image_path.display()),
+    };
+
+    let size = std::fs::metadata(&actual_path)
+        .with_context(|| format!("Failed to read {}", actual_path.display()))?
+        .len();
+    if size == 0 {
+        anyhow::bail!("Image is empty: {}", actual_path.display());
+    }
+
+    Ok(actual_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn erase_comes_right_before_each_slots_flash() {
+        let targets = slots(&["boot_a", "boot_b"]);
+        assert_eq!(
+            write_steps(true, &targets),
+            vec![
+                WriteStep::Prepare,
+                WriteStep::CheckFits("boot_a"),
+                WriteStep::CheckFits("boot_b"),
+                WriteStep::Erase("boot_a"),
+                WriteStep::Flash("boot_a"),
+                WriteStep::Erase("boot_b"),
+                WriteStep::Flash("boot_b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_slot_is_checked_for_room_before_the_first_erase() {
+        let targets = slots(&["boot_a", "boot_b"]);
+        let steps = write_steps(true, &targets);
+        let first_erase = steps
+            .iter()
+            .position(|step| matches!(step, WriteStep::Erase(_)))
+            .unwrap();
+        for target in ["boot_a", "boot_b"] {
+            let check = steps
+                .iter()
+                .position(|step| *step == WriteStep::CheckFits(target))
+                .unwrap();
+            assert!(check < first_erase);
+        }
+    }
+
+    #[test]
+    fn no_erase_unless_asked() {
+        let targets = slots(&["system"]);
+        assert_eq!(
+            write_steps(false, &targets),
+            vec![WriteStep::Flash("system")]
+        );
+    }
+
+    #[test]
+    fn missing_or_empty_images_are_refused_before_any_command() {
+        let dir = std::env::temp_dir().join(format!("sidestep-executor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("empty.img"), b"").unwrap();
+        std::fs::write(dir.join("boot.img"), b"ANDROID!").unwrap();
+        let image = |name: &str| PartitionImage {
+            partition: "boot".to_string(),
+            image: name.to_string(),
+            erase_first: true,
+            aliases: Vec::new(),
+        };
+
+        assert!(resolve_image(&image("missing.img"), &dir).is_err());
+        assert!(resolve_image(&image("empty.img"), &dir).is_err());
+        assert_eq!(
+            resolve_image(&image("boot.img"), &dir).unwrap(),
+            dir.join("boot.img")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Records the device commands, preparing images the way the executor
+    /// does
+    struct RecordingWriter {
+        executor: FlashExecutor,
+        partition_size: u64,
+        commands: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl RecordingWriter {
+        fn new(partition_size: u64) -> Self {
+            Self {
+                executor: FlashExecutor::new(),
+                partition_size,
+                commands: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+
+        fn commands(&self) -> Vec<String> {
+            self.commands.lock().unwrap().clone()
+        }
+    }
+
+    impl PartitionWriter for RecordingWriter {
+        async fn prepare(&self, image: &Path) -> Result<PreparedImage> {
+            self.executor.unpack("boot", image).await
+        }
+
+        async fn check_fits(&self, target: &str, image: &Path) -> Result<()> {
+            let size = std::fs::metadata(image)?.len();
+            self.commands
+                .lock()
+                .unwrap()
+                .push(format!("check {} {}", target, size));
+            if size > self.partition_size {
+                anyhow::bail!("{} doesn't fit in {}", image.display(), target);
+            }
+            Ok(())
+        }
+
+        async fn erase(&self, target: &str) -> Result<()> {
+            self.commands
+                .lock()
+                .unwrap()
+                .push(format!("erase {}", target));
+            Ok(())
+        }
+
+        async fn flash(&self, target: &str, image: &Path) -> Result<()> {
+            let written = std::fs::read(image)?;
+            self.commands
+                .lock()
+                .unwrap()
+                .push(format!("flash {} {}", target, written.len()));
+            Ok(())
+        }
+    }
+
+    fn xz_image(name: &str, contents: &[u8]) -> PathBuf {
+        use std::io::Write;
+        let path = std::env::temp_dir().join(format!(
+            "sidestep-executor-{}-{}.img.xz",
+            std::process::id(),
+            name
+        ));
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(contents).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn a_corrupt_image_is_caught_before_anything_is_erased() {
+        let image = xz_image("corrupt", &[0x5a; 64 * 1024]);
+        let packed = std::fs::read(&image).unwrap();
+        std::fs::write(&image, &packed[..packed.len() / 2]).unwrap();
+        let targets = slots(&["boot_a", "boot_b"]);
+        let writer = RecordingWriter::new(1024 * 1024);
+
+        let result = run_write_steps(&writer, &write_steps(true, &targets), &image).await;
+
+        assert!(result.is_err());
+        assert!(writer.commands().is_empty());
+        let _ = std::fs::remove_file(&image);
+        let _ = std::fs::remove_file(image.with_extension(""));
+    }
+
+    #[tokio::test]
+    async fn each_slot_is_written_from_the_unpacked_image() {
+        let image = xz_image("good", &[0x5a; 64 * 1024]);
+        let targets = slots(&["boot_a", "boot_b"]);
+        let writer = RecordingWriter::new(1024 * 1024);
+
+        run_write_steps(&writer, &write_steps(true, &targets), &image)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            writer.commands(),
+            vec![
+                "check boot_a 65536",
+                "check boot_b 65536",
+                "erase boot_a",
+                "flash boot_a 65536",
+                "erase boot_b",
+                "flash boot_b 65536",
+            ]
+        );
+        assert!(!image.with_extension("").exists());
+        let _ = std::fs::remove_file(&image);
+    }
+
+    #[tokio::test]
+    async fn an_image_too_big_for_any_slot_leaves_them_all_intact() {
+        let image = xz_image("big", &[0x5a; 64 * 1024]);
+        let targets = slots(&["boot_a", "boot_b"]);
+        let writer = RecordingWriter::new(32 * 1024);
+
+        let result = run_write_steps(&writer, &write_steps(true, &targets), &image).await;
+
+        assert!(result.is_err());
+        assert_eq!(writer.commands(), vec!["check boot_a 65536"]);
+        let _ = std::fs::remove_file(&image);
+    }
+}
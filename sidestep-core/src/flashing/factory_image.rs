@@ -1,14 +1,17 @@
 // Factory image installer (stock Android)
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use crate::flashing::cache;
 use crate::flashing::checksum::ChecksumVerifier;
-use crate::flashing::downloader::ImageDownloader;
-use crate::flashing::progress::InstallProgress;
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::mode_wait;
+use crate::flashing::progress::{self, InstallProgress};
+use crate::hardware::cancel::CancelToken;
 use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
 use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
-use std::time::Duration;
 
 /// Orchestrates flashing a stock Android factory image.
 ///
@@ -22,14 +25,17 @@ pub struct FactoryImageInstaller {
     sha256: String,
     android_version: String,
     download_dir: PathBuf,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
 }
 
 impl FactoryImageInstaller {
+    /// `flash-all.sh` starts in the bootloader and only ever reboots back
+    /// into it; there's no adb step to get the device there first
+    pub const REQUIRED_MODE: StartMode = StartMode::Fastboot;
+
     pub fn new(serial: String, url: String, sha256: String, android_version: String) -> Self {
-        let download_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("sidestep")
-            .join("factory-image");
+        let download_dir = cache::installer_dir("factory-image");
 
         Self {
             serial,
@@ -37,9 +43,24 @@ impl FactoryImageInstaller {
             sha256,
             android_version,
             download_dir,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
         }
     }
 
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
     /// Spawn the installer on a background thread, returning immediately.
     pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
         let (sender, receiver) = std::sync::mpsc::channel();
@@ -53,7 +74,7 @@ impl FactoryImageInstaller {
             rt.block_on(async {
                 if let Err(e) = self.run(&sender).await {
                     log::error!("Factory image installation failed: {:#}", e);
-                    let _ = sender.send(InstallProgress::Error(format!("{:#}", e)));
+                    let _ = sender.send(InstallProgress::from_error(&e));
                 }
             });
         });
@@ -62,8 +83,9 @@ impl FactoryImageInstaller {
     }
 
     async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
-        let downloader = ImageDownloader::new(self.download_dir.clone());
-        let fastboot = Fastboot::new();
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
 
         // Derive filename from URL
         let zip_name = self
@@ -77,20 +99,12 @@ impl FactoryImageInstaller {
         let _ = sender.send(InstallProgress::StatusChanged(
             format!("Downloading {}...", self.android_version),
         ));
-        let sender_clone = sender.clone();
-        let android_ver = self.android_version.clone();
         let zip_path = downloader
             .download_if_needed(
                 &self.url,
                 &zip_name,
                 Some(&self.sha256),
-                Some(Box::new(move |downloaded, total| {
-                    let _ = sender_clone.send(InstallProgress::DownloadProgress {
-                        downloaded,
-                        total,
-                        file_name: android_ver.clone(),
-                    });
-                })),
+                progress::report_download(sender, &self.android_version, 1, 1),
             )
             .await
             .context("Failed to download factory image ZIP")?;
@@ -102,10 +116,7 @@ impl FactoryImageInstaller {
             total: 1,
             file_name: zip_name.clone(),
         });
-        let ok = ChecksumVerifier::verify(&zip_path, &self.sha256)?;
-        if !ok {
-            anyhow::bail!("Checksum mismatch for {}", zip_name);
-        }
+        ChecksumVerifier::check(&zip_path, &self.sha256, None)?;
         let _ = sender.send(InstallProgress::VerifyProgress {
             verified: 1,
             total: 1,
@@ -153,7 +164,7 @@ impl FactoryImageInstaller {
             total: total_steps,
             description: "Waiting for device...".into(),
         });
-        self.wait_for_fastboot(&fastboot).await?;
+        mode_wait::wait_for_fastboot(&fastboot, &self.serial, sender).await?;
 
         // ── Step 7: Flash radio ──
         let _ = sender.send(InstallProgress::FlashProgress {
@@ -179,7 +190,7 @@ impl FactoryImageInstaller {
             total: total_steps,
             description: "Waiting for device...".into(),
         });
-        self.wait_for_fastboot(&fastboot).await?;
+        mode_wait::wait_for_fastboot(&fastboot, &self.serial, sender).await?;
 
         // ── Step 9: Flash all system partitions + wipe ──
         let _ = sender.send(InstallProgress::FlashProgress {
@@ -213,20 +224,7 @@ impl FactoryImageInstaller {
         let mut archive =
             zip::ZipArchive::new(file).context("Failed to read factory image ZIP")?;
 
-        for i in 0..archive.len() {
-            let mut entry = archive.by_index(i)?;
-            let out_path = extract_dir.join(entry.name());
-
-            if entry.is_dir() {
-                std::fs::create_dir_all(&out_path)?;
-            } else {
-                if let Some(parent) = out_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
-                let mut outfile = std::fs::File::create(&out_path)?;
-                std::io::copy(&mut entry, &mut outfile)?;
-            }
-        }
+        crate::flashing::archive::unpack_zip(&mut archive, extract_dir)?;
 
         log::info!("Extracted factory image to {}", extract_dir.display());
         Ok(())
@@ -260,16 +258,4 @@ impl FactoryImageInstaller {
             dir.display()
         )
     }
-
-    async fn wait_for_fastboot(&self, fastboot: &Fastboot) -> Result<()> {
-        for _ in 0..60 {
-            if let Ok(devices) = fastboot.devices().await {
-                if devices.iter().any(|d| d.serial == self.serial) {
-                    return Ok(());
-                }
-            }
-            tokio::time::sleep(Duration::from_secs(2)).await;
-        }
-        anyhow::bail!("Timed out waiting for device in fastboot mode")
-    }
 }
@@ -0,0 +1,178 @@
+// Partitions an unfinished install has written, for resuming it
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "flash-state.json";
+
+/// A partition that was fully written, and the checksum of the image it
+/// was written from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlashedPartition {
+    pub partition: String,
+    pub sha256: String,
+}
+
+/// What has been flashed to one device since its last finished install,
+/// in flash order. Kept on disk so a flash interrupted between partitions
+/// picks up at the first one it didn't finish.
+#[derive(Debug, Clone, Default)]
+pub struct FlashState {
+    serial: String,
+    flashed: Vec<FlashedPartition>,
+}
+
+impl FlashState {
+    fn path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join("sidestep")
+            .join(FILE_NAME)
+    }
+
+    fn load_all() -> HashMap<String, Vec<FlashedPartition>> {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut all = Self::load_all();
+        if self.flashed.is_empty() {
+            all.remove(&self.serial);
+        } else {
+            all.insert(self.serial.clone(), self.flashed.clone());
+        }
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create the data directory")?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(&all)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The partitions written to `serial` by installs that didn't finish
+    pub fn load(serial: &str) -> Self {
+        Self {
+            serial: serial.to_string(),
+            flashed: Self::load_all().remove(serial).unwrap_or_default(),
+        }
+    }
+
+    /// Whether nothing is recorded, so there's nothing to resume
+    pub fn is_empty(&self) -> bool {
+        self.flashed.is_empty()
+    }
+
+    /// How many of `planned` `(partition, sha256)` pairs, counted from the
+    /// first, are already on the device from the same images.
+    ///
+    /// Only a leading run counts: once one partition has to be written
+    /// again, everything after it is too, since a later partition may
+    /// depend on what came before.
+    pub fn resume_point(&self, planned: &[(&str, &str)]) -> usize {
+        planned
+            .iter()
+            .zip(&self.flashed)
+            .take_while(|((partition, sha256), done)| {
+                done.partition == *partition && done.sha256.eq_ignore_ascii_case(sha256)
+            })
+            .count()
+    }
+
+    /// Start flashing `planned`, forgetting whatever was recorded past the
+    /// partitions that can be skipped. Returns how many to skip.
+    pub fn resume(&mut self, planned: &[(&str, &str)]) -> Result<usize> {
+        let skip = self.resume_point(planned);
+        if skip < self.flashed.len() {
+            self.flashed.truncate(skip);
+            self.save()?;
+        }
+        if skip > 0 {
+            log::info!(
+                "Resuming the flash on {}: {} already written",
+                self.serial,
+                planned[..skip].iter().map(|(p, _)| *p).collect::<Vec<_>>().join(", ")
+            );
+        }
+        Ok(skip)
+    }
+
+    /// Record `partition` as completely written from the image with `sha256`
+    pub fn mark_flashed(&mut self, partition: &str, sha256: &str) -> Result<()> {
+        self.flashed.retain(|done| done.partition != partition);
+        self.flashed.push(FlashedPartition {
+            partition: partition.to_string(),
+            sha256: sha256.to_string(),
+        });
+        self.save()
+    }
+
+    /// Forget the partitions once every one has been written
+    pub fn finish(&mut self) -> Result<()> {
+        self.flashed.clear();
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &[(&str, &str)] = &[("boot", "aa11"), ("dtbo", "bb22"), ("system", "cc33")];
+
+    /// The state after the first `done` partitions of [`PLAN`] were written
+    fn flashed(done: usize) -> FlashState {
+        FlashState {
+            serial: "test".to_string(),
+            flashed: PLAN[..done]
+                .iter()
+                .map(|(partition, sha256)| FlashedPartition {
+                    partition: partition.to_string(),
+                    sha256: sha256.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn resumes_after_each_partition_boundary() {
+        for done in 0..=PLAN.len() {
+            assert_eq!(
+                flashed(done).resume_point(PLAN),
+                done,
+                "after {} partitions",
+                done
+            );
+        }
+    }
+
+    #[test]
+    fn only_a_state_with_partitions_has_anything_to_resume() {
+        assert!(flashed(0).is_empty());
+        assert!(!flashed(1).is_empty());
+    }
+
+    #[test]
+    fn changed_image_is_flashed_again_with_everything_after_it() {
+        let state = flashed(3);
+        let rebuilt = [("boot", "aa11"), ("dtbo", "ffff"), ("system", "cc33")];
+        assert_eq!(state.resume_point(&rebuilt), 1);
+    }
+
+    #[test]
+    fn checksums_compare_without_case() {
+        let upper = [("boot", "AA11"), ("dtbo", "BB22"), ("system", "CC33")];
+        assert_eq!(flashed(2).resume_point(&upper), 2);
+    }
+
+    #[test]
+    fn a_different_plan_starts_over() {
+        let other = [("recovery", "aa11"), ("boot", "aa11")];
+        assert_eq!(flashed(2).resume_point(&other), 0);
+    }
+}
@@ -0,0 +1,464 @@
+// GitHub Releases source
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::models::distro_config::ChannelConfig;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+pub(crate) const API_BASE: &str = "https://api.github.com";
+
+/// Releases fetched per request; GitHub caps this at 100
+const PER_PAGE: usize = 30;
+
+/// How many pages to walk before giving up on finding a matching asset
+const MAX_PAGES: usize = 5;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl Release {
+    /// The newest asset whose name matches `pattern`, never a checksum file.
+    ///
+    /// Several nightlies can share a release, so matches are ranked by the
+    /// `YYYYMMDD` build date in the name, then by upload time.
+    pub fn newest_asset(&self, pattern: &str) -> Option<&ReleaseAsset> {
+        let candidates: Vec<&ReleaseAsset> = self
+            .assets
+            .iter()
+            .filter(|a| !is_checksum_file(&a.name) && artifact_matches(pattern, &a.name))
+            .collect();
+
+        let newest = candidates.iter().copied().max_by_key(|a| a.sort_key())?;
+        if candidates.len() > 1 {
+            log::info!(
+                "{} assets match '{}', picking newest: {}",
+                candidates.len(),
+                pattern,
+                newest.name
+            );
+        }
+        Some(newest)
+    }
+
+    /// The release's `SHA256SUMS` file, if it publishes one
+    pub fn checksums_asset(&self) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.name.contains("SHA256SUMS"))
+    }
+}
+
+impl ReleaseAsset {
+    /// The `YYYYMMDD` build date in the name, or else the upload day, as
+    /// `YYYY-MM-DD`
+    pub fn build_date(&self) -> Option<String> {
+        match self.name_date() {
+            Some(date) => Some(format!("{}-{}-{}", &date[0..4], &date[4..6], &date[6..8])),
+            None => self
+                .created_at
+                .as_deref()
+                .and_then(|at| at.get(0..10))
+                .map(str::to_string),
+        }
+    }
+
+    fn name_date(&self) -> Option<String> {
+        self.name
+            .as_bytes()
+            .windows(8)
+            .rev()
+            .find(|w| w.iter().all(u8::is_ascii_digit))
+            .map(|w| String::from_utf8_lossy(w).into_owned())
+    }
+
+    fn sort_key(&self) -> (String, String) {
+        let build_date = self.name_date().unwrap_or_default();
+        let uploaded = self
+            .updated_at
+            .clone()
+            .or_else(|| self.created_at.clone())
+            .unwrap_or_default();
+        (build_date, uploaded)
+    }
+}
+
+/// Finds release assets for a GitHub repository.
+///
+/// Releases are walked newest first, following the API's pagination, and
+/// the first one with an asset matching the pattern wins, or with the pinned
+/// asset when there is one. Drafts are always skipped; prereleases only
+/// count when asked for.
+#[derive(Debug, Clone)]
+pub struct GithubReleaseSource {
+    repo: String,
+    include_prereleases: bool,
+    /// Exact asset name to install instead of the newest match
+    pinned_asset: Option<String>,
+}
+
+impl GithubReleaseSource {
+    /// `repo` is `owner/name`, e.g. `droidian-images/droidian`.
+    pub fn new(repo: &str) -> Self {
+        Self {
+            repo: repo.trim_matches('/').to_string(),
+            include_prereleases: false,
+            pinned_asset: None,
+        }
+    }
+
+    /// Accept an API URL such as
+    /// `https://api.github.com/repos/OWNER/REPO/releases/latest`, as older
+    /// device configs give in `release_url`.
+    pub fn from_api_url(url: &str) -> Option<Self> {
+        let rest = url.strip_prefix(API_BASE)?.strip_prefix("/repos/")?;
+        let mut parts = rest.split('/');
+        let owner = parts.next().filter(|s| !s.is_empty())?;
+        let name = parts.next().filter(|s| !s.is_empty())?;
+        Some(Self::new(&format!("{}/{}", owner, name)))
+    }
+
+    /// The source a device config channel declares through `github_repo`,
+    /// or through a GitHub API `release_url`.
+    pub fn for_channel(channel: &ChannelConfig) -> Option<Self> {
+        let source = match (&channel.github_repo, &channel.release_url) {
+            (Some(repo), _) => Self::new(repo),
+            (None, Some(url)) => Self::from_api_url(url)?,
+            (None, None) => return None,
+        };
+        Some(source.include_prereleases(channel.prereleases))
+    }
+
+    pub fn include_prereleases(mut self, enabled: bool) -> Self {
+        self.include_prereleases = enabled;
+        self
+    }
+
+    /// Only accept the asset named `name`, in whichever release has it,
+    /// to install an older build than the newest.
+    pub fn pin_asset(mut self, name: Option<String>) -> Self {
+        self.pinned_asset = name;
+        self
+    }
+
+    pub fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    pub fn pinned_asset(&self) -> Option<&str> {
+        self.pinned_asset.as_deref()
+    }
+
+    /// Find the newest release with an asset matching `pattern`, returning
+    /// the release alongside the chosen asset.
+    pub async fn find_asset(&self, pattern: &str) -> Result<(Release, ReleaseAsset)> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .build()?;
+
+        let mut newest_seen: Option<Release> = None;
+        let mut url = Some(format!(
+            "{}/repos/{}/releases?per_page={}",
+            API_BASE, self.repo, PER_PAGE
+        ));
+
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let (releases, next) = self.fetch_page(&client, &page_url).await?;
+
+            for release in releases {
+                if release.draft || (release.prerelease && !self.include_prereleases) {
+                    continue;
+                }
+                let asset = match self.pinned_asset {
+                    Some(ref pinned) => release.assets.iter().find(|a| a.name == *pinned),
+                    None => release.newest_asset(pattern),
+                };
+                if let Some(asset) = asset.cloned() {
+                    log::info!("Using {} from {} release {}", asset.name, self.repo, release.tag_name);
+                    return Ok((release, asset));
+                }
+                if newest_seen.is_none() {
+                    newest_seen = Some(release);
+                }
+            }
+            url = next;
+        }
+
+        if let Some(ref pinned) = self.pinned_asset {
+            anyhow::bail!("{} is no longer among the {} releases", pinned, self.repo);
+        }
+        let available = newest_seen
+            .map(|r| r.assets.into_iter().map(|a| a.name).collect::<Vec<_>>())
+            .filter(|names| !names.is_empty())
+            .map(|names| names.join("\n"))
+            .unwrap_or_else(|| "(none)".to_string());
+        anyhow::bail!(
+            "No asset matching '{}' in {} releases. Assets in the newest release:\n{}",
+            pattern,
+            self.repo,
+            available
+        )
+    }
+
+    /// Up to `limit` assets matching `pattern`, newest first, each with the
+    /// release it belongs to. Walks the same pages [`find_asset`](Self::find_asset)
+    /// would, so every asset listed can be pinned.
+    pub async fn list_assets(&self, pattern: &str, limit: usize) -> Result<Vec<(Release, ReleaseAsset)>> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .build()?;
+
+        let mut found = Vec::new();
+        let mut url = Some(format!(
+            "{}/repos/{}/releases?per_page={}",
+            API_BASE, self.repo, PER_PAGE
+        ));
+
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let (releases, next) = self.fetch_page(&client, &page_url).await?;
+
+            for release in releases {
+                if release.draft || (release.prerelease && !self.include_prereleases) {
+                    continue;
+                }
+                let mut assets: Vec<ReleaseAsset> = release
+                    .assets
+                    .iter()
+                    .filter(|a| !is_checksum_file(&a.name) && artifact_matches(pattern, &a.name))
+                    .cloned()
+                    .collect();
+                assets.sort_by_key(|a| std::cmp::Reverse(a.sort_key()));
+                for asset in assets {
+                    found.push((release.clone(), asset));
+                    if found.len() == limit {
+                        return Ok(found);
+                    }
+                }
+            }
+            url = next;
+        }
+        Ok(found)
+    }
+
+    /// Fetch one page of releases and the URL of the next, if any.
+    async fn fetch_page(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<(Vec<Release>, Option<String>)> {
+        let resp = client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to query GitHub releases API")?;
+
+        let headers = resp.headers();
+        let remaining = header_u64(headers, "x-ratelimit-remaining");
+        let status = resp.status();
+
+        if (status == reqwest::StatusCode::FORBIDDEN
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+            && (remaining == Some(0) || headers.contains_key("retry-after"))
+        {
+            anyhow::bail!(
+                "GitHub API rate limit reached; try again in {}",
+                rate_limit_wait(headers)
+            );
+        }
+        if !status.is_success() {
+            anyhow::bail!("GitHub API returned status {} for {}", status, url);
+        }
+        if let Some(remaining) = remaining.filter(|&r| r < 10) {
+            log::warn!("Only {} GitHub API requests left before the rate limit", remaining);
+        }
+
+        let next = headers
+            .get("link")
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_page_url);
+
+        let text = resp.text().await.context("Failed to read GitHub release response")?;
+        let releases: Vec<Release> =
+            serde_json::from_str(&text).context("Failed to parse GitHub releases JSON")?;
+        Ok((releases, next))
+    }
+}
+
+/// Match an asset name against an `artifact_match` pattern.
+///
+/// Patterns containing `*` or `?` are treated as globs over the whole name;
+/// plain patterns match as a substring, as the device configs expect.
+pub fn artifact_matches(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return name.contains(pattern);
+    }
+    glob_match(pattern.as_bytes(), name.as_bytes())
+}
+
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], name) || (!name.is_empty() && glob_match(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => glob_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+fn is_checksum_file(name: &str) -> bool {
+    name.contains("SHA256SUMS") || name.ends_with(".sha256") || name.ends_with(".sha256sum")
+}
+
+fn header_u64(headers: &reqwest::header::HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Human-readable wait from `retry-after` or `x-ratelimit-reset` (a Unix time).
+fn rate_limit_wait(headers: &reqwest::header::HeaderMap) -> String {
+    let seconds = header_u64(headers, "retry-after").or_else(|| {
+        let reset = header_u64(headers, "x-ratelimit-reset")?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        Some(reset.saturating_sub(now))
+    });
+    match seconds {
+        Some(s) if s >= 60 => format!("{} minutes", s.div_ceil(60)),
+        Some(s) => format!("{} seconds", s.max(1)),
+        None => "a while".to_string(),
+    }
+}
+
+/// The `rel="next"` target of a `Link` header.
+fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (target, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| p.trim() == "rel=\"next\"")
+            .then(|| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE_JSON: &str = r#"{
+        "tag_name": "nightly",
+        "draft": false,
+        "prerelease": false,
+        "assets": [
+            {
+                "name": "droidian-OFFICIAL-phosh-phone-rootfs-api28-arm64-nightly_20240101.zip",
+                "browser_download_url": "https://example.org/20240101.zip",
+                "size": 1000,
+                "created_at": "2024-01-01T03:00:00Z",
+                "updated_at": "2024-01-01T03:00:00Z"
+            },
+            {
+                "name": "droidian-OFFICIAL-phosh-phone-rootfs-api28-arm64-nightly_20240315.zip",
+                "browser_download_url": "https://example.org/20240315.zip",
+                "size": 2000,
+                "created_at": "2024-03-15T03:00:00Z",
+                "updated_at": "2024-03-15T03:00:00Z"
+            },
+            {
+                "name": "droidian-OFFICIAL-phosh-phone-rootfs-api28-arm64-nightly_20240315.zip.sha256sum",
+                "browser_download_url": "https://example.org/20240315.zip.sha256sum",
+                "size": 100
+            },
+            {
+                "name": "droidian-OFFICIAL-phosh-phone-rootfs-api29-arm64-nightly_20240201.zip",
+                "browser_download_url": "https://example.org/api29.zip",
+                "size": 3000,
+                "created_at": "2024-02-01T03:00:00Z",
+                "updated_at": "2024-02-01T03:00:00Z"
+            },
+            {
+                "name": "SHA256SUMS",
+                "browser_download_url": "https://example.org/SHA256SUMS",
+                "size": 300
+            }
+        ]
+    }"#;
+
+    fn release() -> Release {
+        serde_json::from_str(RELEASE_JSON).unwrap()
+    }
+
+    #[test]
+    fn no_matching_asset() {
+        assert!(release().newest_asset("api30").is_none());
+    }
+
+    #[test]
+    fn single_matching_asset() {
+        let release = release();
+        let asset = release.newest_asset("api29").unwrap();
+        assert_eq!(asset.browser_download_url, "https://example.org/api29.zip");
+    }
+
+    #[test]
+    fn newest_of_several_matches_skipping_checksums() {
+        let release = release();
+        let asset = release.newest_asset("api28").unwrap();
+        assert_eq!(asset.browser_download_url, "https://example.org/20240315.zip");
+    }
+
+    #[test]
+    fn glob_patterns_match_the_whole_name() {
+        let release = release();
+        let asset = release.newest_asset("droidian-*-api29-*.zip").unwrap();
+        assert_eq!(asset.size, 3000);
+        assert!(release.newest_asset("api29-*.zip").is_none());
+    }
+
+    #[test]
+    fn checksums_asset_is_found() {
+        assert_eq!(release().checksums_asset().unwrap().name, "SHA256SUMS");
+    }
+
+    #[test]
+    fn build_date_prefers_the_name() {
+        let release = release();
+        let asset = release.newest_asset("api28").unwrap();
+        assert_eq!(asset.build_date().as_deref(), Some("2024-03-15"));
+    }
+
+    #[test]
+    fn next_page_from_link_header() {
+        let link = "<https://api.github.com/repositories/1/releases?page=2>; rel=\"next\", \
+                    <https://api.github.com/repositories/1/releases?page=5>; rel=\"last\"";
+        assert_eq!(
+            next_page_url(link).as_deref(),
+            Some("https://api.github.com/repositories/1/releases?page=2")
+        );
+        assert!(next_page_url("<https://example.org>; rel=\"prev\"").is_none());
+    }
+}
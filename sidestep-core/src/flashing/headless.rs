@@ -0,0 +1,322 @@
+// Installs started from the command line instead of the GUI
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::{
+    self, DroidianInstaller, EosInstaller, GithubReleaseSource, InstallProgress, LineageosInstaller,
+    MobianInstaller, PostmarketosInstaller, UbportsInstaller, postmarketos,
+};
+use crate::hardware::prerequisites::{self, CheckOutcome};
+use crate::hardware::{Adb, AdbState, Fastboot, StartMode, mode_guard};
+use crate::models::database::maker_key;
+use crate::models::distro_config::{ChannelConfig, DistroConfig};
+use crate::models::installer::InstallerConfig;
+use crate::models::{Device, DeviceDatabase};
+use crate::utils::data_check;
+use crate::utils::yaml_parser::YamlParser;
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+
+/// What to install, as given on the command line
+#[derive(Debug, Clone, Default)]
+pub struct HeadlessOptions {
+    /// Codename or alias of the device, e.g. `sargo`
+    pub device: String,
+    /// Distro id from the device's distros.yml, e.g. `postmarketos`
+    pub distro: String,
+    /// Channel id; may be left out when the distro has only one
+    pub channel: Option<String>,
+    /// Interface id, for distros that offer a choice of desktop
+    pub interface: Option<String>,
+    /// Needed when more than one device is connected
+    pub serial: Option<String>,
+    /// Only download and verify the images
+    pub download_only: bool,
+    /// The user has checked by hand the prerequisites that can't be queried
+    /// from the device. Without it those stop the install.
+    pub prerequisites_confirmed: bool,
+}
+
+/// A run ready to go: what it's installing, where, and the installer's
+/// progress channel
+pub struct HeadlessInstall {
+    pub device: Device,
+    pub distro: DistroConfig,
+    pub serial: String,
+    pub progress: Receiver<InstallProgress>,
+}
+
+/// Resolve `options` against the device database, check the connected
+/// device meets every prerequisite, get it into the mode the installer
+/// starts from and start the installer, the same one the GUI would run.
+///
+/// Anything that would have the GUI ask the user fails here instead, saying
+/// which option or action is missing.
+pub async fn start(options: &HeadlessOptions) -> Result<HeadlessInstall> {
+    let db = DeviceDatabase::new();
+    let Some(device) = db.find_by_codename(&options.device) else {
+        bail!("Unknown device \"{}\"; try the codename, e.g. sargo", options.device);
+    };
+
+    let devices_dir = data_check::devices_dir_candidates()
+        .into_iter()
+        .find(|dir| dir.is_dir())
+        .context("No devices directory found")?;
+    let maker_dir = maker_key(&device.maker);
+    let config = YamlParser::new(&devices_dir)
+        .parse_device_config(&maker_dir, &device.codename)
+        .with_context(|| format!("No distro list for {}", device.codename))?;
+
+    let Some(distro) = config
+        .available_distros
+        .iter()
+        .find(|d| d.id == options.distro || d.name.eq_ignore_ascii_case(&options.distro))
+        .cloned()
+    else {
+        let ids: Vec<&str> = config.available_distros.iter().map(|d| d.id.as_str()).collect();
+        bail!("{} has no distro \"{}\"; available: {}", device.name, options.distro, ids.join(", "));
+    };
+    if !flashing::has_backend(&distro.id) {
+        bail!("{} can't be installed from the command line yet", distro.name);
+    }
+
+    let channel = pick_channel(&distro, options.channel.as_deref())?;
+    let interface = pick_interface(&distro, options.interface.as_deref())?;
+    let serial = match options.serial {
+        Some(ref serial) => serial.clone(),
+        None => connected_serial().await?,
+    };
+
+    if !options.download_only {
+        check_prerequisites(&device, &distro, &devices_dir.join(&maker_dir), &serial, options).await?;
+        mode_guard::ensure(&serial, required_mode(&distro.id)).await?;
+    }
+
+    let progress = spawn_installer(
+        &device,
+        &distro,
+        channel.as_ref(),
+        interface.as_deref(),
+        &serial,
+        options.download_only,
+    )?;
+    Ok(HeadlessInstall {
+        device,
+        distro,
+        serial,
+        progress,
+    })
+}
+
+/// The channel called `wanted`, or the only one there is
+fn pick_channel(distro: &DistroConfig, wanted: Option<&str>) -> Result<Option<ChannelConfig>> {
+    if distro.channels.is_empty() {
+        return Ok(None);
+    }
+    let ids = || distro.channels.iter().map(|c| c.id.as_str()).collect::<Vec<_>>().join(", ");
+    match wanted {
+        Some(wanted) => match distro.channels.iter().find(|c| c.id == wanted) {
+            Some(channel) => Ok(Some(channel.clone())),
+            None => bail!("{} has no channel \"{}\"; available: {}", distro.name, wanted, ids()),
+        },
+        None if distro.channels.len() == 1 => Ok(distro.channels.first().cloned()),
+        None => bail!("{} has several channels; pick one with --channel ({})", distro.name, ids()),
+    }
+}
+
+/// The interface called `wanted`, for distros that offer a choice
+fn pick_interface(distro: &DistroConfig, wanted: Option<&str>) -> Result<Option<String>> {
+    let interfaces = distro.interfaces.as_deref().unwrap_or_default();
+    if interfaces.is_empty() {
+        return Ok(None);
+    }
+    let ids = || interfaces.iter().map(|i| i.id.as_str()).collect::<Vec<_>>().join(", ");
+    match wanted {
+        Some(wanted) if interfaces.iter().any(|i| i.id == wanted) => Ok(Some(wanted.to_string())),
+        Some(wanted) => bail!("{} has no interface \"{}\"; available: {}", distro.name, wanted, ids()),
+        None if interfaces.len() == 1 => Ok(interfaces.first().map(|i| i.id.clone())),
+        None => bail!("{} needs an interface; pick one with --interface ({})", distro.name, ids()),
+    }
+}
+
+/// The serial of the one device connected over adb or fastboot
+async fn connected_serial() -> Result<String> {
+    let mut serials: Vec<String> = Adb::new()
+        .devices()
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|d| d.state != AdbState::Offline)
+        .map(|d| d.serial)
+        .collect();
+    serials.extend(Fastboot::new().devices().await.unwrap_or_default().into_iter().map(|d| d.serial));
+    serials.sort();
+    serials.dedup();
+
+    match serials.as_slice() {
+        [serial] => Ok(serial.clone()),
+        [] => bail!("No device connected over adb or fastboot"),
+        _ => bail!("Several devices are connected ({}); pick one with --serial", serials.join(", ")),
+    }
+}
+
+/// Stop unless the bootloader is unlocked, the battery has enough charge
+/// and the installer's own prerequisites hold. Checks the device can't
+/// answer only pass when the user confirmed them.
+async fn check_prerequisites(
+    device: &Device,
+    distro: &DistroConfig,
+    maker_dir: &std::path::Path,
+    serial: &str,
+    options: &HeadlessOptions,
+) -> Result<()> {
+    let mut checks: Vec<(String, String)> = vec![(
+        "bootloader_unlocked".to_string(),
+        "Unlock the bootloader first; the GUI walks through it".to_string(),
+    )];
+    if let Some(config) = installer_config(maker_dir, &device.codename, &distro.id) {
+        checks.extend(
+            config
+                .prerequisites
+                .into_iter()
+                .map(|prereq| (prereq.check, prereq.on_failure)),
+        );
+    }
+
+    for (check, on_failure) in &checks {
+        match prerequisites::verify(check, serial).await {
+            CheckOutcome::Satisfied(_) => {}
+            CheckOutcome::Unsatisfied(found) => bail!("{}. {}", found, on_failure),
+            CheckOutcome::Manual if options.prerequisites_confirmed => {
+                log::warn!("Couldn't check {} on {}; confirmed by the user", check, serial);
+            }
+            CheckOutcome::Manual => bail!(
+                "Couldn't check {} on the device; make sure of it and run again with --confirm-prerequisites",
+                check
+            ),
+        }
+    }
+
+    if device.battery_min > 0
+        && let CheckOutcome::Unsatisfied(found) = prerequisites::battery(serial, device.battery_min).await
+    {
+        bail!("{}; charge it to at least {}% first", found, device.battery_min);
+    }
+    Ok(())
+}
+
+/// The device's `installers/<distro>.yml`, when it has one
+fn installer_config(maker_dir: &std::path::Path, codename: &str, distro_id: &str) -> Option<InstallerConfig> {
+    let path: PathBuf = maker_dir
+        .join(codename.to_lowercase())
+        .join("installers")
+        .join(format!("{}.yml", distro_id));
+    let content = std::fs::read_to_string(&path).ok()?;
+    match serde_yaml::from_str(&content) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            log::error!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn required_mode(distro_id: &str) -> StartMode {
+    match distro_id {
+        "ubuntutouch" | "ubports" => UbportsInstaller::REQUIRED_MODE,
+        "droidian" => DroidianInstaller::REQUIRED_MODE,
+        "postmarketos" => PostmarketosInstaller::REQUIRED_MODE,
+        "lineageos" => LineageosInstaller::REQUIRED_MODE,
+        "eos" => EosInstaller::REQUIRED_MODE,
+        _ => MobianInstaller::REQUIRED_MODE,
+    }
+}
+
+/// Start the backend for `distro` the way the device details page does
+fn spawn_installer(
+    device: &Device,
+    distro: &DistroConfig,
+    channel: Option<&ChannelConfig>,
+    interface: Option<&str>,
+    serial: &str,
+    download_only: bool,
+) -> Result<Receiver<InstallProgress>> {
+    let serial = serial.to_string();
+    let need_channel = || channel.with_context(|| format!("{} lists no channels for this device", distro.name));
+    let base_url = || {
+        distro
+            .base_url
+            .clone()
+            .with_context(|| format!("No base_url defined for {}", distro.name))
+    };
+    let need_interface = || {
+        interface
+            .map(str::to_string)
+            .with_context(|| format!("{} lists no interfaces for this device", distro.name))
+    };
+
+    let receiver = match distro.id.as_str() {
+        "ubuntutouch" | "ubports" => {
+            let channel = need_channel()?;
+            let path = channel
+                .path
+                .as_deref()
+                .with_context(|| format!("No channel path defined for channel {}", channel.id))?;
+            UbportsInstaller::new(serial, path.trim_end_matches('/').to_string())
+                .download_only(download_only)
+                .spawn()
+        }
+        "droidian" => {
+            let channel = need_channel()?;
+            let source = GithubReleaseSource::for_channel(channel)
+                .with_context(|| format!("No GitHub release source defined for channel {}", channel.id))?;
+            let pattern = channel
+                .artifact_match
+                .clone()
+                .with_context(|| format!("No artifact_match defined for channel {}", channel.id))?;
+            DroidianInstaller::new(serial, source, pattern)
+                .checksum_algorithm(distro.checksum_algorithm)
+                .download_only(download_only)
+                .spawn()
+        }
+        "postmarketos" => PostmarketosInstaller::new(
+            serial,
+            base_url()?,
+            need_channel()?.id.clone(),
+            need_interface()?,
+            postmarketos::device_name(device),
+        )
+        .archive_partitions(distro.archive_partitions.clone())
+        .mirrors(distro.image_hosts())
+        .download_only(download_only)
+        .spawn(),
+        "lineageos" => {
+            let channel = need_channel()?;
+            let url = channel
+                .release_url
+                .clone()
+                .with_context(|| format!("No release_url defined for channel {}", channel.id))?;
+            LineageosInstaller::new(serial, url, false)
+                .download_only(download_only)
+                .spawn()
+        }
+        "eos" => EosInstaller::new(serial, base_url()?, device.codename.clone(), need_channel()?.id.clone())
+            .mirrors(distro.image_hosts())
+            .checksum_algorithm(distro.checksum_algorithm)
+            .download_only(download_only)
+            .spawn(),
+        "mobian" => MobianInstaller::new(
+            serial,
+            base_url()?,
+            need_interface()?,
+            distro.chipset.clone().unwrap_or_else(|| "sdm670".to_string()),
+            distro.device_model.clone().unwrap_or_else(|| device.codename.clone()),
+        )
+        .mirrors(distro.image_hosts())
+        .checksum_algorithm(distro.checksum_algorithm)
+        .download_only(download_only)
+        .spawn(),
+        other => bail!("No installer backend for \"{}\"", other),
+    };
+    Ok(receiver)
+}
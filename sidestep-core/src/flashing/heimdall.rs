@@ -0,0 +1,217 @@
+// Samsung installer: Odin archives written with Heimdall from Download Mode
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::cache;
+use crate::flashing::checksum::ChecksumVerifier;
+use crate::flashing::decompressor::{Decompressor, unpack_cancellable};
+use crate::flashing::progress::{self, InstallProgress};
+use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
+use crate::hardware::heimdall::{DOWNLOAD_MODE_SERIAL, Heimdall};
+use crate::hardware::reboot_window::RebootWindow;
+use crate::models::distro_config::ArchiveEntry;
+use crate::models::samsung::{self, HeimdallImage, OdinSection};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/// How long the user has to get the device into Download Mode
+const DOWNLOAD_MODE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What to do when the device has to be put in Download Mode by hand
+const DOWNLOAD_MODE_INSTRUCTIONS: &str = "Power the device off, then hold Volume Down + Home + Power until a warning screen appears. Press Volume Up to enter Download Mode.";
+
+/// Flashes Samsung firmware packages (`AP_…tar.md5`, `BL_…`, `CP_…`,
+/// `CSC_…`, or a plain `.tar`) the way Odin would.
+///
+/// 1. Check the MD5 each `.tar.md5` carries
+/// 2. Unpack the images the device's table knows from every archive
+/// 3. Get the device into Download Mode, from adb when it's booted
+/// 4. Write everything in one `heimdall flash` session
+///
+/// The device is left in Download Mode, since booting stock Android
+/// straight after replaces a custom recovery with Samsung's.
+pub struct HeimdallInstaller {
+    serial: String,
+    codename: String,
+    archives: Vec<PathBuf>,
+    work_dir: PathBuf,
+    cancel: CancelToken,
+}
+
+impl HeimdallInstaller {
+    /// `serial` is the device's adb serial when it's booted, or
+    /// [`DOWNLOAD_MODE_SERIAL`] when it's already in Download Mode.
+    pub fn new(serial: String, codename: String, archives: Vec<PathBuf>) -> Self {
+        Self {
+            serial,
+            codename,
+            archives,
+            work_dir: cache::installer_dir("heimdall"),
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Let the UI stop the whole install. The heimdall or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Spawn the installer on a background thread, returning immediately.
+    pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(async {
+                if let Err(e) = self.run(&sender).await {
+                    log::error!("Heimdall installation failed: {:#}", e);
+                    let _ = sender.send(InstallProgress::from_error(&e));
+                }
+            });
+        });
+
+        receiver
+    }
+
+    async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
+        let table = samsung::images_for(&self.codename)
+            .with_context(|| format!("There's no Heimdall partition table for {}", self.codename))?;
+        let heimdall = Heimdall::new().with_cancel(self.cancel.clone());
+
+        // ── Step 1: Check the archives' own MD5s ──
+        let total = self.archives.len();
+        for (index, archive) in self.archives.iter().enumerate() {
+            let file_name = file_name(archive);
+            let _ = sender.send(InstallProgress::VerifyProgress {
+                verified: index,
+                total,
+                file_name: file_name.clone(),
+            });
+            let path = archive.clone();
+            let verified = tokio::task::spawn_blocking(move || ChecksumVerifier::check_tar_md5(&path))
+                .await
+                .context("Checksum task failed")??;
+            if !verified {
+                log::info!("{} carries no MD5; flashing it unchecked", file_name);
+            }
+        }
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: total,
+            total,
+            file_name: String::new(),
+        });
+
+        // ── Step 2: Unpack the images ──
+        let mut images: Vec<(String, PathBuf)> = Vec::new();
+        for archive in &self.archives {
+            self.cancel.check()?;
+            let file_name = file_name(archive);
+            let entries = archive_entries(table, OdinSection::from_archive_name(&file_name));
+            let dest = self.work_dir.join(file_name.split('.').next().unwrap_or("archive"));
+            let _ = sender.send(InstallProgress::StatusChanged(format!("Unpacking {}...", file_name)));
+            let on_progress = progress::report_decompress(sender, &file_name);
+            let path = archive.clone();
+            let extracted = unpack_cancellable(&self.cancel, move |cancel| {
+                Decompressor::extract_partitions(&path, &dest, &entries, on_progress, Some(cancel))
+            })
+            .await?;
+            for (partition, image) in extracted {
+                images.retain(|(p, _)| *p != partition);
+                images.push((partition, image));
+            }
+        }
+        if images.is_empty() {
+            anyhow::bail!(
+                "None of the archives contain images Sidestep knows how to flash on {}",
+                self.codename
+            );
+        }
+
+        // ── Step 3: Download Mode ──
+        self.enter_download_mode(&heimdall, sender).await?;
+
+        // ── Step 4: Flash ──
+        let names: Vec<&str> = images.iter().map(|(partition, _)| partition.as_str()).collect();
+        let _ = sender.send(InstallProgress::FlashProgress {
+            current: 1,
+            total: 1,
+            description: format!("Writing {} with Heimdall...", names.join(", ")),
+        });
+        let files: Vec<(&str, &Path)> = images
+            .iter()
+            .map(|(partition, image)| (partition.as_str(), image.as_path()))
+            .collect();
+        heimdall
+            .flash(&files, |partition, percent| {
+                let _ = sender.send(InstallProgress::PartitionProgress {
+                    partition: partition.to_string(),
+                    chunk: 1,
+                    chunks: 1,
+                    percent,
+                });
+            })
+            .await
+            .context("Failed to flash with Heimdall")?;
+        let _ = std::fs::remove_dir_all(&self.work_dir);
+
+        // ── Done ──
+        let _ = sender.send(InstallProgress::WaitingForUserAction(
+            "Flashing complete. Hold Volume Down + Power to leave Download Mode, then straight away hold Volume Up + Home + Power to boot into the new recovery.".into(),
+        ));
+        let _ = sender.send(InstallProgress::Complete);
+        Ok(())
+    }
+
+    /// Reboot a device booted into Android to Download Mode, or ask for it
+    /// by hand, and wait until Heimdall sees it
+    async fn enter_download_mode(&self, heimdall: &Heimdall, sender: &Sender<InstallProgress>) -> Result<()> {
+        if heimdall.detect().await.unwrap_or(false) {
+            return Ok(());
+        }
+
+        let booted = self.serial != DOWNLOAD_MODE_SERIAL;
+        let _window = booted.then(|| RebootWindow::open(&self.serial, "Download Mode"));
+        if booted {
+            let _ = sender.send(InstallProgress::StatusChanged("Rebooting to Download Mode...".into()));
+            let adb = Adb::new().with_cancel(self.cancel.clone());
+            if let Err(e) = adb.reboot_download(&self.serial).await {
+                log::warn!("adb reboot download failed: {:#}", e);
+            }
+        }
+
+        let _ = sender.send(InstallProgress::WaitingForUserAction(format!(
+            "Waiting for Download Mode. If the device doesn't get there by itself: {}",
+            DOWNLOAD_MODE_INSTRUCTIONS
+        )));
+        heimdall.wait_for_device(DOWNLOAD_MODE_TIMEOUT).await
+    }
+}
+
+/// Which images to take from an archive of `section`. Images are optional
+/// since firmware packages differ in what they carry; an archive whose
+/// section isn't recognised from its name is searched for every image.
+fn archive_entries(table: &[HeimdallImage], section: Option<OdinSection>) -> Vec<ArchiveEntry> {
+    table
+        .iter()
+        .filter(|image| section.is_none_or(|section| image.section == section))
+        .map(|image| ArchiveEntry {
+            file: image.file.to_string(),
+            partition: image.partition.to_string(),
+            optional: true,
+        })
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
@@ -0,0 +1,149 @@
+// Install logs on disk, one file per run
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::config;
+use crate::flashing::{EventBus, InstallEvent, InstallProgress, InstallReport};
+use crate::utils::session_log;
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Older logs are deleted when a run starts past this many
+const KEEP_LOGS: usize = 10;
+
+/// Where install logs are written
+pub fn logs_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("sidestep")
+        .join("logs")
+}
+
+/// The newest install log, if any run has written one
+pub fn latest() -> Option<PathBuf> {
+    logs().pop()
+}
+
+/// Start writing the log of the run `bus` carries: a header from `report`
+/// (device, distro and the options chosen), then every line the session log
+/// keeps, which includes each adb and fastboot command and its output, and
+/// the run's progress events. Writing stops when the run finishes.
+///
+/// Must be called before the bus starts forwarding, so no event is missed.
+pub fn start(report: &InstallReport, bus: &EventBus) -> Result<PathBuf> {
+    let dir = logs_dir();
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let old = logs();
+    for stale in old.iter().take((old.len() + 1).saturating_sub(KEEP_LOGS)) {
+        let _ = std::fs::remove_file(stale);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let codename = report.device.as_ref().map_or("device", |d| d.codename.as_str());
+    let path = dir.join(format!("install-{}-{}.log", file_timestamp(now), codename));
+
+    let mut header = String::new();
+    let _ = writeln!(header, "Sidestep {} install log", config::VERSION);
+    let _ = writeln!(header, "Started: {} UTC", readable_timestamp(now));
+    if let Some(ref device) = report.device {
+        let _ = writeln!(header, "Device: {} ({})", device.name, device.codename);
+    }
+    if let Some(ref serial) = report.serial {
+        let _ = writeln!(header, "Serial: {}", serial);
+    }
+    let _ = writeln!(header, "Distro: {}", report.distro);
+    for (label, value) in &report.options {
+        let _ = writeln!(header, "{}: {}", label, value);
+    }
+    let _ = writeln!(header);
+    session_log::tee_to(&path, &header).with_context(|| format!("Failed to create {}", path.display()))?;
+
+    log_progress(bus);
+    Ok(path)
+}
+
+/// Log the events [`EventBus::log_events`] leaves out, the progress ones
+/// once per whole percent, and close the file at the end of the run
+fn log_progress(bus: &EventBus) {
+    let events = bus.subscribe();
+    std::thread::spawn(move || {
+        let mut last_percent = None;
+        for event in events.iter() {
+            match event {
+                InstallEvent::Progress(
+                    InstallProgress::StatusChanged(_) | InstallProgress::FlashProgress { .. },
+                ) => {}
+                InstallEvent::Progress(progress) => {
+                    if let Some((phase, fraction)) = progress.phase_fraction() {
+                        let percent = Some((phase, (fraction * 100.0) as u32));
+                        if percent == last_percent {
+                            continue;
+                        }
+                        last_percent = percent;
+                    }
+                    log::debug!("Install event: {:?}", progress);
+                }
+                InstallEvent::Finished(_) => break,
+            }
+        }
+        session_log::end_tee();
+    });
+}
+
+/// Install logs on disk, oldest first. The timestamp in the name sorts them.
+fn logs() -> Vec<PathBuf> {
+    let mut logs: Vec<PathBuf> = std::fs::read_dir(logs_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("install-") && n.ends_with(".log"))
+        })
+        .collect();
+    logs.sort();
+    logs
+}
+
+/// `secs` since the epoch as a UTC date and time: year, month, day, hour,
+/// minute, second
+fn civil_time(secs: u64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = (secs / 86_400) as i64;
+    let of_day = secs % 86_400;
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (
+        year,
+        month,
+        day,
+        (of_day / 3600) as u32,
+        (of_day % 3600 / 60) as u32,
+        (of_day % 60) as u32,
+    )
+}
+
+/// "20261014-093012", for file names
+fn file_timestamp(secs: u64) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(secs);
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, hour, minute, second)
+}
+
+/// "2026-10-14 09:30:12"
+fn readable_timestamp(secs: u64) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(secs);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, hour, minute, second)
+}
@@ -0,0 +1,82 @@
+// The last install started on each device, for recovering from it
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "last-installs.json";
+
+/// What was last installed on a device, recorded when the flash starts so
+/// an interrupted one can be run again from scratch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastInstall {
+    pub distro_id: String,
+    /// Channel id, for distros that have channels
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Interface id, for distros chosen by interface alone (Mobian)
+    #[serde(default)]
+    pub interface: Option<String>,
+    /// False until the install finished; an unfinished one usually
+    /// leaves the device stuck in fastboot.
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl LastInstall {
+    pub fn new(distro_id: &str, channel: Option<&str>, interface: Option<&str>) -> Self {
+        Self {
+            distro_id: distro_id.to_string(),
+            channel: channel.map(str::to_string),
+            interface: interface.map(str::to_string),
+            completed: false,
+        }
+    }
+
+    fn path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join("sidestep")
+            .join(FILE_NAME)
+    }
+
+    fn load_all() -> HashMap<String, LastInstall> {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_all(installs: &HashMap<String, LastInstall>) -> Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create the data directory")?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(installs)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The last install started on `codename`, if any.
+    pub fn load(codename: &str) -> Option<Self> {
+        Self::load_all().remove(codename)
+    }
+
+    /// Remember this as the install now starting on `codename`.
+    pub fn record(&self, codename: &str) -> Result<()> {
+        let mut installs = Self::load_all();
+        installs.insert(codename.to_string(), self.clone());
+        Self::save_all(&installs)
+    }
+
+    /// Mark the last install on `codename` as finished.
+    pub fn mark_completed(codename: &str) -> Result<()> {
+        let mut installs = Self::load_all();
+        match installs.get_mut(codename) {
+            Some(install) if !install.completed => install.completed = true,
+            _ => return Ok(()),
+        }
+        Self::save_all(&installs)
+    }
+}
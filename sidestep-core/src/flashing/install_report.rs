@@ -0,0 +1,89 @@
+// A self-contained report of one install, for sharing by hand
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::{InstallOutcome, SessionStats};
+use crate::hardware::{device_report, mode_guard};
+use crate::models::Device;
+use crate::utils::session_log;
+use std::fmt::Write;
+
+/// What the user picked and how the run ended. Rendering it adds the
+/// device diagnostics and this session's log; nothing is sent anywhere.
+#[derive(Debug, Clone, Default)]
+pub struct InstallReport {
+    pub distro: String,
+    /// Choices made for this run, as label and value ("Channel", "stable")
+    pub options: Vec<(String, String)>,
+    pub outcome: Option<InstallOutcome>,
+    pub serial: Option<String>,
+    pub device: Option<Device>,
+    /// Download and flash figures, once the run has ended
+    pub stats: Option<SessionStats>,
+}
+
+impl InstallReport {
+    /// The report as Markdown. The device is probed again if it's still
+    /// connected; IMEIs are masked throughout.
+    pub async fn render(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "# Sidestep install report");
+        let _ = writeln!(report);
+        let _ = writeln!(report, "Distro: {}", self.distro);
+        let _ = writeln!(report, "Outcome: {}", describe_outcome(self.outcome.as_ref()));
+        for (label, value) in &self.options {
+            let _ = writeln!(report, "{}: {}", label, value);
+        }
+        let _ = writeln!(report);
+
+        let stats = self.stats.as_ref().map(SessionStats::rows).unwrap_or_default();
+        if !stats.is_empty() {
+            let _ = writeln!(report, "## Session statistics");
+            for (label, value) in stats {
+                let _ = writeln!(report, "{}: {}", label, value);
+            }
+            let _ = writeln!(report);
+        }
+
+        let mode = match self.serial {
+            Some(ref serial) => mode_guard::current_mode(serial).await,
+            None => None,
+        };
+        let diagnostics = device_report::collect(self.serial.clone(), mode, self.device.clone()).await;
+        // The device report brings its own "##" heading
+        report.push_str(diagnostics.trim_end());
+        let _ = writeln!(report, "\n");
+
+        let _ = writeln!(report, "## Session log");
+        let _ = writeln!(report, "```");
+        for line in session_log::lines() {
+            let _ = writeln!(report, "{}", line);
+        }
+        let _ = writeln!(report, "```");
+
+        device_report::redact(&report)
+    }
+
+    /// A file name for saving the report, such as
+    /// `sidestep-report-sargo-postmarketos.md`
+    pub fn file_name(&self) -> String {
+        let codename = self.device.as_ref().map_or("device", |d| d.codename.as_str());
+        let distro = self
+            .distro
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        format!("sidestep-report-{}-{}.md", codename, distro)
+    }
+}
+
+fn describe_outcome(outcome: Option<&InstallOutcome>) -> String {
+    match outcome {
+        Some(InstallOutcome::Installed) => "Installed".to_string(),
+        Some(InstallOutcome::Downloaded(dir)) => format!("Downloaded to {}", dir.display()),
+        Some(InstallOutcome::BootedTemporarily) => "Booted temporarily".to_string(),
+        Some(InstallOutcome::Cancelled) => "Cancelled".to_string(),
+        Some(InstallOutcome::Failed(message)) => format!("Failed: {}", message),
+        None => "Still running".to_string(),
+    }
+}
@@ -1,32 +1,36 @@
 // LineageOS installer backend
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::flashing::checksum::ChecksumVerifier;
-use crate::flashing::downloader::ImageDownloader;
-use crate::flashing::progress::InstallProgress;
+use crate::flashing::cache;
+use crate::flashing::checksum::{ChecksumAlgorithm, ChecksumVerifier};
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::mode_wait;
+use crate::flashing::progress::{self, InstallProgress};
+use crate::flashing::recovery::RecoveryFlash;
 use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
 use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
 use std::time::Duration;
 
 /// LineageOS build metadata from the API
 #[derive(Debug, Deserialize)]
-struct LineageBuild {
-    #[allow(dead_code)]
-    version: String,
-    files: Vec<LineageFile>,
+pub(crate) struct LineageBuild {
+    pub(crate) version: String,
+    pub(crate) files: Vec<LineageFile>,
 }
 
 /// A single file within a LineageOS build
 #[derive(Debug, Deserialize)]
-struct LineageFile {
+pub(crate) struct LineageFile {
     filename: String,
     url: String,
     sha256: String,
-    size: u64,
+    pub(crate) size: u64,
 }
 
 /// Orchestrates LineageOS installation for supported devices.
@@ -56,27 +60,57 @@ pub struct LineageosInstaller {
     api_url: String,
     update_only: bool,
     download_dir: PathBuf,
+    download_only: bool,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
 }
 
 impl LineageosInstaller {
+    /// Starts from Android or straight from the bootloader
+    pub const REQUIRED_MODE: StartMode = StartMode::AdbOrFastboot;
+
     pub fn new(
         serial: String,
         api_url: String,
         update_only: bool,
     ) -> Self {
-        let download_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("sidestep")
-            .join("lineageos");
+        let download_dir = cache::installer_dir("lineageos");
 
         Self {
             serial,
             api_url,
             update_only,
             download_dir,
+            download_only: false,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
         }
     }
 
+    /// Where downloaded images are cached
+    pub fn download_dir(&self) -> &Path {
+        &self.download_dir
+    }
+
+    /// Stop after downloading and verifying images, leaving them in the cache.
+    pub fn download_only(mut self, enabled: bool) -> Self {
+        self.download_only = enabled;
+        self
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
     /// Spawn the installer on a background thread, returning immediately.
     /// Progress is reported via the returned mpsc::Receiver.
     pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
@@ -91,7 +125,7 @@ impl LineageosInstaller {
             rt.block_on(async {
                 if let Err(e) = self.run(&sender).await {
                     log::error!("LineageOS installation failed: {:#}", e);
-                    let _ = sender.send(InstallProgress::Error(format!("{:#}", e)));
+                    let _ = sender.send(InstallProgress::from_error(&e));
                 }
             });
         });
@@ -101,9 +135,10 @@ impl LineageosInstaller {
 
     /// Main installation flow (runs on background thread)
     async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
-        let downloader = ImageDownloader::new(self.download_dir.clone());
-        let adb = Adb::new();
-        let fastboot = Fastboot::new();
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone());
+        let adb = Adb::new().with_cancel(self.cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
 
         // ── Step 1: Fetch latest build from API ──
         let _ = sender.send(InstallProgress::StatusChanged(
@@ -137,25 +172,19 @@ impl LineageosInstaller {
         }
 
         // ── Step 3: Download files ──
+        let downloads = if !self.update_only && boot_file.is_some() { 2 } else { 1 };
         if !self.update_only {
             if let Some(boot) = boot_file {
                 // Download boot.img
                 let _ = sender.send(InstallProgress::StatusChanged(
                     "Downloading boot image...".into(),
                 ));
-                let sender_clone = sender.clone();
                 downloader
                     .download_if_needed(
                         &boot.url,
                         &boot.filename,
                         Some(&boot.sha256),
-                        Some(Box::new(move |downloaded, total| {
-                            let _ = sender_clone.send(InstallProgress::DownloadProgress {
-                                downloaded,
-                                total,
-                                file_name: "Boot image".into(),
-                            });
-                        })),
+                        progress::report_download(sender, "Boot image", 1, downloads),
                     )
                     .await
                     .context("Failed to download boot image")?;
@@ -166,19 +195,12 @@ impl LineageosInstaller {
         let _ = sender.send(InstallProgress::StatusChanged(
             "Downloading LineageOS ROM...".into(),
         ));
-        let sender_clone = sender.clone();
         let zip_path = downloader
             .download_if_needed(
                 &zip_file.url,
                 &zip_file.filename,
                 Some(&zip_file.sha256),
-                Some(Box::new(move |downloaded, total| {
-                    let _ = sender_clone.send(InstallProgress::DownloadProgress {
-                        downloaded,
-                        total,
-                        file_name: "LineageOS ROM".into(),
-                    });
-                })),
+                progress::report_download(sender, "LineageOS ROM", downloads, downloads),
             )
             .await
             .context("Failed to download LineageOS ROM")?;
@@ -195,10 +217,7 @@ impl LineageosInstaller {
                     file_name: "Verifying boot image".into(),
                 });
                 let boot_path = self.download_dir.join(&boot.filename);
-                let ok = ChecksumVerifier::verify(&boot_path, &boot.sha256)?;
-                if !ok {
-                    anyhow::bail!("Checksum mismatch for boot image");
-                }
+                ChecksumVerifier::check(&boot_path, &boot.sha256, Some(ChecksumAlgorithm::Sha256))?;
                 verified += 1;
             }
         }
@@ -208,10 +227,7 @@ impl LineageosInstaller {
             total: verify_count,
             file_name: "Verifying ROM zip".into(),
         });
-        let ok = ChecksumVerifier::verify(&zip_path, &zip_file.sha256)?;
-        if !ok {
-            anyhow::bail!("Checksum mismatch for LineageOS ROM zip");
-        }
+        ChecksumVerifier::check(&zip_path, &zip_file.sha256, Some(ChecksumAlgorithm::Sha256))?;
         verified += 1;
         let _ = sender.send(InstallProgress::VerifyProgress {
             verified,
@@ -219,6 +235,13 @@ impl LineageosInstaller {
             file_name: "All checksums verified".into(),
         });
 
+        // Download-only mode stops here, leaving the verified images cached
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
+        let recovery = RecoveryFlash::new(&self.serial, &adb, &fastboot, sender);
         if self.update_only {
             // ── Update flow: reboot straight to recovery ──
             let _ = sender.send(InstallProgress::StatusChanged(
@@ -230,55 +253,22 @@ impl LineageosInstaller {
                     e
                 );
             }
+            recovery.wait_for_recovery().await?;
         } else {
             // ── Fresh install: reboot to bootloader → flash boot → reboot to recovery ──
-            let _ = sender.send(InstallProgress::StatusChanged(
-                "Rebooting to bootloader...".into(),
-            ));
-            if let Err(e) = adb.reboot_bootloader(&self.serial).await {
-                log::warn!(
-                    "ADB reboot-bootloader failed (device may already be in fastboot): {}",
-                    e
-                );
-            }
-
-            // Wait for fastboot
-            let _ = sender.send(InstallProgress::StatusChanged(
-                "Waiting for device in fastboot mode...".into(),
-            ));
-            self.wait_for_fastboot(&fastboot).await?;
+            recovery.enter_bootloader().await?;
 
             // Flash boot.img (installs LineageOS recovery)
             if let Some(boot) = boot_file {
                 let boot_path = self.download_dir.join(&boot.filename);
-                let _ = sender.send(InstallProgress::FlashProgress {
-                    current: 1,
-                    total: 2,
-                    description: "Flashing LineageOS recovery (boot.img)...".into(),
-                });
-                fastboot
-                    .flash(&self.serial, "boot", &boot_path)
-                    .await
-                    .context("Failed to flash boot image")?;
+                recovery
+                    .flash_to("boot", &boot_path, "LineageOS recovery", (1, 2))
+                    .await?;
             }
 
-            // Reboot to recovery
-            let _ = sender.send(InstallProgress::StatusChanged(
-                "Rebooting to recovery...".into(),
-            ));
-            fastboot.reboot_recovery(&self.serial).await?;
+            recovery.boot_recovery().await?;
         }
 
-        // ── Wait for recovery mode ──
-        let _ = sender.send(InstallProgress::StatusChanged(
-            "Waiting for recovery mode...".into(),
-        ));
-        adb.wait_for_recovery(&self.serial).await?;
-        let _ = sender.send(InstallProgress::RecoveryDetected);
-
-        // Give recovery a moment to fully initialize
-        tokio::time::sleep(Duration::from_secs(3)).await;
-
         // ── Prompt: Factory reset (fresh install only) ──
         if !self.update_only {
             let _ = sender.send(InstallProgress::StatusChanged(
@@ -291,17 +281,15 @@ impl LineageosInstaller {
             // Wait for the user to perform the factory reset in recovery.
             // After a factory reset, recovery restarts — we wait for it to reappear.
             tokio::time::sleep(Duration::from_secs(5)).await;
-            adb.wait_for_recovery(&self.serial).await?;
+            mode_wait::wait_for_recovery(&adb, &self.serial, sender).await?;
             tokio::time::sleep(Duration::from_secs(3)).await;
         }
 
         // ── Prompt: Apply from ADB ──
-        let _ = sender.send(InstallProgress::WaitingForUserAction(
-            "On your phone: Select \"Apply update\" → \"Apply from ADB\"".into(),
-        ));
-
-        // Give the user time to navigate the recovery menu
-        tokio::time::sleep(Duration::from_secs(5)).await;
+        // Wait until the user has actually opened "Apply from ADB"
+        recovery
+            .wait_for_sideload("On your phone: Select \"Apply update\" → \"Apply from ADB\"")
+            .await?;
 
         // ── Sideload the ROM zip ──
         let sideload_step = if self.update_only { 1 } else { 2 };
@@ -311,7 +299,16 @@ impl LineageosInstaller {
             total: sideload_total,
             description: "Sideloading LineageOS ROM...".into(),
         });
-        adb.sideload(&self.serial, &zip_path)
+        let file_name = zip_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        adb.sideload(&self.serial, &zip_path, |percent| {
+            let _ = sender.send(InstallProgress::SideloadProgress {
+                percent,
+                file_name: file_name.clone(),
+            });
+        })
             .await
             .context("Failed to sideload LineageOS ROM")?;
 
@@ -325,7 +322,7 @@ impl LineageosInstaller {
     }
 
     /// Fetch the latest build from the LineageOS API v2
-    async fn fetch_latest_build(&self) -> Result<LineageBuild> {
+    pub(crate) async fn fetch_latest_build(&self) -> Result<LineageBuild> {
         let client = reqwest::Client::builder()
             .user_agent(format!("Sidestep/{}", crate::config::VERSION))
             .build()?;
@@ -353,17 +350,4 @@ impl LineageosInstaller {
             .next()
             .ok_or_else(|| anyhow::anyhow!("No builds found in LineageOS API response"))
     }
-
-    /// Poll fastboot devices until our device appears.
-    async fn wait_for_fastboot(&self, fastboot: &Fastboot) -> Result<()> {
-        for _ in 0..60 {
-            if let Ok(devices) = fastboot.devices().await {
-                if devices.iter().any(|d| d.serial == self.serial) {
-                    return Ok(());
-                }
-            }
-            tokio::time::sleep(Duration::from_secs(2)).await;
-        }
-        anyhow::bail!("Timed out waiting for device in fastboot mode")
-    }
 }
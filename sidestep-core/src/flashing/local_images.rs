@@ -0,0 +1,274 @@
+// Local image flashing (pre-extracted images on disk)
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::executor::{DEFAULT_STALL_THRESHOLD, FlashExecutor};
+use crate::flashing::progress::{self, InstallProgress};
+use crate::models::{Device, PartitionImage};
+use crate::hardware::cancel::CancelToken;
+use crate::hardware::mode_guard::StartMode;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/// Partition names recognised from bare filenames such as `vendor_boot.img`.
+pub const COMMON_PARTITIONS: &[&str] = &[
+    "boot",
+    "init_boot",
+    "vendor_boot",
+    "recovery",
+    "dtbo",
+    "vbmeta",
+    "vbmeta_system",
+    "vbmeta_vendor",
+    "super",
+    "system",
+    "system_ext",
+    "product",
+    "vendor",
+    "odm",
+    "userdata",
+    "cache",
+    "persist",
+    "bootloader",
+    "radio",
+    "modem",
+];
+
+/// Extensions accepted as raw, flashable images.
+const IMAGE_EXTENSIONS: &[&str] = &["img", "bin", "mbn", "elf"];
+
+/// A file found in the chosen directory and the partition it will be flashed to.
+///
+/// `partition` is `None` when no confident match was found; those files are
+/// skipped unless the user assigns one.
+#[derive(Debug, Clone)]
+pub struct LocalImageMapping {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub partition: Option<String>,
+    pub erase_first: bool,
+}
+
+/// List the flashable images in `dir` and guess a target partition for each.
+///
+/// Filenames listed in `known` (the device's distro partition layouts) win,
+/// ignoring any compression suffix since the files here are already
+/// extracted. Otherwise the file stem is matched against
+/// [`COMMON_PARTITIONS`], with A/B slot suffixes stripped.
+pub fn scan_directory(dir: &Path, known: &[PartitionImage]) -> Result<Vec<LocalImageMapping>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?;
+
+    let mut mappings = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let is_image = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+        if !is_image {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let (partition, erase_first) = match match_known(&file_name, known) {
+            Some(image) => (Some(image.partition.clone()), image.erase_first),
+            None => (match_common(&file_name), false),
+        };
+
+        mappings.push(LocalImageMapping {
+            path,
+            file_name,
+            partition,
+            erase_first,
+        });
+    }
+
+    if mappings.is_empty() {
+        anyhow::bail!("No image files (.img, .bin) found in {}", dir.display());
+    }
+
+    // Stable ordering: matched files first in layout order, then the rest by name
+    mappings.sort_by_key(|m| {
+        let rank = m
+            .partition
+            .as_ref()
+            .and_then(|p| known.iter().position(|k| &k.partition == p))
+            .unwrap_or(known.len());
+        (m.partition.is_none(), rank, m.file_name.clone())
+    });
+
+    Ok(mappings)
+}
+
+fn match_known<'a>(file_name: &str, known: &'a [PartitionImage]) -> Option<&'a PartitionImage> {
+    known.iter().find(|k| {
+        let expected = k
+            .image
+            .strip_suffix(".xz")
+            .or_else(|| k.image.strip_suffix(".gz"))
+            .unwrap_or(&k.image);
+        expected == file_name
+    })
+}
+
+fn match_common(file_name: &str) -> Option<String> {
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+    let stem = stem
+        .strip_suffix("_a")
+        .or_else(|| stem.strip_suffix("_b"))
+        .unwrap_or(stem);
+    COMMON_PARTITIONS
+        .iter()
+        .find(|p| p.eq_ignore_ascii_case(stem))
+        .map(|p| p.to_string())
+}
+
+/// Flashes a confirmed set of local images, skipping download and decompress.
+pub struct LocalImageInstaller {
+    serial: String,
+    images: Vec<LocalImageMapping>,
+    stall_threshold: Duration,
+    device: Option<Device>,
+    cancel: CancelToken,
+}
+
+impl LocalImageInstaller {
+    /// Images are flashed from the bootloader, moving to fastbootd only
+    /// for logical partitions; nothing reboots the device there from
+    /// Android
+    pub const REQUIRED_MODE: StartMode = StartMode::Fastboot;
+
+    pub fn new(serial: String, images: Vec<LocalImageMapping>) -> Self {
+        Self {
+            serial,
+            images,
+            stall_threshold: DEFAULT_STALL_THRESHOLD,
+            device: None,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// How long a flash may go silent before it is reported as stalled.
+    pub fn stall_threshold(mut self, threshold: Duration) -> Self {
+        self.stall_threshold = threshold;
+        self
+    }
+
+    /// Abort before the first write if the connected phone isn't `device`.
+    pub fn confirmed_device(mut self, device: Device) -> Self {
+        self.device = Some(device);
+        self
+    }
+
+    /// Stop between partitions, killing the flash in progress, once
+    /// `handle` is cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Spawn the installer on a background thread, returning immediately.
+    pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(async {
+                if let Err(e) = self.run(&sender).await {
+                    log::error!("Local image flashing failed: {:#}", e);
+                    let _ = sender.send(InstallProgress::from_error(&e));
+                }
+            });
+        });
+
+        receiver
+    }
+
+    async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
+        let stall_sender = sender.clone();
+        let step_sender = sender.clone();
+        let retry_sender = sender.clone();
+        let mut executor = FlashExecutor::new()
+            .with_cancel(self.cancel.clone())
+            .with_stall_threshold(self.stall_threshold)
+            .on_stall(Box::new(move |partition, silent| {
+                let _ = stall_sender.send(InstallProgress::TransferStalled {
+                    partition: partition.to_string(),
+                    silent_secs: silent.as_secs(),
+                });
+            }))
+            .on_step(Box::new(move |partition, step| {
+                let _ = step_sender.send(InstallProgress::PartitionProgress {
+                    partition: partition.to_string(),
+                    chunk: step.chunk,
+                    chunks: step.chunks,
+                    percent: step.percent,
+                });
+            }))
+            .on_retry(Box::new(move |partition, attempt, attempts, reason| {
+                let _ = retry_sender.send(InstallProgress::FlashRetrying {
+                    partition: partition.to_string(),
+                    attempt,
+                    attempts,
+                    reason: reason.to_string(),
+                });
+            }))
+            .on_unpack(progress::report_unpack(sender));
+        if let Some(ref device) = self.device {
+            executor = executor.confirmed_device(&self.serial, device);
+        }
+        let images: Vec<&LocalImageMapping> =
+            self.images.iter().filter(|m| m.partition.is_some()).collect();
+        if images.is_empty() {
+            anyhow::bail!("No images are assigned to a partition");
+        }
+
+        // Check every file before touching the device, so a vanished image
+        // can't leave an erase_first partition empty halfway through
+        if let Some(missing) = images.iter().find(|m| !m.path.is_file()) {
+            anyhow::bail!("Image not found: {}", missing.path.display());
+        }
+
+        let total = images.len() + 1;
+        for (index, mapping) in images.iter().enumerate() {
+            let partition = mapping.partition.clone().unwrap_or_default();
+            let _ = sender.send(InstallProgress::FlashProgress {
+                current: index + 1,
+                total,
+                description: format!("Flashing {} → {}...", mapping.file_name, partition),
+            });
+
+            let image = PartitionImage {
+                partition,
+                image: mapping.file_name.clone(),
+                erase_first: mapping.erase_first,
+                aliases: Vec::new(),
+            };
+            let images_dir = mapping.path.parent().unwrap_or(Path::new("/"));
+            executor
+                .flash_partition(&self.serial, &image, images_dir)
+                .await
+                .with_context(|| format!("Failed to flash {}", mapping.file_name))?;
+        }
+
+        let _ = sender.send(InstallProgress::FlashProgress {
+            current: total,
+            total,
+            description: "Rebooting device...".into(),
+        });
+        executor.reboot(&self.serial).await?;
+
+        let _ = sender.send(InstallProgress::Complete);
+        Ok(())
+    }
+}
@@ -1,15 +1,18 @@
 // Mobian installer backend
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::flashing::checksum::ChecksumVerifier;
-use crate::flashing::downloader::ImageDownloader;
-use crate::flashing::progress::InstallProgress;
+use crate::flashing::cache;
+use crate::flashing::checksum::{ChecksumAlgorithm, ChecksumVerifier};
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::mode_wait;
+use crate::flashing::progress::{self, InstallProgress};
 use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
 use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
-use std::time::Duration;
 
 /// Orchestrates Mobian installation for Qualcomm-based devices.
 ///
@@ -28,9 +31,17 @@ pub struct MobianInstaller {
     device_model: String,
     chipset: String,
     download_dir: PathBuf,
+    download_only: bool,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
+    checksum_algorithm: Option<ChecksumAlgorithm>,
+    mirrors: Vec<String>,
 }
 
 impl MobianInstaller {
+    /// Starts from Android or straight from the bootloader
+    pub const REQUIRED_MODE: StartMode = StartMode::AdbOrFastboot;
+
     pub fn new(
         serial: String,
         base_url: String,
@@ -38,10 +49,7 @@ impl MobianInstaller {
         chipset: String,
         device_model: String,
     ) -> Self {
-        let download_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("sidestep")
-            .join("mobian");
+        let download_dir = cache::installer_dir("mobian");
 
         Self {
             serial,
@@ -50,9 +58,52 @@ impl MobianInstaller {
             device_model,
             chipset,
             download_dir,
+            download_only: false,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
+            checksum_algorithm: None,
+            mirrors: Vec::new(),
         }
     }
 
+    /// Where downloaded images are cached
+    pub fn download_dir(&self) -> &Path {
+        &self.download_dir
+    }
+
+    /// Stop after downloading and verifying images, leaving them in the cache.
+    pub fn download_only(mut self, enabled: bool) -> Self {
+        self.download_only = enabled;
+        self
+    }
+
+    /// Hash function the distro publishes its digests with, for when
+    /// their length can't tell (BLAKE3 and SHA256 are both 64 hex digits)
+    pub fn checksum_algorithm(mut self, algorithm: Option<ChecksumAlgorithm>) -> Self {
+        self.checksum_algorithm = algorithm;
+        self
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Hosts serving the same tree as `base_url`, tried in order when it
+    /// fails while downloading
+    pub fn mirrors(mut self, mirrors: Vec<String>) -> Self {
+        self.mirrors = mirrors;
+        self
+    }
+
     /// Spawn the installer on a background thread, returning immediately.
     /// Progress is reported via the returned mpsc::Receiver.
     pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
@@ -67,7 +118,7 @@ impl MobianInstaller {
             rt.block_on(async {
                 if let Err(e) = self.run(&sender).await {
                     log::error!("Mobian installation failed: {:#}", e);
-                    let _ = sender.send(InstallProgress::Error(format!("{:#}", e)));
+                    let _ = sender.send(InstallProgress::from_error(&e));
                 }
             });
         });
@@ -77,9 +128,11 @@ impl MobianInstaller {
 
     /// Main installation flow (runs on background thread)
     async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
-        let downloader = ImageDownloader::new(self.download_dir.clone());
-        let adb = Adb::new();
-        let fastboot = Fastboot::new();
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone())
+            .with_mirrors(&self.base_url, &self.mirrors);
+        let adb = Adb::new().with_cancel(self.cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
 
         // ── Step 1: Discover latest image ──
         let _ = sender.send(InstallProgress::StatusChanged(
@@ -88,55 +141,46 @@ impl MobianInstaller {
         let (tar_name, tar_url) = self.discover_latest_image().await?;
         log::info!("Found latest Mobian image: {}", tar_name);
 
-        // ── Step 2: Download checksums ──
-        let _ = sender.send(InstallProgress::StatusChanged(
-            "Downloading checksums...".into(),
-        ));
-        let checksums_url = format!("{}{}.sha256sums", self.base_url, tar_name);
-        let expected_hash = self
-            .download_and_parse_checksums(&downloader, &checksums_url, &tar_name)
-            .await?;
-
-        // ── Step 3: Download tar.xz ──
+        // ── Step 2: Download tar.xz, fetching its checksums alongside ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Downloading Mobian image...".into(),
         ));
-        let sender_clone = sender.clone();
-        let tar_path = downloader
-            .download_if_needed(
+        let checksums_url = format!("{}{}.sha256sums", self.base_url, tar_name);
+        let (tar_path, expected_hash) = downloader
+            .download_verified(
                 &tar_url,
                 &tar_name,
-                expected_hash.as_deref(),
-                Some(Box::new(move |downloaded, total| {
-                    let _ = sender_clone.send(InstallProgress::DownloadProgress {
-                        downloaded,
-                        total,
-                        file_name: "Mobian image".into(),
-                    });
-                })),
+                self.download_and_parse_checksums(&downloader, &checksums_url, &tar_name),
+                self.checksum_algorithm
+                    .or_else(|| ChecksumAlgorithm::from_file_name(&checksums_url)),
+                progress::report_download(sender, "Mobian image", 1, 1),
             )
             .await
             .context("Failed to download Mobian tar.xz")?;
 
-        // ── Step 4: Verify checksum ──
-        if let Some(ref hash) = expected_hash {
-            let _ = sender.send(InstallProgress::VerifyProgress {
-                verified: 0,
-                total: 1,
-                file_name: tar_name.clone(),
-            });
-            let ok = ChecksumVerifier::verify(&tar_path, hash)?;
-            if !ok {
-                anyhow::bail!("Checksum mismatch for {}", tar_name);
+        // ── Step 3: Checksum ──
+        // Verified during the download when one was published
+        match expected_hash {
+            Some(_) => {
+                let _ = sender.send(InstallProgress::VerifyProgress {
+                    verified: 1,
+                    total: 1,
+                    file_name: tar_name.clone(),
+                });
+            }
+            None => {
+                log::warn!("No checksum published for {}", tar_name);
+                progress::confirm_unverified(sender, &tar_name)?;
             }
-            let _ = sender.send(InstallProgress::VerifyProgress {
-                verified: 1,
-                total: 1,
-                file_name: tar_name.clone(),
-            });
         }
 
-        // ── Step 5: Extract tar.xz ──
+        // Download-only mode stops here, leaving the verified images cached
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
+        // ── Step 4: Extract tar.xz ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Extracting image...".into(),
         ));
@@ -149,7 +193,7 @@ impl MobianInstaller {
         log::info!("Boot image: {}", boot_img.display());
         log::info!("Rootfs image: {}", rootfs_img.display());
 
-        // ── Step 6: Reboot to bootloader ──
+        // ── Step 5: Reboot to bootloader ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Rebooting to bootloader...".into(),
         ));
@@ -160,13 +204,13 @@ impl MobianInstaller {
             );
         }
 
-        // ── Step 7: Wait for fastboot device ──
+        // ── Step 6: Wait for fastboot device ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Waiting for device in fastboot mode...".into(),
         ));
-        self.wait_for_fastboot(&fastboot).await?;
+        mode_wait::wait_for_fastboot(&fastboot, &self.serial, sender).await?;
 
-        // ── Step 8: Flash partitions ──
+        // ── Step 7: Flash partitions ──
         let total_steps = 5;
 
         // 8a: Flash boot
@@ -176,7 +220,7 @@ impl MobianInstaller {
             description: "Flashing boot...".into(),
         });
         fastboot
-            .flash(&self.serial, "boot", &boot_img)
+            .flash_all_slots(&self.serial, "boot", &boot_img)
             .await
             .context("Failed to flash boot")?;
 
@@ -224,7 +268,7 @@ impl MobianInstaller {
             .await
             .context("Failed to run oem uart enable")?;
 
-        // ── Step 9: Reboot ──
+        // ── Step 8: Reboot ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Rebooting device...".into(),
         ));
@@ -311,14 +355,16 @@ impl MobianInstaller {
     }
 
     /// Download SHA256SUMS and extract the hash for the given tar filename.
+    /// Mobian may publish no sums for an image, but a sums file without
+    /// one for it is a [`ChecksumNotFound`](crate::flashing::ChecksumNotFound).
     async fn download_and_parse_checksums(
         &self,
         downloader: &ImageDownloader,
         checksums_url: &str,
         tar_name: &str,
     ) -> Result<Option<String>> {
-        match downloader.download_checksums(checksums_url).await {
-            Ok(checksums) => Ok(checksums.get(tar_name).cloned()),
+        match downloader.download_sums(checksums_url).await {
+            Ok(sums) => ChecksumVerifier::from_sums_file(&sums, tar_name).map(Some),
             Err(e) => {
                 log::warn!("Failed to download checksums (continuing without): {}", e);
                 Ok(None)
@@ -341,8 +387,7 @@ impl MobianInstaller {
         let xz_reader = xz2::read::XzDecoder::new(file);
         let mut archive = tar::Archive::new(xz_reader);
 
-        archive
-            .unpack(extract_dir)
+        crate::flashing::archive::unpack_tar(&mut archive, extract_dir)
             .context("Failed to extract tar.xz archive")?;
 
         log::info!("Extracted tar.xz to {}", extract_dir.display());
@@ -369,19 +414,6 @@ impl MobianInstaller {
             extract_dir.display()
         )
     }
-
-    /// Poll fastboot devices until our device appears.
-    async fn wait_for_fastboot(&self, fastboot: &Fastboot) -> Result<()> {
-        for _ in 0..60 {
-            if let Ok(devices) = fastboot.devices().await {
-                if devices.iter().any(|d| d.serial == self.serial) {
-                    return Ok(());
-                }
-            }
-            tokio::time::sleep(Duration::from_secs(2)).await;
-        }
-        anyhow::bail!("Timed out waiting for device in fastboot mode")
-    }
 }
 
 /// Simple recursive directory walk, returning all files (not directories).
@@ -0,0 +1,85 @@
+// Flashing engine module
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod archive;
+pub mod backup;
+pub mod cache;
+pub mod channel_info;
+pub mod downloader;
+pub mod decompressor;
+pub mod executor;
+pub mod checksum;
+pub mod events;
+pub mod progress;
+pub mod ubports;
+pub mod droidian;
+pub mod mobian;
+pub mod postmarketos;
+pub mod lineageos;
+pub mod eos;
+pub mod github_release;
+pub mod headless;
+pub mod heimdall;
+pub mod factory_image;
+pub mod flash_state;
+pub mod install_log;
+pub mod install_record;
+pub mod install_report;
+pub mod local_images;
+pub mod mode_wait;
+pub mod post_install;
+pub mod recovery;
+pub mod session_stats;
+pub mod step_times;
+pub mod temporary_boot;
+pub mod upstream_probe;
+
+pub use archive::UnsafeArchiveEntry;
+pub use backup::PartitionBackup;
+pub use downloader::{
+    available_space, set_max_bytes_per_sec, set_max_connections, DownloadCancelHandle, DownloadFailed, ImageDownloader,
+    InsufficientSpace, DEFAULT_DOWNLOAD_CHUNKS, DEFAULT_MAX_CONNECTIONS,
+};
+pub use decompressor::{Compression, DecompressedStream, Decompressor, UnsupportedFormat};
+pub use executor::{DeviceMismatch, FlashExecutor};
+pub use checksum::{ChecksumAlgorithm, ChecksumMismatch, ChecksumNotFound, ChecksumVerifier};
+pub use events::{EventBus, InstallEvent, InstallOutcome};
+pub use heimdall::HeimdallInstaller;
+pub use progress::{InstallProgress, OverallProgress, PhaseWeights, ProgressPhase, RemainingTime};
+pub use ubports::UbportsInstaller;
+pub use droidian::DroidianInstaller;
+pub use mobian::MobianInstaller;
+pub use postmarketos::{ChannelUnavailable, PostmarketosInstaller};
+pub use lineageos::LineageosInstaller;
+pub use eos::EosInstaller;
+pub use github_release::GithubReleaseSource;
+pub use factory_image::FactoryImageInstaller;
+pub use flash_state::FlashState;
+pub use install_record::LastInstall;
+pub use install_report::InstallReport;
+pub use local_images::{LocalImageInstaller, LocalImageMapping};
+pub use temporary_boot::TemporaryBootInstaller;
+pub use post_install::{PostInstallProgress, PostInstallSetup};
+pub use recovery::RecoveryFlash;
+pub use session_stats::SessionStats;
+pub use step_times::{StepTimer, StepTimes};
+pub use channel_info::{ChannelBuild, ChannelDetails};
+pub use upstream_probe::UpstreamCheck;
+
+/// Distro ids that have an installer behind them. The device page's
+/// `launch_install` dispatches on the same ids, except Mobian, which is
+/// picked by interface rather than channel.
+pub const INSTALLER_BACKENDS: &[&str] = &[
+    "ubuntutouch",
+    "ubports",
+    "droidian",
+    "postmarketos",
+    "lineageos",
+    "eos",
+    "mobian",
+];
+
+/// Whether `distro_id` can be installed at all
+pub fn has_backend(distro_id: &str) -> bool {
+    INSTALLER_BACKENDS.contains(&distro_id)
+}
@@ -0,0 +1,134 @@
+// Waiting for the device to switch modes
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::progress::InstallProgress;
+use crate::hardware::{Adb, CancelToken, Fastboot, RebootWindow};
+use anyhow::Result;
+use std::future::Future;
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+
+/// How long a transition may take before the user is offered help and a
+/// manual override.
+pub const GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Reboots into the bootloader can't need user input, so they give up
+/// eventually. Recovery and sideload wait on the user and never time out.
+const FASTBOOT_TIMEOUT: Duration = Duration::from_secs(300);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The mode an installer is waiting for the device to reach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetMode {
+    Fastboot,
+    Recovery,
+    Sideload,
+}
+
+impl TargetMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TargetMode::Fastboot => "fastboot",
+            TargetMode::Recovery => "recovery",
+            TargetMode::Sideload => "sideload",
+        }
+    }
+}
+
+/// Poll until `serial` is listed by `fastboot devices`.
+pub async fn wait_for_fastboot(
+    fastboot: &Fastboot,
+    serial: &str,
+    sender: &Sender<InstallProgress>,
+) -> Result<()> {
+    let _window = RebootWindow::open(serial, TargetMode::Fastboot.label());
+    let cancel = fastboot.cancel_token();
+    wait_for_mode(TargetMode::Fastboot, Some(FASTBOOT_TIMEOUT), cancel, sender, move || async move {
+        fastboot
+            .devices()
+            .await
+            .is_ok_and(|devices| devices.iter().any(|d| d.serial == serial))
+    })
+    .await
+}
+
+/// Poll until `serial` shows up in adb in recovery.
+pub async fn wait_for_recovery(adb: &Adb, serial: &str, sender: &Sender<InstallProgress>) -> Result<()> {
+    wait_for_adb_state(adb, serial, TargetMode::Recovery, sender).await
+}
+
+/// Poll until `serial` is in adb sideload ("Apply from ADB").
+pub async fn wait_for_sideload(adb: &Adb, serial: &str, sender: &Sender<InstallProgress>) -> Result<()> {
+    wait_for_adb_state(adb, serial, TargetMode::Sideload, sender).await
+}
+
+async fn wait_for_adb_state(
+    adb: &Adb,
+    serial: &str,
+    mode: TargetMode,
+    sender: &Sender<InstallProgress>,
+) -> Result<()> {
+    let _window = RebootWindow::open(serial, mode.label());
+    wait_for_mode(mode, None, adb.cancel_token(), sender, move || async move {
+        adb.devices().await.is_ok_and(|devices| {
+            devices
+                .iter()
+                .any(|d| d.serial == serial && d.state.label() == mode.label())
+        })
+    })
+    .await
+}
+
+/// Poll `reached` until it reports the device in `mode`.
+///
+/// Once [`GRACE_PERIOD`] has passed the UI is sent
+/// [`InstallProgress::ModeTransitionSlow`], whose `proceed` sender lets the
+/// user declare the device ready when detection can't see it. Cancelling
+/// `cancel` ends the wait with [`InstallCancelled`](crate::hardware::InstallCancelled).
+pub async fn wait_for_mode<F, Fut>(
+    mode: TargetMode,
+    timeout: Option<Duration>,
+    cancel: &CancelToken,
+    sender: &Sender<InstallProgress>,
+    mut reached: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let start = Instant::now();
+    let (proceed, overridden) = mpsc::channel();
+    let mut prompted = false;
+
+    loop {
+        cancel.check()?;
+        if reached().await {
+            break;
+        }
+        if overridden.try_recv().is_ok() {
+            log::warn!("User confirmed the device is in {} mode", mode.label());
+            break;
+        }
+
+        let elapsed = start.elapsed();
+        if !prompted && elapsed >= GRACE_PERIOD {
+            log::info!("Still waiting for {} mode after {}s", mode.label(), elapsed.as_secs());
+            let _ = sender.send(InstallProgress::ModeTransitionSlow {
+                mode,
+                proceed: proceed.clone(),
+            });
+            prompted = true;
+        }
+        if timeout.is_some_and(|t| elapsed >= t) {
+            anyhow::bail!("Timed out waiting for device in {} mode", mode.label());
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    if prompted {
+        let _ = sender.send(InstallProgress::ModeTransitionDone);
+    }
+    Ok(())
+}
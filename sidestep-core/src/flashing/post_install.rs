@@ -0,0 +1,148 @@
+// Post-install setup over adb
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::{Adb, AdbState};
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::{Duration, Instant};
+
+/// The only commands a device config may list under `post_install_commands`.
+/// Entries are compared as whole strings, so nothing can be appended to them.
+pub const ALLOWED_COMMANDS: &[&str] = &[
+    "android-gadget-service enable ssh",
+    "android-gadget-service enable mtp",
+    "systemctl enable --now ssh",
+    "systemctl enable --now sshd",
+    "timedatectl set-ntp true",
+];
+
+/// How long to wait for the new OS to come up over adb after first boot
+const ADB_WAIT: Duration = Duration::from_secs(180);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub fn is_allowed(command: &str) -> bool {
+    ALLOWED_COMMANDS.contains(&command.trim())
+}
+
+/// Messages from a post-install run to the success page
+#[derive(Debug, Clone)]
+pub enum PostInstallProgress {
+    /// Waiting for the freshly installed OS to show up over adb
+    WaitingForDevice,
+    /// Running this command
+    Running(String),
+    /// Every command was attempted
+    Done {
+        applied: Vec<String>,
+        failed: Vec<(String, String)>,
+    },
+    /// Nothing was run; carries the reason
+    Skipped(String),
+}
+
+/// Whitelisted adb shell commands to run once the new OS has booted.
+#[derive(Debug, Clone)]
+pub struct PostInstallSetup {
+    serial: String,
+    commands: Vec<String>,
+}
+
+impl PostInstallSetup {
+    /// Keep the allowed entries of `commands`; the rest are logged and dropped.
+    pub fn new(serial: &str, commands: &[String]) -> Self {
+        let commands = commands
+            .iter()
+            .filter(|command| {
+                let allowed = is_allowed(command);
+                if !allowed {
+                    log::warn!("Ignoring post-install command not on the allow list: {}", command);
+                }
+                allowed
+            })
+            .map(|command| command.trim().to_string())
+            .collect();
+        Self {
+            serial: serial.to_string(),
+            commands,
+        }
+    }
+
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Run on a background thread, returning a receiver for progress.
+    pub fn spawn(self) -> Receiver<PostInstallProgress> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(self.run(&sender));
+        });
+
+        receiver
+    }
+
+    async fn run(&self, sender: &Sender<PostInstallProgress>) {
+        let adb = Adb::new();
+        let _ = sender.send(PostInstallProgress::WaitingForDevice);
+
+        if let Err(reason) = self.wait_for_adb(&adb).await {
+            log::info!("Skipping post-install setup: {}", reason);
+            let _ = sender.send(PostInstallProgress::Skipped(reason));
+            return;
+        }
+
+        let mut applied = Vec::new();
+        let mut failed = Vec::new();
+        for command in &self.commands {
+            let _ = sender.send(PostInstallProgress::Running(command.clone()));
+            log::info!("Post-install on {}: {}", self.serial, command);
+            match adb.shell(&self.serial, command).await {
+                Ok(_) => applied.push(command.clone()),
+                Err(e) => {
+                    log::warn!("Post-install command '{}' failed: {:#}", command, e);
+                    failed.push((command.clone(), format!("{:#}", e)));
+                }
+            }
+        }
+
+        let _ = sender.send(PostInstallProgress::Done { applied, failed });
+    }
+
+    /// Poll until the device is listed as an authorised adb device.
+    async fn wait_for_adb(&self, adb: &Adb) -> Result<(), String> {
+        let deadline = Instant::now() + ADB_WAIT;
+        loop {
+            let devices = adb
+                .devices()
+                .await
+                .map_err(|_| "adb isn't available on this computer".to_string())?;
+            let state = devices
+                .iter()
+                .find(|d| d.serial == self.serial)
+                .map(|d| &d.state);
+            if state == Some(&AdbState::Device) {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(match state {
+                    Some(AdbState::Unauthorized) => {
+                        "The device didn't authorise this computer for adb".to_string()
+                    }
+                    _ => "The device didn't show up over adb. The installed system may not offer adb"
+                        .to_string(),
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
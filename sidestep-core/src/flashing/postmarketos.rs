@@ -0,0 +1,730 @@
+// postmarketOS installer backend
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::cache;
+use crate::flashing::checksum::ChecksumVerifier;
+use crate::flashing::decompressor::Decompressor;
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::executor::FlashExecutor;
+use crate::flashing::flash_state::FlashState;
+use crate::flashing::mode_wait;
+use crate::flashing::progress::{self, InstallProgress};
+use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
+use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
+use crate::models::{Device, PartitionImage};
+use crate::models::distro_config::ArchiveEntry;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+
+/// The channel's directory is gone from the image server, as happens when
+/// a release reaches end of life. Carries the nearest channel that still
+/// has builds for the device, if one was found.
+#[derive(Debug, Clone)]
+pub struct ChannelUnavailable {
+    pub channel: String,
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for ChannelUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.suggestion {
+            Some(ref suggestion) => write!(
+                f,
+                "{} is no longer available; {} is",
+                self.channel, suggestion
+            ),
+            None => write!(f, "{} is no longer available on the image server", self.channel),
+        }
+    }
+}
+
+impl std::error::Error for ChannelUnavailable {}
+
+/// How many other channels to probe before giving up on a suggestion
+const MAX_CHANNEL_PROBES: usize = 4;
+
+/// Orchestrates postmarketOS installation for supported devices.
+///
+/// Flow:
+///   1. Scrape the image server for the latest build directory
+///   2. Scrape the build directory for boot + rootfs image URLs and SHA256 hashes
+///   3. Download both .img.xz files with progress
+///   4. Verify SHA256 checksums
+///   5. Reboot to bootloader → wait for fastboot
+///   6. Flash boot, userdata, unpacking each on the way to the device
+///   7. Reboot
+pub struct PostmarketosInstaller {
+    serial: String,
+    base_url: String,
+    channel: String,
+    interface: String,
+    device: String,
+    download_dir: PathBuf,
+    download_only: bool,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
+    mirrors: Vec<String>,
+    archive_partitions: Vec<ArchiveEntry>,
+}
+
+impl PostmarketosInstaller {
+    /// Starts from Android or straight from the bootloader
+    pub const REQUIRED_MODE: StartMode = StartMode::AdbOrFastboot;
+
+    pub fn new(
+        serial: String,
+        base_url: String,
+        channel: String,
+        interface: String,
+        device: String,
+    ) -> Self {
+        let download_dir = cache::installer_dir("postmarketos");
+
+        Self {
+            serial,
+            base_url,
+            channel,
+            interface,
+            device,
+            download_dir,
+            download_only: false,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
+            mirrors: Vec::new(),
+            archive_partitions: Vec::new(),
+        }
+    }
+
+    /// Where downloaded images are cached
+    pub fn download_dir(&self) -> &Path {
+        &self.download_dir
+    }
+
+    /// Stop after downloading and verifying images, leaving them in the cache.
+    pub fn download_only(mut self, enabled: bool) -> Self {
+        self.download_only = enabled;
+        self
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Hosts serving the same tree as `base_url`, tried in order when it
+    /// fails while downloading
+    pub fn mirrors(mut self, mirrors: Vec<String>) -> Self {
+        self.mirrors = mirrors;
+        self
+    }
+
+    /// Install from one archive holding several images instead of separate
+    /// boot and rootfs images, flashing its files as `entries` maps them.
+    pub fn archive_partitions(mut self, entries: Vec<ArchiveEntry>) -> Self {
+        self.archive_partitions = entries;
+        self
+    }
+
+    /// Spawn the installer on a background thread, returning immediately.
+    /// Progress is reported via the returned mpsc::Receiver.
+    pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(async {
+                if let Err(e) = self.run(&sender).await {
+                    log::error!("postmarketOS installation failed: {:#}", e);
+                    let _ = sender.send(InstallProgress::from_error(&e));
+                }
+            });
+        });
+
+        receiver
+    }
+
+    /// Main installation flow (runs on background thread)
+    async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone())
+            .with_mirrors(&self.base_url, &self.mirrors);
+
+        // ── Step 1: Discover latest build directory ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Discovering latest postmarketOS build...".into(),
+        ));
+        let listing_url = format!(
+            "{}{}/{}/{}/",
+            self.base_url, self.channel, self.device, self.interface
+        );
+        let build_dir = self.discover_latest_build(&listing_url).await?;
+        let build_url = format!("{}{}/", listing_url, build_dir);
+        log::info!("Found latest postmarketOS build: {}", build_dir);
+
+        if !self.archive_partitions.is_empty() {
+            return self.install_archive(sender, &downloader, &build_url).await;
+        }
+
+        // ── Step 2: Discover image files and checksums ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Fetching image details...".into(),
+        ));
+        let (boot_name, boot_hash, rootfs_name, rootfs_hash) =
+            self.discover_images(&build_url).await?;
+        log::info!("Boot image: {} (sha256: {})", boot_name, boot_hash);
+        log::info!("Rootfs image: {} (sha256: {})", rootfs_name, rootfs_hash);
+
+        // ── Step 3: Download boot image ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Downloading boot image...".into(),
+        ));
+        let boot_url = format!("{}{}", build_url, boot_name);
+        let boot_path = downloader
+            .download_if_needed(
+                &boot_url,
+                &boot_name,
+                Some(&boot_hash),
+                progress::report_download(sender, "Boot image", 1, 2),
+            )
+            .await
+            .context("Failed to download boot image")?;
+
+        // ── Step 4: Download rootfs image ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Downloading rootfs image...".into(),
+        ));
+        let rootfs_url = format!("{}{}", build_url, rootfs_name);
+        let rootfs_path = downloader
+            .download_if_needed(
+                &rootfs_url,
+                &rootfs_name,
+                Some(&rootfs_hash),
+                progress::report_download(sender, "Rootfs image", 2, 2),
+            )
+            .await
+            .context("Failed to download rootfs image")?;
+
+        // ── Step 5: Verify checksums ──
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: 0,
+            total: 2,
+            file_name: "Verifying boot image".into(),
+        });
+        ChecksumVerifier::check(&boot_path, &boot_hash, None)?;
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: 1,
+            total: 2,
+            file_name: "Verifying rootfs image".into(),
+        });
+        ChecksumVerifier::check(&rootfs_path, &rootfs_hash, None)?;
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: 2,
+            total: 2,
+            file_name: "All checksums verified".into(),
+        });
+
+        // Download-only mode stops here, leaving the verified images cached
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
+        // The executor streams each image into the bootloader, or unpacks
+        // it there first when the transport can't take it in one piece
+        self.flash_images(
+            sender,
+            &[
+                ("boot", boot_path.as_path(), boot_hash.as_str()),
+                ("userdata", rootfs_path.as_path(), rootfs_hash.as_str()),
+            ],
+        )
+        .await?;
+
+        let _ = sender.send(InstallProgress::Complete);
+        Ok(())
+    }
+
+    // ────────────────────────────────────────────────────────────────
+    // Sub-steps
+    // ────────────────────────────────────────────────────────────────
+
+    /// Download a build published as one archive, check it holds every
+    /// image the config expects, and flash them.
+    async fn install_archive(
+        &self,
+        sender: &Sender<InstallProgress>,
+        downloader: &ImageDownloader,
+        build_url: &str,
+    ) -> Result<()> {
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Fetching image details...".into(),
+        ));
+        let html = self.fetch_listing(build_url).await?;
+        let archive_name = hrefs(&html)
+            .find(|href| is_archive(href))
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Could not find an image archive in {}", build_url))?;
+        let archive_hash = self.extract_sha256(&html, &archive_name)?;
+        log::info!("Image archive: {} (sha256: {})", archive_name, archive_hash);
+
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Downloading image archive...".into(),
+        ));
+        let archive_path = downloader
+            .download_if_needed(
+                &format!("{}{}", build_url, archive_name),
+                &archive_name,
+                Some(&archive_hash),
+                progress::report_download(sender, "Image archive", 1, 1),
+            )
+            .await
+            .context("Failed to download image archive")?;
+
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: 0,
+            total: 1,
+            file_name: "Verifying image archive".into(),
+        });
+        ChecksumVerifier::check(&archive_path, &archive_hash, None)?;
+        let _ = sender.send(InstallProgress::VerifyProgress {
+            verified: 1,
+            total: 1,
+            file_name: "All checksums verified".into(),
+        });
+
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Extracting images (this may take a while)...".into(),
+        ));
+        let images = Decompressor::extract_partitions(
+            &archive_path,
+            &self.download_dir.join("archive"),
+            &self.archive_partitions,
+            progress::report_decompress(sender, "Image archive"),
+            Some(&self.download_cancel),
+        )
+        .context("Failed to extract image archive")?;
+
+        // Every image comes from the one verified archive
+        let images: Vec<(&str, &Path, &str)> = images
+            .iter()
+            .map(|(partition, path)| (partition.as_str(), path.as_path(), archive_hash.as_str()))
+            .collect();
+        self.flash_images(sender, &images).await?;
+
+        let _ = sender.send(InstallProgress::Complete);
+        Ok(())
+    }
+
+    /// Get the device to the bootloader, flash `images` in order and reboot.
+    ///
+    /// Each image comes with the checksum it was verified against. Leading
+    /// partitions an interrupted run already wrote from the same images are
+    /// skipped.
+    async fn flash_images(&self, sender: &Sender<InstallProgress>, images: &[(&str, &Path, &str)]) -> Result<()> {
+        let adb = Adb::new().with_cancel(self.cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
+
+        // ── Step 6: Reboot to bootloader ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Rebooting to bootloader...".into(),
+        ));
+        if let Err(e) = adb.reboot_bootloader(&self.serial).await {
+            log::warn!(
+                "ADB reboot-bootloader failed (device may already be in fastboot): {}",
+                e
+            );
+        }
+
+        // ── Step 7: Wait for fastboot device ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Waiting for device in fastboot mode...".into(),
+        ));
+        mode_wait::wait_for_fastboot(&fastboot, &self.serial, sender).await?;
+
+        // ── Step 8: Flash partitions ──
+        let mut state = FlashState::load(&self.serial);
+        let planned: Vec<(&str, &str)> = images.iter().map(|(partition, _, sha256)| (*partition, *sha256)).collect();
+        let skip = state.resume(&planned).unwrap_or_else(|e| {
+            log::warn!("Couldn't read what was flashed before, flashing everything: {:#}", e);
+            0
+        });
+
+        // Both slots, so the boot image is there whichever one the device
+        // comes up on
+        let executor = FlashExecutor::new()
+            .with_cancel(self.cancel.clone())
+            .with_all_slots(true)
+            .on_unpack(progress::report_unpack(sender));
+        let total_steps = images.len();
+        for (index, (partition, image, sha256)) in images.iter().enumerate() {
+            let images_dir = image.parent().unwrap_or(Path::new("/"));
+            if index < skip {
+                let _ = sender.send(InstallProgress::FlashProgress {
+                    current: index + 1,
+                    total: total_steps,
+                    description: format!("Already flashed {}, skipping", partition),
+                });
+                continue;
+            }
+            let description = if *partition == "userdata" {
+                "Flashing rootfs (this may take a while)...".to_string()
+            } else {
+                format!("Flashing {}...", partition)
+            };
+            let _ = sender.send(InstallProgress::FlashProgress {
+                current: index + 1,
+                total: total_steps,
+                description,
+            });
+            let image = PartitionImage {
+                partition: partition.to_string(),
+                image: image.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                erase_first: false,
+                aliases: Vec::new(),
+            };
+            executor
+                .flash_partition(&self.serial, &image, images_dir)
+                .await
+                .with_context(|| format!("Failed to flash {}", partition))?;
+            if let Err(e) = state.mark_flashed(partition, sha256) {
+                log::warn!("Couldn't record that {} was flashed: {:#}", partition, e);
+            }
+        }
+        if let Err(e) = state.finish() {
+            log::warn!("Couldn't clear the flash state: {:#}", e);
+        }
+
+        // ── Step 9: Reboot ──
+        let _ = sender.send(InstallProgress::StatusChanged(
+            "Rebooting device...".into(),
+        ));
+        fastboot.reboot(&self.serial).await?;
+
+        Ok(())
+    }
+
+    /// Scrape the interface listing page for the latest date-stamped build directory.
+    /// Directories follow the pattern `YYYYMMDD-HHMM/`.
+    pub(crate) async fn discover_latest_build(&self, listing_url: &str) -> Result<String> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .build()?;
+
+        let resp = client
+            .get(listing_url)
+            .send()
+            .await
+            .context("Failed to fetch postmarketOS build listing")?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            let suggestion = self.suggest_channel(&client).await;
+            return Err(ChannelUnavailable {
+                channel: self.channel.clone(),
+                suggestion,
+            }
+            .into());
+        }
+        if !resp.status().is_success() {
+            anyhow::bail!(
+                "postmarketOS image server returned status {} for {}",
+                resp.status(),
+                listing_url
+            );
+        }
+
+        let html = resp.text().await.context("Failed to read build listing")?;
+
+        // Look for href="YYYYMMDD-HHMM/" patterns (date-stamped directories)
+        let mut best_dir = String::new();
+
+        for line in html.lines() {
+            if let Some(start) = line.find("href=\"") {
+                let rest = &line[start + 6..];
+                if let Some(end) = rest.find('"') {
+                    let href = &rest[..end];
+                    if is_build_dir(href) {
+                        let dir_name = &href[..13]; // without trailing slash
+                        if dir_name > best_dir.as_str() {
+                            best_dir = dir_name.to_string();
+                        }
+                    }
+                }
+            }
+        }
+
+        if best_dir.is_empty() {
+            anyhow::bail!(
+                "No build directories found at {}. \
+                 Check that channel={} and interface={} are valid for device={}.",
+                listing_url,
+                self.channel,
+                self.interface,
+                self.device
+            );
+        }
+
+        Ok(best_dir)
+    }
+
+    /// Look through the server's top-level index for the release nearest to
+    /// ours that still has builds for this device and interface: the next
+    /// newer one first, then older ones, then edge.
+    async fn suggest_channel(&self, client: &reqwest::Client) -> Option<String> {
+        let html = client.get(&self.base_url).send().await.ok()?.text().await.ok()?;
+
+        let mut releases: Vec<((u32, u32), String)> = hrefs(&html)
+            .filter_map(|href| {
+                let name = href.trim_start_matches("./").strip_suffix('/')?;
+                Some((release_version(name)?, name.to_string()))
+            })
+            .collect();
+        releases.sort();
+        releases.dedup();
+
+        let mut candidates: Vec<String> = match release_version(&self.channel) {
+            Some(ours) => {
+                let newer = releases.iter().filter(|(v, _)| *v > ours);
+                let older = releases.iter().rev().filter(|(v, _)| *v < ours);
+                newer.chain(older).map(|(_, name)| name.clone()).collect()
+            }
+            None => releases.iter().rev().map(|(_, name)| name.clone()).collect(),
+        };
+        if self.channel != "edge" && hrefs(&html).any(|href| href.trim_end_matches('/') == "edge") {
+            candidates.push("edge".to_string());
+        }
+
+        for candidate in candidates.into_iter().take(MAX_CHANNEL_PROBES) {
+            let url = format!("{}{}/{}/{}/", self.base_url, candidate, self.device, self.interface);
+            let found = client
+                .get(&url)
+                .send()
+                .await
+                .is_ok_and(|resp| resp.status().is_success());
+            if found {
+                log::info!("{} is gone from the server; suggesting {}", self.channel, candidate);
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
+    /// Scrape a build directory page for boot + rootfs image filenames and their SHA256 hashes.
+    /// Returns (boot_filename, boot_sha256, rootfs_filename, rootfs_sha256).
+    pub(crate) async fn discover_images(
+        &self,
+        build_url: &str,
+    ) -> Result<(String, String, String, String)> {
+        let html = self.fetch_listing(build_url).await?;
+
+        // Collect all .img.xz hrefs from the page
+        let mut boot_name: Option<String> = None;
+        let mut rootfs_name: Option<String> = None;
+
+        for line in html.lines() {
+            if let Some(start) = line.find("href=\"") {
+                let rest = &line[start + 6..];
+                if let Some(end) = rest.find('"') {
+                    let href = &rest[..end];
+                    if href.ends_with(".img.xz") && !href.ends_with(".sha256") && !href.ends_with(".sha512") {
+                        if href.contains("-boot.img.xz") {
+                            boot_name = Some(href.to_string());
+                        } else {
+                            rootfs_name = Some(href.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        let boot_name = boot_name.ok_or_else(|| {
+            anyhow::anyhow!("Could not find boot image in {}", build_url)
+        })?;
+        let rootfs_name = rootfs_name.ok_or_else(|| {
+            anyhow::anyhow!("Could not find rootfs image in {}", build_url)
+        })?;
+
+        // Parse SHA256 hashes from the page.
+        // Format on the page: sha256 link followed by ": HASH" on the same line or nearby.
+        // The HTML contains patterns like:
+        //   <a href="filename.sha256">sha256</a>: HASH_VALUE
+        let boot_hash = self.extract_sha256(&html, &boot_name)?;
+        let rootfs_hash = self.extract_sha256(&html, &rootfs_name)?;
+
+        Ok((boot_name, boot_hash, rootfs_name, rootfs_hash))
+    }
+
+    /// The HTML index of a build directory
+    async fn fetch_listing(&self, build_url: &str) -> Result<String> {
+        let client = reqwest::Client::builder()
+            .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+            .build()?;
+
+        let resp = client
+            .get(build_url)
+            .send()
+            .await
+            .context("Failed to fetch postmarketOS build directory")?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!(
+                "postmarketOS image server returned status {} for {}",
+                resp.status(),
+                build_url
+            );
+        }
+
+        resp.text().await.context("Failed to read build directory")
+    }
+
+    /// Extract the SHA256 hash for a given filename from the build directory HTML.
+    /// Looks for patterns like: `href="filename.sha256">sha256</a>: HASH`
+    fn extract_sha256(&self, html: &str, filename: &str) -> Result<String> {
+        let sha256_file = format!("{}.sha256", filename);
+
+        // The HTML may span multiple lines, e.g.:
+        //   <a href="file.sha256"
+        //      >sha256</a>: HASH
+        // So we check both the current line and the next line for the hash.
+        let lines: Vec<&str> = html.lines().collect();
+        for (i, line) in lines.iter().enumerate() {
+            if line.contains(&sha256_file) {
+                // Check current line and next line for the hash pattern
+                for check_line in &lines[i..std::cmp::min(i + 3, lines.len())] {
+                    if let Some(pos) = check_line.find("sha256</a>:") {
+                        let after = &check_line[pos + 11..]; // skip "sha256</a>:"
+                        let hash = after.trim().split_whitespace().next().unwrap_or("");
+                        // Strip any trailing HTML tags
+                        let hash = hash.split('<').next().unwrap_or(hash);
+                        if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                            return Ok(hash.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        anyhow::bail!(
+            "Could not find SHA256 hash for {} in build directory",
+            filename
+        )
+    }
+}
+
+/// Release directories on the image server that have images for `device`,
+/// newest release first and `edge` last.
+///
+/// The server root lists one directory per release (`v24.06/`, `v25.12/`,
+/// `edge/`, ...); a device only appears under the releases that built it.
+pub async fn list_versions(base_url: &str, device: &str) -> Result<Vec<String>> {
+    let client = reqwest::Client::builder()
+        .user_agent(format!("Sidestep/{}", crate::config::VERSION))
+        .build()?;
+
+    let resp = client
+        .get(base_url)
+        .send()
+        .await
+        .context("Failed to fetch postmarketOS release listing")?;
+    if !resp.status().is_success() {
+        anyhow::bail!(
+            "postmarketOS image server returned status {} for {}",
+            resp.status(),
+            base_url
+        );
+    }
+    let html = resp.text().await.context("Failed to read release listing")?;
+
+    let mut versions: Vec<String> = Vec::new();
+    for chunk in html.split("href=\"").skip(1) {
+        let Some(href) = chunk.split('"').next() else {
+            continue;
+        };
+        let Some(name) = href.strip_suffix('/') else {
+            continue;
+        };
+        let is_release = name
+            .strip_prefix('v')
+            .is_some_and(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit() || c == '.'));
+        if (is_release || name == "edge") && !versions.iter().any(|v| v == name) {
+            versions.push(name.to_string());
+        }
+    }
+
+    // "vYY.MM" sorts correctly as text; edge goes after every release
+    versions.sort_by(|a, b| match (a.as_str(), b.as_str()) {
+        ("edge", _) => std::cmp::Ordering::Greater,
+        (_, "edge") => std::cmp::Ordering::Less,
+        _ => b.cmp(a),
+    });
+
+    let checks = versions.iter().map(|version| {
+        let url = format!("{}{}/{}/", base_url, version, device);
+        let client = &client;
+        async move {
+            client
+                .get(&url)
+                .send()
+                .await
+                .is_ok_and(|resp| resp.status().is_success())
+        }
+    });
+    let available = futures_util::future::join_all(checks).await;
+
+    Ok(versions
+        .into_iter()
+        .zip(available)
+        .filter_map(|(version, ok)| ok.then_some(version))
+        .collect())
+}
+
+/// Every `href="…"` target in an HTML directory listing
+/// Device name on the image server: manufacturer-codename (e.g. google-sargo)
+pub fn device_name(device: &Device) -> String {
+    format!("{}-{}", device.maker.to_lowercase(), device.codename)
+}
+
+/// Combined archives [`Decompressor::extract_partitions`] can unpack
+fn is_archive(name: &str) -> bool {
+    [".zip", ".tar", ".tar.gz", ".tgz", ".tar.xz", ".txz"]
+        .iter()
+        .any(|extension| name.ends_with(extension))
+}
+
+/// Whether `href` names a build directory: `YYYYMMDD-HHMM/`
+pub(crate) fn is_build_dir(href: &str) -> bool {
+    href.ends_with('/')
+        && href.len() == 14
+        && href[..8].chars().all(|c| c.is_ascii_digit())
+        && href.as_bytes()[8] == b'-'
+        && href[9..13].chars().all(|c| c.is_ascii_digit())
+}
+
+pub(crate) fn hrefs(html: &str) -> impl Iterator<Item = &str> {
+    html.split("href=\"").skip(1).filter_map(|rest| rest.split('"').next())
+}
+
+/// `(year, month)` of a release channel such as `v25.12`
+fn release_version(channel: &str) -> Option<(u32, u32)> {
+    let (year, month) = channel.strip_prefix('v')?.split_once('.')?;
+    Some((year.parse().ok()?, month.parse().ok()?))
+}
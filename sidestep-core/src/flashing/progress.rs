@@ -0,0 +1,501 @@
+// Shared installation progress types
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::decompressor::CorruptImage;
+use crate::flashing::downloader::{DownloadCancelled, DownloadFailed, InsufficientSpace};
+use crate::flashing::mode_wait::TargetMode;
+use crate::flashing::postmarketos::ChannelUnavailable;
+use crate::hardware::{DeviceDisconnected, FastbootdUnsupported, InstallCancelled, SideloadRejected};
+use crate::flashing::downloader::ProgressCallback;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// Progress messages sent from installer threads to the UI
+#[derive(Debug, Clone)]
+pub enum InstallProgress {
+    /// File download progress (aggregated across all downloads)
+    DownloadProgress {
+        downloaded: u64,
+        total: u64,
+        file_name: String,
+    },
+    /// Checksum verification progress
+    VerifyProgress {
+        verified: usize,
+        total: usize,
+        file_name: String,
+    },
+    /// A downloaded image is being unpacked; `percent` is how much of the
+    /// compressed file has been read
+    DecompressProgress {
+        percent: u64,
+        file_name: String,
+    },
+    /// How much of a package the recovery has read during `adb sideload`
+    SideloadProgress {
+        percent: u64,
+        file_name: String,
+    },
+    /// Flash/push step progress
+    FlashProgress {
+        current: usize,
+        total: usize,
+        description: String,
+    },
+    /// How far fastboot or Heimdall has got with the partition of the current
+    /// [`FlashProgress`](Self::FlashProgress) step. `chunks` is 1 for images
+    /// sent in one piece.
+    PartitionProgress {
+        partition: String,
+        chunk: u32,
+        chunks: u32,
+        percent: u64,
+    },
+    /// A flash has produced no progress for a while; it is still running
+    TransferStalled {
+        partition: String,
+        silent_secs: u64,
+    },
+    /// A partition flash failed in a way that usually clears up and is
+    /// about to be tried again; `attempt` counts from 1
+    FlashRetrying {
+        partition: String,
+        attempt: u32,
+        attempts: u32,
+        reason: String,
+    },
+    /// Status text update
+    StatusChanged(String),
+    /// A file has no published checksum. The installer blocks until the user
+    /// answers through `reply` (`true` = proceed without verification).
+    ChecksumUnavailable {
+        file_name: String,
+        reply: Sender<bool>,
+    },
+    /// The current command is blocked on a prompt that must be answered on
+    /// the device itself; carries the instructions to show
+    AwaitingDeviceConfirmation(String),
+    /// The device is taking longer than expected to reach `mode`. Sending on
+    /// `proceed` tells the installer the user says it's ready anyway
+    ModeTransitionSlow {
+        mode: TargetMode,
+        proceed: Sender<()>,
+    },
+    /// A slow transition finished; clears the prompt
+    ModeTransitionDone,
+    /// Waiting for user to select Recovery mode on device
+    WaitingForRecovery,
+    /// Device entered recovery mode
+    RecoveryDetected,
+    /// Waiting for user to perform an action on the device (shown as info banner)
+    WaitingForUserAction(String),
+    /// Download-only run finished; verified images are cached in this directory
+    DownloadOnlyComplete(std::path::PathBuf),
+    /// The device was booted once with `fastboot boot`; nothing was flashed
+    BootedTemporarily,
+    /// Installation completed successfully
+    Complete,
+    /// The user stopped the download or the unpacking after it; nothing was
+    /// written to the device
+    DownloadCancelled,
+    /// The user cancelled the install. Whatever fastboot or adb was doing
+    /// was killed, so the device may be partly flashed.
+    Cancelled,
+    /// The USB connection dropped while writing to the device
+    DeviceDisconnected(String),
+    /// A downloaded image failed its own integrity checks when unpacked
+    ImageCorrupted(String),
+    /// A download failed on the server side; another mirror may work
+    DownloadFailed(String),
+    /// The download directory is too full for the images
+    InsufficientSpace {
+        path: std::path::PathBuf,
+        needed: u64,
+        available: u64,
+    },
+    /// The release channel was removed from the server; `suggestion` is the
+    /// nearest one still there
+    ChannelUnavailable {
+        channel: String,
+        suggestion: Option<String>,
+    },
+    /// An error occurred
+    Error(String),
+}
+
+/// A stretch of an install with its own progress bar, in the order an
+/// install goes through them. Time estimates start over whenever it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProgressPhase {
+    Download,
+    Decompress,
+    Flash,
+}
+
+impl ProgressPhase {
+    const ALL: [ProgressPhase; 3] = [Self::Download, Self::Decompress, Self::Flash];
+
+    /// Caption for the overall progress bar while this phase runs
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Download => "Downloading",
+            Self::Decompress => "Verifying and unpacking",
+            Self::Flash => "Flashing",
+        }
+    }
+}
+
+/// How much of the overall progress bar each phase takes up. Only the
+/// ratios matter; a phase weighted 0 is one the install skips.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseWeights {
+    pub download: f64,
+    pub decompress: f64,
+    pub flash: f64,
+}
+
+impl Default for PhaseWeights {
+    fn default() -> Self {
+        Self {
+            download: 0.4,
+            decompress: 0.2,
+            flash: 0.4,
+        }
+    }
+}
+
+impl PhaseWeights {
+    /// These weights with `phase` left out
+    pub fn without(mut self, phase: ProgressPhase) -> Self {
+        match phase {
+            ProgressPhase::Download => self.download = 0.0,
+            ProgressPhase::Decompress => self.decompress = 0.0,
+            ProgressPhase::Flash => self.flash = 0.0,
+        }
+        self
+    }
+
+    fn of(&self, phase: ProgressPhase) -> f64 {
+        match phase {
+            ProgressPhase::Download => self.download,
+            ProgressPhase::Decompress => self.decompress,
+            ProgressPhase::Flash => self.flash,
+        }
+    }
+
+    /// Share of the bar taken by the phases before `phase`
+    fn before(&self, phase: ProgressPhase) -> f64 {
+        ProgressPhase::ALL
+            .iter()
+            .take_while(|p| **p < phase)
+            .map(|p| self.of(*p))
+            .sum()
+    }
+
+    fn total(&self) -> f64 {
+        ProgressPhase::ALL.iter().map(|p| self.of(*p)).sum()
+    }
+}
+
+/// One bar for the whole install. Each phase fills its share of
+/// [`PhaseWeights`], the flash share split evenly between the flash steps,
+/// and the bar never moves backwards: a phase that's skipped or restarts
+/// (a retried partition, a second download) holds it where it was.
+#[derive(Debug, Default)]
+pub struct OverallProgress {
+    weights: PhaseWeights,
+    phase: Option<ProgressPhase>,
+    /// `current` and `total` of the last flash step
+    flash_step: (usize, usize),
+    fraction: f64,
+}
+
+impl OverallProgress {
+    pub fn new(weights: PhaseWeights) -> Self {
+        Self {
+            weights,
+            ..Default::default()
+        }
+    }
+
+    /// Update from `progress` and return the overall fraction, 0.0 to 1.0
+    pub fn observe(&mut self, progress: &InstallProgress) -> f64 {
+        let flash_step = |(current, total): (usize, usize), within: f64| {
+            if total == 0 {
+                return 0.0;
+            }
+            ((current.saturating_sub(1) as f64 + within) / total as f64).min(1.0)
+        };
+
+        let position = match progress {
+            InstallProgress::DownloadProgress { downloaded, total, .. } if *total > 0 => Some((
+                ProgressPhase::Download,
+                *downloaded as f64 / *total as f64,
+            )),
+            InstallProgress::VerifyProgress { .. } => Some((ProgressPhase::Decompress, 0.0)),
+            InstallProgress::DecompressProgress { percent, .. } => {
+                Some((ProgressPhase::Decompress, *percent as f64 / 100.0))
+            }
+            InstallProgress::FlashProgress { current, total, .. } => {
+                self.flash_step = (*current, *total);
+                Some((ProgressPhase::Flash, flash_step(self.flash_step, 0.0)))
+            }
+            InstallProgress::PartitionProgress { percent, .. }
+            | InstallProgress::SideloadProgress { percent, .. } => Some((
+                ProgressPhase::Flash,
+                flash_step(self.flash_step, *percent as f64 / 100.0),
+            )),
+            InstallProgress::Complete
+            | InstallProgress::DownloadOnlyComplete(_)
+            | InstallProgress::BootedTemporarily => {
+                self.fraction = 1.0;
+                None
+            }
+            _ => None,
+        };
+
+        if let Some((phase, within)) = position {
+            let total = self.weights.total();
+            if total > 0.0 {
+                let overall = (self.weights.before(phase) + self.weights.of(phase) * within.min(1.0)) / total;
+                self.fraction = self.fraction.max(overall.min(1.0));
+            }
+            self.phase = Some(phase);
+        }
+        self.fraction
+    }
+
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// The phase the last progress message belonged to
+    pub fn phase(&self) -> Option<ProgressPhase> {
+        self.phase
+    }
+}
+
+impl InstallProgress {
+    /// The phase this message moves and how far through it the install
+    /// is, from 0.0 to 1.0. `None` for messages that aren't progress.
+    pub fn phase_fraction(&self) -> Option<(ProgressPhase, f64)> {
+        let percent = |percent: u64| percent.min(100) as f64 / 100.0;
+        match self {
+            InstallProgress::DownloadProgress { downloaded, total, .. } if *total > 0 => Some((
+                ProgressPhase::Download,
+                (*downloaded as f64 / *total as f64).min(1.0),
+            )),
+            InstallProgress::DecompressProgress { percent: p, .. } => {
+                Some((ProgressPhase::Decompress, percent(*p)))
+            }
+            InstallProgress::SideloadProgress { percent: p, .. }
+            | InstallProgress::PartitionProgress { percent: p, .. } => {
+                Some((ProgressPhase::Flash, percent(*p)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Map an installer failure to the message shown in the UI.
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        if error.downcast_ref::<DownloadCancelled>().is_some() {
+            return InstallProgress::DownloadCancelled;
+        }
+        if error.downcast_ref::<InstallCancelled>().is_some() {
+            return InstallProgress::Cancelled;
+        }
+        if let Some(disconnect) = error.downcast_ref::<DeviceDisconnected>() {
+            return InstallProgress::DeviceDisconnected(disconnect.operation.clone());
+        }
+        if let Some(rejected) = error.downcast_ref::<SideloadRejected>() {
+            return InstallProgress::Error(rejected.to_string());
+        }
+        if let Some(unsupported) = error.downcast_ref::<FastbootdUnsupported>() {
+            return InstallProgress::Error(unsupported.to_string());
+        }
+        if let Some(corrupt) = error.downcast_ref::<CorruptImage>() {
+            return InstallProgress::ImageCorrupted(corrupt.to_string());
+        }
+        if let Some(gone) = error.downcast_ref::<ChannelUnavailable>() {
+            return InstallProgress::ChannelUnavailable {
+                channel: gone.channel.clone(),
+                suggestion: gone.suggestion.clone(),
+            };
+        }
+        if let Some(full) = error.downcast_ref::<InsufficientSpace>() {
+            return InstallProgress::InsufficientSpace {
+                path: full.path.clone(),
+                needed: full.needed,
+                available: full.available,
+            };
+        }
+        if let Some(failed) = error.downcast_ref::<DownloadFailed>() {
+            return InstallProgress::DownloadFailed(failed.to_string());
+        }
+        InstallProgress::Error(format!("{:#}", error))
+    }
+}
+
+/// Weight of the newest throughput sample in [`RemainingTime`]'s moving
+/// average; lower is smoother
+const THROUGHPUT_SMOOTHING: f64 = 0.2;
+
+/// Progress messages closer together than this are merged into one
+/// throughput sample, so a burst of callbacks doesn't read as a spike
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time left in the current phase, from an exponential moving average of
+/// how fast its progress has been moving. Feed it every progress message;
+/// it starts over when the phase changes or the progress goes backwards,
+/// as it does between partitions.
+#[derive(Debug, Default)]
+pub struct RemainingTime {
+    phase: Option<ProgressPhase>,
+    /// When the last throughput sample was taken and the fraction then
+    sample: Option<(Instant, f64)>,
+    fraction: f64,
+    /// Smoothed progress per second
+    rate: Option<f64>,
+}
+
+impl RemainingTime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update from `progress` and return the new estimate. Messages that
+    /// aren't progress leave the estimate alone.
+    pub fn observe(&mut self, progress: &InstallProgress) -> Option<Duration> {
+        if let Some((phase, fraction)) = progress.phase_fraction() {
+            self.update(phase, fraction, Instant::now());
+        }
+        self.remaining()
+    }
+
+    fn update(&mut self, phase: ProgressPhase, fraction: f64, now: Instant) {
+        if self.phase != Some(phase) || fraction < self.fraction {
+            *self = Self {
+                phase: Some(phase),
+                sample: Some((now, fraction)),
+                fraction,
+                rate: None,
+            };
+            return;
+        }
+        self.fraction = fraction;
+
+        let Some((then, before)) = self.sample else {
+            self.sample = Some((now, fraction));
+            return;
+        };
+        let elapsed = now.duration_since(then);
+        if elapsed < MIN_SAMPLE_INTERVAL {
+            return;
+        }
+        let rate = (fraction - before) / elapsed.as_secs_f64();
+        self.rate = Some(match self.rate {
+            Some(average) => average + THROUGHPUT_SMOOTHING * (rate - average),
+            None => rate,
+        });
+        self.sample = Some((now, fraction));
+    }
+
+    /// Estimated time to the end of the current phase, once there's been
+    /// enough progress to measure
+    pub fn remaining(&self) -> Option<Duration> {
+        let rate = self.rate.filter(|rate| *rate > 0.0)?;
+        Duration::try_from_secs_f64((1.0 - self.fraction) / rate).ok()
+    }
+
+    /// Forget the current phase, e.g. when a new install starts
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Downloads smaller than this don't report progress; signatures and
+/// keyrings are done before the bar would visibly move.
+pub const SMALL_DOWNLOAD_BYTES: u64 = 1024 * 1024;
+
+/// Name a download by its place in the run, e.g. "Recovery image (2/3)".
+/// A run with a single download keeps the bare label.
+pub fn download_label(label: &str, index: usize, count: usize) -> String {
+    if count > 1 {
+        format!("{} ({}/{})", label, index, count)
+    } else {
+        label.to_string()
+    }
+}
+
+/// Progress callback reporting download `index` of `count` under `label`.
+pub fn report_download(
+    sender: &Sender<InstallProgress>,
+    label: &str,
+    index: usize,
+    count: usize,
+) -> Option<ProgressCallback> {
+    let sender = sender.clone();
+    let file_name = download_label(label, index, count);
+    Some(Box::new(move |downloaded, total| {
+        if total > 0 && total < SMALL_DOWNLOAD_BYTES {
+            return;
+        }
+        let _ = sender.send(InstallProgress::DownloadProgress {
+            downloaded,
+            total,
+            file_name: file_name.clone(),
+        });
+    }))
+}
+
+/// Progress callback for unpacking `label`, sending one message per
+/// percent rather than one per chunk.
+pub fn report_decompress(
+    sender: &Sender<InstallProgress>,
+    label: &str,
+) -> Option<crate::flashing::decompressor::ProgressCallback> {
+    let sender = sender.clone();
+    let file_name = label.to_string();
+    let last = std::sync::atomic::AtomicU64::new(u64::MAX);
+    Some(Box::new(move |done, total| {
+        let percent = (done * 100 / total.max(1)).min(100);
+        if last.swap(percent, std::sync::atomic::Ordering::Relaxed) != percent {
+            let _ = sender.send(InstallProgress::DecompressProgress {
+                percent,
+                file_name: file_name.clone(),
+            });
+        }
+    }))
+}
+
+/// Forward a [`FlashExecutor`](crate::flashing::FlashExecutor) unpacking
+/// an image before its flash as [`InstallProgress::DecompressProgress`],
+/// labelled with the partition
+pub fn report_unpack(sender: &Sender<InstallProgress>) -> crate::flashing::executor::UnpackCallback {
+    let sender = sender.clone();
+    Box::new(move |partition, done, total| {
+        let _ = sender.send(InstallProgress::DecompressProgress {
+            percent: (done * 100 / total.max(1)).min(100),
+            file_name: partition.to_string(),
+        });
+    })
+}
+
+/// Ask the user to accept installing a file that cannot be verified.
+///
+/// Blocks the installer thread until the UI answers; declining (or the
+/// page going away) aborts the installation.
+pub fn confirm_unverified(sender: &Sender<InstallProgress>, file_name: &str) -> anyhow::Result<()> {
+    let (reply, answer) = std::sync::mpsc::channel();
+    let _ = sender.send(InstallProgress::ChecksumUnavailable {
+        file_name: file_name.to_string(),
+        reply,
+    });
+
+    if answer.recv().unwrap_or(false) {
+        log::warn!("Proceeding without checksum verification for {}", file_name);
+        Ok(())
+    } else {
+        anyhow::bail!("Installation cancelled: {} has no checksum and cannot be verified", file_name)
+    }
+}
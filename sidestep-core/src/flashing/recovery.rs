@@ -0,0 +1,147 @@
+// Flashing a custom recovery and booting into it
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::mode_wait;
+use crate::flashing::progress::InstallProgress;
+use crate::hardware::adb::Adb;
+use crate::hardware::fastboot::Fastboot;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/// How long a freshly booted recovery gets before it's asked to do anything
+const RECOVERY_SETTLE: Duration = Duration::from_secs(3);
+
+/// The shared first half of the sideload installers (/e/OS, LineageOS):
+/// put a recovery on the device from fastboot, boot it and wait for it
+/// over adb. Each phase is reported on `sender`.
+pub struct RecoveryFlash<'a> {
+    serial: &'a str,
+    adb: &'a Adb,
+    fastboot: &'a Fastboot,
+    sender: &'a Sender<InstallProgress>,
+}
+
+impl<'a> RecoveryFlash<'a> {
+    pub fn new(
+        serial: &'a str,
+        adb: &'a Adb,
+        fastboot: &'a Fastboot,
+        sender: &'a Sender<InstallProgress>,
+    ) -> Self {
+        Self {
+            serial,
+            adb,
+            fastboot,
+            sender,
+        }
+    }
+
+    /// Flash `image` as the recovery and boot into it: [`enter_bootloader`],
+    /// [`flash`] and [`boot_recovery`] in turn. `step` is the
+    /// `(current, total)` the flash is shown as.
+    ///
+    /// [`enter_bootloader`]: Self::enter_bootloader
+    /// [`flash`]: Self::flash
+    /// [`boot_recovery`]: Self::boot_recovery
+    pub async fn install(&self, image: &Path, label: &str, step: (usize, usize)) -> Result<()> {
+        self.enter_bootloader().await?;
+        self.flash(image, label, step).await?;
+        self.boot_recovery().await
+    }
+
+    /// Reboot from Android to the bootloader and wait for fastboot. A
+    /// device already in fastboot just gets waited for.
+    pub async fn enter_bootloader(&self) -> Result<()> {
+        let _ = self.sender.send(InstallProgress::StatusChanged(
+            "Rebooting to bootloader...".into(),
+        ));
+        if let Err(e) = self.adb.reboot_bootloader(self.serial).await {
+            log::warn!(
+                "ADB reboot-bootloader failed (device may already be in fastboot): {}",
+                e
+            );
+        }
+
+        let _ = self.sender.send(InstallProgress::StatusChanged(
+            "Waiting for device in fastboot mode...".into(),
+        ));
+        mode_wait::wait_for_fastboot(self.fastboot, self.serial, self.sender).await
+    }
+
+    /// Where a recovery image goes: `recovery`, or `boot` on A/B devices
+    /// and others without a recovery partition, whose recovery lives in
+    /// the boot image
+    pub async fn partition(&self) -> &'static str {
+        if self.fastboot.has_ab_slots(self.serial).await.unwrap_or(false) {
+            return "boot";
+        }
+        if self.fastboot.has_partition(self.serial, "recovery").await.unwrap_or(false) {
+            "recovery"
+        } else {
+            "boot"
+        }
+    }
+
+    /// Flash `image` to the recovery's [`partition`](Self::partition),
+    /// returning which one it went to
+    pub async fn flash(&self, image: &Path, label: &str, step: (usize, usize)) -> Result<&'static str> {
+        let partition = self.partition().await;
+        self.flash_to(partition, image, label, step).await?;
+        Ok(partition)
+    }
+
+    /// Flash `image` to `partition`, for recoveries that come as a boot
+    /// image whatever the device's layout
+    pub async fn flash_to(
+        &self,
+        partition: &str,
+        image: &Path,
+        label: &str,
+        (current, total): (usize, usize),
+    ) -> Result<()> {
+        let _ = self.sender.send(InstallProgress::FlashProgress {
+            current,
+            total,
+            description: format!("Flashing {} ({})...", label, partition),
+        });
+        self.fastboot
+            .flash(self.serial, partition, image)
+            .await
+            .with_context(|| format!("Failed to flash {} to the {} partition", label, partition))
+    }
+
+    /// Reboot from fastboot into recovery and wait for it. Bootloaders
+    /// that refuse `reboot recovery` get the user to pick Recovery from
+    /// the bootloader menu instead.
+    pub async fn boot_recovery(&self) -> Result<()> {
+        let _ = self.sender.send(InstallProgress::StatusChanged(
+            "Rebooting to recovery...".into(),
+        ));
+        if let Err(e) = self.fastboot.reboot_recovery(self.serial).await {
+            log::warn!("fastboot reboot recovery failed, asking the user: {:#}", e);
+            let _ = self.sender.send(InstallProgress::WaitingForRecovery);
+        }
+        self.wait_for_recovery().await
+    }
+
+    /// Wait for the device to show up in recovery over adb, then give the
+    /// recovery a moment to finish starting
+    pub async fn wait_for_recovery(&self) -> Result<()> {
+        let _ = self.sender.send(InstallProgress::StatusChanged(
+            "Waiting for recovery mode...".into(),
+        ));
+        mode_wait::wait_for_recovery(self.adb, self.serial, self.sender).await?;
+        let _ = self.sender.send(InstallProgress::RecoveryDetected);
+        tokio::time::sleep(RECOVERY_SETTLE).await;
+        Ok(())
+    }
+
+    /// Show `instructions` for opening "Apply from ADB" and wait until
+    /// the recovery is ready for `adb sideload`
+    pub async fn wait_for_sideload(&self, instructions: &str) -> Result<()> {
+        let _ = self.sender.send(InstallProgress::WaitingForUserAction(instructions.to_string()));
+        mode_wait::wait_for_sideload(self.adb, self.serial, self.sender).await
+    }
+}
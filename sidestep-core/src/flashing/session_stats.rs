@@ -0,0 +1,152 @@
+// Download and flash figures for one install
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::{InstallOutcome, InstallProgress};
+use crate::utils::format::{format_elapsed, format_size, format_speed};
+use std::time::{Duration, Instant};
+
+/// Downloads shorter than this don't give a meaningful average speed
+const MIN_SPEED_SAMPLE: Duration = Duration::from_secs(1);
+
+/// The download currently reporting progress
+#[derive(Debug, Clone)]
+struct ActiveDownload {
+    file_name: String,
+    /// Bytes already on disk when it started reporting, e.g. a resumed file
+    start_bytes: u64,
+    bytes: u64,
+    started: Instant,
+    last_report: Instant,
+}
+
+/// Totals for one install run, built from its progress messages the same
+/// way [`OverallProgress`](crate::flashing::OverallProgress) is: feed it
+/// every message in order. Download time only counts while a download is
+/// reporting, so a prompt between two files doesn't lower the average
+/// speed; flash time runs from the first flash step to the end of the run.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    /// Bytes fetched by finished downloads
+    downloaded: u64,
+    download_time: Duration,
+    download: Option<ActiveDownload>,
+    downloads: usize,
+    flash_started: Option<Instant>,
+    flash_time: Duration,
+    flash_steps: usize,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update from `progress`; a terminal message ends the run
+    pub fn observe(&mut self, progress: &InstallProgress) {
+        let now = Instant::now();
+        match progress {
+            InstallProgress::DownloadProgress {
+                downloaded, file_name, ..
+            } => self.observe_download(file_name, *downloaded, now),
+            InstallProgress::FlashProgress { current, .. } => {
+                self.end_download();
+                self.flash_started.get_or_insert(now);
+                self.flash_steps = self.flash_steps.max(*current);
+            }
+            _ if InstallOutcome::from_progress(progress).is_some() => self.finish(),
+            _ => {}
+        }
+    }
+
+    fn observe_download(&mut self, file_name: &str, downloaded: u64, now: Instant) {
+        if let Some(ref mut download) = self.download
+            && download.file_name == file_name
+            && downloaded >= download.bytes
+        {
+            download.bytes = downloaded;
+            download.last_report = now;
+            return;
+        }
+        // Another file, or the same one starting over from another mirror
+        self.end_download();
+        self.downloads += 1;
+        self.download = Some(ActiveDownload {
+            file_name: file_name.to_string(),
+            start_bytes: downloaded,
+            bytes: downloaded,
+            started: now,
+            last_report: now,
+        });
+    }
+
+    fn end_download(&mut self) {
+        if let Some(download) = self.download.take() {
+            self.downloaded += download.bytes - download.start_bytes;
+            self.download_time += download.last_report.duration_since(download.started);
+        }
+    }
+
+    /// Stop the clocks, for a run that ended without a terminal message.
+    /// Calling it again changes nothing.
+    pub fn finish(&mut self) {
+        self.end_download();
+        if let Some(started) = self.flash_started.take() {
+            self.flash_time += started.elapsed();
+        }
+    }
+
+    /// Bytes fetched so far, not counting what resumed downloads already had
+    pub fn bytes_downloaded(&self) -> u64 {
+        self.downloaded
+            + self
+                .download
+                .as_ref()
+                .map_or(0, |download| download.bytes - download.start_bytes)
+    }
+
+    /// Time spent receiving data
+    pub fn download_time(&self) -> Duration {
+        self.download_time
+            + self
+                .download
+                .as_ref()
+                .map_or(Duration::ZERO, |download| download.last_report.duration_since(download.started))
+    }
+
+    /// Bytes per second across all downloads, once there's enough to measure
+    pub fn average_download_speed(&self) -> Option<f64> {
+        let time = self.download_time();
+        (time >= MIN_SPEED_SAMPLE && self.bytes_downloaded() > 0)
+            .then(|| self.bytes_downloaded() as f64 / time.as_secs_f64())
+    }
+
+    /// How long flashing took, `None` when nothing was flashed
+    pub fn flash_time(&self) -> Option<Duration> {
+        match self.flash_started {
+            Some(started) => Some(self.flash_time + started.elapsed()),
+            None => (self.flash_steps > 0).then_some(self.flash_time),
+        }
+    }
+
+    /// The figures as label and value, leaving out what this run didn't do
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let mut rows = Vec::new();
+        if self.downloads > 0 {
+            rows.push((
+                "Downloaded".to_string(),
+                format!(
+                    "{} in {}",
+                    format_size(self.bytes_downloaded()),
+                    format_elapsed(self.download_time())
+                ),
+            ));
+        }
+        if let Some(speed) = self.average_download_speed() {
+            rows.push(("Average download speed".to_string(), format_speed(speed)));
+        }
+        if let Some(time) = self.flash_time() {
+            rows.push(("Flash time".to_string(), format_elapsed(time)));
+        }
+        rows
+    }
+}
@@ -0,0 +1,133 @@
+// Step duration history for time estimates
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const FILE_NAME: &str = "step-times.json";
+
+/// How many past runs of a step the estimate averages over
+const SAMPLES: usize = 5;
+
+/// Recorded durations of completed steps, keyed by scope ("sargo/ubports")
+/// and then by step name, newest last.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StepTimes {
+    #[serde(default)]
+    steps: HashMap<String, HashMap<String, Vec<u64>>>,
+}
+
+impl StepTimes {
+    fn path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join("sidestep")
+            .join(FILE_NAME)
+    }
+
+    /// The history on disk; a missing or unreadable file is an empty one.
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create the data directory")?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Average of the last few recorded runs of `step`, or `fallback` when
+    /// it has never completed in this scope.
+    pub fn estimate(&self, scope: &str, step: &str, fallback: Option<Duration>) -> Option<Duration> {
+        let samples = self.steps.get(scope).and_then(|steps| steps.get(step));
+        match samples {
+            Some(samples) if !samples.is_empty() => {
+                let total: u64 = samples.iter().sum();
+                Some(Duration::from_secs(total / samples.len() as u64))
+            }
+            _ => fallback,
+        }
+    }
+
+    pub fn record(&mut self, scope: &str, step: &str, took: Duration) {
+        let samples = self
+            .steps
+            .entry(scope.to_string())
+            .or_default()
+            .entry(step.to_string())
+            .or_default();
+        samples.push(took.as_secs());
+        if samples.len() > SAMPLES {
+            samples.drain(..samples.len() - SAMPLES);
+        }
+    }
+}
+
+/// Times consecutive steps of one run. A step counts as completed when the
+/// next one begins or the run finishes, so a failed step is never recorded.
+#[derive(Debug)]
+pub struct StepTimer {
+    scope: String,
+    times: StepTimes,
+    current: Option<(String, Instant)>,
+}
+
+impl StepTimer {
+    /// `scope` is usually the device codename and distro, e.g. "sargo/ubports".
+    pub fn new(scope: &str) -> Self {
+        Self {
+            scope: scope.to_string(),
+            times: StepTimes::load(),
+            current: None,
+        }
+    }
+
+    /// Start timing `step`, completing the one before it, and return how
+    /// long `step` usually takes. Repeating the current step is a no-op.
+    pub fn begin(&mut self, step: &str, fallback: Option<Duration>) -> Option<Duration> {
+        if self.current.as_ref().is_none_or(|(name, _)| name != step) {
+            self.complete_current();
+            self.current = Some((step.to_string(), Instant::now()));
+        }
+        self.estimate(step, fallback)
+    }
+
+    /// How long `step` usually takes in this timer's scope
+    pub fn estimate(&self, step: &str, fallback: Option<Duration>) -> Option<Duration> {
+        self.times.estimate(&self.scope, step, fallback)
+    }
+
+    /// Complete the running step and write the history.
+    pub fn finish(&mut self) {
+        self.complete_current();
+        self.save();
+    }
+
+    /// Drop the running step, which didn't complete, and write the history
+    /// of the ones before it.
+    pub fn abandon(&mut self) {
+        self.current = None;
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.times.save() {
+            log::warn!("Could not save step durations: {:#}", e);
+        }
+    }
+
+    fn complete_current(&mut self) {
+        if let Some((step, started)) = self.current.take() {
+            self.times.record(&self.scope, &step, started.elapsed());
+        }
+    }
+}
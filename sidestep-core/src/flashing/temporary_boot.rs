@@ -0,0 +1,148 @@
+// Temporary boot ("try without installing")
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::cache;
+use crate::flashing::checksum::ChecksumVerifier;
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::progress::{self, InstallProgress};
+use crate::hardware::cancel::CancelToken;
+use crate::hardware::fastboot::Fastboot;
+use crate::hardware::mode_guard::StartMode;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+/// Downloads a distro's boot image and starts it once with `fastboot boot`.
+///
+/// Nothing is written to the device; the next reboot returns to whatever
+/// is installed.
+pub struct TemporaryBootInstaller {
+    serial: String,
+    distro_name: String,
+    url: String,
+    sha256: Option<String>,
+    download_dir: PathBuf,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
+}
+
+impl TemporaryBootInstaller {
+    /// `fastboot boot` is only answered by the bootloader, and there's no
+    /// adb step to get the device there first
+    pub const REQUIRED_MODE: StartMode = StartMode::Fastboot;
+
+    pub fn new(
+        serial: String,
+        distro_id: &str,
+        distro_name: String,
+        url: String,
+        sha256: Option<String>,
+    ) -> Self {
+        let download_dir = cache::installer_dir("temporary-boot").join(distro_id);
+
+        Self {
+            serial,
+            distro_name,
+            url,
+            sha256,
+            download_dir,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
+    /// Spawn the installer on a background thread, returning immediately.
+    pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(async {
+                if let Err(e) = self.run(&sender).await {
+                    log::error!("Temporary boot failed: {:#}", e);
+                    let _ = sender.send(InstallProgress::from_error(&e));
+                }
+            });
+        });
+
+        receiver
+    }
+
+    async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
+
+        let file_name = self
+            .url
+            .rsplit('/')
+            .next()
+            .filter(|n| !n.is_empty())
+            .unwrap_or("boot.img")
+            .to_string();
+
+        // ── Step 1: Download boot image ──
+        let _ = sender.send(InstallProgress::StatusChanged(format!(
+            "Downloading {} boot image...",
+            self.distro_name
+        )));
+        let image_path = downloader
+            .download_if_needed(
+                &self.url,
+                &file_name,
+                self.sha256.as_deref(),
+                progress::report_download(sender, &file_name, 1, 1),
+            )
+            .await
+            .context("Failed to download boot image")?;
+
+        // ── Step 2: Verify ──
+        match self.sha256 {
+            Some(ref expected) => {
+                let _ = sender.send(InstallProgress::VerifyProgress {
+                    verified: 0,
+                    total: 1,
+                    file_name: file_name.clone(),
+                });
+                ChecksumVerifier::check(&image_path, expected, None)?;
+                let _ = sender.send(InstallProgress::VerifyProgress {
+                    verified: 1,
+                    total: 1,
+                    file_name: file_name.clone(),
+                });
+            }
+            None => progress::confirm_unverified(sender, &file_name)?,
+        }
+
+        // ── Step 3: Boot once from RAM ──
+        let _ = sender.send(InstallProgress::FlashProgress {
+            current: 1,
+            total: 1,
+            description: "Booting image (nothing is written)...".into(),
+        });
+        fastboot
+            .boot(&self.serial, &image_path)
+            .await
+            .context("Failed to boot image")?;
+
+        let _ = sender.send(InstallProgress::BootedTemporarily);
+        Ok(())
+    }
+}
@@ -1,16 +1,19 @@
 // UBports (Ubuntu Touch) installer backend
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::flashing::checksum::ChecksumVerifier;
-use crate::flashing::downloader::ImageDownloader;
-use crate::flashing::progress::InstallProgress;
+use crate::flashing::cache;
+use crate::flashing::checksum::{ChecksumAlgorithm, ChecksumVerifier};
+use crate::flashing::downloader::{DownloadCancelHandle, ImageDownloader};
+use crate::flashing::mode_wait;
+use crate::flashing::progress::{self, InstallProgress};
 use crate::hardware::adb::Adb;
+use crate::hardware::cancel::CancelToken;
 use crate::hardware::fastboot::Fastboot;
 use crate::models::system_image::SystemImageIndex;
+use crate::hardware::mode_guard::StartMode;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
-use std::time::Duration;
 
 /// Firmware image descriptor (hardcoded for sargo MVP)
 struct FirmwareImage {
@@ -48,7 +51,7 @@ const SARGO_FIRMWARE: &[FirmwareImage] = &[
 ];
 
 /// UBports system-image server base URL
-const SYSTEM_IMAGE_SERVER: &str = "https://system-image.ubports.com";
+pub(crate) const SYSTEM_IMAGE_SERVER: &str = "https://system-image.ubports.com";
 
 /// GPG keyring files needed for system-image recovery install
 const GPG_KEYRINGS: &[&str] = &[
@@ -62,23 +65,63 @@ const GPG_KEYRINGS: &[&str] = &[
 pub struct UbportsInstaller {
     serial: String,
     channel_path: String,
+    /// System-image version to install instead of the newest
+    version: Option<u32>,
     download_dir: PathBuf,
+    download_only: bool,
+    download_cancel: DownloadCancelHandle,
+    cancel: CancelToken,
 }
 
 impl UbportsInstaller {
+    /// The device must be booted into Android: the first reboot is sent over adb
+    pub const REQUIRED_MODE: StartMode = StartMode::Adb;
+
     pub fn new(serial: String, channel_path: String) -> Self {
-        let download_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("sidestep")
-            .join("ubports");
+        let download_dir = cache::installer_dir("ubports");
 
         Self {
             serial,
             channel_path,
+            version: None,
             download_dir,
+            download_only: false,
+            download_cancel: DownloadCancelHandle::new(),
+            cancel: CancelToken::new(),
         }
     }
 
+    /// Where downloaded images are cached
+    pub fn download_dir(&self) -> &Path {
+        &self.download_dir
+    }
+
+    /// Install system-image `version` rather than the channel's newest build.
+    /// The run fails if the server no longer carries it.
+    pub fn version(mut self, version: Option<u32>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Stop after downloading and verifying images, leaving them in the cache.
+    pub fn download_only(mut self, enabled: bool) -> Self {
+        self.download_only = enabled;
+        self
+    }
+
+    /// Let the UI abort in-flight downloads without cancelling the install.
+    pub fn download_cancel_handle(mut self, handle: DownloadCancelHandle) -> Self {
+        self.download_cancel = handle;
+        self
+    }
+
+    /// Let the UI stop the whole install. The fastboot or adb command
+    /// running at the time is killed and the run ends as cancelled.
+    pub fn cancel_handle(mut self, handle: CancelToken) -> Self {
+        self.cancel = handle;
+        self
+    }
+
     /// Spawn the installer on a background thread, returning immediately.
     /// Progress is reported via the returned mpsc::Receiver.
     pub fn spawn(self) -> std::sync::mpsc::Receiver<InstallProgress> {
@@ -93,7 +136,7 @@ impl UbportsInstaller {
             rt.block_on(async {
                 if let Err(e) = self.run(&sender).await {
                     log::error!("UBports installation failed: {:#}", e);
-                    let _ = sender.send(InstallProgress::Error(format!("{:#}", e)));
+                    let _ = sender.send(InstallProgress::from_error(&e));
                 }
             });
         });
@@ -103,9 +146,10 @@ impl UbportsInstaller {
 
     /// Main installation flow (runs on background thread)
     async fn run(&self, sender: &Sender<InstallProgress>) -> Result<()> {
-        let downloader = ImageDownloader::new(self.download_dir.clone());
-        let adb = Adb::new();
-        let fastboot = Fastboot::new();
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_cancel_handle(self.download_cancel.clone());
+        let adb = Adb::new().with_cancel(self.cancel.clone());
+        let fastboot = Fastboot::new().with_cancel(self.cancel.clone());
 
         // ── Step 1: Download firmware images ──
         let _ = sender.send(InstallProgress::StatusChanged(
@@ -139,6 +183,12 @@ impl UbportsInstaller {
         ));
         self.verify_system_image(&system_files, sender)?;
 
+        // Download-only mode stops here, leaving the verified images cached
+        if self.download_only {
+            let _ = sender.send(InstallProgress::DownloadOnlyComplete(self.download_dir.clone()));
+            return Ok(());
+        }
+
         // ── Step 6: Reboot to bootloader ──
         let _ = sender.send(InstallProgress::StatusChanged(
             "Rebooting to bootloader...".into(),
@@ -149,7 +199,7 @@ impl UbportsInstaller {
         let _ = sender.send(InstallProgress::StatusChanged(
             "Waiting for device in fastboot mode...".into(),
         ));
-        self.wait_for_fastboot(&fastboot).await?;
+        mode_wait::wait_for_fastboot(&fastboot, &self.serial, sender).await?;
 
         // ── Step 8: Flash firmware partitions ──
         let _ = sender.send(InstallProgress::StatusChanged(
@@ -175,7 +225,7 @@ impl UbportsInstaller {
         // must boot in recovery mode — a normal reboot will fail
         // because there is no system partition yet.
         let _ = sender.send(InstallProgress::WaitingForRecovery);
-        adb.wait_for_recovery(&self.serial).await?;
+        mode_wait::wait_for_recovery(&adb, &self.serial, sender).await?;
         let _ = sender.send(InstallProgress::RecoveryDetected);
 
         // ── Step 11: Prepare system image (matches UBports adb:preparesystemimage) ──
@@ -225,8 +275,8 @@ impl UbportsInstaller {
         let total_size: u64 = 0; // We don't know sizes upfront; use per-file progress
         let mut cumulative_downloaded: u64 = 0;
 
-        for fw in SARGO_FIRMWARE {
-            let file_name = fw.filename.to_string();
+        for (i, fw) in SARGO_FIRMWARE.iter().enumerate() {
+            let file_name = progress::download_label(fw.filename, i + 1, SARGO_FIRMWARE.len());
             let sender_clone = sender.clone();
             let prev_downloaded = cumulative_downloaded;
 
@@ -266,19 +316,12 @@ impl UbportsInstaller {
                 file_name: fw.filename.to_string(),
             });
 
-            let ok = ChecksumVerifier::verify(&path, fw.sha256)?;
-            if !ok {
-                anyhow::bail!(
-                    "Checksum mismatch for {} — expected {}",
-                    fw.filename,
-                    fw.sha256
-                );
-            }
+            ChecksumVerifier::check(&path, fw.sha256, Some(ChecksumAlgorithm::Sha256))?;
         }
         Ok(())
     }
 
-    /// Fetch the system-image index.json, pick latest full image, download all files.
+    /// Fetch the system-image index.json, pick the pinned or latest full image, download all files.
     /// Returns list of (local_path, remote_filename) pairs for files to push.
     async fn download_system_image(
         &self,
@@ -298,9 +341,14 @@ impl UbportsInstaller {
         let index: SystemImageIndex =
             serde_json::from_str(&index_text).context("Failed to parse system-image index.json")?;
 
-        let entry = index
-            .latest_full()
-            .context("No full image found in system-image index")?;
+        let entry = match self.version {
+            Some(version) => index.full(version).with_context(|| {
+                format!("Version {} is no longer available in {}", version, self.channel_path)
+            })?,
+            None => index
+                .latest_full()
+                .context("No full image found in system-image index")?,
+        };
 
         log::info!(
             "Selected system-image version {} ({} files)",
@@ -314,7 +362,7 @@ impl UbportsInstaller {
         let mut downloaded_so_far: u64 = 0;
         let mut system_files = Vec::new();
 
-        for file in &entry.files {
+        for (i, file) in entry.files.iter().enumerate() {
             let url = format!("{}{}", SYSTEM_IMAGE_SERVER, file.path);
             let filename = file
                 .path
@@ -323,7 +371,7 @@ impl UbportsInstaller {
                 .unwrap_or(&file.path);
 
             let sender_clone = sender.clone();
-            let file_name_str = filename.to_string();
+            let file_name_str = progress::download_label(filename, i + 1, entry.files.len());
             let prev_downloaded = downloaded_so_far;
             let total = total_size;
 
@@ -358,7 +406,12 @@ impl UbportsInstaller {
                 .unwrap_or(&file.signature);
 
             let sig_path = downloader
-                .download_if_needed(&sig_url, sig_filename, None, None)
+                .download_if_needed(
+                    &sig_url,
+                    sig_filename,
+                    None,
+                    progress::report_download(sender, sig_filename, 1, 1),
+                )
                 .await
                 .with_context(|| format!("Failed to download signature {}", sig_filename))?;
 
@@ -379,7 +432,7 @@ impl UbportsInstaller {
         downloader: &ImageDownloader,
         sender: &Sender<InstallProgress>,
     ) -> Result<()> {
-        for keyring_path in GPG_KEYRINGS {
+        for (i, keyring_path) in GPG_KEYRINGS.iter().enumerate() {
             let url = format!("{}/{}", SYSTEM_IMAGE_SERVER, keyring_path);
             let filename = keyring_path.rsplit('/').next().unwrap_or(keyring_path);
 
@@ -388,7 +441,12 @@ impl UbportsInstaller {
             ));
 
             downloader
-                .download_if_needed(&url, filename, None, None)
+                .download_if_needed(
+                    &url,
+                    filename,
+                    None,
+                    progress::report_download(sender, filename, i + 1, GPG_KEYRINGS.len()),
+                )
                 .await
                 .with_context(|| format!("Failed to download GPG keyring {}", filename))?;
         }
@@ -413,30 +471,11 @@ impl UbportsInstaller {
                 file_name: file.remote_name.clone(),
             });
 
-            let ok = ChecksumVerifier::verify(&file.local_path, &file.checksum)?;
-            if !ok {
-                anyhow::bail!(
-                    "Checksum mismatch for {}",
-                    file.remote_name
-                );
-            }
+            ChecksumVerifier::check(&file.local_path, &file.checksum, Some(ChecksumAlgorithm::Sha256))?;
         }
         Ok(())
     }
 
-    async fn wait_for_fastboot(&self, fastboot: &Fastboot) -> Result<()> {
-        // Poll fastboot devices until our device appears
-        for _ in 0..60 {
-            if let Ok(devices) = fastboot.devices().await {
-                if devices.iter().any(|d| d.serial == self.serial) {
-                    return Ok(());
-                }
-            }
-            tokio::time::sleep(Duration::from_secs(2)).await;
-        }
-        anyhow::bail!("Timed out waiting for device in fastboot mode")
-    }
-
     async fn flash_firmware(
         &self,
         fastboot: &Fastboot,
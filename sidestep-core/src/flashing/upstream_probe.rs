@@ -0,0 +1,148 @@
+// Checking that a distro's server still looks the way its backend reads it
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::channel_info::{self, client};
+use crate::flashing::eos;
+use crate::flashing::github_release::{self, GithubReleaseSource, Release};
+use crate::flashing::lineageos::LineageBuild;
+use crate::flashing::postmarketos;
+use crate::flashing::ubports::SYSTEM_IMAGE_SERVER;
+use crate::models::Device;
+use crate::models::distro_config::{ChannelConfig, DistroConfig};
+use crate::models::system_image::SystemImageIndex;
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+
+/// What a look at a backend's upstream server found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpstreamCheck {
+    /// Laid out the way the backend expects, or nothing to check
+    Expected,
+    /// The server answered in a shape the backend doesn't read. Usually
+    /// means the upstream changed and this Sidestep is too old for it.
+    Changed { detail: String },
+    /// The server couldn't be asked; the install reports that itself
+    Unreachable,
+}
+
+/// Look at the index or listing `distro`'s backend will read for `channel`
+/// (Mobian and /e/OS read one listing for every channel).
+///
+/// Only the structure is checked. Missing builds, error statuses and rate
+/// limits are left for the installer, which already explains them.
+pub async fn check(
+    device: &Device,
+    distro: &DistroConfig,
+    channel: Option<&ChannelConfig>,
+) -> UpstreamCheck {
+    let probe = async {
+        match (distro.id.as_str(), channel) {
+            ("ubuntutouch" | "ubports", Some(channel)) => ubports(channel).await,
+            ("lineageos", Some(channel)) => lineageos(channel).await,
+            ("droidian", Some(channel)) => droidian(channel).await,
+            ("postmarketos", Some(channel)) => postmarketos(device, distro, channel).await,
+            ("eos", _) => listing(distro, ".zip").await,
+            ("mobian", _) => listing(distro, ".tar.xz").await,
+            _ => Ok(None),
+        }
+    };
+    match probe.await {
+        Ok(None) => UpstreamCheck::Expected,
+        Ok(Some(detail)) => {
+            log::warn!("{} upstream looks different: {}", distro.name, detail);
+            UpstreamCheck::Changed { detail }
+        }
+        Err(e) if channel_info::is_unreachable(&e) => UpstreamCheck::Unreachable,
+        Err(e) => {
+            log::debug!("Skipping the {} upstream check: {:#}", distro.name, e);
+            UpstreamCheck::Expected
+        }
+    }
+}
+
+/// The body of `url`, or `None` when it answers with an error status
+async fn fetch(url: &str) -> Result<Option<String>> {
+    let response = client()?.get(url).send().await?;
+    if !response.status().is_success() {
+        log::debug!("{} answered {}", url, response.status());
+        return Ok(None);
+    }
+    Ok(Some(response.text().await?))
+}
+
+/// Whether `body` still deserializes into the type its backend reads.
+/// An unknown field is fine; a missing or retyped one isn't.
+fn parses_as<T: DeserializeOwned>(body: &str, what: &str) -> Option<String> {
+    serde_json::from_str::<T>(body)
+        .err()
+        .map(|e| format!("{} isn't in the expected format ({})", what, e))
+}
+
+async fn ubports(channel: &ChannelConfig) -> Result<Option<String>> {
+    let Some(ref path) = channel.path else {
+        return Ok(None);
+    };
+    let body = fetch(&format!("{}/{}/index.json", SYSTEM_IMAGE_SERVER, path)).await?;
+    Ok(body.and_then(|body| parses_as::<SystemImageIndex>(&body, "The system-image index")))
+}
+
+async fn lineageos(channel: &ChannelConfig) -> Result<Option<String>> {
+    let Some(ref api_url) = channel.release_url else {
+        return Ok(None);
+    };
+    let body = fetch(api_url).await?;
+    Ok(body.and_then(|body| parses_as::<Vec<LineageBuild>>(&body, "The LineageOS API response")))
+}
+
+async fn droidian(channel: &ChannelConfig) -> Result<Option<String>> {
+    let Some(source) = GithubReleaseSource::for_channel(channel) else {
+        return Ok(None);
+    };
+    let url = format!("{}/repos/{}/releases?per_page=1", github_release::API_BASE, source.repo());
+    let body = fetch(&url).await?;
+    Ok(body.and_then(|body| parses_as::<Vec<Release>>(&body, "The GitHub releases list")))
+}
+
+/// The interface directory lists builds in `YYYYMMDD-HHMM/` directories.
+async fn postmarketos(
+    device: &Device,
+    distro: &DistroConfig,
+    channel: &ChannelConfig,
+) -> Result<Option<String>> {
+    let (Some(base_url), Some(interface)) = (
+        distro.base_url.as_deref(),
+        distro.interfaces.as_ref().and_then(|i| i.first()),
+    ) else {
+        return Ok(None);
+    };
+    let listing_url = format!(
+        "{}{}/{}/{}/",
+        base_url,
+        channel.id,
+        postmarketos::device_name(device),
+        interface.id
+    );
+    let Some(html) = fetch(&listing_url).await? else {
+        return Ok(None);
+    };
+    if postmarketos::hrefs(&html).any(postmarketos::is_build_dir) {
+        Ok(None)
+    } else {
+        Ok(Some(format!("{} has no YYYYMMDD-HHMM build directories", listing_url)))
+    }
+}
+
+/// A directory listing with at least one file ending in `extension`
+async fn listing(distro: &DistroConfig, extension: &str) -> Result<Option<String>> {
+    let Some(ref base_url) = distro.base_url else {
+        return Ok(None);
+    };
+    let Some(html) = fetch(base_url).await? else {
+        return Ok(None);
+    };
+    if eos::index_links(&html).iter().any(|link| link.ends_with(extension)) {
+        Ok(None)
+    } else {
+        Ok(Some(format!("{} no longer lists {} files", base_url, extension)))
+    }
+}
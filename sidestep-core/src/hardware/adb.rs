@@ -0,0 +1,531 @@
+// ADB wrapper
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use crate::hardware::cancel::{CancelToken, CancellableCommand, InstallCancelled};
+use crate::hardware::command_log::LoggedCommand;
+use crate::hardware::sideload;
+use crate::hardware::simulator::{SimulatedMode, Simulator};
+
+/// Represents an ADB connection to a device
+#[derive(Debug, Clone)]
+pub struct AdbDevice {
+    pub serial: String,
+    pub state: AdbState,
+    /// `model:` from `adb devices -l`, when the device reports one
+    pub model: Option<String>,
+    /// `product:` from `adb devices -l`
+    pub product: Option<String>,
+    /// The USB port it's on (`1-1` for `usb:1-1`), telling apart devices
+    /// that share a serial
+    pub usb: Option<String>,
+}
+
+/// The state column of `adb devices`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdbState {
+    /// Booted and authorised: commands will work
+    Device,
+    /// Waiting for the user to accept this computer's RSA key on the phone
+    Unauthorized,
+    /// Connected, but adbd isn't answering, often mid-boot
+    Offline,
+    Recovery,
+    /// In recovery's "Apply from ADB"
+    Sideload,
+    /// The USB device can't be opened; the udev rules are missing
+    NoPermissions,
+    Other(String),
+}
+
+impl AdbState {
+    pub fn parse(state: &str) -> Self {
+        match state {
+            "device" => AdbState::Device,
+            "unauthorized" => AdbState::Unauthorized,
+            "offline" => AdbState::Offline,
+            "recovery" => AdbState::Recovery,
+            "sideload" => AdbState::Sideload,
+            "no permissions" => AdbState::NoPermissions,
+            other => AdbState::Other(other.to_string()),
+        }
+    }
+
+    /// The state as adb prints it
+    pub fn label(&self) -> &str {
+        match self {
+            AdbState::Device => "device",
+            AdbState::Unauthorized => "unauthorized",
+            AdbState::Offline => "offline",
+            AdbState::Recovery => "recovery",
+            AdbState::Sideload => "sideload",
+            AdbState::NoPermissions => "no permissions",
+            AdbState::Other(state) => state,
+        }
+    }
+}
+
+/// One line of `adb devices -l`:
+/// `SERIAL  device usb:1-1 product:x model:Pixel_5 device:redfin transport_id:1`.
+/// The state may be two words ("no permissions"), followed by a
+/// parenthesised explanation.
+fn parse_device_line(line: &str) -> Option<AdbDevice> {
+    let mut parts = line.split_whitespace();
+    let serial = parts.next()?.to_string();
+    let mut state = parts.next()?.to_string();
+    let rest: Vec<&str> = parts.collect();
+    if state == "no" && rest.first() == Some(&"permissions") {
+        state = "no permissions".to_string();
+    }
+    let field = |name: &str| {
+        rest.iter()
+            .find_map(|part| part.strip_prefix(name).and_then(|v| v.strip_prefix(':')))
+            .map(str::to_string)
+    };
+    Some(AdbDevice {
+        serial,
+        state: AdbState::parse(&state),
+        model: field("model"),
+        product: field("product"),
+        usb: field("usb"),
+    })
+}
+
+/// ADB command wrapper
+///
+/// With `SIDESTEP_SIMULATE` set, commands are answered by the [`Simulator`].
+#[derive(Debug, Clone)]
+pub struct Adb {
+    binary_path: String,
+    cancel: CancelToken,
+}
+
+impl Default for Adb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Adb {
+    pub fn new() -> Self {
+        // Try to find adb in common locations
+        let binary_path = std::env::var("ADB_PATH")
+            .unwrap_or_else(|_| "adb".to_string());
+        
+        Self::with_path(binary_path)
+    }
+
+    pub fn with_path(path: String) -> Self {
+        Self {
+            binary_path: path,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Kill a running wait, push or sideload once `cancel` is set
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// List connected ADB devices
+    pub async fn devices(&self) -> Result<Vec<AdbDevice>> {
+        if let Some(simulator) = Simulator::active() {
+            return Ok(simulator.adb_devices());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["devices", "-l"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run adb devices")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let devices: Vec<AdbDevice> = stdout
+            .lines()
+            .skip(1)
+            .filter(|line| !line.starts_with('*'))
+            .filter_map(parse_device_line)
+            .collect();
+
+        log::debug!("ADB devices: {:?}", devices);
+        Ok(devices)
+    }
+
+    /// Get device property via getprop
+    pub async fn getprop(&self, serial: &str, prop: &str) -> Result<String> {
+        if let Some(simulator) = Simulator::active() {
+            return simulator.getprop(serial, prop);
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "shell", "getprop", prop])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run adb getprop")?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// First line of `adb version`
+    pub async fn version(&self) -> Result<String> {
+        if Simulator::active().is_some() {
+            return Ok("Android Debug Bridge (simulated)".to_string());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .arg("version")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run adb version")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+    }
+
+    /// Get device codename (ro.product.device)
+    pub async fn get_codename(&self, serial: &str) -> Result<String> {
+        self.getprop(serial, "ro.product.device").await
+    }
+
+    /// Get device model name
+    pub async fn get_model(&self, serial: &str) -> Result<String> {
+        self.getprop(serial, "ro.product.model").await
+    }
+
+    /// Get device manufacturer
+    pub async fn get_manufacturer(&self, serial: &str) -> Result<String> {
+        self.getprop(serial, "ro.product.manufacturer").await
+    }
+
+    /// Reboot into bootloader mode
+    pub async fn reboot_bootloader(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting {} to bootloader", serial);
+        if let Some(simulator) = Simulator::active() {
+            return simulator.adb_reboot(serial, SimulatedMode::Bootloader);
+        }
+
+        Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot", "bootloader"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot to bootloader")?;
+
+        Ok(())
+    }
+
+    /// Reboot a Samsung device into Download Mode, where Heimdall flashes it
+    pub async fn reboot_download(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting {} to Download Mode", serial);
+        Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot", "download"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot to Download Mode")?;
+
+        Ok(())
+    }
+
+    /// Run a shell command on the device
+    pub async fn shell(&self, serial: &str, cmd: &str) -> Result<String> {
+        if let Some(simulator) = Simulator::active() {
+            return simulator.adb_shell(serial, cmd);
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "shell", cmd])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run adb shell")?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Get Android version (ro.build.version.release)
+    pub async fn get_android_version(&self, serial: &str) -> Result<String> {
+        self.getprop(serial, "ro.build.version.release").await
+    }
+
+    /// Get build display ID (ro.build.display.id)
+    pub async fn get_build_id(&self, serial: &str) -> Result<String> {
+        self.getprop(serial, "ro.build.display.id").await
+    }
+
+    /// Get battery level (0–100)
+    pub async fn get_battery_level(&self, serial: &str) -> Result<u8> {
+        // Try sysfs first
+        let output = self.shell(serial, "cat /sys/class/power_supply/battery/capacity").await?;
+        let trimmed = output.trim();
+        if let Ok(level) = trimmed.parse::<u8>() {
+            return Ok(level);
+        }
+
+        // Fallback: parse "dumpsys battery" for the "level:" line
+        let dump = self.shell(serial, "dumpsys battery").await?;
+        for line in dump.lines() {
+            let line = line.trim();
+            if let Some(val) = line.strip_prefix("level:") {
+                if let Ok(level) = val.trim().parse::<u8>() {
+                    return Ok(level);
+                }
+            }
+        }
+
+        anyhow::bail!("Could not determine battery level")
+    }
+
+    /// Check if device is unlocked
+    pub async fn is_unlocked(&self, serial: &str) -> Result<bool> {
+        // Method 1: Check ro.boot.flash.locked
+        let locked = self.getprop(serial, "ro.boot.flash.locked").await?;
+        if locked == "0" {
+            return Ok(true);
+        }
+        
+        // Method 2: Check sys.oem_unlock_allowed (developer settings)
+        // This usually just means it CAN be unlocked, not that it IS unlocked, 
+        // but sometimes it's relevant context. 
+        // For actual lock status, ro.boot.flash.locked or ro.boot.verifiedbootstate are better.
+        
+        // Method 3: Check ro.boot.verifiedbootstate
+        let boot_state = self.getprop(serial, "ro.boot.verifiedbootstate").await?;
+        if boot_state == "orange" { // Orange means unlocked/unverified
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Whether "OEM unlocking" is enabled in developer options.
+    ///
+    /// Returns `None` when the device doesn't expose `sys.oem_unlock_allowed`.
+    pub async fn oem_unlock_allowed(&self, serial: &str) -> Result<Option<bool>> {
+        let value = self.getprop(serial, "sys.oem_unlock_allowed").await?;
+        Ok(match value.as_str() {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        })
+    }
+
+    /// Wait for device to be connected
+    pub async fn wait_for_device(&self, serial: &str) -> Result<()> {
+        if let Some(simulator) = Simulator::active() {
+            while simulator.mode() != Some(SimulatedMode::Android) {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+            return Ok(());
+        }
+
+        Command::new(&self.binary_path)
+            .args(["-s", serial, "wait-for-device"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to wait for device")?;
+
+        Ok(())
+    }
+
+    /// Reboot into recovery mode
+    pub async fn reboot_recovery(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting {} to recovery", serial);
+        if let Some(simulator) = Simulator::active() {
+            return simulator.adb_reboot(serial, SimulatedMode::Recovery);
+        }
+
+        Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot", "recovery"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot to recovery")?;
+
+        Ok(())
+    }
+
+    /// Sideload a zip file via ADB sideload (used in recovery mode)
+    ///
+    /// `on_progress` gets the percentage adb reports as the recovery reads
+    /// the package. Failures that adb or the recovery are known to print
+    /// come back as [`sideload::SideloadRejected`], saying what to do about them,
+    /// as does a device that's connected but not in "Apply from ADB".
+    pub async fn sideload<F: Fn(u64)>(&self, serial: &str, zip_path: &Path, on_progress: F) -> Result<()> {
+        log::info!("Sideloading {} to {}", zip_path.display(), serial);
+        self.cancel.check()?;
+        if let Some(simulator) = Simulator::active() {
+            return simulator.adb_transfer(serial, "sideload", zip_path).await;
+        }
+
+        // adb would sit waiting for a device in the wrong mode; say so
+        // instead. A device adb can't see is left for adb to report.
+        if let Ok(devices) = self.devices().await
+            && let Some(device) = devices.iter().find(|d| d.serial == serial)
+            && device.state != AdbState::Sideload
+        {
+            return Err(sideload::not_in_sideload(device.state.label()).into());
+        }
+
+        log::debug!("$ adb -s {} sideload {}", serial, zip_path.display());
+        let mut child = Command::new(&self.binary_path)
+            .args([
+                "-s", serial,
+                "sideload",
+                zip_path.to_str().unwrap(),
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to run adb sideload")?;
+        let mut stdout_pipe = child.stdout.take().context("adb stdout unavailable")?;
+        let mut stderr_pipe = child.stderr.take().context("adb stderr unavailable")?;
+
+        // The progress line is redrawn in place with '\r', so read whatever
+        // has arrived rather than waiting for whole lines
+        let read_stdout = async {
+            let mut stdout = String::new();
+            let mut buf = [0u8; 512];
+            let mut last = None;
+            loop {
+                let n = stdout_pipe.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                let chunk = String::from_utf8_lossy(&buf[..n]);
+                stdout.push_str(&chunk);
+                let percent = chunk.split(['\r', '\n']).filter_map(sideload::parse_progress).next_back();
+                if percent.is_some() && percent != last {
+                    on_progress(percent.unwrap_or_default());
+                    last = percent;
+                }
+            }
+            Ok::<_, std::io::Error>(stdout)
+        };
+        let read_stderr = async {
+            let mut stderr = String::new();
+            stderr_pipe.read_to_string(&mut stderr).await.map(|_| stderr)
+        };
+        let (stdout, stderr) = tokio::select! {
+            output = async { tokio::join!(read_stdout, read_stderr) } => output,
+            () = self.cancel.cancelled() => {
+                log::info!("Stopping the sideload for the cancelled install");
+                return Err(InstallCancelled.into());
+            }
+        };
+        let stdout = stdout.context("Failed to read adb sideload output")?;
+        let stderr = stderr.context("Failed to read adb sideload output")?;
+        let status = child.wait().await.context("Failed to wait for adb sideload")?;
+        log::debug!("adb sideload stdout: {}", stdout);
+        log::debug!("adb sideload stderr: {}", stderr);
+
+        if let Some(rejected) = sideload::classify(&format!("{}\n{}", stdout, stderr)) {
+            log::error!("adb sideload refused: {}", rejected.output);
+            return Err(rejected.into());
+        }
+
+        // adb sideload returns exit code 0 on success, but may also return
+        // exit code 1 with "serving" messages that are actually fine.
+        // The real failure indicator is specific error strings.
+        if !status.success() {
+            if sideload::completed(&format!("{}\n{}", stdout, stderr)) {
+                log::warn!("adb sideload exited with {} after sending the whole package", status);
+                return Ok(());
+            }
+            if stderr.contains("error") {
+                anyhow::bail!("adb sideload failed: {}", stderr);
+            }
+            // Otherwise it likely completed successfully despite non-zero exit
+            log::warn!("adb sideload exited with non-zero status but no error detected");
+        }
+
+        Ok(())
+    }
+
+    /// Copy a raw partition off the device into `dest`.
+    ///
+    /// Block devices are only readable as root, so this needs `su` on the
+    /// device; without it the command produces no data and an error is returned.
+    pub async fn pull_partition(&self, serial: &str, partition: &str, dest: &Path) -> Result<u64> {
+        log::info!("Backing up partition {} from {}", partition, serial);
+        if let Some(simulator) = Simulator::active() {
+            return simulator.pull_partition(serial, partition, dest).await;
+        }
+
+        let remote = format!("su -c 'cat /dev/block/by-name/{}'", partition);
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "exec-out", &remote])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run adb exec-out")?;
+
+        if !output.status.success() || output.stdout.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!(
+                "Could not read partition {} (root access is required): {}",
+                partition,
+                stderr.trim()
+            );
+        }
+
+        tokio::fs::write(dest, &output.stdout)
+            .await
+            .with_context(|| format!("Failed to write {}", dest.display()))?;
+
+        Ok(output.stdout.len() as u64)
+    }
+
+    /// Push a local file to the device
+    pub async fn push(&self, serial: &str, local: &Path, remote: &str) -> Result<()> {
+        log::info!("Pushing {} to {}", local.display(), remote);
+        self.cancel.check()?;
+        if let Some(simulator) = Simulator::active() {
+            return simulator.adb_transfer(serial, "push", local).await;
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args([
+                "-s", serial,
+                "push",
+                local.to_str().unwrap(),
+                remote,
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run adb push")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("adb push failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+}
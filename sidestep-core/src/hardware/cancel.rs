@@ -0,0 +1,81 @@
+// Stopping an install part way
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::command_log::LoggedCommand;
+use std::process::Output;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// How often a waiting [`CancelToken::cancelled`] looks at the flag
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Shared flag the UI sets to stop an install. [`Fastboot`](super::Fastboot)
+/// and [`Adb`](super::Adb) built with one kill the command they're running
+/// when it's set, and installers check it between steps.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Fail with [`InstallCancelled`] once the token has been cancelled
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.is_cancelled() {
+            return Err(InstallCancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Resolves when the token is cancelled, for racing against a command
+    pub async fn cancelled(&self) {
+        while !self.is_cancelled() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// The user stopped the install through its [`CancelToken`]
+#[derive(Debug, Clone)]
+pub struct InstallCancelled;
+
+impl std::fmt::Display for InstallCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Installation cancelled")
+    }
+}
+
+impl std::error::Error for InstallCancelled {}
+
+/// Running a command so that cancelling `cancel` kills it
+pub(crate) trait CancellableCommand {
+    /// [`Command::output`], failing with [`InstallCancelled`] instead of
+    /// starting when `cancel` is already set
+    async fn output_or_cancel(&mut self, cancel: &CancelToken) -> anyhow::Result<Output>;
+}
+
+impl CancellableCommand for Command {
+    async fn output_or_cancel(&mut self, cancel: &CancelToken) -> anyhow::Result<Output> {
+        cancel.check()?;
+        let program = self.as_std().get_program().to_os_string();
+        self.kill_on_drop(true);
+        tokio::select! {
+            output = self.logged_output() => Ok(output?),
+            () = cancel.cancelled() => {
+                log::info!("Killing {:?} for the cancelled install", program);
+                Err(InstallCancelled.into())
+            }
+        }
+    }
+}
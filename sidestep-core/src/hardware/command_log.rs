@@ -0,0 +1,55 @@
+// adb and fastboot command lines and their output in the log
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::process::Output;
+use tokio::process::Command;
+
+/// Running a command so that it and what it printed end up in the session
+/// log, and so in the install log while one is being written
+pub(crate) trait LoggedCommand {
+    /// [`Command::output`], logging the command line, exit status and output
+    async fn logged_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl LoggedCommand for Command {
+    async fn logged_output(&mut self) -> std::io::Result<Output> {
+        let line = command_line(self);
+        let output = self.output().await;
+        // The device detector polls `devices` every second or so; logging
+        // each one would drown out the install itself
+        let level = if self.as_std().get_args().any(|arg| arg == "devices") {
+            log::Level::Trace
+        } else {
+            log::Level::Debug
+        };
+        match &output {
+            Ok(output) => record(level, &line, output),
+            Err(e) => log::log!(level, "$ {}: failed to start: {}", line, e),
+        }
+        output
+    }
+}
+
+/// `command` as it would be typed, program name without its directory
+pub(crate) fn command_line(command: &Command) -> String {
+    let command = command.as_std();
+    let program = std::path::Path::new(command.get_program())
+        .file_name()
+        .unwrap_or(command.get_program())
+        .to_string_lossy()
+        .into_owned();
+    std::iter::once(program)
+        .chain(command.get_args().map(|arg| arg.to_string_lossy().into_owned()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn record(level: log::Level, line: &str, output: &Output) {
+    log::log!(level, "$ {} ({})", line, output.status);
+    for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        let text = String::from_utf8_lossy(bytes);
+        for text_line in text.lines().filter(|l| !l.trim().is_empty()) {
+            log::log!(level, "  {}: {}", stream, text_line);
+        }
+    }
+}
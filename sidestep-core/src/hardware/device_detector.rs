@@ -0,0 +1,551 @@
+// Device detector - polls for connected devices, woken by udev where it can be
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::mode_guard;
+use crate::hardware::reboot_window::{self, ExpectedReboot};
+use crate::hardware::heimdall::{self, Heimdall};
+use crate::hardware::adb::AdbDevice;
+use crate::hardware::{Adb, AdbState, Fastboot};
+use crate::models::{Device, DeviceDatabase};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often adb, fastboot and Heimdall are asked for devices when udev
+/// isn't telling us about USB changes
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How a detected device is talking to us
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceMode {
+    Adb,
+    Fastboot,
+    /// Userspace fastboot, which runs from recovery and can flash the
+    /// logical partitions inside `super`
+    Fastbootd,
+    /// Samsung's Download Mode, flashed with Heimdall. The device has no
+    /// serial here; it's reported as [`heimdall::DOWNLOAD_MODE_SERIAL`].
+    Download,
+    /// Recovery with adb running, e.g. on the way to sideloading
+    Recovery,
+    /// Recovery's "Apply from ADB", waiting for `adb sideload`
+    Sideload,
+}
+
+impl DeviceMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeviceMode::Adb => "adb",
+            DeviceMode::Fastboot => "fastboot",
+            DeviceMode::Fastbootd => "fastbootd",
+            DeviceMode::Download => "download",
+            DeviceMode::Recovery => "recovery",
+            DeviceMode::Sideload => "sideload",
+        }
+    }
+}
+
+/// Device event for communication between detector thread and main loop
+#[derive(Debug, Clone)]
+pub enum DeviceEvent {
+    Connected(Device, DeviceMode),
+    Disconnected,
+    /// The device left the bus in a reboot an installer asked for
+    Rebooting { serial: String, target: &'static str },
+    /// The device is back from an expected reboot
+    Reconnected { serial: String, mode: DeviceMode },
+    /// A device is on adb but hasn't accepted this computer's key yet.
+    /// It's reported [`Connected`](Self::Connected) once the user allows
+    /// USB debugging on the phone.
+    Unauthorized { serial: String },
+    /// The connected device moved to another mode without being unplugged,
+    /// like recovery starting "Apply from ADB". `usb` is the port it's on,
+    /// when the tools say.
+    ModeChanged { serial: String, usb: Option<String>, mode: DeviceMode },
+    /// `tool` (adb or fastboot) sees a device on USB but isn't allowed to
+    /// open it, as without the udev rules or plugdev group membership.
+    /// `serial` is `None` when it couldn't read that either.
+    PermissionDenied { serial: Option<String>, tool: &'static str },
+}
+
+/// Device detector that polls for USB connected Android devices. Built
+/// with the `udev-hotplug` feature it checks as soon as a phone is plugged
+/// in or removed and only polls occasionally in between.
+pub struct DeviceDetector {
+    running: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    sender: Sender<DeviceEvent>,
+}
+
+impl std::fmt::Debug for DeviceDetector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceDetector")
+            .field("running", &self.running)
+            .field("paused", &self.paused)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DeviceDetector {
+    /// Create a new detector, returning the detector and event receiver
+    pub fn new() -> (Self, Receiver<DeviceEvent>) {
+        let (sender, receiver) = mpsc::channel();
+
+        let detector = Self {
+            running: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
+            sender,
+        };
+
+        (detector, receiver)
+    }
+
+    pub fn start(&self) {
+        self.running.store(true, Ordering::SeqCst);
+
+        let running = self.running.clone();
+        let paused = self.paused.clone();
+        let sender = self.sender.clone();
+
+        // Start polling in a background thread
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+
+            rt.block_on(async {
+                Self::poll_loop(running, paused, sender).await;
+            });
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Pause detection — the poll loop will skip all device checks and send no events.
+    pub fn pause(&self) {
+        log::info!("Device detection paused");
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume detection — resets internal state so reconnection is detected fresh.
+    pub fn resume(&self) {
+        log::info!("Device detection resumed");
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    async fn poll_loop(running: Arc<AtomicBool>, paused: Arc<AtomicBool>, sender: Sender<DeviceEvent>) {
+        let adb = Adb::new();
+        let fastboot = Fastboot::new();
+        let heimdall = Heimdall::new();
+        let db = DeviceDatabase::new();
+
+        let mut last_device: Option<String> = None;
+        // The mode `last_device` was last reported in
+        let mut last_mode: Option<DeviceMode> = None;
+        // A device waiting on the USB debugging prompt, already reported
+        let mut unauthorized: Option<String> = None;
+        // A device adb or fastboot can't open, already reported
+        let mut denied: Option<(&'static str, Option<String>)> = None;
+        // The last phone identified over adb. Download Mode can't say which
+        // model it is, so a Samsung seen just before stands in for it.
+        let mut last_identified: Option<Device> = None;
+        // What was last reported during an expected reboot: the device
+        // gone (`None`) or back in a mode
+        let mut rebooting: Option<(ExpectedReboot, Option<DeviceMode>)> = None;
+        #[cfg(feature = "udev-hotplug")]
+        let mut hotplug = crate::hardware::hotplug::Hotplug::start();
+
+        while running.load(Ordering::SeqCst) {
+            // An installer is rebooting the device on purpose: follow it
+            // through the reboot, even while paused, and never report it
+            // disconnected. It's detected fresh once the window closes.
+            if let Some(reboot) = reboot_window::current() {
+                let mode = mode_guard::current_mode(&reboot.serial).await;
+                let reported = rebooting.as_ref().filter(|(r, _)| *r == reboot).map(|(_, m)| *m);
+                if reported != Some(mode) {
+                    let event = match mode {
+                        Some(mode) => DeviceEvent::Reconnected { serial: reboot.serial.clone(), mode },
+                        None => DeviceEvent::Rebooting { serial: reboot.serial.clone(), target: reboot.target },
+                    };
+                    log::debug!("During expected reboot: {:?}", event);
+                    let _ = sender.send(event);
+                    rebooting = Some((reboot, mode));
+                }
+                last_device = None;
+                unauthorized = None;
+                denied = None;
+                tokio::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+            rebooting = None;
+
+            // When paused, skip all device checks and reset state so
+            // reconnection is detected fresh when we resume.
+            if paused.load(Ordering::SeqCst) {
+                last_device = None;
+                unauthorized = None;
+                denied = None;
+                tokio::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+
+            let mut found_device = false;
+
+            // Check ADB devices first
+            if let Ok(adb_devices) = adb.devices().await {
+                for dev in adb_devices {
+                    if dev.state == AdbState::NoPermissions {
+                        found_device = true;
+                        last_device = None;
+                        let serial = Some(dev.serial).filter(|s| !s.chars().all(|c| c == '?'));
+                        Self::report_permission_denied(&sender, &mut denied, "adb", serial);
+                        break;
+                    }
+                    if dev.state == AdbState::Unauthorized {
+                        // Keeps being seen until the prompt is accepted, then
+                        // comes back as a device and is identified below
+                        found_device = true;
+                        if unauthorized.as_ref() != Some(&dev.serial) {
+                            log::info!("Device {} is waiting for USB debugging to be allowed", dev.serial);
+                            last_device = None;
+                            unauthorized = Some(dev.serial.clone());
+                            let _ = sender.send(DeviceEvent::Unauthorized { serial: dev.serial });
+                        }
+                        break;
+                    }
+                    let Some(mode) = mode_guard::adb_mode(&dev.state) else {
+                        continue;
+                    };
+                    found_device = true;
+                    unauthorized = None;
+                    denied = None;
+
+                    if last_device.as_ref() == Some(&dev.serial) {
+                        if last_mode != Some(mode) {
+                            log::info!("Device {} is now in {} mode", dev.serial, mode.label());
+                            last_mode = Some(mode);
+                            let _ = sender.send(DeviceEvent::ModeChanged { serial: dev.serial, usb: dev.usb, mode });
+                        }
+                        break;
+                    }
+
+                    // Recovery can't always run getprop, and not at all
+                    // while sideloading
+                    if mode != DeviceMode::Adb {
+                        log::info!("Detected device in {} mode: {}", mode.label(), dev.serial);
+                        let device = Self::identify_in_recovery(&adb, &db, &dev, mode, last_identified.as_ref()).await;
+                        let _ = sender.send(DeviceEvent::Connected(device, mode));
+                        last_device = Some(dev.serial);
+                        last_mode = Some(mode);
+                        break;
+                    }
+
+                    // New device detected
+                    // Try to get codename
+                    let codename_result = adb.get_codename(&dev.serial).await;
+                    
+                    let (codename, display_name) = match codename_result {
+                        Ok(name) => (name.clone(), name),
+                        Err(e) => {
+                            log::warn!("Failed to get codename for {}: {}", dev.serial, e);
+                            // Try model as fallback
+                            match adb.get_model(&dev.serial).await {
+                                Ok(model) => (model.clone(), model),
+                                Err(_) => ("unknown".to_string(), dev.serial.clone()),
+                            }
+                        }
+                    };
+
+                    log::info!("Detected device via ADB: {} ({})", dev.serial, codename);
+                    
+                    // Query additional device info via ADB
+                    let android_version = match adb.get_android_version(&dev.serial).await {
+                        Ok(v) if !v.is_empty() => Some(v),
+                        _ => None,
+                    };
+                    let build_id = match adb.get_build_id(&dev.serial).await {
+                        Ok(v) if !v.is_empty() => Some(v),
+                        _ => None,
+                    };
+                    let battery_level = adb.get_battery_level(&dev.serial).await.ok();
+
+                    // Look up device in database
+                    if let Some(mut device) = db.find_by_codename(&codename) {
+                        log::info!("Found device in database: {}", device.name);
+
+                        device.serial = Some(dev.serial.clone());
+                        device.android_version = android_version;
+                        device.build_id = build_id;
+                        device.battery_level = battery_level;
+
+                        // Check lock status
+                        match adb.is_unlocked(&dev.serial).await {
+                            Ok(unlocked) => device.is_locked = Some(!unlocked),
+                            Err(e) => log::warn!("Failed to check lock status: {}", e),
+                        }
+
+                        last_identified = Some(device.clone());
+                        let _ = sender.send(DeviceEvent::Connected(device, DeviceMode::Adb));
+                    } else {
+                        log::warn!("Device {} not in database", codename);
+                        // Create an unknown device entry
+                        let unknown_device = Device {
+                            codename: codename.clone(),
+                            name: format!("Unknown ({})", display_name),
+                            maker: "Unknown".to_string(),
+                            experimental: true,
+                            battery_min: 50,
+                            warnings: vec!["This device is not in the database.".to_string()],
+                            aliases: vec![],
+                            variants: vec![],
+                            is_locked: None,
+                            serial: Some(dev.serial.clone()),
+                            android_version,
+                            build_id,
+                            battery_level,
+                        };
+                        let _ = sender.send(DeviceEvent::Connected(unknown_device, DeviceMode::Adb));
+                    }
+                    
+                    last_device = Some(dev.serial);
+                    last_mode = Some(mode);
+                    break; // Only handle one device at a time
+                }
+            }
+
+            // Also check fastboot devices
+            if !found_device
+                && let Ok(listing) = fastboot.list().await
+            {
+                if listing.devices.is_empty()
+                    && let Some(serial) = listing.no_permissions.into_iter().next()
+                {
+                    found_device = true;
+                    last_device = None;
+                    Self::report_permission_denied(&sender, &mut denied, "fastboot", serial);
+                }
+                if let Some(dev) = listing.devices.into_iter().next() {
+                    found_device = true;
+                    denied = None;
+
+                    // Rebooted from adb to fastboot between two polls.
+                    // Once in fastboot it has to leave the bus to
+                    // switch again, so fastbootd is only asked about
+                    // here.
+                    if last_device.as_ref() == Some(&dev.serial)
+                        && !matches!(last_mode, Some(DeviceMode::Fastboot | DeviceMode::Fastbootd))
+                    {
+                        let mode = match fastboot.is_fastbootd(&dev.serial).await {
+                            Ok(true) => DeviceMode::Fastbootd,
+                            _ => DeviceMode::Fastboot,
+                        };
+                        log::info!("Device {} is now in {} mode", dev.serial, mode.label());
+                        last_mode = Some(mode);
+                        let _ = sender.send(DeviceEvent::ModeChanged { serial: dev.serial, usb: dev.usb, mode });
+                    } else if last_device.as_ref() != Some(&dev.serial) {
+                        log::info!("Detected device in fastboot mode: {}", dev.serial);
+                        let mode = match fastboot.is_fastbootd(&dev.serial).await {
+                            Ok(true) => DeviceMode::Fastbootd,
+                            _ => DeviceMode::Fastboot,
+                        };
+                        
+                        // For fastboot, we might need to get product name
+                        if let Ok(product) = fastboot.getvar(&dev.serial, "product").await {
+                            log::info!("Fastboot product: {}", product);
+                            
+                            if let Some(mut device) = db.find_by_codename(&product) {
+                                device.serial = Some(dev.serial.clone());
+                                // Check lock status via fastboot
+                                match fastboot.unlock_state(&dev.serial).await {
+                                    Ok(unlocked) => device.is_locked = unlocked.map(|u| !u),
+                                    Err(e) => log::warn!("Failed to check fastboot lock status: {}", e),
+                                }
+                                let _ = sender.send(DeviceEvent::Connected(device, mode));
+                            } else {
+                                // Handle unknown fastboot device
+                                log::warn!("Device {} (fastboot) not in database", product);
+                                let unknown_device = Device {
+                                    codename: product.clone(),
+                                    name: format!("Unknown Fastboot ({})", product),
+                                    maker: "Unknown".to_string(),
+                                    experimental: true,
+                                    battery_min: 0, // Cannot read battery in fastboot usually
+                                    warnings: vec![
+                                        "This device is not in the database.".to_string(),
+                                        "Device is in Fastboot mode.".to_string(),
+                                    ],
+                                    aliases: vec![],
+                                    variants: vec![],
+                                    is_locked: None,
+                                    serial: Some(dev.serial.clone()),
+                                    android_version: None,
+                                    build_id: None,
+                                    battery_level: None,
+                                };
+                                let _ = sender.send(DeviceEvent::Connected(unknown_device, mode));
+                            }
+                        } else {
+                            // Failed to get product, but device is present
+                            log::warn!("Fastboot device detected but failed to get product: {}", dev.serial);
+                            let unknown_device = Device {
+                                codename: "unknown".to_string(),
+                                name: format!("Unknown Device ({})", dev.serial),
+                                maker: "Unknown".to_string(),
+                                experimental: true,
+                                battery_min: 0,
+                                warnings: vec![
+                                    "Could not identify device details.".to_string(),
+                                    "Device is in Fastboot mode.".to_string(),
+                                ],
+                                aliases: vec![],
+                                variants: vec![],
+                                is_locked: None,
+                                serial: Some(dev.serial.clone()),
+                                android_version: None,
+                                build_id: None,
+                                battery_level: None,
+                            };
+                            let _ = sender.send(DeviceEvent::Connected(unknown_device, mode));
+                        }
+                        
+                        last_device = Some(dev.serial);
+                        last_mode = Some(mode);
+                    }
+                }
+            }
+
+            // Samsung devices in Download Mode only show up to Heimdall
+            if !found_device && heimdall.detect().await.unwrap_or(false) {
+                found_device = true;
+                if last_device.as_deref() != Some(heimdall::DOWNLOAD_MODE_SERIAL) {
+                    log::info!("Detected a device in Download Mode");
+                    let device = match last_identified.clone() {
+                        Some(device) if device.maker == "Samsung" => Device {
+                            serial: Some(heimdall::DOWNLOAD_MODE_SERIAL.to_string()),
+                            battery_level: None,
+                            ..device
+                        },
+                        _ => Device {
+                            codename: "unknown".to_string(),
+                            name: "Samsung Device (Download Mode)".to_string(),
+                            maker: "Samsung".to_string(),
+                            experimental: true,
+                            battery_min: 0,
+                            warnings: vec![
+                                "Download Mode doesn't say which model this is.".to_string(),
+                                "Connect it booted into Android once to identify it.".to_string(),
+                            ],
+                            aliases: vec![],
+                            variants: vec![],
+                            is_locked: None,
+                            serial: Some(heimdall::DOWNLOAD_MODE_SERIAL.to_string()),
+                            android_version: None,
+                            build_id: None,
+                            battery_level: None,
+                        },
+                    };
+                    let _ = sender.send(DeviceEvent::Connected(device, DeviceMode::Download));
+                    last_device = Some(heimdall::DOWNLOAD_MODE_SERIAL.to_string());
+                    last_mode = Some(DeviceMode::Download);
+                }
+            }
+
+            // Check if device was disconnected
+            if !found_device && (last_device.is_some() || unauthorized.is_some() || denied.is_some()) {
+                log::info!("Device disconnected");
+                last_device = None;
+                unauthorized = None;
+                denied = None;
+                let _ = sender.send(DeviceEvent::Disconnected);
+            }
+
+            // With udev, wait for a USB change, still checking now and
+            // then; an unauthorized device is watched closely since
+            // accepting the prompt doesn't touch the bus, and so is one
+            // without permissions, as reloading udev rules doesn't either
+            #[cfg(feature = "udev-hotplug")]
+            if let Some(hotplug) = hotplug.as_mut() {
+                hotplug.wait(POLL_INTERVAL, unauthorized.is_some() || denied.is_some()).await;
+                continue;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        log::debug!("Device detection stopped");
+    }
+
+    /// Report a device `tool` can't open, unless it's the one last reported
+    fn report_permission_denied(
+        sender: &Sender<DeviceEvent>,
+        reported: &mut Option<(&'static str, Option<String>)>,
+        tool: &'static str,
+        serial: Option<String>,
+    ) {
+        let current = Some((tool, serial.clone()));
+        if *reported == current {
+            return;
+        }
+        log::warn!(
+            "{} has no permission to open device {}",
+            tool,
+            serial.as_deref().unwrap_or("(serial unreadable)")
+        );
+        *reported = current;
+        let _ = sender.send(DeviceEvent::PermissionDenied { serial, tool });
+    }
+
+    /// Work out which phone is in recovery or sideload. It's usually the
+    /// one just identified booted into Android; otherwise recovery is
+    /// asked for its codename, which fails while sideloading.
+    async fn identify_in_recovery(
+        adb: &Adb,
+        db: &DeviceDatabase,
+        dev: &AdbDevice,
+        mode: DeviceMode,
+        last_identified: Option<&Device>,
+    ) -> Device {
+        if let Some(device) = last_identified.filter(|d| d.serial.as_deref() == Some(dev.serial.as_str())) {
+            return Device {
+                battery_level: None,
+                ..device.clone()
+            };
+        }
+
+        let codename = adb.get_codename(&dev.serial).await.ok().filter(|c| !c.is_empty());
+        if let Some(mut device) = codename.as_deref().and_then(|c| db.find_by_codename(c)) {
+            log::info!("Found device in database: {}", device.name);
+            device.serial = Some(dev.serial.clone());
+            return device;
+        }
+
+        let display_name = dev.model.as_ref().map(|m| m.replace('_', " ")).unwrap_or_else(|| dev.serial.clone());
+        Device {
+            codename: codename.unwrap_or_else(|| "unknown".to_string()),
+            name: format!("Unknown ({})", display_name),
+            maker: "Unknown".to_string(),
+            experimental: true,
+            battery_min: 0,
+            warnings: vec![
+                "Could not identify device details.".to_string(),
+                format!("Device is in {} mode.", mode.label()),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: Some(dev.serial.clone()),
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        }
+    }
+}
+
+impl Default for DeviceDetector {
+    fn default() -> Self {
+        Self::new().0
+    }
+}
@@ -0,0 +1,186 @@
+// Device report for bug reports
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::{Adb, DeviceMode, Fastboot};
+use crate::models::{Device, DeviceStub};
+use std::fmt::Write;
+
+/// IMEIs are 15 digits; a run of exactly that many is masked wherever it appears.
+const IMEI_DIGITS: usize = 15;
+
+/// Gather everything known about the connected device into a plain-text
+/// report suitable for pasting into an issue.
+///
+/// Each probe is best effort; a failing command is noted in the report
+/// instead of aborting it. IMEIs are redacted.
+pub async fn collect(serial: Option<String>, mode: Option<DeviceMode>, device: Option<Device>) -> String {
+    let adb = Adb::new();
+    let fastboot = Fastboot::new();
+    let mut report = String::new();
+
+    let _ = writeln!(report, "## Sidestep device report");
+    let _ = writeln!(report, "Sidestep: {}", crate::config::VERSION);
+    let _ = writeln!(report, "Host OS: {}", host_os());
+    let _ = writeln!(report, "adb: {}", adb.version().await.unwrap_or_else(|e| format!("unavailable ({})", e)));
+    let _ = writeln!(
+        report,
+        "fastboot: {}",
+        fastboot.version().await.unwrap_or_else(|e| format!("unavailable ({})", e))
+    );
+    let _ = writeln!(report);
+
+    let _ = writeln!(report, "Serial: {}", serial.as_deref().unwrap_or("none"));
+    let _ = writeln!(report, "Mode: {}", mode.map(|m| m.label()).unwrap_or("not connected"));
+
+    match device {
+        Some(ref device) => {
+            let _ = writeln!(report, "Matched device: {} {} ({})", device.maker, device.name, device.codename);
+            let _ = writeln!(report, "Experimental: {}", device.experimental);
+            let _ = writeln!(report, "Bootloader locked: {}", describe(device.is_locked));
+            let _ = writeln!(report, "Android version: {}", device.android_version.as_deref().unwrap_or("unknown"));
+            let _ = writeln!(report, "Build: {}", device.build_id.as_deref().unwrap_or("unknown"));
+            let _ = writeln!(report, "Battery: {}", describe(device.battery_level.map(|b| format!("{}%", b))));
+        }
+        None => {
+            let _ = writeln!(report, "Matched device: none");
+        }
+    }
+
+    if let Some(ref serial) = serial {
+        match mode {
+            Some(DeviceMode::Fastboot | DeviceMode::Fastbootd) => {
+                let _ = writeln!(report, "\n## fastboot getvar all");
+                match fastboot.getvar_all(serial).await {
+                    Ok(output) => report.push_str(output.trim_end()),
+                    Err(e) => {
+                        let _ = write!(report, "failed: {:#}", e);
+                    }
+                }
+                report.push('\n');
+            }
+            Some(DeviceMode::Adb) => {
+                let _ = writeln!(report, "\n## adb getprop");
+                match adb.shell(serial, "getprop").await {
+                    Ok(output) => report.push_str(output.trim_end()),
+                    Err(e) => {
+                        let _ = write!(report, "failed: {:#}", e);
+                    }
+                }
+                report.push('\n');
+            }
+            Some(DeviceMode::Download | DeviceMode::Recovery | DeviceMode::Sideload) | None => {}
+        }
+    }
+
+    redact(&report)
+}
+
+/// Read what can be learned about a device missing from the database.
+///
+/// Properties come from adb; in fastboot mode only the codename from
+/// detection is known.
+pub async fn probe_stub(serial: &str, device: &Device) -> DeviceStub {
+    let adb = Adb::new();
+    let prop = |name: &'static str| {
+        let adb = &adb;
+        async move { adb.getprop(serial, name).await.ok().filter(|v| !v.is_empty()) }
+    };
+
+    let soc = match prop("ro.soc.model").await {
+        Some(model) => match prop("ro.soc.manufacturer").await {
+            Some(maker) => Some(format!("{} {}", maker, model)),
+            None => Some(model),
+        },
+        None => prop("ro.board.platform").await,
+    };
+
+    let ram = adb
+        .shell(serial, "cat /proc/meminfo")
+        .await
+        .ok()
+        .and_then(|meminfo| {
+            meminfo.lines().find_map(|line| {
+                let kb: u64 = line.strip_prefix("MemTotal:")?.trim().trim_end_matches("kB").trim().parse().ok()?;
+                // MemTotal excludes firmware reservations, so round up to the marketed size
+                Some(format!("{} GB", kb.div_ceil(1024 * 1024)))
+            })
+        });
+
+    let resolution = adb.shell(serial, "wm size").await.ok().and_then(|output| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix("Physical size:"))
+            .map(|size| size.trim().to_string())
+    });
+
+    DeviceStub {
+        codename: device.codename.clone(),
+        maker: prop("ro.product.manufacturer").await,
+        model: prop("ro.product.model").await,
+        android_version: device.android_version.clone(),
+        build_id: device.build_id.clone(),
+        soc,
+        arch: prop("ro.product.cpu.abi").await,
+        ram,
+        resolution,
+    }
+}
+
+fn describe<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "unknown".to_string())
+}
+
+/// PRETTY_NAME from os-release; inside Flatpak this is the runtime, so the
+/// host's copy is preferred when visible.
+fn host_os() -> String {
+    ["/run/host/os-release", "/etc/os-release"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find_map(|content| {
+            content.lines().find_map(|line| {
+                line.strip_prefix("PRETTY_NAME=")
+                    .map(|v| v.trim_matches('"').to_string())
+            })
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Mask IMEIs: the value of any line mentioning "imei", and any standalone
+/// run of exactly 15 digits.
+pub(crate) fn redact(report: &str) -> String {
+    report
+        .lines()
+        .map(|line| {
+            if line.to_lowercase().contains("imei") {
+                match line.find([':', '=']) {
+                    Some(pos) => format!("{} [redacted]", &line[..=pos]),
+                    None => "[redacted]".to_string(),
+                }
+            } else {
+                mask_digit_runs(line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn mask_digit_runs(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut run = String::new();
+    for c in line.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_digit() {
+            run.push(c);
+            continue;
+        }
+        if run.len() == IMEI_DIGITS {
+            out.push_str("[redacted]");
+        } else {
+            out.push_str(&run);
+        }
+        run.clear();
+        if c != '\0' {
+            out.push(c);
+        }
+    }
+    out
+}
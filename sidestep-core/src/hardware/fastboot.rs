@@ -0,0 +1,1294 @@
+// Fastboot wrapper
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use crate::hardware::cancel::{CancelToken, CancellableCommand, InstallCancelled};
+use crate::hardware::command_log::LoggedCommand;
+use crate::hardware::simulator::Simulator;
+#[cfg(feature = "usb-transport")]
+use crate::hardware::fastboot_usb::{TransportUnavailable, UsbFastboot};
+
+/// Represents a device in fastboot mode
+#[derive(Debug, Clone)]
+pub struct FastbootDevice {
+    pub serial: String,
+    pub product: Option<String>,
+    /// The USB port it's on (`1-1` for `usb:1-1`), from `fastboot devices -l`
+    pub usb: Option<String>,
+}
+
+/// What `fastboot devices -l` lists: the devices commands can reach, and
+/// those it sees on the bus but isn't allowed to open
+#[derive(Debug, Clone, Default)]
+pub struct FastbootListing {
+    pub devices: Vec<FastbootDevice>,
+    /// Serials of devices shown as `no permissions`; `None` for one whose
+    /// serial it couldn't read either (`????????????`)
+    pub no_permissions: Vec<Option<String>>,
+}
+
+/// A write command failed because the device dropped off the USB bus,
+/// as opposed to the bootloader rejecting the command.
+#[derive(Debug, Clone)]
+pub struct DeviceDisconnected {
+    pub operation: String,
+}
+
+impl std::fmt::Display for DeviceDisconnected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Device disconnected while running '{}'", self.operation)
+    }
+}
+
+impl std::error::Error for DeviceDisconnected {}
+
+/// The device has no fastbootd to reboot into: its bootloader predates
+/// dynamic partitions, or the recovery doesn't carry userspace fastboot.
+#[derive(Debug, Clone)]
+pub struct FastbootdUnsupported {
+    pub serial: String,
+}
+
+impl std::fmt::Display for FastbootdUnsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Device {} doesn't support fastbootd, which is needed to flash its logical partitions",
+            self.serial
+        )
+    }
+}
+
+impl std::error::Error for FastbootdUnsupported {}
+
+/// Messages printed by fastboot/libusb when the device vanishes mid-command.
+const USB_GONE_MARKERS: &[&str] = &[
+    "no such device",
+    "libusb_error_no_device",
+    "libusb_error_io",
+    "device not found",
+    "no devices/emulators found",
+    "write to device failed",
+    "read from device failed",
+    "status read failed",
+    "couldn't read",
+    "protocol error",
+];
+
+/// Parse `fastboot devices -l`. Usable devices read `SERIAL  fastboot
+/// usb:1-1`, the port missing on older fastboot. One it can't open reads
+/// `SERIAL  no permissions (missing udev rules? user is in the plugdev
+/// group); see [URL]`, and older versions print question marks for a
+/// serial they couldn't read.
+fn parse_devices(stdout: &str) -> FastbootListing {
+    let mut listing = FastbootListing::default();
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.get(1).copied() {
+            Some("fastboot") => listing.devices.push(FastbootDevice {
+                serial: parts[0].to_string(),
+                product: None,
+                usb: parts[2..].iter().find_map(|p| p.strip_prefix("usb:")).map(str::to_string),
+            }),
+            Some("no") if parts.get(2).is_some_and(|p| p.starts_with("permissions")) => {
+                let serial = Some(parts[0]).filter(|s| !s.chars().all(|c| c == '?'));
+                listing.no_permissions.push(serial.map(str::to_string));
+            }
+            _ => {}
+        }
+    }
+    listing
+}
+
+/// Build the error for a failed write command, tagging USB disconnects so
+/// the UI can tell them apart from the bootloader refusing the command.
+fn command_error(operation: &str, summary: &str, stderr: &str) -> anyhow::Error {
+    let lower = stderr.to_lowercase();
+    if USB_GONE_MARKERS.iter().any(|m| lower.contains(m)) {
+        log::error!("USB connection lost during '{}': {}", operation, stderr.trim());
+        return anyhow::Error::new(DeviceDisconnected {
+            operation: operation.to_string(),
+        })
+        .context(format!("{}: {}", summary, stderr.trim()));
+    }
+    anyhow::anyhow!("{}: {}", summary, stderr)
+}
+
+/// Android sparse image magic (little endian), see libsparse's sparse_format.h
+const SPARSE_MAGIC: u32 = 0xed26_ff3a;
+
+/// Size `image` occupies once written: the expanded size for sparse images,
+/// the file size otherwise.
+fn written_size(image: &Path) -> Result<u64> {
+    use std::io::Read;
+
+    let file_size = std::fs::metadata(image)
+        .with_context(|| format!("Failed to read {}", image.display()))?
+        .len();
+
+    let mut header = [0u8; 20];
+    let mut file = std::fs::File::open(image)
+        .with_context(|| format!("Failed to open {}", image.display()))?;
+    if file.read_exact(&mut header).is_err() {
+        return Ok(file_size);
+    }
+    let word = |at: usize| u32::from_le_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]]);
+    if word(0) != SPARSE_MAGIC {
+        return Ok(file_size);
+    }
+    // blk_sz at offset 12, total_blks at offset 16
+    Ok(word(12) as u64 * word(16) as u64)
+}
+
+/// Parse a fastboot size variable, which bootloaders report in hex ("0x4000000")
+/// or, less often, decimal.
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// How far a `fastboot flash` has got, read from its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashStep {
+    /// The chunk being transferred, from 1. Images sent in one piece have
+    /// a single chunk.
+    pub chunk: u32,
+    pub chunks: u32,
+    /// Share of the whole flash done, counting sending and writing each
+    /// chunk as a step
+    pub percent: u64,
+}
+
+/// Follows the status lines of one `fastboot flash`. Sparse images print
+/// `Sending sparse 'system' 1/8 (524284 KB)` and `Writing 'system'` per
+/// chunk, anything else a single `Sending 'boot' (65536 KB)` and
+/// `Writing 'boot'`. Older fastboot writes them in lower case and numbers
+/// the writing lines too. fastboot ends each line once the phase is done.
+#[derive(Debug, Default)]
+struct FlashOutput {
+    chunk: u32,
+    chunks: u32,
+}
+
+impl FlashOutput {
+    /// The step a finished status line leaves the flash at, or `None` for
+    /// other output
+    fn feed(&mut self, line: &str) -> Option<FlashStep> {
+        let line = line.trim().to_lowercase();
+        let writing = if let Some(rest) = line.strip_prefix("sending sparse '") {
+            let (chunk, chunks) = chunk_counter(rest)?;
+            self.chunk = chunk;
+            self.chunks = chunks;
+            false
+        } else if line.starts_with("sending '") {
+            self.chunk = 1;
+            self.chunks = 1;
+            false
+        } else if let Some(rest) = line.strip_prefix("writing ") {
+            if self.chunks == 0 {
+                return None;
+            }
+            if let Some((chunk, chunks)) = chunk_counter(rest) {
+                self.chunk = chunk;
+                self.chunks = chunks;
+            }
+            true
+        } else {
+            return None;
+        };
+
+        let done = (self.chunk.saturating_sub(1) * 2 + 1 + writing as u32) as u64;
+        Some(FlashStep {
+            chunk: self.chunk,
+            chunks: self.chunks,
+            percent: (done * 100 / (self.chunks as u64 * 2)).min(100),
+        })
+    }
+}
+
+/// The `1/8` after the quoted partition name in a status line
+fn chunk_counter(rest: &str) -> Option<(u32, u32)> {
+    let after_name = &rest[rest.find("' ")? + 2..];
+    // Older fastboot runs the dots of "writing 'system' 1/8..." into it
+    let counter = after_name.split_whitespace().next()?.trim_end_matches('.');
+    let (chunk, chunks) = counter.split_once('/')?;
+    let (chunk, chunks) = (chunk.parse().ok()?, chunks.parse().ok()?);
+    (chunk > 0 && chunk <= chunks).then_some((chunk, chunks))
+}
+
+/// Commands the direct USB transport and the simulator implement.
+pub(crate) enum DirectCommand {
+    Getvar(String),
+    Flash { partition: String, image: PathBuf },
+    Boot(PathBuf),
+    Erase(String),
+    SetActive(String),
+    Oem(Vec<String>),
+    /// `reboot`, `reboot-bootloader`, `reboot-recovery` or `reboot-fastboot`
+    Reboot(&'static str),
+}
+
+/// Fastboot command wrapper
+///
+/// With the `usb-transport` feature, common commands go straight to the
+/// bootloader over libusb and only fall back to the `fastboot` binary when
+/// the interface can't be claimed or the command isn't supported there.
+/// Set `SIDESTEP_FASTBOOT_TRANSPORT=binary` to always use the binary, or
+/// `SIDESTEP_SIMULATE` to talk to a [`Simulator`] instead of a device.
+#[derive(Debug, Clone)]
+pub struct Fastboot {
+    binary_path: String,
+    cancel: CancelToken,
+    #[cfg(feature = "usb-transport")]
+    direct_usb: bool,
+}
+
+impl Default for Fastboot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fastboot {
+    pub fn new() -> Self {
+        // Try to find fastboot in common locations
+        let binary_path = std::env::var("FASTBOOT_PATH")
+            .unwrap_or_else(|_| "fastboot".to_string());
+        
+        Self::with_path(binary_path)
+    }
+
+    pub fn with_path(path: String) -> Self {
+        Self {
+            binary_path: path,
+            cancel: CancelToken::new(),
+            #[cfg(feature = "usb-transport")]
+            direct_usb: std::env::var("SIDESTEP_FASTBOOT_TRANSPORT").as_deref() != Ok("binary"),
+        }
+    }
+
+    /// Kill the running command, and refuse to start commands that write
+    /// to the device, once `cancel` is set
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn cancel_token(&self) -> &CancelToken {
+        &self.cancel
+    }
+
+    /// Answer `command` from the simulator or run it over the direct USB
+    /// transport, or return `None` when the caller should use the binary
+    /// instead. Only `Getvar` yields a value.
+    async fn try_direct(&self, serial: &str, command: DirectCommand) -> Option<Result<String>> {
+        if let Some(simulator) = Simulator::active() {
+            return Some(simulator.fastboot(serial, command).await);
+        }
+        self.try_usb(serial, command).await
+    }
+
+    #[cfg(feature = "usb-transport")]
+    async fn try_usb(&self, serial: &str, command: DirectCommand) -> Option<Result<String>> {
+        if !self.direct_usb {
+            return None;
+        }
+        let serial = serial.to_string();
+        let result = tokio::task::spawn_blocking(move || {
+            let usb = UsbFastboot::open(&serial)?;
+            match command {
+                // The binary prints nothing for unknown variables; match that
+                DirectCommand::Getvar(var) => match usb.getvar(&var) {
+                    Err(e) if e.downcast_ref::<DeviceDisconnected>().is_none() => {
+                        log::debug!("getvar {} over USB: {:#}", var, e);
+                        Ok(String::new())
+                    }
+                    other => other,
+                },
+                DirectCommand::Flash { partition, image } => {
+                    usb.flash(&partition, &image).map(|_| String::new())
+                }
+                DirectCommand::Boot(image) => usb.boot(&image).map(|_| String::new()),
+                DirectCommand::Erase(partition) => usb.erase(&partition).map(|_| String::new()),
+                DirectCommand::SetActive(slot) => usb.set_active(&slot).map(|_| String::new()),
+                DirectCommand::Oem(args) => {
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    usb.oem(&args).map(|_| String::new())
+                }
+                DirectCommand::Reboot(target) => usb.reboot(target).map(|_| String::new()),
+            }
+        })
+        .await;
+
+        match result {
+            Ok(Err(e)) if e.downcast_ref::<TransportUnavailable>().is_some() => {
+                log::info!("{}; falling back to the fastboot binary", e);
+                None
+            }
+            Ok(result) => Some(result),
+            Err(e) => Some(Err(anyhow::anyhow!("USB transport task failed: {}", e))),
+        }
+    }
+
+    #[cfg(not(feature = "usb-transport"))]
+    async fn try_usb(&self, _serial: &str, _command: DirectCommand) -> Option<Result<String>> {
+        None
+    }
+
+    /// List connected fastboot devices
+    pub async fn devices(&self) -> Result<Vec<FastbootDevice>> {
+        Ok(self.list().await?.devices)
+    }
+
+    /// List connected fastboot devices along with those it can't open,
+    /// which without the udev rules is every one
+    pub async fn list(&self) -> Result<FastbootListing> {
+        if let Some(simulator) = Simulator::active() {
+            return Ok(FastbootListing {
+                devices: simulator.fastboot_devices(),
+                no_permissions: Vec::new(),
+            });
+        }
+
+        // Devices we can't open (e.g. missing udev rules) simply don't show
+        // up here; the binary gets a chance to find them below.
+        #[cfg(feature = "usb-transport")]
+        if self.direct_usb {
+            if let Ok(Ok(serials)) = tokio::task::spawn_blocking(UsbFastboot::list).await {
+                if !serials.is_empty() {
+                    let devices: Vec<FastbootDevice> = serials
+                        .into_iter()
+                        .map(|serial| FastbootDevice {
+                            serial,
+                            product: None,
+                            usb: None,
+                        })
+                        .collect();
+                    log::debug!("Fastboot devices (usb): {:?}", devices);
+                    return Ok(FastbootListing {
+                        devices,
+                        no_permissions: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["devices", "-l"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run fastboot devices")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let listing = parse_devices(&stdout);
+        log::debug!("Fastboot devices: {:?}", listing);
+        Ok(listing)
+    }
+
+    /// Get a variable from the device
+    pub async fn getvar(&self, serial: &str, var: &str) -> Result<String> {
+        if let Some(result) = self.try_direct(serial, DirectCommand::Getvar(var.to_string())).await {
+            return result;
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "getvar", var])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run fastboot getvar")?;
+
+        // Fastboot outputs to stderr
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        
+        // Parse "var: value" format
+        for line in stderr.lines() {
+            if let Some(value) = line.strip_prefix(&format!("{}: ", var)) {
+                return Ok(value.to_string());
+            }
+        }
+
+        Ok(String::new())
+    }
+
+    /// Raw `fastboot getvar all` output, one "(bootloader) name: value" per line
+    pub async fn getvar_all(&self, serial: &str) -> Result<String> {
+        if let Some(simulator) = Simulator::active() {
+            return simulator.getvar_all(serial);
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "getvar", "all"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run fastboot getvar all")?;
+
+        // Fastboot outputs to stderr
+        Ok(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+
+    /// First line of `fastboot --version`
+    pub async fn version(&self) -> Result<String> {
+        if Simulator::active().is_some() {
+            return Ok("fastboot (simulated)".to_string());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .arg("--version")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run fastboot --version")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+    }
+
+    /// Get unlock status
+    pub async fn is_unlocked(&self, serial: &str) -> Result<bool> {
+        let value = self.getvar(serial, "unlocked").await?;
+        Ok(value == "yes")
+    }
+
+    /// Whether the device is in fastbootd (userspace fastboot in recovery)
+    /// rather than the bootloader's own fastboot.
+    pub async fn is_fastbootd(&self, serial: &str) -> Result<bool> {
+        let value = self.getvar(serial, "is-userspace").await?;
+        Ok(value == "yes")
+    }
+
+    /// Whether the device has a fastbootd at all. Bootloaders that know
+    /// about it answer `is-userspace` with "no"; older ones don't know the
+    /// variable.
+    pub async fn supports_fastbootd(&self, serial: &str) -> Result<bool> {
+        let value = self.getvar(serial, "is-userspace").await?;
+        Ok(!value.is_empty())
+    }
+
+    /// Whether `partition` lives inside the `super` partition. Logical
+    /// partitions can only be flashed from fastbootd.
+    pub async fn is_logical(&self, serial: &str, partition: &str) -> Result<bool> {
+        let value = self.getvar(serial, &format!("is-logical:{}", partition)).await?;
+        Ok(value == "yes")
+    }
+
+    /// How many slots the bootloader reports through `slot-count`; 0 when
+    /// it doesn't, as on devices without A/B
+    pub async fn slot_count(&self, serial: &str) -> Result<u32> {
+        let value = self.getvar(serial, "slot-count").await?;
+        Ok(value.trim().parse().unwrap_or(0))
+    }
+
+    /// Whether the device keeps two copies of its slotted partitions
+    pub async fn has_ab_slots(&self, serial: &str) -> Result<bool> {
+        Ok(self.slot_count(serial).await? >= 2)
+    }
+
+    /// The slot the device boots from ("a" or "b"), or `None` on devices
+    /// without A/B
+    pub async fn active_slot(&self, serial: &str) -> Result<Option<String>> {
+        if !self.has_ab_slots(serial).await? {
+            return Ok(None);
+        }
+        let slot = self.getvar(serial, "current-slot").await?;
+        let slot = slot.trim().trim_start_matches('_');
+        Ok((!slot.is_empty()).then(|| slot.to_string()))
+    }
+
+    /// Every slot's name for `partition`: `boot_a` and `boot_b` for a
+    /// slotted partition on an A/B device, otherwise `partition` itself.
+    /// A name that already carries a slot suffix is kept as it is.
+    pub async fn slot_names(&self, serial: &str, partition: &str) -> Result<Vec<String>> {
+        if partition.ends_with("_a") || partition.ends_with("_b") {
+            return Ok(vec![partition.to_string()]);
+        }
+        let slots = self.slot_count(serial).await?.min(26);
+        if slots < 2 || self.getvar(serial, &format!("has-slot:{}", partition)).await? != "yes" {
+            return Ok(vec![partition.to_string()]);
+        }
+        Ok((0..slots)
+            .map(|i| format!("{}_{}", partition, char::from(b'a' + i as u8)))
+            .collect())
+    }
+
+    /// Whether the bootloader reports a partition called `partition`
+    /// through `partition-type`, `has-slot` or `is-logical`. Bootloaders that
+    /// answer none of them make every partition look absent.
+    pub async fn has_partition(&self, serial: &str, partition: &str) -> Result<bool> {
+        let partition_type = self.getvar(serial, &format!("partition-type:{}", partition)).await?;
+        if !partition_type.trim().is_empty() {
+            return Ok(true);
+        }
+        if self.getvar(serial, &format!("has-slot:{}", partition)).await? == "yes" {
+            return Ok(true);
+        }
+        self.is_logical(serial, partition).await
+    }
+
+    /// Whether the bootloader is unlocked, from the first variable that
+    /// answers: `unlocked`, then Motorola's `securestate`. A bootloader
+    /// reporting only `unlock_ability: 0` has "OEM unlocking" off, which
+    /// an unlocked device can't have.
+    ///
+    /// Returns `None` when none of them give a reading.
+    pub async fn unlock_state(&self, serial: &str) -> Result<Option<bool>> {
+        match self.getvar(serial, "unlocked").await?.trim() {
+            "yes" => return Ok(Some(true)),
+            "no" => return Ok(Some(false)),
+            _ => {}
+        }
+        match self.getvar(serial, "securestate").await?.trim() {
+            "flashing_unlocked" => return Ok(Some(true)),
+            "flashing_locked" | "oem_locked" | "locked" => return Ok(Some(false)),
+            _ => {}
+        }
+        Ok(match self.unlock_ability(serial).await? {
+            Some(false) => Some(false),
+            _ => None,
+        })
+    }
+
+    /// Read `unlock_ability` (mirrors the "OEM unlocking" toggle on Pixels and others).
+    ///
+    /// Returns `None` when the bootloader doesn't report the variable.
+    pub async fn unlock_ability(&self, serial: &str) -> Result<Option<bool>> {
+        let value = self.getvar(serial, "unlock_ability").await?;
+        Ok(match value.trim() {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        })
+    }
+
+    /// Poll until the bootloader reports itself unlocked.
+    ///
+    /// Many devices reboot or wipe after the unlock prompt is accepted, so
+    /// failed queries are treated as "not yet" rather than errors.
+    pub async fn wait_for_unlocked(&self, serial: &str, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            self.cancel.check()?;
+            if let Ok(true) = self.is_unlocked(serial).await {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+        anyhow::bail!("Timed out waiting for the unlock to be confirmed on the device")
+    }
+
+    /// Unlock the bootloader (OEM unlock)
+    pub async fn oem_unlock(&self, serial: &str) -> Result<()> {
+        log::info!("Attempting OEM unlock on {}", serial);
+
+        let unlock = DirectCommand::Oem(vec!["unlock".to_string()]);
+        if let Some(result) = self.try_direct(serial, unlock).await {
+            return result.map(|_| ());
+        }
+        
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "oem", "unlock"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to run fastboot oem unlock")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("OEM unlock output: {}", stderr);
+
+        if !output.status.success() {
+            anyhow::bail!("OEM unlock failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Refuse to flash `image` if the bootloader reports `partition` as smaller.
+    ///
+    /// Sizes come from `getvar partition-size:<name>`, trying the current
+    /// slot's suffix for A/B devices. When the bootloader doesn't report a
+    /// size, or the partition is a logical one fastbootd resizes on flash,
+    /// the check is skipped.
+    pub async fn check_image_fits(&self, serial: &str, partition: &str, image: &Path) -> Result<()> {
+        let image_size = written_size(image)?;
+        self.check_size_fits(serial, partition, &image.display().to_string(), image_size)
+            .await
+    }
+
+    /// [`check_image_fits`](Self::check_image_fits) for `image_size` bytes
+    /// of `image` that aren't in a file of their own
+    async fn check_size_fits(
+        &self,
+        serial: &str,
+        partition: &str,
+        image: &str,
+        image_size: u64,
+    ) -> Result<()> {
+        if self.is_logical(serial, partition).await.unwrap_or(false) {
+            return Ok(());
+        }
+
+        let mut candidates = vec![partition.to_string()];
+        let slot = self.getvar(serial, "current-slot").await.unwrap_or_default();
+        let slot = slot.trim().trim_start_matches('_');
+        if !slot.is_empty() && !partition.ends_with("_a") && !partition.ends_with("_b") {
+            candidates.push(format!("{}_{}", partition, slot));
+        }
+
+        let mut partition_size = None;
+        for name in &candidates {
+            let value = self
+                .getvar(serial, &format!("partition-size:{}", name))
+                .await
+                .unwrap_or_default();
+            if let Some(size) = parse_size(&value).filter(|&size| size > 0) {
+                partition_size = Some(size);
+                break;
+            }
+        }
+        let Some(partition_size) = partition_size else {
+            log::debug!("Size of partition {} not reported; skipping size check", partition);
+            return Ok(());
+        };
+
+        if image_size > partition_size {
+            anyhow::bail!(
+                "Image too large for partition {}: {} is {} bytes, the partition holds {} bytes",
+                partition,
+                image,
+                image_size,
+                partition_size
+            );
+        }
+        log::debug!(
+            "{} ({} bytes) fits partition {} ({} bytes)",
+            image,
+            image_size,
+            partition,
+            partition_size
+        );
+        Ok(())
+    }
+
+    /// Flash `size` bytes read from `reader`, an image that isn't on disk
+    /// as such, to `partition` over the direct USB transport.
+    ///
+    /// The bootloader must take the whole image in one download, so this
+    /// only works when `size` fits its buffer. Returns `false` without
+    /// having sent anything when the stream can't be flashed this way,
+    /// including with the transport disabled or not built in; the caller
+    /// then writes the image to a file and flashes that.
+    #[cfg(feature = "usb-transport")]
+    pub async fn flash_stream(
+        &self,
+        serial: &str,
+        partition: &str,
+        image: &str,
+        reader: Box<dyn std::io::Read + Send>,
+        size: u64,
+    ) -> Result<bool> {
+        self.cancel.check()?;
+        if !self.direct_usb || Simulator::active().is_some() {
+            return Ok(false);
+        }
+        self.check_size_fits(serial, partition, image, size).await?;
+        log::info!("Streaming {} ({} bytes) to partition {}", image, size, partition);
+
+        let serial = serial.to_string();
+        let partition = partition.to_string();
+        let image = image.to_string();
+        let result = tokio::task::spawn_blocking(move || {
+            let usb = UsbFastboot::open(&serial)?;
+            let mut reader = reader;
+            usb.flash_from(&partition, &mut *reader, size, &image)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(())) => Ok(true),
+            Ok(Err(e)) if e.downcast_ref::<TransportUnavailable>().is_some() => {
+                log::info!("{}; flashing from an unpacked copy instead", e);
+                Ok(false)
+            }
+            Ok(Err(e)) => Err(e),
+            Err(e) => Err(anyhow::anyhow!("USB transport task failed: {}", e)),
+        }
+    }
+
+    #[cfg(not(feature = "usb-transport"))]
+    pub async fn flash_stream(
+        &self,
+        _serial: &str,
+        _partition: &str,
+        _image: &str,
+        _reader: Box<dyn std::io::Read + Send>,
+        _size: u64,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Flash an image to a partition
+    pub async fn flash(&self, serial: &str, partition: &str, image: &Path) -> Result<()> {
+        log::info!("Flashing {} to partition {}", image.display(), partition);
+        self.cancel.check()?;
+        self.check_image_fits(serial, partition, image).await?;
+
+        let direct = DirectCommand::Flash {
+            partition: partition.to_string(),
+            image: image.to_path_buf(),
+        };
+        if let Some(result) = self.try_direct(serial, direct).await {
+            return result.map(|_| ());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args([
+                "-s", serial,
+                "flash", partition,
+                image.to_str().unwrap()
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run fastboot flash")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("Flash output: {}", stderr);
+
+        if !output.status.success() {
+            return Err(command_error(&format!("flash {}", partition), "Flash failed", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Flash `image` to every slot of `partition`, so the inactive slot isn't
+    /// left with an older image. Devices without A/B and unslotted
+    /// partitions get a single [`flash`](Self::flash), exactly as before.
+    pub async fn flash_all_slots(&self, serial: &str, partition: &str, image: &Path) -> Result<()> {
+        for name in self.slot_names(serial, partition).await? {
+            self.flash(serial, &name, image).await?;
+        }
+        Ok(())
+    }
+
+    /// Flash an image while watching fastboot's output for signs of life.
+    ///
+    /// fastboot prints a line per phase ("Sending", "Writing", "OKAY") and per
+    /// sparse chunk; each finished phase is passed to `on_step`. If nothing
+    /// arrives for `stall_after`, `on_stall` is called with the time since
+    /// the last line; the transfer is left running.
+    ///
+    /// Over the direct USB transport there is no output to watch; a stalled
+    /// bulk transfer times out and fails instead.
+    pub async fn flash_watched<F: Fn(Duration), G: Fn(FlashStep)>(
+        &self,
+        serial: &str,
+        partition: &str,
+        image: &Path,
+        stall_after: Duration,
+        on_stall: F,
+        on_step: G,
+    ) -> Result<()> {
+        log::info!("Flashing {} to partition {}", image.display(), partition);
+        self.cancel.check()?;
+        self.check_image_fits(serial, partition, image).await?;
+
+        let direct = DirectCommand::Flash {
+            partition: partition.to_string(),
+            image: image.to_path_buf(),
+        };
+        if let Some(result) = self.try_direct(serial, direct).await {
+            return result.map(|_| ());
+        }
+
+        log::debug!("$ fastboot -s {} flash {} {}", serial, partition, image.display());
+        let mut child = Command::new(&self.binary_path)
+            .args([
+                "-s", serial,
+                "flash", partition,
+                image.to_str().unwrap()
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to run fastboot flash")?;
+
+        let stderr_pipe = child.stderr.take().context("fastboot stderr unavailable")?;
+        let mut lines = BufReader::new(stderr_pipe).lines();
+        let mut stderr = String::new();
+        let mut output = FlashOutput::default();
+        let mut last_output = Instant::now();
+        let mut warned = false;
+
+        loop {
+            let next = tokio::select! {
+                next = tokio::time::timeout(stall_after, lines.next_line()) => next,
+                () = self.cancel.cancelled() => {
+                    log::info!("Stopping the flash of {} for the cancelled install", partition);
+                    return Err(InstallCancelled.into());
+                }
+            };
+            match next {
+                Ok(Ok(Some(line))) => {
+                    log::debug!("fastboot: {}", line);
+                    if let Some(step) = output.feed(&line) {
+                        on_step(step);
+                    }
+                    stderr.push_str(&line);
+                    stderr.push('\n');
+                    last_output = Instant::now();
+                    warned = false;
+                }
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => return Err(e).context("Failed to read fastboot output"),
+                Err(_) => {
+                    // Warn once per silent stretch, not on every timeout tick
+                    if !warned {
+                        let silent = last_output.elapsed();
+                        log::warn!(
+                            "No output from fastboot flash {} for {}s",
+                            partition,
+                            silent.as_secs()
+                        );
+                        on_stall(silent);
+                        warned = true;
+                    }
+                }
+            }
+        }
+
+        let status = child.wait().await.context("Failed to wait for fastboot flash")?;
+        if !status.success() {
+            return Err(command_error(&format!("flash {}", partition), "Flash failed", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Boot an image once from RAM without writing it (`fastboot boot`)
+    pub async fn boot(&self, serial: &str, image: &Path) -> Result<()> {
+        log::info!("Temporarily booting {} on {}", image.display(), serial);
+        self.cancel.check()?;
+
+        if let Some(result) = self.try_direct(serial, DirectCommand::Boot(image.to_path_buf())).await {
+            return result.map(|_| ());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "boot", image.to_str().unwrap()])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run fastboot boot")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("Boot output: {}", stderr);
+
+        if !output.status.success() {
+            return Err(command_error("boot", "fastboot boot failed", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Reboot the device back into the bootloader
+    pub async fn reboot_bootloader(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting device {} into bootloader", serial);
+
+        if let Some(result) = self.try_direct(serial, DirectCommand::Reboot("reboot-bootloader")).await {
+            return result.map(|_| ());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot-bootloader"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot into bootloader")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Reboot to bootloader failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Reboot from the bootloader into fastbootd
+    pub async fn reboot_fastboot(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting device {} into fastbootd", serial);
+
+        if let Some(result) = self.try_direct(serial, DirectCommand::Reboot("reboot-fastboot")).await {
+            return result.map(|_| ());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot", "fastboot"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot into fastbootd")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Reboot to fastbootd failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Switch from the bootloader to fastbootd and wait for the device to
+    /// come back there. Does nothing when it's already in fastbootd, and
+    /// fails with [`FastbootdUnsupported`] when it has none.
+    pub async fn reboot_fastbootd(&self, serial: &str, timeout: Duration) -> Result<()> {
+        if self.is_fastbootd(serial).await? {
+            return Ok(());
+        }
+        if !self.supports_fastbootd(serial).await? {
+            return Err(FastbootdUnsupported {
+                serial: serial.to_string(),
+            }
+            .into());
+        }
+        self.reboot_fastboot(serial)
+            .await
+            .context("Failed to switch to fastbootd")?;
+        self.wait_for_mode(serial, true, timeout).await
+    }
+
+    /// Poll until the device is back in fastboot, in fastbootd when
+    /// `userspace` is set or in the bootloader otherwise.
+    ///
+    /// The device drops off the bus while it switches over, so failed
+    /// queries count as "not yet".
+    pub async fn wait_for_mode(&self, serial: &str, userspace: bool, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            self.cancel.check()?;
+            let listed = self
+                .devices()
+                .await
+                .map(|devices| devices.iter().any(|d| d.serial == serial))
+                .unwrap_or(false);
+            if listed && self.is_fastbootd(serial).await.ok() == Some(userspace) {
+                return Ok(());
+            }
+        }
+        let mode = if userspace { "fastbootd" } else { "the bootloader" };
+        anyhow::bail!("Timed out waiting for the device to come back in {}", mode)
+    }
+
+    /// Run `fastboot update` to flash all partitions from a ZIP
+    ///
+    /// Produces: `fastboot -s SERIAL [-w] update ZIP_PATH`
+    pub async fn update(&self, serial: &str, zip_path: &Path, wipe: bool) -> Result<()> {
+        log::info!(
+            "Running fastboot update {} (wipe={})",
+            zip_path.display(),
+            wipe
+        );
+        self.cancel.check()?;
+
+        if let Some(simulator) = Simulator::active() {
+            return simulator.fastboot_write(serial, "update", Some(zip_path)).await;
+        }
+
+        let mut args = vec!["-s", serial];
+        if wipe {
+            args.push("-w");
+        }
+        args.push("update");
+        args.push(zip_path.to_str().unwrap());
+
+        let output = Command::new(&self.binary_path)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run fastboot update")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("Update output: {}", stderr);
+
+        if !output.status.success() {
+            return Err(command_error("update", "fastboot update failed", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Reboot the device
+    pub async fn reboot(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting device {}", serial);
+
+        if let Some(result) = self.try_direct(serial, DirectCommand::Reboot("reboot")).await {
+            return result.map(|_| ());
+        }
+        
+        Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot device")?;
+
+        Ok(())
+    }
+
+    /// Reboot the device directly into recovery mode
+    pub async fn reboot_recovery(&self, serial: &str) -> Result<()> {
+        log::info!("Rebooting device {} into recovery", serial);
+
+        if let Some(result) = self.try_direct(serial, DirectCommand::Reboot("reboot-recovery")).await {
+            return result.map(|_| ());
+        }
+
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "reboot", "recovery"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged_output()
+            .await
+            .context("Failed to reboot into recovery")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Reboot to recovery failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Erase a partition
+    pub async fn erase(&self, serial: &str, partition: &str) -> Result<()> {
+        log::info!("Erasing partition {} on {}", partition, serial);
+        self.cancel.check()?;
+
+        let direct = DirectCommand::Erase(partition.to_string());
+        if let Some(result) = self.try_direct(serial, direct).await {
+            return result.map(|_| ());
+        }
+        
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, "erase", partition])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to erase partition")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(command_error(&format!("erase {}", partition), "Erase failed", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Set active slot (for A/B devices)
+    pub async fn set_active(&self, serial: &str, slot: &str) -> Result<()> {
+        log::info!("Setting active slot to {} on {}", slot, serial);
+        self.cancel.check()?;
+
+        let direct = DirectCommand::SetActive(slot.to_string());
+        if let Some(result) = self.try_direct(serial, direct).await {
+            return result.map(|_| ());
+        }
+
+        Command::new(&self.binary_path)
+            .args(["-s", serial, "set_active", slot])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to set active slot")?;
+
+        Ok(())
+    }
+
+    /// Flash an image with additional flags (e.g., --disable-verity --disable-verification for vbmeta)
+    ///
+    /// Matches UBports promise-android-tools flag placement:
+    ///   fastboot -s SERIAL flash PARTITION [FLAGS...] FILE
+    pub async fn flash_with_flags(
+        &self,
+        serial: &str,
+        partition: &str,
+        image: &Path,
+        flags: &[&str],
+    ) -> Result<()> {
+        log::info!(
+            "Flashing {} to partition {} with flags {:?}",
+            image.display(),
+            partition,
+            flags
+        );
+        self.cancel.check()?;
+        self.check_image_fits(serial, partition, image).await?;
+        if let Some(simulator) = Simulator::active() {
+            return simulator
+                .fastboot_write(serial, &format!("flash {}", partition), Some(image))
+                .await;
+        }
+
+        let mut args = vec!["-s", serial, "flash", partition];
+        args.extend_from_slice(flags);
+        args.push(image.to_str().unwrap());
+
+        let output = Command::new(&self.binary_path)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run fastboot flash")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("Flash output: {}", stderr);
+
+        if !output.status.success() {
+            return Err(command_error(
+                &format!("flash {}", partition),
+                "Flash with flags failed",
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Flash an image with sparse transfer (`-S` flag before `flash`).
+    ///
+    /// Produces: `fastboot -s SERIAL -S 100M flash PARTITION FILE`
+    pub async fn flash_sparse(
+        &self,
+        serial: &str,
+        partition: &str,
+        image: &Path,
+        chunk_size: &str,
+    ) -> Result<()> {
+        log::info!(
+            "Flashing {} to partition {} with -S {}",
+            image.display(),
+            partition,
+            chunk_size
+        );
+        self.cancel.check()?;
+        self.check_image_fits(serial, partition, image).await?;
+        if let Some(simulator) = Simulator::active() {
+            return simulator
+                .fastboot_write(serial, &format!("flash {}", partition), Some(image))
+                .await;
+        }
+
+        let sparse_flag = format!("-S{}", chunk_size);
+        let output = Command::new(&self.binary_path)
+            .args([
+                "-s", serial,
+                &sparse_flag,
+                "flash", partition,
+                image.to_str().unwrap(),
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run fastboot flash (sparse)")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("Flash sparse output: {}", stderr);
+
+        if !output.status.success() {
+            return Err(command_error(&format!("flash {}", partition), "Sparse flash failed", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Run an arbitrary `fastboot oem` subcommand.
+    ///
+    /// Example: `oem(serial, &["uart", "enable"])` → `fastboot -s SERIAL oem uart enable`
+    pub async fn oem(&self, serial: &str, args: &[&str]) -> Result<()> {
+        log::info!("Running fastboot oem {:?} on {}", args, serial);
+        self.cancel.check()?;
+
+        let direct = DirectCommand::Oem(args.iter().map(|a| a.to_string()).collect());
+        if let Some(result) = self.try_direct(serial, direct).await {
+            return result.map(|_| ());
+        }
+
+        let mut cmd_args = vec!["-s", serial, "oem"];
+        cmd_args.extend_from_slice(args);
+
+        let output = Command::new(&self.binary_path)
+            .args(&cmd_args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run fastboot oem")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("OEM output: {}", stderr);
+
+        if !output.status.success() {
+            anyhow::bail!("fastboot oem failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Format a partition with a given filesystem type
+    pub async fn format(&self, serial: &str, partition: &str, fs_type: &str) -> Result<()> {
+        log::info!("Formatting partition {} as {} on {}", partition, fs_type, serial);
+        self.cancel.check()?;
+
+        if let Some(simulator) = Simulator::active() {
+            return simulator
+                .fastboot_write(serial, &format!("format {}", partition), None)
+                .await;
+        }
+
+        let format_arg = format!("format:{}", fs_type);
+        let output = Command::new(&self.binary_path)
+            .args(["-s", serial, &format_arg, partition])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to format partition")?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("Format output: {}", stderr);
+
+        if !output.status.success() {
+            return Err(command_error(&format!("format {}", partition), "Format failed", &stderr));
+        }
+
+        Ok(())
+    }
+}
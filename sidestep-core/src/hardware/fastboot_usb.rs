@@ -0,0 +1,346 @@
+// Direct USB fastboot transport (no external binary)
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::fastboot::DeviceDisconnected;
+use anyhow::{Context, Result};
+use rusb::{Direction, TransferType, UsbContext};
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+/// Interface triple Android bootloaders expose for fastboot.
+const FASTBOOT_CLASS: u8 = 0xff;
+const FASTBOOT_SUBCLASS: u8 = 0x42;
+const FASTBOOT_PROTOCOL: u8 = 0x03;
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+/// Writing or erasing a large partition can take minutes before the reply.
+const LONG_TIMEOUT: Duration = Duration::from_secs(600);
+/// Bulk transfer size used while streaming an image to the device.
+const TRANSFER_CHUNK: usize = 1024 * 1024;
+/// Responses are at most 256 bytes: a 4-byte status plus payload.
+const MAX_RESPONSE: usize = 256;
+
+/// The direct transport can't serve this request; the caller should fall
+/// back to the `fastboot` binary.
+#[derive(Debug, Clone)]
+pub struct TransportUnavailable {
+    pub reason: String,
+}
+
+impl std::fmt::Display for TransportUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Direct USB transport unavailable: {}", self.reason)
+    }
+}
+
+impl std::error::Error for TransportUnavailable {}
+
+fn unavailable(reason: impl Into<String>) -> anyhow::Error {
+    anyhow::Error::new(TransportUnavailable {
+        reason: reason.into(),
+    })
+}
+
+/// A USB error in the middle of a command means the device went away;
+/// tag it the same way the binary backend does.
+fn transfer_error(operation: &str, error: rusb::Error) -> anyhow::Error {
+    match error {
+        rusb::Error::NoDevice | rusb::Error::Io | rusb::Error::Pipe => {
+            anyhow::Error::new(DeviceDisconnected {
+                operation: operation.to_string(),
+            })
+            .context(format!("USB transfer failed: {}", error))
+        }
+        other => anyhow::anyhow!("USB transfer failed during '{}': {}", operation, other),
+    }
+}
+
+enum Reply {
+    Okay(String),
+    Data(usize),
+}
+
+/// A claimed fastboot interface on a single device.
+///
+/// All calls block; `Fastboot` runs them on tokio's blocking pool.
+pub struct UsbFastboot {
+    handle: rusb::DeviceHandle<rusb::Context>,
+    interface: u8,
+    endpoint_in: u8,
+    endpoint_out: u8,
+}
+
+impl UsbFastboot {
+    /// Serial numbers of every device currently exposing a fastboot interface.
+    pub fn list() -> Result<Vec<String>> {
+        let context = rusb::Context::new().map_err(|e| unavailable(e.to_string()))?;
+        let devices = context.devices().map_err(|e| unavailable(e.to_string()))?;
+
+        let mut serials = Vec::new();
+        for device in devices.iter() {
+            let Ok(descriptor) = device.device_descriptor() else {
+                continue;
+            };
+            if find_fastboot_interface(&device).is_none() {
+                continue;
+            }
+            let Ok(handle) = device.open() else {
+                continue;
+            };
+            if let Ok(serial) = handle.read_serial_number_string_ascii(&descriptor) {
+                serials.push(serial);
+            }
+        }
+        Ok(serials)
+    }
+
+    /// Find the device with `serial` and claim its fastboot interface.
+    pub fn open(serial: &str) -> Result<Self> {
+        let context = rusb::Context::new().map_err(|e| unavailable(e.to_string()))?;
+        let devices = context.devices().map_err(|e| unavailable(e.to_string()))?;
+
+        for device in devices.iter() {
+            let Ok(descriptor) = device.device_descriptor() else {
+                continue;
+            };
+            let Some((interface, endpoint_in, endpoint_out)) = find_fastboot_interface(&device)
+            else {
+                continue;
+            };
+            let handle = match device.open() {
+                Ok(handle) => handle,
+                Err(e) => {
+                    log::debug!("Cannot open USB device {:?}: {}", device, e);
+                    continue;
+                }
+            };
+            if handle.read_serial_number_string_ascii(&descriptor).ok().as_deref() != Some(serial) {
+                continue;
+            }
+
+            let _ = handle.set_auto_detach_kernel_driver(true);
+            handle.claim_interface(interface).map_err(|e| {
+                unavailable(format!("cannot claim fastboot interface of {}: {}", serial, e))
+            })?;
+
+            log::debug!("Claimed fastboot interface {} of {}", interface, serial);
+            return Ok(Self {
+                handle,
+                interface,
+                endpoint_in,
+                endpoint_out,
+            });
+        }
+
+        Err(unavailable(format!("no accessible fastboot device with serial {}", serial)))
+    }
+
+    /// `getvar:<name>`
+    pub fn getvar(&self, var: &str) -> Result<String> {
+        match self.command(&format!("getvar:{}", var), COMMAND_TIMEOUT)? {
+            Reply::Okay(value) => Ok(value),
+            Reply::Data(_) => anyhow::bail!("Unexpected DATA reply to getvar:{}", var),
+        }
+    }
+
+    /// Largest payload the bootloader accepts in one `download`.
+    pub fn max_download_size(&self) -> Result<u64> {
+        let value = self.getvar("max-download-size")?;
+        let value = value.trim();
+        let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => value.parse(),
+        };
+        parsed.with_context(|| format!("Invalid max-download-size '{}'", value))
+    }
+
+    /// Stream `image` into the bootloader's download buffer.
+    ///
+    /// Images larger than `max-download-size` need sparse splitting, which
+    /// only the binary implements, so those are reported as unavailable.
+    pub fn download(&self, image: &Path) -> Result<()> {
+        let size = std::fs::metadata(image)
+            .with_context(|| format!("Failed to read {}", image.display()))?
+            .len();
+        let mut file = std::fs::File::open(image)
+            .with_context(|| format!("Failed to open {}", image.display()))?;
+        self.download_from(&mut file, size, &image.display().to_string())
+    }
+
+    /// Send the next `size` bytes of `reader`, named `image` in messages,
+    /// as one download. The size limits are checked before anything is
+    /// sent, so an unavailable transport leaves `reader` untouched.
+    pub fn download_from(&self, reader: &mut dyn Read, size: u64, image: &str) -> Result<()> {
+        let limit = self.max_download_size().unwrap_or(0);
+        if limit > 0 && size > limit {
+            return Err(unavailable(format!(
+                "{} is larger than the device's download buffer ({} > {})",
+                image,
+                size,
+                limit
+            )));
+        }
+        if size > u32::MAX as u64 {
+            return Err(unavailable(format!("{} exceeds 4 GiB", image)));
+        }
+
+        match self.command(&format!("download:{:08x}", size), COMMAND_TIMEOUT)? {
+            Reply::Data(accepted) if accepted as u64 == size => {}
+            Reply::Data(accepted) => {
+                anyhow::bail!("Device accepted {} bytes, expected {}", accepted, size)
+            }
+            Reply::Okay(_) => anyhow::bail!("Device did not enter the data phase"),
+        }
+
+        let mut buffer = vec![0u8; TRANSFER_CHUNK];
+        let mut sent = 0u64;
+        while sent < size {
+            let wanted = buffer.len().min((size - sent) as usize);
+            let read = reader
+                .read(&mut buffer[..wanted])
+                .with_context(|| format!("Failed to read {}", image))?;
+            if read == 0 {
+                anyhow::bail!("{} ended after {} of {} bytes", image, sent, size);
+            }
+            let mut offset = 0;
+            while offset < read {
+                let written = self
+                    .handle
+                    .write_bulk(self.endpoint_out, &buffer[offset..read], LONG_TIMEOUT)
+                    .map_err(|e| transfer_error("download", e))?;
+                offset += written;
+            }
+            sent += read as u64;
+        }
+
+        match self.read_reply(LONG_TIMEOUT, "download")? {
+            Reply::Okay(_) => Ok(()),
+            Reply::Data(_) => anyhow::bail!("Unexpected DATA reply after download"),
+        }
+    }
+
+    /// `download` followed by `flash:<partition>`.
+    pub fn flash(&self, partition: &str, image: &Path) -> Result<()> {
+        self.download(image)?;
+        self.simple(&format!("flash:{}", partition), LONG_TIMEOUT)
+    }
+
+    /// [`download_from`](Self::download_from) followed by `flash:<partition>`.
+    pub fn flash_from(&self, partition: &str, reader: &mut dyn Read, size: u64, image: &str) -> Result<()> {
+        self.download_from(reader, size, image)?;
+        self.simple(&format!("flash:{}", partition), LONG_TIMEOUT)
+    }
+
+    /// `download` followed by `boot`.
+    pub fn boot(&self, image: &Path) -> Result<()> {
+        self.download(image)?;
+        self.simple("boot", COMMAND_TIMEOUT)
+    }
+
+    pub fn erase(&self, partition: &str) -> Result<()> {
+        self.simple(&format!("erase:{}", partition), LONG_TIMEOUT)
+    }
+
+    pub fn set_active(&self, slot: &str) -> Result<()> {
+        self.simple(&format!("set_active:{}", slot), COMMAND_TIMEOUT)
+    }
+
+    /// `oem <args...>`; unlock prompts wait on the user, hence the long timeout.
+    pub fn oem(&self, args: &[&str]) -> Result<()> {
+        self.simple(&format!("oem {}", args.join(" ")), LONG_TIMEOUT)
+    }
+
+    /// `reboot`, `reboot-bootloader`, `reboot-recovery`, ...
+    ///
+    /// The device may drop off the bus before replying, which is not an error.
+    pub fn reboot(&self, command: &str) -> Result<()> {
+        match self.simple(command, COMMAND_TIMEOUT) {
+            Err(e) if e.downcast_ref::<DeviceDisconnected>().is_some() => Ok(()),
+            other => other,
+        }
+    }
+
+    // ────────────────────────────────────────────────────────────────
+    // Protocol
+    // ────────────────────────────────────────────────────────────────
+
+    fn simple(&self, command: &str, timeout: Duration) -> Result<()> {
+        match self.command(command, timeout)? {
+            Reply::Okay(_) => Ok(()),
+            Reply::Data(_) => anyhow::bail!("Unexpected DATA reply to {}", command),
+        }
+    }
+
+    fn command(&self, command: &str, timeout: Duration) -> Result<Reply> {
+        log::debug!("fastboot(usb) > {}", command);
+        self.handle
+            .write_bulk(self.endpoint_out, command.as_bytes(), COMMAND_TIMEOUT)
+            .map_err(|e| transfer_error(command, e))?;
+        self.read_reply(timeout, command)
+    }
+
+    /// Read replies until a terminal one, logging INFO/TEXT along the way.
+    fn read_reply(&self, timeout: Duration, command: &str) -> Result<Reply> {
+        let mut buffer = [0u8; MAX_RESPONSE];
+        loop {
+            let len = self
+                .handle
+                .read_bulk(self.endpoint_in, &mut buffer, timeout)
+                .map_err(|e| transfer_error(command, e))?;
+            if len < 4 {
+                anyhow::bail!("Short reply ({} bytes) to {}", len, command);
+            }
+            let status = String::from_utf8_lossy(&buffer[..4]).to_string();
+            let payload = String::from_utf8_lossy(&buffer[4..len]).to_string();
+
+            match status.as_str() {
+                "OKAY" => return Ok(Reply::Okay(payload)),
+                "DATA" => {
+                    let size = usize::from_str_radix(payload.trim(), 16)
+                        .with_context(|| format!("Invalid DATA size '{}'", payload))?;
+                    return Ok(Reply::Data(size));
+                }
+                "FAIL" => anyhow::bail!("{} failed: {}", command, payload),
+                "INFO" | "TEXT" => log::debug!("fastboot(usb) < {}", payload),
+                other => anyhow::bail!("Unknown reply '{}' to {}", other, command),
+            }
+        }
+    }
+}
+
+impl Drop for UsbFastboot {
+    fn drop(&mut self) {
+        let _ = self.handle.release_interface(self.interface);
+    }
+}
+
+/// Locate the fastboot interface and its bulk IN/OUT endpoints.
+fn find_fastboot_interface(device: &rusb::Device<rusb::Context>) -> Option<(u8, u8, u8)> {
+    let config = device.active_config_descriptor().ok()?;
+    for interface in config.interfaces() {
+        for setting in interface.descriptors() {
+            if setting.class_code() != FASTBOOT_CLASS
+                || setting.sub_class_code() != FASTBOOT_SUBCLASS
+                || setting.protocol_code() != FASTBOOT_PROTOCOL
+            {
+                continue;
+            }
+            let mut endpoint_in = None;
+            let mut endpoint_out = None;
+            for endpoint in setting.endpoint_descriptors() {
+                if endpoint.transfer_type() != TransferType::Bulk {
+                    continue;
+                }
+                match endpoint.direction() {
+                    Direction::In => endpoint_in = Some(endpoint.address()),
+                    Direction::Out => endpoint_out = Some(endpoint.address()),
+                }
+            }
+            if let (Some(endpoint_in), Some(endpoint_out)) = (endpoint_in, endpoint_out) {
+                return Some((setting.interface_number(), endpoint_in, endpoint_out));
+            }
+        }
+    }
+    None
+}
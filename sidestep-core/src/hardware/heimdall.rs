@@ -0,0 +1,200 @@
+// Heimdall wrapper, for Samsung devices in Download Mode
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use crate::hardware::cancel::{CancelToken, CancellableCommand, InstallCancelled};
+use crate::hardware::fastboot::DeviceDisconnected;
+
+/// Stands in for a serial number: Download Mode doesn't report one, and
+/// Heimdall talks to the one Samsung device it finds
+pub const DOWNLOAD_MODE_SERIAL: &str = "download-mode";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Heimdall's messages for a device that went away or was never claimed
+const USB_GONE_MARKERS: &[&str] = &[
+    "failed to detect compatible download-mode device",
+    "failed to access device",
+    "failed to claim interface",
+    "failed to send data",
+    "failed to receive",
+    "libusb error",
+];
+
+/// Follows `heimdall flash` output. Each partition starts with
+/// `Uploading RECOVERY` and redraws its percentage in place with
+/// backspaces, ending in `RECOVERY upload successful`.
+#[derive(Debug, Default)]
+struct FlashOutput {
+    partition: Option<String>,
+    percent: Option<u64>,
+}
+
+impl FlashOutput {
+    /// The partition and percentage after `chunk`, when it moved them on
+    fn feed(&mut self, chunk: &str) -> Option<(String, u64)> {
+        let mut changed = false;
+        for part in chunk.split(['\r', '\n', '\x08']) {
+            let part = part.trim();
+            if let Some(partition) = part.strip_prefix("Uploading ") {
+                self.partition = Some(partition.trim().to_string());
+                self.percent = None;
+                changed = true;
+            } else if let Some(percent) = part.strip_suffix('%').and_then(|p| p.trim().parse::<u64>().ok())
+                && self.percent != Some(percent)
+            {
+                self.percent = Some(percent.min(100));
+                changed = true;
+            }
+        }
+        let partition = self.partition.clone()?;
+        changed.then(|| (partition, self.percent.unwrap_or_default()))
+    }
+}
+
+/// Heimdall command wrapper
+#[derive(Debug, Clone)]
+pub struct Heimdall {
+    binary_path: String,
+    cancel: CancelToken,
+}
+
+impl Default for Heimdall {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Heimdall {
+    pub fn new() -> Self {
+        Self::with_path("heimdall".to_string())
+    }
+
+    pub fn with_path(path: String) -> Self {
+        Self {
+            binary_path: path,
+            cancel: CancelToken::new(),
+        }
+    }
+
+    /// Kill the running command, and refuse to start new ones, once
+    /// `cancel` is set
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Whether a device in Download Mode is connected. `heimdall detect`
+    /// only succeeds when it finds one.
+    pub async fn detect(&self) -> Result<bool> {
+        let output = Command::new(&self.binary_path)
+            .arg("detect")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output_or_cancel(&self.cancel)
+            .await
+            .context("Failed to run heimdall detect")?;
+        Ok(output.status.success())
+    }
+
+    /// Wait until a device in Download Mode shows up
+    pub async fn wait_for_device(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.cancel.check()?;
+            if self.detect().await? {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "No device in Download Mode after {}s",
+                    timeout.as_secs()
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Write `files` as `(partition, image)` pairs in a single session,
+    /// passing the partition being uploaded and its percentage to
+    /// `on_progress`. The device is left in Download Mode afterwards; see
+    /// [`heimdall_flash_args`](crate::models::samsung::heimdall_flash_args).
+    pub async fn flash<F: Fn(&str, u64)>(&self, files: &[(&str, &Path)], on_progress: F) -> Result<()> {
+        let names: Vec<&str> = files.iter().map(|(partition, _)| *partition).collect();
+        log::info!("Flashing {} with Heimdall", names.join(", "));
+        self.cancel.check()?;
+
+        let paths: Vec<String> = files.iter().map(|(_, image)| image.to_string_lossy().into_owned()).collect();
+        let pairs: Vec<(&str, &str)> = names.iter().copied().zip(paths.iter().map(String::as_str)).collect();
+        let args = crate::models::samsung::heimdall_flash_args(&pairs);
+        log::debug!("$ heimdall {}", args.join(" "));
+        let mut child = Command::new(&self.binary_path)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to run heimdall flash")?;
+        let mut stdout_pipe = child.stdout.take().context("heimdall stdout unavailable")?;
+        let mut stderr_pipe = child.stderr.take().context("heimdall stderr unavailable")?;
+
+        let read_stdout = async {
+            let mut stdout = String::new();
+            let mut output = FlashOutput::default();
+            let mut buf = [0u8; 512];
+            loop {
+                let n = stdout_pipe.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                let chunk = String::from_utf8_lossy(&buf[..n]);
+                stdout.push_str(&chunk);
+                if let Some((partition, percent)) = output.feed(&chunk) {
+                    on_progress(&partition, percent);
+                }
+            }
+            Ok::<_, std::io::Error>(stdout)
+        };
+        let read_stderr = async {
+            let mut stderr = String::new();
+            stderr_pipe.read_to_string(&mut stderr).await.map(|_| stderr)
+        };
+        let (stdout, stderr) = tokio::select! {
+            output = async { tokio::join!(read_stdout, read_stderr) } => output,
+            () = self.cancel.cancelled() => {
+                log::info!("Stopping heimdall for the cancelled install");
+                return Err(InstallCancelled.into());
+            }
+        };
+        let stdout = stdout.context("Failed to read heimdall output")?;
+        let stderr = stderr.context("Failed to read heimdall output")?;
+        let status = child.wait().await.context("Failed to wait for heimdall")?;
+        log::debug!("heimdall: {}{}", stdout, stderr);
+
+        if !status.success() {
+            let message = stderr
+                .lines()
+                .chain(stdout.lines())
+                .find(|line| line.starts_with("ERROR:"))
+                .unwrap_or("heimdall exited with an error")
+                .trim_start_matches("ERROR:")
+                .trim()
+                .to_string();
+            let lower = message.to_lowercase();
+            if USB_GONE_MARKERS.iter().any(|m| lower.contains(m)) {
+                return Err(anyhow::Error::new(DeviceDisconnected {
+                    operation: "heimdall flash".to_string(),
+                })
+                .context(format!("Heimdall failed: {}", message)));
+            }
+            anyhow::bail!("Heimdall failed: {}", message);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+// USB hotplug notifications from udev
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::sync::mpsc as std_mpsc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// Vendors whose phones show up on adb, fastboot or Download Mode
+const ANDROID_VENDOR_IDS: &[u16] = &[
+    0x04e8, // Samsung
+    0x0502, // Acer
+    0x05c6, // Qualcomm
+    0x0b05, // ASUS
+    0x0e8d, // MediaTek
+    0x0fce, // Sony
+    0x1004, // LG
+    0x12d1, // Huawei
+    0x18d1, // Google, and most fastboot bootloaders
+    0x19d2, // ZTE
+    0x1ebf, // Coolpad
+    0x22b8, // Motorola
+    0x22d9, // OPPO
+    0x2717, // Xiaomi
+    0x29a9, // Smartisan
+    0x2a45, // Meizu
+    0x2a70, // OnePlus
+    0x2ae5, // Fairphone
+    0x2b4c, // ZUK
+    0x2d95, // vivo
+];
+
+/// The adb and fastboot interfaces are class 0xff, subclass 0x42 on every
+/// vendor, which catches phones not in [`ANDROID_VENDOR_IDS`]
+const ANDROID_INTERFACE_PREFIX: &str = "255/66/";
+
+/// Replug events closer together than this are handled as one
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How long to wait for a device check while nothing is happening on the
+/// bus. Events start a check straight away; this only catches what udev
+/// can't see, like a phone leaving fastboot for fastbootd.
+const IDLE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Checks at the normal interval after an event, since adb and fastboot
+/// take a moment to list a device that's just appeared
+const FOLLOW_UP_CHECKS: u32 = 3;
+
+/// How often the monitor thread looks up from the socket to see whether
+/// the detector is still listening
+const MONITOR_TIMEOUT_MS: i32 = 1000;
+
+/// Wakes the device detector when an Android device is plugged in or
+/// removed, so it doesn't have to poll constantly
+pub struct Hotplug {
+    events: UnboundedReceiver<()>,
+    follow_ups: u32,
+}
+
+impl Hotplug {
+    /// Start watching udev for USB add and remove events. `None` where
+    /// udev can't be used, such as inside a Flatpak sandbox, where events
+    /// from the host's udevd never arrive; the detector polls instead.
+    pub fn start() -> Option<Self> {
+        if Path::new("/.flatpak-info").exists() {
+            log::info!("Running in Flatpak, polling for devices instead of using udev");
+            return None;
+        }
+        if !Path::new("/run/udev/control").exists() {
+            log::info!("udev isn't running, polling for devices");
+            return None;
+        }
+
+        let (sender, events) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = std_mpsc::channel();
+        // udev handles can't leave the thread they were made on, so the
+        // monitor is opened there and only the outcome is sent back
+        std::thread::spawn(move || match open_monitor() {
+            Ok(socket) => {
+                let _ = ready_tx.send(Ok(()));
+                monitor_loop(socket, sender);
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+            }
+        });
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => {
+                log::info!("Watching udev for USB devices");
+                Some(Self { events, follow_ups: 0 })
+            }
+            Ok(Err(e)) => {
+                log::info!("Can't monitor udev ({}), polling for devices", e);
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Wait until the detector should look for devices again: straight
+    /// after a burst of hotplug events has settled, or after a timeout.
+    /// `watch_closely` keeps the timeout at `poll_interval`, for state
+    /// udev can't report, like a device waiting on the USB debugging
+    /// prompt.
+    pub async fn wait(&mut self, poll_interval: Duration, watch_closely: bool) {
+        let timeout = if watch_closely || self.follow_ups > 0 {
+            poll_interval
+        } else {
+            IDLE_INTERVAL
+        };
+        self.follow_ups = self.follow_ups.saturating_sub(1);
+
+        tokio::select! {
+            _ = tokio::time::sleep(timeout) => {}
+            event = self.events.recv() => match event {
+                Some(()) => {
+                    // A replug sends remove then add in quick succession;
+                    // checking once after both means an unchanged device
+                    // isn't reported gone and back
+                    while let Ok(Some(())) = tokio::time::timeout(DEBOUNCE, self.events.recv()).await {}
+                    log::debug!("USB hotplug, checking devices");
+                    self.follow_ups = FOLLOW_UP_CHECKS;
+                }
+                None => {
+                    // The monitor thread is gone; carry on by polling
+                    log::warn!("udev monitor stopped, polling for devices");
+                    self.follow_ups = u32::MAX;
+                    tokio::time::sleep(poll_interval).await;
+                }
+            },
+        }
+    }
+}
+
+fn open_monitor() -> std::io::Result<udev::MonitorSocket> {
+    udev::MonitorBuilder::new()?.match_subsystem("usb")?.listen()
+}
+
+/// Forward matching events until the detector drops its receiver
+fn monitor_loop(socket: udev::MonitorSocket, sender: UnboundedSender<()>) {
+    let mut fds = libc::pollfd {
+        fd: socket.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    while !sender.is_closed() {
+        // SAFETY: `fds` is a single valid pollfd for a socket we own
+        let ready = unsafe { libc::poll(&mut fds, 1, MONITOR_TIMEOUT_MS) };
+        if ready < 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            log::warn!("Polling the udev monitor failed: {}", err);
+            return;
+        }
+        for event in socket.iter() {
+            if !matches!(event.event_type(), udev::EventType::Add | udev::EventType::Remove) {
+                continue;
+            }
+            let device = event.device();
+            if is_android(&device) {
+                log::debug!("udev {:?} {}", event.event_type(), device.syspath().display());
+                let _ = sender.send(());
+            }
+        }
+    }
+}
+
+/// Whether a USB device or interface event looks like a phone. Remove
+/// events carry no sysfs attributes, so this goes by the kernel's
+/// `PRODUCT` (`vendor/product/bcd` in hex) and `INTERFACE`
+/// (`class/subclass/protocol` in decimal) properties.
+fn is_android(device: &udev::Device) -> bool {
+    let property = |name: &str| {
+        device
+            .property_value(name)
+            .and_then(|v| v.to_str())
+            .map(str::to_string)
+    };
+
+    if let Some(interface) = property("INTERFACE")
+        && interface.starts_with(ANDROID_INTERFACE_PREFIX)
+    {
+        return true;
+    }
+    property("PRODUCT")
+        .and_then(|product| {
+            let vendor = product.split('/').next()?;
+            u16::from_str_radix(vendor, 16).ok()
+        })
+        .is_some_and(|vendor| ANDROID_VENDOR_IDS.contains(&vendor))
+}
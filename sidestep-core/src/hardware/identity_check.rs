@@ -0,0 +1,100 @@
+// Best-effort check for clones posing as a supported device
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::{Adb, DeviceMode, Fastboot};
+use crate::models::device_info::HardwareIdentity;
+
+/// Serials that cheap boards ship with instead of a real one
+const PLACEHOLDER_SERIALS: &[&str] = &["0123456789ABCDEF", "0123456789", "1234567890ABCDEF"];
+
+/// What the device says about itself
+#[derive(Debug, Clone)]
+struct Reported {
+    product: String,
+    revision: String,
+    serial: String,
+}
+
+/// Compare what `serial` reports against `expected` and describe every
+/// mismatch. An empty result means nothing looked off, not that the device
+/// is genuine; clones can report anything.
+pub async fn inconsistencies(serial: &str, mode: DeviceMode, expected: &HardwareIdentity) -> Vec<String> {
+    let reported = read(serial, mode).await;
+    let mut found = Vec::new();
+
+    if !expected.products.is_empty()
+        && !reported.product.is_empty()
+        && !expected.products.iter().any(|p| p.eq_ignore_ascii_case(&reported.product))
+    {
+        found.push(format!(
+            "It reports product \"{}\", but this model reports {}",
+            reported.product,
+            expected.products.join(" or ")
+        ));
+    }
+
+    if !expected.revisions.is_empty()
+        && !reported.revision.is_empty()
+        && !expected.revisions.iter().any(|r| r.eq_ignore_ascii_case(&reported.revision))
+    {
+        found.push(format!(
+            "Its hardware revision \"{}\" isn't one this model shipped with",
+            reported.revision
+        ));
+    }
+
+    let serial_no = reported.serial.as_str();
+    if PLACEHOLDER_SERIALS.iter().any(|s| s.eq_ignore_ascii_case(serial_no)) {
+        found.push(format!("Its serial number \"{}\" is a generic placeholder", serial_no));
+    } else if !serial_no.is_empty() {
+        if !expected.serial_lengths.is_empty() && !expected.serial_lengths.contains(&serial_no.len()) {
+            found.push(format!(
+                "Its serial number is {} characters long; this model's are {}",
+                serial_no.len(),
+                expected
+                    .serial_lengths
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ));
+        }
+        if expected.serial_hex && !serial_no.chars().all(|c| c.is_ascii_hexdigit()) {
+            found.push("Its serial number isn't hexadecimal like this model's".to_string());
+        }
+    }
+
+    for line in &found {
+        log::warn!("{} may not be genuine: {}", serial, line);
+    }
+    found
+}
+
+async fn read(serial: &str, mode: DeviceMode) -> Reported {
+    let value = |result: anyhow::Result<String>| result.unwrap_or_default().trim().to_string();
+    match mode {
+        DeviceMode::Fastboot | DeviceMode::Fastbootd => {
+            let fastboot = Fastboot::new();
+            Reported {
+                product: value(fastboot.getvar(serial, "product").await),
+                revision: value(fastboot.getvar(serial, "hw-revision").await),
+                serial: value(fastboot.getvar(serial, "serialno").await),
+            }
+        }
+        // Download Mode reports nothing to compare, and neither can adb
+        // from recovery, which has no shell while sideloading
+        DeviceMode::Download | DeviceMode::Recovery | DeviceMode::Sideload => Reported {
+            product: String::new(),
+            revision: String::new(),
+            serial: String::new(),
+        },
+        DeviceMode::Adb => {
+            let adb = Adb::new();
+            Reported {
+                product: value(adb.getprop(serial, "ro.product.device").await),
+                revision: value(adb.getprop(serial, "ro.revision").await),
+                serial: value(adb.getprop(serial, "ro.serialno").await),
+            }
+        }
+    }
+}
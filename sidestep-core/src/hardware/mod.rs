@@ -0,0 +1,31 @@
+// Hardware bridge module
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod adb;
+pub mod cancel;
+pub(crate) mod command_log;
+pub mod fastboot;
+#[cfg(feature = "usb-transport")]
+pub mod fastboot_usb;
+pub mod device_detector;
+pub mod device_report;
+pub mod heimdall;
+#[cfg(feature = "udev-hotplug")]
+pub mod hotplug;
+pub mod identity_check;
+pub mod mode_guard;
+pub mod prerequisites;
+pub mod reboot_window;
+pub mod sideload;
+pub mod simulator;
+
+pub use adb::{Adb, AdbState};
+pub use cancel::{CancelToken, InstallCancelled};
+pub use fastboot::{DeviceDisconnected, Fastboot, FastbootListing, FastbootdUnsupported, FlashStep};
+pub use device_detector::{DeviceDetector, DeviceEvent, DeviceMode};
+pub use heimdall::Heimdall;
+pub use mode_guard::StartMode;
+pub use reboot_window::RebootWindow;
+pub use sideload::SideloadRejected;
+pub use simulator::Simulator;
+
@@ -0,0 +1,174 @@
+// Start-mode guard for installers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::heimdall::{self, Heimdall};
+use crate::hardware::{Adb, AdbState, DeviceMode, Fastboot, RebootWindow};
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// How long a reboot into the needed mode may take
+const SWITCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The mode an installer expects the device in when it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartMode {
+    /// Booted into Android with USB debugging on
+    Adb,
+    /// The bootloader's fastboot, not fastbootd
+    Fastboot,
+    /// Either; the installer reboots from Android to fastboot itself
+    AdbOrFastboot,
+}
+
+impl StartMode {
+    pub fn accepts(&self, mode: DeviceMode) -> bool {
+        matches!(
+            (self, mode),
+            (StartMode::Adb, DeviceMode::Adb)
+                | (StartMode::Fastboot, DeviceMode::Fastboot)
+                | (StartMode::AdbOrFastboot, DeviceMode::Adb | DeviceMode::Fastboot)
+        )
+    }
+
+    /// What to tell the user when the device has to be put in this mode by hand
+    pub fn instructions(&self) -> &'static str {
+        match self {
+            StartMode::Adb => {
+                "Start the device normally into Android and check that USB debugging is enabled and this computer is allowed."
+            }
+            StartMode::Fastboot | StartMode::AdbOrFastboot => {
+                "Power the device off, then hold its key combination for the bootloader (usually Volume Down + Power) until the fastboot screen appears."
+            }
+        }
+    }
+}
+
+/// The device needs to be in another mode and couldn't be switched there
+#[derive(Debug, Clone)]
+pub struct WrongMode {
+    pub needed: StartMode,
+    pub found: Option<DeviceMode>,
+}
+
+impl std::fmt::Display for WrongMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let needed = match self.needed {
+            StartMode::Adb => "booted into Android with adb",
+            StartMode::Fastboot => "in fastboot mode",
+            StartMode::AdbOrFastboot => "booted into Android with adb, or in fastboot mode",
+        };
+        match self.found {
+            Some(mode) => write!(f, "The device is in {} mode but needs to be {}", mode.label(), needed),
+            None => write!(f, "The device isn't responding; it needs to be {}", needed),
+        }
+    }
+}
+
+impl std::error::Error for WrongMode {}
+
+/// Which mode `serial` is in right now, if it's connected.
+pub async fn current_mode(serial: &str) -> Option<DeviceMode> {
+    if serial == heimdall::DOWNLOAD_MODE_SERIAL {
+        return Heimdall::new()
+            .detect()
+            .await
+            .unwrap_or(false)
+            .then_some(DeviceMode::Download);
+    }
+
+    let fastboot = Fastboot::new();
+    if fastboot
+        .devices()
+        .await
+        .is_ok_and(|devices| devices.iter().any(|d| d.serial == serial))
+    {
+        return Some(if fastboot.is_fastbootd(serial).await.unwrap_or(false) {
+            DeviceMode::Fastbootd
+        } else {
+            DeviceMode::Fastboot
+        });
+    }
+
+    let adb = Adb::new();
+    let devices = adb.devices().await.ok()?;
+    devices
+        .iter()
+        .find(|d| d.serial == serial)
+        .and_then(|d| adb_mode(&d.state))
+}
+
+/// The mode an adb state puts a device in; `None` for states nothing can
+/// be done with, like unauthorized or offline
+pub fn adb_mode(state: &AdbState) -> Option<DeviceMode> {
+    match state {
+        AdbState::Device => Some(DeviceMode::Adb),
+        AdbState::Recovery => Some(DeviceMode::Recovery),
+        AdbState::Sideload => Some(DeviceMode::Sideload),
+        _ => None,
+    }
+}
+
+/// Get `serial` into a mode `needed` accepts, rebooting it when that can be
+/// done from where it is. Returns the mode it ended up in, or a
+/// [`WrongMode`] error when the user has to step in.
+pub async fn ensure(serial: &str, needed: StartMode) -> Result<DeviceMode> {
+    let found = current_mode(serial).await;
+    let wrong = || WrongMode { needed, found };
+
+    let Some(mode) = found else {
+        return Err(wrong().into());
+    };
+    if needed.accepts(mode) {
+        return Ok(mode);
+    }
+
+    log::info!("{} is in {} mode; switching for the installer", serial, mode.label());
+    let adb = Adb::new();
+    let fastboot = Fastboot::new();
+    let target = match needed {
+        StartMode::Adb => "Android",
+        _ => "the bootloader",
+    };
+    let _window = RebootWindow::open(serial, target);
+    let switched = match (mode, needed) {
+        // Recovery's adbd takes the same reboot command; sideload doesn't
+        (DeviceMode::Adb, _) | (DeviceMode::Recovery, StartMode::Fastboot | StartMode::AdbOrFastboot) => {
+            adb.reboot_bootloader(serial).await.is_ok()
+                && fastboot.wait_for_mode(serial, false, SWITCH_TIMEOUT).await.is_ok()
+        }
+        (DeviceMode::Fastbootd, StartMode::Fastboot | StartMode::AdbOrFastboot) => {
+            fastboot.reboot_bootloader(serial).await.is_ok()
+                && fastboot.wait_for_mode(serial, false, SWITCH_TIMEOUT).await.is_ok()
+        }
+        (DeviceMode::Fastboot | DeviceMode::Fastbootd, StartMode::Adb) => {
+            fastboot.reboot(serial).await.is_ok() && wait_for_adb(&adb, serial).await
+        }
+        _ => false,
+    };
+
+    if !switched {
+        return Err(wrong().into());
+    }
+    let now = current_mode(serial).await;
+    match now {
+        Some(mode) if needed.accepts(mode) => Ok(mode),
+        _ => Err(WrongMode { needed, found: now }.into()),
+    }
+}
+
+async fn wait_for_adb(adb: &Adb, serial: &str) -> bool {
+    let deadline = Instant::now() + SWITCH_TIMEOUT;
+    while Instant::now() < deadline {
+        if adb
+            .devices()
+            .await
+            .is_ok_and(|devices| devices.iter().any(|d| d.serial == serial && d.state == AdbState::Device))
+        {
+            return true;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    false
+}
@@ -0,0 +1,168 @@
+// Prerequisite checks
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::{Adb, Fastboot};
+
+/// Result of checking an installer prerequisite against the connected device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    /// The device meets it; the text says what was found
+    Satisfied(String),
+    /// The device doesn't; the text says what was found instead
+    Unsatisfied(String),
+    /// Not something we can query, or the device didn't say: ask the user
+    Manual,
+}
+
+/// Check an installer config `check` against the device.
+///
+/// Understood checks are `android_version_<N>`, `bootloader_unlocked` and
+/// `partition_<name>`. Anything else, or a device that doesn't report the
+/// needed value in its current mode, comes back as [`CheckOutcome::Manual`].
+pub async fn verify(check: &str, serial: &str) -> CheckOutcome {
+    let adb = Adb::new();
+    let fastboot = Fastboot::new();
+    let in_fastboot = fastboot
+        .devices()
+        .await
+        .map(|devices| devices.iter().any(|d| d.serial == serial))
+        .unwrap_or(false);
+
+    let outcome = if let Some(wanted) = check.strip_prefix("android_version_") {
+        let reported = if in_fastboot {
+            fastboot.getvar(serial, "version-os").await.unwrap_or_default()
+        } else {
+            adb.get_android_version(serial).await.unwrap_or_default()
+        };
+        android_version(wanted, reported.trim())
+    } else if check == "bootloader_unlocked" {
+        if in_fastboot {
+            match fastboot.unlock_state(serial).await {
+                Ok(Some(true)) => CheckOutcome::Satisfied("The bootloader is unlocked".into()),
+                Ok(Some(false)) => CheckOutcome::Unsatisfied("The bootloader is still locked".into()),
+                _ => CheckOutcome::Manual,
+            }
+        } else {
+            match adb.getprop(serial, "ro.boot.flash.locked").await.as_deref() {
+                Ok("0") => CheckOutcome::Satisfied("The bootloader is unlocked".into()),
+                Ok("1") => CheckOutcome::Unsatisfied("The bootloader is still locked".into()),
+                _ => CheckOutcome::Manual,
+            }
+        }
+    } else if let Some(partition) = check.strip_prefix("partition_") {
+        // Partition tables are only visible from fastboot
+        if in_fastboot {
+            partition_present(&fastboot, serial, partition).await
+        } else {
+            CheckOutcome::Manual
+        }
+    } else {
+        CheckOutcome::Manual
+    };
+
+    log::info!("Prerequisite {} on {}: {:?}", check, serial, outcome);
+    outcome
+}
+
+/// Check the device's charge against `minimum` percent before an install.
+///
+/// Booted devices report a level over adb. In fastboot only some
+/// bootloaders answer `battery-soc-ok` or `battery-voltage`, and the
+/// voltage gives a rough estimate at best; a device that says nothing is
+/// [`CheckOutcome::Manual`], and the check is skipped.
+pub async fn battery(serial: &str, minimum: u8) -> CheckOutcome {
+    let fastboot = Fastboot::new();
+    let in_fastboot = fastboot
+        .devices()
+        .await
+        .map(|devices| devices.iter().any(|d| d.serial == serial))
+        .unwrap_or(false);
+
+    let outcome = if in_fastboot {
+        let soc_ok = fastboot.getvar(serial, "battery-soc-ok").await.unwrap_or_default();
+        let voltage = fastboot.getvar(serial, "battery-voltage").await.unwrap_or_default();
+        match (soc_ok.trim(), voltage_percent(&voltage)) {
+            (_, Some(level)) => battery_level(level, minimum, "about "),
+            ("no", None) => CheckOutcome::Unsatisfied("The bootloader reports the battery too low to flash".into()),
+            ("yes", None) => CheckOutcome::Satisfied("The bootloader reports enough charge to flash".into()),
+            _ => CheckOutcome::Manual,
+        }
+    } else {
+        match Adb::new().get_battery_level(serial).await {
+            Ok(level) => battery_level(level, minimum, ""),
+            Err(_) => CheckOutcome::Manual,
+        }
+    };
+
+    if outcome == CheckOutcome::Manual {
+        log::warn!("Couldn't read the battery level of {}; skipping the check", serial);
+    } else {
+        log::info!("Battery on {} (minimum {}%): {:?}", serial, minimum, outcome);
+    }
+    outcome
+}
+
+fn battery_level(level: u8, minimum: u8, qualifier: &str) -> CheckOutcome {
+    if level >= minimum {
+        CheckOutcome::Satisfied(format!("The battery is at {}{}%", qualifier, level))
+    } else {
+        CheckOutcome::Unsatisfied(format!(
+            "The battery is at {}{}%, but at least {}% is needed",
+            qualifier, level, minimum
+        ))
+    }
+}
+
+/// Rough charge from a `battery-voltage` reading in millivolts ("3850" or
+/// "3850mV"), taking a lithium cell as empty at 3.5 V and full at 4.2 V
+fn voltage_percent(value: &str) -> Option<u8> {
+    let millivolts: u32 = value.trim().trim_end_matches("mV").trim().parse().ok()?;
+    if !(2500..=5000).contains(&millivolts) {
+        return None;
+    }
+    let percent = millivolts.clamp(3500, 4200).saturating_sub(3500) * 100 / 700;
+    Some(percent as u8)
+}
+
+/// Compare major versions, so "12" accepts a device reporting "12.1".
+fn android_version(wanted: &str, reported: &str) -> CheckOutcome {
+    let major = reported.split('.').next().unwrap_or_default();
+    if major.is_empty() || !major.chars().all(|c| c.is_ascii_digit()) {
+        return CheckOutcome::Manual;
+    }
+    if major == wanted {
+        CheckOutcome::Satisfied(format!("The device runs Android {}", reported))
+    } else {
+        CheckOutcome::Unsatisfied(format!(
+            "The device runs Android {}, but Android {} is required",
+            reported, wanted
+        ))
+    }
+}
+
+async fn partition_present(fastboot: &Fastboot, serial: &str, partition: &str) -> CheckOutcome {
+    for name in [partition.to_string(), format!("{}_a", partition)] {
+        let size = fastboot
+            .getvar(serial, &format!("partition-size:{}", name))
+            .await
+            .unwrap_or_default();
+        if !size.trim().is_empty() {
+            return CheckOutcome::Satisfied(format!("The {} partition is present", partition));
+        }
+    }
+    // Some bootloaders don't report sizes at all; only trust a negative
+    // answer when they do for a partition every device has
+    let boot = fastboot
+        .getvar(serial, "partition-size:boot")
+        .await
+        .unwrap_or_default();
+    let boot_a = fastboot
+        .getvar(serial, "partition-size:boot_a")
+        .await
+        .unwrap_or_default();
+    if boot.trim().is_empty() && boot_a.trim().is_empty() {
+        CheckOutcome::Manual
+    } else {
+        CheckOutcome::Unsatisfied(format!("The device has no {} partition", partition))
+    }
+}
@@ -0,0 +1,50 @@
+// Reboots an installer expects, so detection doesn't treat them as unplugging
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+static OPEN: Lazy<Mutex<Vec<ExpectedReboot>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// A device that is meant to drop off the bus and come back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedReboot {
+    pub serial: String,
+    /// Where it's rebooting to, as shown to the user ("fastbootd", "recovery")
+    pub target: &'static str,
+}
+
+/// Open while an installer reboots a device and waits for it to return.
+/// The device detector watches the device through the window instead of
+/// reporting it disconnected; dropping the guard closes it.
+#[derive(Debug)]
+#[must_use = "the window closes as soon as the guard is dropped"]
+pub struct RebootWindow {
+    reboot: ExpectedReboot,
+}
+
+impl RebootWindow {
+    pub fn open(serial: &str, target: &'static str) -> Self {
+        let reboot = ExpectedReboot {
+            serial: serial.to_string(),
+            target,
+        };
+        log::debug!("Expecting {} to reboot into {}", serial, target);
+        OPEN.lock().unwrap().push(reboot.clone());
+        Self { reboot }
+    }
+}
+
+impl Drop for RebootWindow {
+    fn drop(&mut self) {
+        let mut open = OPEN.lock().unwrap();
+        if let Some(index) = open.iter().rposition(|r| *r == self.reboot) {
+            open.remove(index);
+        }
+    }
+}
+
+/// The most recently opened window, if a reboot is expected right now
+pub fn current() -> Option<ExpectedReboot> {
+    OPEN.lock().unwrap().last().cloned()
+}
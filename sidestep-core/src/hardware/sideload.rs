@@ -0,0 +1,256 @@
+// Reading `adb sideload` output: progress and the ways recoveries refuse a package
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// A sideload that adb or the recovery refused. The message says what to do
+/// about it rather than echoing the recovery.
+#[derive(Debug, Clone)]
+pub struct SideloadRejected {
+    pub message: String,
+    /// The output line it was recognised from
+    pub output: String,
+}
+
+impl std::fmt::Display for SideloadRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for SideloadRejected {}
+
+/// Lower-case markers in adb or recovery output, first match wins
+const KNOWN_FAILURES: &[(&str, &str)] = &[
+    (
+        "signature verification failed",
+        "The recovery rejected the package's signature. Flash the recovery that \
+         comes with this build, then sideload again.",
+    ),
+    (
+        "this package is for device",
+        "This package was built for a different device. Check that the model \
+         and variant match the download.",
+    ),
+    (
+        "can't install this package on top of incompatible data",
+        "The package can't be installed over the data on the phone. Format \
+         data in recovery (Factory reset), then sideload again.",
+    ),
+    (
+        "older than the current build",
+        "The package is older than what's installed, and the recovery won't \
+         downgrade. Format data in recovery, then sideload again.",
+    ),
+    (
+        "sideload connection failed",
+        "The phone isn't waiting for a sideload. On the phone choose \"Apply \
+         update\" → \"Apply from ADB\", then try again.",
+    ),
+    (
+        "no devices/emulators found",
+        "The phone disappeared from ADB. Check the cable, make sure \"Apply \
+         from ADB\" is open on the phone, then try again.",
+    ),
+    (
+        "insufficient permissions",
+        "This computer isn't allowed to talk to the phone over USB. Install \
+         the Android udev rules, reconnect the phone and try again.",
+    ),
+    (
+        "failed to stat",
+        "The downloaded package is missing. Clear the download cache and \
+         start the install again.",
+    ),
+    (
+        "no space left",
+        "The phone ran out of space for the package. Format data in recovery, \
+         then sideload again.",
+    ),
+    (
+        "installation aborted",
+        "The recovery stopped the installation. Check the message on the \
+         phone's screen; updating the recovery usually helps.",
+    ),
+];
+
+/// What adb prints once the whole package has been sent. adb often exits
+/// non-zero after a sideload that worked, when the recovery drops the
+/// connection to start installing ("failed to read command: Success").
+const COMPLETED_MARKERS: &[&str] = &[
+    "total xfer:",
+    "failed to read command: success",
+    "failed to read command: no error",
+];
+
+/// The percentage in an adb progress line such as
+/// `serving: 'lineage.zip'  (~47%)`.
+pub fn parse_progress(line: &str) -> Option<u64> {
+    let start = line.rfind("(~")? + 2;
+    let end = start + line[start..].find('%')?;
+    line[start..end].trim().parse().ok().filter(|p| *p <= 100)
+}
+
+/// The first known failure anywhere in `output`
+pub fn classify(output: &str) -> Option<SideloadRejected> {
+    output.lines().find_map(|line| {
+        let lower = line.to_lowercase();
+        KNOWN_FAILURES
+            .iter()
+            .find(|(marker, _)| lower.contains(marker))
+            .map(|(_, message)| SideloadRejected {
+                message: message.to_string(),
+                output: line.trim().to_string(),
+            })
+    })
+}
+
+/// Whether `output` shows the package was sent in full, whatever adb's
+/// exit status
+pub fn completed(output: &str) -> bool {
+    let lower = output.to_lowercase();
+    COMPLETED_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+/// Refusal for a device that adb sees, but in `state` rather than waiting
+/// in "Apply from ADB"
+pub fn not_in_sideload(state: &str) -> SideloadRejected {
+    SideloadRejected {
+        message: format!(
+            "The phone is in {} mode, not waiting for a sideload. Boot it to \
+             recovery and choose \"Apply update\" → \"Apply from ADB\", then try again.",
+            state
+        ),
+        output: format!("device state: {}", state),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// adb's progress lines run together on one line with carriage returns
+    const SUCCESS: &str = "serving: 'lineage-22.1-20250105-nightly-FP4-signed.zip'  (~0%)    \rserving: 'lineage-22.1-20250105-nightly-FP4-signed.zip'  (~47%)    \rserving: 'lineage-22.1-20250105-nightly-FP4-signed.zip'  (~100%)    \nTotal xfer: 1.00x\n";
+
+    fn message_for(output: &str) -> String {
+        classify(output).expect("a known failure").message
+    }
+
+    #[test]
+    fn progress_is_read_from_each_serving_line() {
+        let percents: Vec<u64> = SUCCESS
+            .split(['\r', '\n'])
+            .filter_map(parse_progress)
+            .collect();
+        assert_eq!(percents, vec![0, 47, 100]);
+        assert_eq!(parse_progress("serving: 'a.zip'  (~101%)"), None);
+        assert_eq!(parse_progress("Total xfer: 1.00x"), None);
+    }
+
+    #[test]
+    fn a_full_transfer_is_a_success() {
+        assert!(completed(SUCCESS));
+        assert!(classify(SUCCESS).is_none());
+    }
+
+    #[test]
+    fn recovery_dropping_the_connection_still_completed() {
+        let output = "serving: 'e.zip'  (~94%)    \nadb: failed to read command: Success\n";
+        assert!(completed(output));
+        assert!(classify(output).is_none());
+    }
+
+    #[test]
+    fn signature_failure() {
+        let output = "serving: 'a.zip'  (~12%)    \nE:Zip signature verification failed: 1\nInstallation aborted.\n";
+        assert!(message_for(output).starts_with("The recovery rejected the package's signature."));
+        assert_eq!(
+            classify(output).unwrap().output,
+            "E:Zip signature verification failed: 1"
+        );
+    }
+
+    #[test]
+    fn wrong_device() {
+        let output = "E:This package is for device: FP3; this device is FP4.\n";
+        assert!(message_for(output).starts_with("This package was built for a different device."));
+    }
+
+    #[test]
+    fn incompatible_data() {
+        let output = "Can't install this package on top of incompatible data. Please try another package or run a factory reset\n";
+        assert!(message_for(output).contains("Format data in recovery (Factory reset)"));
+    }
+
+    #[test]
+    fn downgrade() {
+        let output = "E:Update package is older than the current build, expected a build newer than timestamp 1736000000\n";
+        assert!(message_for(output).starts_with("The package is older than what's installed"));
+    }
+
+    #[test]
+    fn not_waiting_for_sideload() {
+        let output = "adb: sideload connection failed: closed\n";
+        assert!(message_for(output).contains("\"Apply from ADB\""));
+    }
+
+    #[test]
+    fn device_gone_and_permissions() {
+        assert!(
+            message_for("adb: error: no devices/emulators found\n")
+                .starts_with("The phone disappeared from ADB.")
+        );
+        assert!(message_for("adb: insufficient permissions for device\n").contains("udev rules"));
+    }
+
+    #[test]
+    fn missing_package_and_no_space() {
+        assert!(
+            message_for("adb: failed to stat file lineage.zip: No such file or directory\n")
+                .contains("download cache")
+        );
+        assert!(
+            message_for("E:failed to write: No space left on device\n")
+                .contains("ran out of space")
+        );
+    }
+
+    #[test]
+    fn bare_abort_falls_back_to_the_generic_message() {
+        assert!(
+            message_for("Installation aborted.\n")
+                .starts_with("The recovery stopped the installation.")
+        );
+    }
+
+    #[test]
+    fn unknown_output_is_not_classified() {
+        assert!(classify("Verifying update package...\nInstalling update...\n").is_none());
+        assert!(!completed("serving: 'a.zip'  (~50%)    \n"));
+    }
+
+    #[test]
+    fn wrong_mode_names_the_mode() {
+        let rejected = not_in_sideload("recovery");
+        assert!(
+            rejected
+                .message
+                .starts_with("The phone is in recovery mode")
+        );
+        assert_eq!(rejected.output, "device state: recovery");
+    }
+
+    #[test]
+    fn outcome_shows_the_advice_not_the_context() {
+        use crate::flashing::InstallProgress;
+        use anyhow::Context;
+
+        let error = Err::<(), _>(classify("E:Zip signature verification failed: 1").unwrap())
+            .context("Failed to sideload lineage.zip")
+            .unwrap_err();
+        match InstallProgress::from_error(&error) {
+            InstallProgress::Error(message) => {
+                assert!(message.starts_with("The recovery rejected the package's signature."))
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,353 @@
+// Simulated device for working without hardware
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::hardware::adb::{AdbDevice, AdbState};
+use crate::hardware::fastboot::{DeviceDisconnected, DirectCommand, FastbootDevice};
+use crate::models::DeviceDatabase;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long the simulated device is gone from both adb and fastboot while
+/// it reboots
+const REBOOT_DELAY: Duration = Duration::from_secs(3);
+
+/// How long the simulated user takes to pick "Apply from ADB" in recovery
+const RECOVERY_MENU_DELAY: Duration = Duration::from_secs(5);
+
+/// How long every flash, erase or sideload takes
+const WRITE_DELAY: Duration = Duration::from_secs(1);
+
+/// Bytes written for a simulated partition backup
+const BACKUP_SIZE: usize = 4096;
+
+static SIMULATOR: Lazy<Option<Simulator>> = Lazy::new(Simulator::from_env);
+
+/// Where the simulated device currently is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulatedMode {
+    Android,
+    Bootloader,
+    Fastbootd,
+    Recovery,
+}
+
+impl SimulatedMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "adb" | "android" => Some(Self::Android),
+            "fastboot" | "bootloader" => Some(Self::Bootloader),
+            "fastbootd" => Some(Self::Fastbootd),
+            "recovery" => Some(Self::Recovery),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    mode: SimulatedMode,
+    /// When the device last entered `mode`; before this it is rebooting
+    entered: Instant,
+    unlocked: bool,
+}
+
+/// A fake device that [`Adb`](crate::hardware::Adb) and
+/// [`Fastboot`](crate::hardware::Fastboot) answer from instead of running
+/// their binaries.
+///
+/// Enabled with `SIDESTEP_SIMULATE=<codename>`, optionally followed by the
+/// mode to start in: `SIDESTEP_SIMULATE=sargo:fastboot`. The device boots
+/// locked into Android by default. Reboots take it offline for a few
+/// seconds before it appears in the new mode, unlocking succeeds straight
+/// away, and recovery switches to sideload on its own as if the user had
+/// picked it. Images are checked to exist but never sent anywhere; downloads
+/// still go to the network.
+#[derive(Debug)]
+pub struct Simulator {
+    codename: String,
+    serial: String,
+    model: String,
+    manufacturer: String,
+    state: Mutex<State>,
+}
+
+impl Simulator {
+    /// The simulator, when `SIDESTEP_SIMULATE` is set.
+    pub fn active() -> Option<&'static Simulator> {
+        SIMULATOR.as_ref()
+    }
+
+    fn from_env() -> Option<Self> {
+        let value = std::env::var("SIDESTEP_SIMULATE").ok()?;
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        let (codename, mode) = value.split_once(':').unwrap_or((value, "adb"));
+        let mode = SimulatedMode::parse(mode).unwrap_or_else(|| {
+            log::warn!("Unknown simulated mode '{}', starting in Android", mode);
+            SimulatedMode::Android
+        });
+        let (model, manufacturer) = match DeviceDatabase::new().find_by_codename(codename) {
+            Some(device) => (device.name, device.maker),
+            None => {
+                log::warn!("{} isn't in the device database; simulating it anyway", codename);
+                (codename.to_string(), "Unknown".to_string())
+            }
+        };
+
+        log::warn!(
+            "Simulating a {} ({}) in {:?} mode; no real device will be used",
+            model,
+            codename,
+            mode
+        );
+        Some(Self {
+            codename: codename.to_string(),
+            serial: format!("SIM{}", codename.to_uppercase()),
+            model,
+            manufacturer,
+            state: Mutex::new(State {
+                mode,
+                entered: Instant::now(),
+                unlocked: false,
+            }),
+        })
+    }
+
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    /// The current mode, or `None` while rebooting
+    pub fn mode(&self) -> Option<SimulatedMode> {
+        let state = self.state.lock().unwrap();
+        (Instant::now() >= state.entered).then_some(state.mode)
+    }
+
+    fn reboot_into(&self, mode: SimulatedMode) {
+        log::info!("Simulated {} rebooting into {:?}", self.codename, mode);
+        let mut state = self.state.lock().unwrap();
+        state.mode = mode;
+        state.entered = Instant::now() + REBOOT_DELAY;
+    }
+
+    /// Fail like a real device would when `serial` isn't this one or it
+    /// isn't in one of `modes`.
+    fn expect(&self, serial: &str, modes: &[SimulatedMode], operation: &str) -> Result<()> {
+        let reachable = serial == self.serial && self.mode().is_some_and(|mode| modes.contains(&mode));
+        if reachable {
+            Ok(())
+        } else {
+            Err(DeviceDisconnected {
+                operation: operation.to_string(),
+            }
+            .into())
+        }
+    }
+
+    /// A write that takes a moment and needs `image` to be on disk
+    async fn write(&self, serial: &str, operation: &str, image: Option<&Path>) -> Result<()> {
+        self.expect(
+            serial,
+            &[SimulatedMode::Bootloader, SimulatedMode::Fastbootd],
+            operation,
+        )?;
+        if let Some(image) = image.filter(|image| !image.is_file()) {
+            anyhow::bail!("{}: {} doesn't exist", operation, image.display());
+        }
+        log::info!("Simulated {}", operation);
+        tokio::time::sleep(WRITE_DELAY).await;
+        Ok(())
+    }
+
+    // ── adb ──────────────────────────────────────────────────────────
+
+    pub fn adb_devices(&self) -> Vec<AdbDevice> {
+        let state = match self.mode() {
+            Some(SimulatedMode::Android) => AdbState::Device,
+            Some(SimulatedMode::Recovery) => {
+                let entered = self.state.lock().unwrap().entered;
+                if entered.elapsed() >= RECOVERY_MENU_DELAY {
+                    AdbState::Sideload
+                } else {
+                    AdbState::Recovery
+                }
+            }
+            _ => return Vec::new(),
+        };
+        vec![AdbDevice {
+            serial: self.serial.clone(),
+            state,
+            model: Some(self.model.replace(' ', "_")),
+            product: Some(self.codename.clone()),
+            usb: None,
+        }]
+    }
+
+    pub fn getprop(&self, serial: &str, prop: &str) -> Result<String> {
+        self.expect(serial, &[SimulatedMode::Android], "getprop")?;
+        let unlocked = self.state.lock().unwrap().unlocked;
+        Ok(match prop {
+            "ro.product.device" => self.codename.clone(),
+            "ro.product.model" => self.model.clone(),
+            "ro.product.manufacturer" => self.manufacturer.clone(),
+            "ro.build.version.release" => "11".to_string(),
+            "ro.build.display.id" => "SIM.000101.001".to_string(),
+            "ro.boot.flash.locked" => if unlocked { "0" } else { "1" }.to_string(),
+            "ro.boot.verifiedbootstate" => if unlocked { "orange" } else { "green" }.to_string(),
+            "sys.oem_unlock_allowed" => "1".to_string(),
+            _ => String::new(),
+        })
+    }
+
+    pub fn adb_shell(&self, serial: &str, command: &str) -> Result<String> {
+        self.expect(serial, &[SimulatedMode::Android], "shell")?;
+        log::debug!("Simulated shell: {}", command);
+        Ok(if command.contains("power_supply/battery/capacity") {
+            "84\n".to_string()
+        } else {
+            String::new()
+        })
+    }
+
+    pub fn adb_reboot(&self, serial: &str, mode: SimulatedMode) -> Result<()> {
+        self.expect(serial, &[SimulatedMode::Android, SimulatedMode::Recovery], "reboot")?;
+        self.reboot_into(mode);
+        Ok(())
+    }
+
+    /// `adb sideload` or `adb push` of `file`
+    pub async fn adb_transfer(&self, serial: &str, operation: &str, file: &Path) -> Result<()> {
+        let modes: &[SimulatedMode] = if operation == "sideload" {
+            &[SimulatedMode::Recovery]
+        } else {
+            &[SimulatedMode::Android, SimulatedMode::Recovery]
+        };
+        self.expect(serial, modes, operation)?;
+        if !file.is_file() {
+            anyhow::bail!("adb {}: {} doesn't exist", operation, file.display());
+        }
+        log::info!("Simulated adb {} of {}", operation, file.display());
+        tokio::time::sleep(WRITE_DELAY).await;
+
+        // Back to the recovery menu, as after a real sideload
+        if operation == "sideload" {
+            self.state.lock().unwrap().entered = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// Write a small dummy image in place of a partition backup.
+    pub async fn pull_partition(&self, serial: &str, partition: &str, dest: &Path) -> Result<u64> {
+        self.expect(serial, &[SimulatedMode::Android], "exec-out")?;
+        log::info!("Simulated backup of {}", partition);
+        tokio::fs::write(dest, vec![0u8; BACKUP_SIZE]).await?;
+        Ok(BACKUP_SIZE as u64)
+    }
+
+    // ── fastboot ─────────────────────────────────────────────────────
+
+    pub fn fastboot_devices(&self) -> Vec<FastbootDevice> {
+        match self.mode() {
+            Some(SimulatedMode::Bootloader | SimulatedMode::Fastbootd) => vec![FastbootDevice {
+                serial: self.serial.clone(),
+                product: Some(self.codename.clone()),
+                usb: None,
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn getvar(&self, serial: &str, var: &str) -> Result<String> {
+        self.expect(
+            serial,
+            &[SimulatedMode::Bootloader, SimulatedMode::Fastbootd],
+            "getvar",
+        )?;
+        let state = self.state.lock().unwrap();
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+
+        Ok(match var.split_once(':') {
+            Some(("partition-type", _)) => "raw".to_string(),
+            Some(("partition-size", _)) => "0x400000000".to_string(),
+            Some(("has-slot", _)) | Some(("is-logical", _)) => "no".to_string(),
+            _ => match var {
+                "product" => self.codename.clone(),
+                "serialno" => self.serial.clone(),
+                "unlocked" => yes_no(state.unlocked),
+                "unlock_ability" => "1".to_string(),
+                "is-userspace" => yes_no(state.mode == SimulatedMode::Fastbootd),
+                "current-slot" => "a".to_string(),
+                "slot-count" => "2".to_string(),
+                "max-download-size" => "0x10000000".to_string(),
+                "version-bootloader" => "sim-1.0".to_string(),
+                "secure" => "yes".to_string(),
+                _ => String::new(),
+            },
+        })
+    }
+
+    /// `getvar all` in the binary's `(bootloader) name: value` format
+    pub fn getvar_all(&self, serial: &str) -> Result<String> {
+        let vars = ["product", "serialno", "unlocked", "is-userspace", "current-slot", "version-bootloader"];
+        let mut out = String::new();
+        for var in vars {
+            out.push_str(&format!("(bootloader) {}: {}\n", var, self.getvar(serial, var)?));
+        }
+        Ok(out)
+    }
+
+    /// Answer a command the direct transport would otherwise send.
+    pub(crate) async fn fastboot(&self, serial: &str, command: DirectCommand) -> Result<String> {
+        let fastboot_modes = [SimulatedMode::Bootloader, SimulatedMode::Fastbootd];
+        match command {
+            DirectCommand::Getvar(var) => self.getvar(serial, &var),
+            DirectCommand::Flash { partition, image } => self
+                .write(serial, &format!("flash {}", partition), Some(&image))
+                .await
+                .map(|_| String::new()),
+            DirectCommand::Boot(image) => {
+                self.write(serial, "boot", Some(&image)).await?;
+                self.reboot_into(SimulatedMode::Android);
+                Ok(String::new())
+            }
+            DirectCommand::Erase(partition) => self
+                .write(serial, &format!("erase {}", partition), None)
+                .await
+                .map(|_| String::new()),
+            DirectCommand::SetActive(slot) => {
+                self.expect(serial, &fastboot_modes, "set_active")?;
+                log::info!("Simulated set_active {}", slot);
+                Ok(String::new())
+            }
+            DirectCommand::Oem(args) => {
+                self.expect(serial, &fastboot_modes, "oem")?;
+                if args == ["unlock"] {
+                    log::info!("Simulated {} unlocked", self.codename);
+                    self.state.lock().unwrap().unlocked = true;
+                }
+                Ok(String::new())
+            }
+            DirectCommand::Reboot(target) => {
+                self.expect(serial, &fastboot_modes, target)?;
+                self.reboot_into(match target {
+                    "reboot-bootloader" => SimulatedMode::Bootloader,
+                    "reboot-fastboot" => SimulatedMode::Fastbootd,
+                    "reboot-recovery" => SimulatedMode::Recovery,
+                    _ => SimulatedMode::Android,
+                });
+                Ok(String::new())
+            }
+        }
+    }
+
+    /// `fastboot update`, `format` and flashes with extra flags
+    pub async fn fastboot_write(&self, serial: &str, operation: &str, image: Option<&Path>) -> Result<()> {
+        self.write(serial, operation, image).await
+    }
+}
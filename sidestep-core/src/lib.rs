@@ -0,0 +1,9 @@
+// Sidestep core: devices, downloads and flashing without any UI
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod config;
+pub mod flashing;
+pub mod hardware;
+pub mod models;
+pub mod utils;
+pub mod wizard;
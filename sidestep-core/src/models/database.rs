@@ -0,0 +1,2086 @@
+// Device database - loads device data from YAML files
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::models::{Device, Distro, DistroTreeNode, UnlockingStep};
+use crate::utils::data_check;
+use crate::utils::yaml_parser::YamlParser;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use crate::models::PartitionImage;
+
+/// In-memory database of supported devices
+pub struct DeviceDatabase {
+    devices: HashMap<String, Device>,
+    unlocking_steps: HashMap<String, Vec<UnlockingStep>>,
+    distros: HashMap<String, Vec<Distro>>,
+    data_dir: PathBuf,
+}
+
+impl DeviceDatabase {
+    /// Load the devices directory (`SIDESTEP_DATA_DIR`, or the first of
+    /// [`data_check::devices_dir_candidates`] that exists) over the built-in
+    /// set. The files win where they say something; the built-in records
+    /// fill in what they don't, such as flashing data for distros.
+    pub fn new() -> Self {
+        let data_dir = std::env::var("SIDESTEP_DATA_DIR")
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| data_check::devices_dir_candidates().into_iter().find(|p| p.is_dir()))
+            .unwrap_or_else(|| PathBuf::from("devices"));
+
+        let mut db = Self {
+            devices: HashMap::new(),
+            unlocking_steps: HashMap::new(),
+            distros: HashMap::new(),
+            data_dir,
+        };
+
+        db.load_hardcoded_devices();
+        if db.data_dir.is_dir() {
+            let loaded = db.load_from_dir();
+            log::info!(
+                "Loaded {} devices from {} ({} in total)",
+                loaded,
+                db.data_dir.display(),
+                db.devices.len()
+            );
+        } else {
+            log::warn!(
+                "No devices directory at {}; using the {} built-in devices",
+                db.data_dir.display(),
+                db.devices.len()
+            );
+        }
+
+        db
+    }
+
+    /// Read every `<maker>/<codename>/` under the data directory, returning
+    /// how many devices were loaded. A device whose info.yml doesn't parse
+    /// keeps its built-in record, if it has one.
+    fn load_from_dir(&mut self) -> usize {
+        let parser = YamlParser::new(&self.data_dir);
+        let mut loaded = 0;
+        for (maker_dir, codename_dir) in data_check::device_dirs(&self.data_dir) {
+            let info = match parser.parse_device_info(&maker_dir, &codename_dir) {
+                Ok(info) => info.device,
+                Err(e) => {
+                    log::warn!("Skipping {}/{}: {:#}", maker_dir, codename_dir, e);
+                    continue;
+                }
+            };
+            let codename = info.codename.clone();
+            let builtin = self.devices.remove(&codename);
+            let device = device_from_info(info, &maker_dir, builtin);
+            self.devices.insert(codename.clone(), device);
+            loaded += 1;
+
+            match parser.parse_unlocking_steps(&maker_dir, &codename_dir) {
+                Ok(Some(steps)) => {
+                    self.unlocking_steps.insert(codename.clone(), steps);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("{}/{}: {:#}", maker_dir, codename_dir, e),
+            }
+
+            // distros.yml doesn't carry partition layouts, so the built-in
+            // entries are kept where there are any
+            if !self.distros.contains_key(&codename)
+                && let Ok(tree) = parser.parse_distros(&maker_dir, &codename_dir)
+            {
+                let mut distros = Vec::new();
+                flatten_distros(tree, &mut distros);
+                self.distros.insert(codename, distros);
+            }
+        }
+        loaded
+    }
+
+    /// Load hardcoded device data (MVP approach)
+    fn load_hardcoded_devices(&mut self) {
+        // Pixel 3a (sargo)
+        self.devices.insert("sargo".to_string(), Device {
+            codename: "sargo".to_string(),
+            name: "Google Pixel 3a".to_string(),
+            maker: "Google".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Pixel 3a".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus 6 (enchilada)
+        self.devices.insert("enchilada".to_string(), Device {
+            codename: "enchilada".to_string(),
+            name: "OnePlus 6".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["OnePlus6".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Motorola Edge 30 (dubai)
+        self.devices.insert("dubai".to_string(), Device {
+            codename: "dubai".to_string(),
+            name: "Motorola Edge 30".to_string(),
+            maker: "Motorola".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["moto edge 30".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Surface Duo (zeta)
+        self.devices.insert("zeta".to_string(), Device {
+            codename: "zeta".to_string(),
+            name: "Microsoft Surface Duo".to_string(),
+            maker: "Microsoft".to_string(),
+            experimental: true,
+            battery_min: 60,
+            warnings: vec![
+                "Experimental device - proceed with caution".to_string(),
+                "Dual screen functionality may be limited".to_string(),
+            ],
+            aliases: vec!["surfaceduo".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Xiaomi POCO M2 Pro / Redmi Note 9 Pro (miatoll)
+        self.devices.insert("miatoll".to_string(), Device {
+            codename: "miatoll".to_string(),
+            name: "POCO M2 Pro".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![
+                "Redmi Note 9S".to_string(),
+                "Redmi Note 9 Pro".to_string(),
+                "Redmi Note 9 Pro Max".to_string(),
+                "Redmi Note 10 Lite".to_string(),
+            ],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // F(x)tec Pro1 (pro1)
+        self.devices.insert("pro1".to_string(), Device {
+            codename: "pro1".to_string(),
+            name: "F(x)tec Pro1".to_string(),
+            maker: "F(x)tec".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Pro1".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // F(x)tec Pro1 X (pro1x)
+        self.devices.insert("pro1x".to_string(), Device {
+            codename: "pro1x".to_string(),
+            name: "F(x)tec Pro1 X".to_string(),
+            maker: "F(x)tec".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Pro1X".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Sony Xperia 5 (bahamut)
+        self.devices.insert("bahamut".to_string(), Device {
+            codename: "bahamut".to_string(),
+            name: "Sony Xperia 5".to_string(),
+            maker: "Sony".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "Sony DRM keys will be permanently lost after unlocking".to_string(),
+            ],
+            aliases: vec!["Xperia 5".to_string(), "J9210".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Volla Phone (yggdrasil)
+        self.devices.insert("yggdrasil".to_string(), Device {
+            codename: "yggdrasil".to_string(),
+            name: "Volla Phone".to_string(),
+            maker: "Volla".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["GS290".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Volla Phone X (yggdrasilx)
+        self.devices.insert("yggdrasilx".to_string(), Device {
+            codename: "yggdrasilx".to_string(),
+            name: "Volla Phone X".to_string(),
+            maker: "Volla".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Volla Phone 22 (mimameid)
+        self.devices.insert("mimameid".to_string(), Device {
+            codename: "mimameid".to_string(),
+            name: "Volla Phone 22".to_string(),
+            maker: "Volla".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Volla Phone X23 (vidofnir)
+        self.devices.insert("vidofnir".to_string(), Device {
+            codename: "vidofnir".to_string(),
+            name: "Volla Phone X23".to_string(),
+            maker: "Volla".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Volla Phone Quintus (algiz)
+        self.devices.insert("algiz".to_string(), Device {
+            codename: "algiz".to_string(),
+            name: "Volla Phone Quintus".to_string(),
+            maker: "Volla".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Volla 5".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Lenovo ThinkPhone by Motorola (bronco)
+        self.devices.insert("bronco".to_string(), Device {
+            codename: "bronco".to_string(),
+            name: "Lenovo ThinkPhone".to_string(),
+            maker: "Lenovo".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["ThinkPhone".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Volla Tablet (mimir)
+        self.devices.insert("mimir".to_string(), Device {
+            codename: "mimir".to_string(),
+            name: "Volla Tablet".to_string(),
+            maker: "Volla".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus 6T (fajita)
+        self.devices.insert("fajita".to_string(), Device {
+            codename: "fajita".to_string(),
+            name: "OnePlus 6T".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["OnePlus6T".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Xiaomi Pocophone F1 (beryllium)
+        self.devices.insert("beryllium".to_string(), Device {
+            codename: "beryllium".to_string(),
+            name: "Pocophone F1".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["POCO F1".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Fairphone 4 (FP4)
+        self.devices.insert("FP4".to_string(), Device {
+            codename: "FP4".to_string(),
+            name: "Fairphone 4".to_string(),
+            maker: "Fairphone".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["fp4".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Fairphone 5 (FP5)
+        self.devices.insert("FP5".to_string(), Device {
+            codename: "FP5".to_string(),
+            name: "Fairphone 5".to_string(),
+            maker: "Fairphone".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["fp5".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // SHIFT6mq (axolotl)
+        self.devices.insert("axolotl".to_string(), Device {
+            codename: "axolotl".to_string(),
+            name: "SHIFT6mq".to_string(),
+            maker: "SHIFT".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["SHIFT 6mq".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Motorola Moto Z (griffin)
+        self.devices.insert("griffin".to_string(), Device {
+            codename: "griffin".to_string(),
+            name: "Motorola Moto Z".to_string(),
+            maker: "Motorola".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "Verizon Droid Edition may have bootloader unlock restrictions".to_string(),
+            ],
+            aliases: vec!["moto z".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus 3 (oneplus3)
+        self.devices.insert("oneplus3".to_string(), Device {
+            codename: "oneplus3".to_string(),
+            name: "OnePlus 3".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["OnePlus3".to_string()],
+            variants: vec![
+                "OnePlus 3T".to_string(),
+            ],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Xiaomi Redmi Note 7 Pro (violet)
+        self.devices.insert("violet".to_string(), Device {
+            codename: "violet".to_string(),
+            name: "Redmi Note 7 Pro".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Redmi Note 7 Pro".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Sony Xperia 5 II (pdx206)
+        self.devices.insert("pdx206".to_string(), Device {
+            codename: "pdx206".to_string(),
+            name: "Sony Xperia 5 II".to_string(),
+            maker: "Sony".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "Sony DRM keys will be permanently lost after unlocking".to_string(),
+            ],
+            aliases: vec!["Xperia 5 II".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Fairphone 3/3+ (fp3)
+        self.devices.insert("fp3".to_string(), Device {
+            codename: "fp3".to_string(),
+            name: "Fairphone 3/3+".to_string(),
+            maker: "Fairphone".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["FP3".to_string(), "Fairphone 3".to_string()],
+            variants: vec![
+                "Fairphone 3+".to_string(),
+            ],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Fairphone 2 (fp2)
+        self.devices.insert("fp2".to_string(), Device {
+            codename: "fp2".to_string(),
+            name: "Fairphone 2".to_string(),
+            maker: "Fairphone".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "Older device with limited Linux distribution support".to_string(),
+            ],
+            aliases: vec!["FP2".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Google Pixel 3a XL (bonito)
+        self.devices.insert("bonito".to_string(), Device {
+            codename: "bonito".to_string(),
+            name: "Google Pixel 3a XL".to_string(),
+            maker: "Google".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Pixel 3a XL".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus Nord N10 5G (billie)
+        self.devices.insert("billie".to_string(), Device {
+            codename: "billie".to_string(),
+            name: "OnePlus Nord N10 5G".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["Nord N10".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus Nord N100 (billie2)
+        self.devices.insert("billie2".to_string(), Device {
+            codename: "billie2".to_string(),
+            name: "OnePlus Nord N100".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["Nord N100".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus 5 (cheeseburger)
+        self.devices.insert("cheeseburger".to_string(), Device {
+            codename: "cheeseburger".to_string(),
+            name: "OnePlus 5".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["OnePlus5".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus 5T (dumpling)
+        self.devices.insert("dumpling".to_string(), Device {
+            codename: "dumpling".to_string(),
+            name: "OnePlus 5T".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["OnePlus5T".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus Nord 2 5G (denniz)
+        self.devices.insert("denniz".to_string(), Device {
+            codename: "denniz".to_string(),
+            name: "OnePlus Nord 2 5G".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "OxygenOS backup recommended before proceeding".to_string(),
+            ],
+            aliases: vec!["Nord 2".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // OnePlus One (bacon)
+        self.devices.insert("bacon".to_string(), Device {
+            codename: "bacon".to_string(),
+            name: "OnePlus One".to_string(),
+            maker: "OnePlus".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "Older device with limited Linux distribution support".to_string(),
+            ],
+            aliases: vec!["OnePlus1".to_string(), "A0001".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // POCO X3 NFC (surya)
+        self.devices.insert("surya".to_string(), Device {
+            codename: "surya".to_string(),
+            name: "POCO X3 NFC".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["POCO X3".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Redmi Note 8 Pro (begonia)
+        self.devices.insert("begonia".to_string(), Device {
+            codename: "begonia".to_string(),
+            name: "Redmi Note 8 Pro".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Redmi Note 7 (lavender)
+        self.devices.insert("lavender".to_string(), Device {
+            codename: "lavender".to_string(),
+            name: "Redmi Note 7".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // POCO M3 (citrus)
+        self.devices.insert("citrus".to_string(), Device {
+            codename: "citrus".to_string(),
+            name: "POCO M3".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Redmi 9 / 9 Prime (lancelot)
+        self.devices.insert("lancelot".to_string(), Device {
+            codename: "lancelot".to_string(),
+            name: "Redmi 9".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![
+                "Redmi 9 Prime".to_string(),
+            ],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Redmi Note 9 (merlin)
+        self.devices.insert("merlin".to_string(), Device {
+            codename: "merlin".to_string(),
+            name: "Redmi Note 9".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Mi A2 (jasmine_sprout)
+        self.devices.insert("jasmine_sprout".to_string(), Device {
+            codename: "jasmine_sprout".to_string(),
+            name: "Mi A2".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["Mi 6X".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Mi 6 (sagit)
+        self.devices.insert("sagit".to_string(), Device {
+            codename: "sagit".to_string(),
+            name: "Mi 6".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Redmi Note 8 (2021) (biloba)
+        self.devices.insert("biloba".to_string(), Device {
+            codename: "biloba".to_string(),
+            name: "Redmi Note 8 (2021)".to_string(),
+            maker: "Xiaomi".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec![],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // ZenFone Max Pro M1 (x00td)
+        self.devices.insert("x00td".to_string(), Device {
+            codename: "x00td".to_string(),
+            name: "ZenFone Max Pro M1".to_string(),
+            maker: "ASUS".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+            ],
+            aliases: vec!["ZB601KL".to_string(), "ZB602KL".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Galaxy S7 Exynos (herolte)
+        self.devices.insert("herolte".to_string(), Device {
+            codename: "herolte".to_string(),
+            name: "Galaxy S7 (Exynos)".to_string(),
+            maker: "Samsung".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Exynos variant only — Snapdragon variant is not supported".to_string(),
+                "Samsung devices use Odin/Heimdall for flashing, not standard fastboot".to_string(),
+            ],
+            aliases: vec!["SM-G930F".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Galaxy S7 Edge Exynos (hero2lte)
+        self.devices.insert("hero2lte".to_string(), Device {
+            codename: "hero2lte".to_string(),
+            name: "Galaxy S7 Edge (Exynos)".to_string(),
+            maker: "Samsung".to_string(),
+            experimental: true,
+            battery_min: 50,
+            warnings: vec![
+                "Exynos variant only — Snapdragon variant is not supported".to_string(),
+                "Samsung devices use Odin/Heimdall for flashing, not standard fastboot".to_string(),
+            ],
+            aliases: vec!["SM-G935F".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Sony Xperia X (suzu)
+        self.devices.insert("suzu".to_string(), Device {
+            codename: "suzu".to_string(),
+            name: "Sony Xperia X".to_string(),
+            maker: "Sony".to_string(),
+            experimental: false,
+            battery_min: 50,
+            warnings: vec![
+                "Unlocking will factory reset the device".to_string(),
+                "Sony DRM keys will be permanently lost after unlocking".to_string(),
+            ],
+            aliases: vec!["Xperia X".to_string(), "F5121".to_string()],
+            variants: vec![],
+            is_locked: None,
+            serial: None,
+            android_version: None,
+            build_id: None,
+            battery_level: None,
+        });
+
+        // Load unlocking steps for each device
+        self.load_unlocking_steps();
+
+        // Load distros for each device
+        self.load_distros();
+    }
+
+    /// Steps for devices without a data directory to keep a steps.yml in.
+    /// Everything else comes from the devices directory, see
+    /// [`YamlParser::parse_unlocking_steps`].
+    fn load_unlocking_steps(&mut self) {
+        // Surface Duo steps
+        self.unlocking_steps.insert("zeta".to_string(), vec![
+            UnlockingStep {
+                order: 1,
+                title: "Enable Developer Options".to_string(),
+                description: "Go to Settings > About Phone and tap 'Build Number' 7 times".to_string(),
+                step_type: crate::models::StepType::Manual,
+                command: None,
+                duration_secs: None,
+                optional: false,
+                warning: None,
+            },
+            UnlockingStep {
+                order: 2,
+                title: "Enable OEM Unlocking".to_string(),
+                description: "Go to Settings > System > Developer Options and enable 'OEM unlocking'".to_string(),
+                step_type: crate::models::StepType::Manual,
+                command: None,
+                duration_secs: None,
+                optional: false,
+                warning: Some("Microsoft may detect this and void warranty".to_string()),
+            },
+            UnlockingStep {
+                order: 3,
+                title: "Reboot to Bootloader".to_string(),
+                description: "Hold Power + Volume Down when the device is off".to_string(),
+                step_type: crate::models::StepType::Manual,
+                command: None,
+                duration_secs: None,
+                optional: false,
+                warning: None,
+            },
+            UnlockingStep {
+                order: 4,
+                title: "Unlock Bootloader".to_string(),
+                description: "Run unlock command via fastboot".to_string(),
+                step_type: crate::models::StepType::Automated,
+                command: Some("fastboot flashing unlock".to_string()),
+                duration_secs: Some(30),
+                optional: false,
+                warning: Some("This will factory reset your device!".to_string()),
+            },
+        ]);
+    }
+
+    fn load_distros(&mut self) {
+        // postmarketOS for Pixel 3a
+        self.distros.insert("sargo".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/google-sargo/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/google-sargo/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-google-sargo.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(500_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+            Distro {
+                name: "/e/OS".to_string(),
+                version: "3.4".to_string(),
+                description: "De-Googled Android-based mobile OS focused on privacy".to_string(),
+                download_base_url: "https://images.ecloud.global/community/sargo/".to_string(),
+                checksum_url: None,
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "recovery-e-community-sargo.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://e.foundation".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(1_500_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("Flash recovery, then sideload ROM zip via adb sideload".to_string()),
+            },
+        ]);
+
+        // postmarketOS for OnePlus 6
+        self.distros.insert("enchilada".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/oneplus-enchilada/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/oneplus-enchilada/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-oneplus-enchilada.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // OnePlus 6T (fajita) - postmarketOS
+        self.distros.insert("fajita".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/oneplus-fajita/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/oneplus-fajita/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-oneplus-fajita.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Pocophone F1 (beryllium) - postmarketOS
+        self.distros.insert("beryllium".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/xiaomi-beryllium/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/xiaomi-beryllium/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-beryllium.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Fairphone 4 (FP4) - postmarketOS
+        self.distros.insert("FP4".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/fairphone-fp4/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/fairphone-fp4/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-fairphone-fp4.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Fairphone 5 (FP5) - postmarketOS
+        self.distros.insert("FP5".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/fairphone-fp5/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/fairphone-fp5/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-fairphone-fp5.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // SHIFT6mq (axolotl) - postmarketOS
+        self.distros.insert("axolotl".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/shift-axolotl/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/shift-axolotl/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-shift-axolotl.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // postmarketOS for Surface Duo
+        self.distros.insert("zeta".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux (experimental)".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/microsoft-zeta/".to_string(),
+                checksum_url: None,
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(400_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("Dual screen support is experimental".to_string()),
+            },
+        ]);
+
+        // OnePlus 3 (oneplus3) - postmarketOS
+        self.distros.insert("oneplus3".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/oneplus-oneplus3/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/oneplus-oneplus3/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-oneplus-oneplus3.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(500_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Motorola Edge 30 (dubai) - /e/OS
+        self.distros.insert("dubai".to_string(), vec![
+            Distro {
+                name: "/e/OS".to_string(),
+                version: "3.4".to_string(),
+                description: "De-Googled Android-based mobile OS focused on privacy".to_string(),
+                download_base_url: "https://images.ecloud.global/community/dubai/".to_string(),
+                checksum_url: None,
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "recovery-IMG-e-community-dubai.zip".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://e.foundation".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(2_000_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("Flash recovery (dtbo + vendor_boot), then sideload ROM zip via adb sideload".to_string()),
+            },
+        ]);
+
+        // Fairphone 3/3+ (fp3) - postmarketOS
+        self.distros.insert("fp3".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/fairphone-fp3/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/fairphone-fp3/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-fairphone-fp3.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Fairphone 2 (fp2) - postmarketOS
+        self.distros.insert("fp2".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/fairphone-fp2/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/fairphone-fp2/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-fairphone-fp2.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(500_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Google Pixel 3a XL (bonito) - postmarketOS
+        self.distros.insert("bonito".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/google-bonito/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/google-bonito/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-google-bonito.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(500_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // OnePlus 5 (cheeseburger) - postmarketOS
+        self.distros.insert("cheeseburger".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/oneplus-cheeseburger/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/oneplus-cheeseburger/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-oneplus-cheeseburger.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // OnePlus 5T (dumpling) - postmarketOS
+        self.distros.insert("dumpling".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/oneplus-dumpling/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/oneplus-dumpling/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-oneplus-dumpling.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // OnePlus One (bacon) - postmarketOS
+        self.distros.insert("bacon".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/oneplus-bacon/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/oneplus-bacon/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-oneplus-bacon.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(500_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // POCO X3 NFC (surya) - postmarketOS
+        self.distros.insert("surya".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "24.06".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/v24.06/xiaomi-surya/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/v24.06/xiaomi-surya/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-surya.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Redmi Note 8 Pro (begonia) - postmarketOS
+        self.distros.insert("begonia".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/xiaomi-begonia/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/xiaomi-begonia/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-begonia.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Redmi Note 7 (lavender) - postmarketOS
+        self.distros.insert("lavender".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/xiaomi-lavender/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/xiaomi-lavender/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-lavender.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Redmi 9 (lancelot) - postmarketOS
+        self.distros.insert("lancelot".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/xiaomi-lancelot/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/xiaomi-lancelot/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-lancelot.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Mi A2 (jasmine_sprout) - postmarketOS
+        self.distros.insert("jasmine_sprout".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/xiaomi-jasmine_sprout/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/xiaomi-jasmine_sprout/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-jasmine_sprout.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Mi 6 (sagit) - postmarketOS
+        self.distros.insert("sagit".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/xiaomi-sagit/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/xiaomi-sagit/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-xiaomi-sagit.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // ZenFone Max Pro M1 (x00td) - postmarketOS
+        self.distros.insert("x00td".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux distribution".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/asus-x00td/".to_string(),
+                checksum_url: Some("https://images.postmarketos.org/bpo/edge/asus-x00td/SHA256SUMS".to_string()),
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                    PartitionImage {
+                        partition: "system".to_string(),
+                        image: "rootfs-asus-x00td.img.xz".to_string(),
+                        erase_first: true,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(550_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("First boot may take several minutes".to_string()),
+            },
+        ]);
+
+        // Galaxy S7 Exynos (herolte) - postmarketOS
+        self.distros.insert("herolte".to_string(), vec![
+            Distro {
+                name: "postmarketOS".to_string(),
+                version: "edge".to_string(),
+                description: "Alpine-based mobile Linux (experimental)".to_string(),
+                download_base_url: "https://images.postmarketos.org/bpo/edge/samsung-herolte/".to_string(),
+                checksum_url: None,
+                checksum_algorithm: None,
+                partitions: vec![
+                    PartitionImage {
+                        partition: "boot".to_string(),
+                        image: "boot.img".to_string(),
+                        erase_first: false,
+                        aliases: Vec::new(),
+                    },
+                ],
+                homepage: Some("https://postmarketos.org".to_string()),
+                mirrors: vec![],
+                download_size_bytes: Some(400_000_000),
+                requires_unlock: true,
+                post_install_notes: Some("Exynos platform — experimental support".to_string()),
+            },
+        ]);
+    }
+
+    /// Find a device by its codename
+    pub fn find_by_codename(&self, codename: &str) -> Option<Device> {
+        let codename = codename.trim();
+
+        // Direct lookup
+        if let Some(device) = self.devices.get(codename) {
+            return Some(device.clone());
+        }
+
+        // Check aliases
+        for device in self.devices.values() {
+            if device.matches_codename(codename) {
+                return Some(device.clone());
+            }
+        }
+
+        // Same codename written differently: "fp4" for "FP4",
+        // "jasmine sprout" for "jasmine_sprout"
+        let wanted = normalize(codename);
+        self.devices
+            .values()
+            .find(|device| {
+                std::iter::once(&device.codename)
+                    .chain(&device.aliases)
+                    .any(|name| normalize(name) == wanted)
+            })
+            .cloned()
+    }
+
+    /// Whether `device` belongs in a list that hides experimental devices
+    /// unless `include_experimental`, given the `connected` device's codename
+    pub fn listed(device: &Device, include_experimental: bool, connected: Option<&str>) -> bool {
+        include_experimental
+            || !device.experimental
+            || connected.is_some_and(|codename| device.matches_codename(codename))
+    }
+
+    /// Devices matching `query` by name, codename, alias or variant, best
+    /// match first. Case and separators don't matter, and a query whose
+    /// letters appear in order ("px3a" for "Pixel 3a") still matches, below
+    /// anything that contains it outright.
+    pub fn search(&self, query: &str) -> Vec<Device> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<(u32, &Device)> = self
+            .devices
+            .values()
+            .filter_map(|device| {
+                std::iter::once(&device.name)
+                    .chain(std::iter::once(&device.codename))
+                    .chain(&device.aliases)
+                    .chain(&device.variants)
+                    .chain(std::iter::once(&format!("{} {}", device.maker, device.name)))
+                    .filter_map(|field| match_score(&query, &normalize(field)))
+                    .max()
+                    .map(|score| (score, device))
+            })
+            .collect();
+        matches.sort_by(|(a_score, a), (b_score, b)| b_score.cmp(a_score).then_with(|| a.name.cmp(&b.name)));
+        matches.into_iter().map(|(_, device)| device.clone()).collect()
+    }
+
+    /// Get unlocking steps for a device
+    pub fn get_unlocking_steps(&self, codename: &str) -> Vec<UnlockingStep> {
+        self.unlocking_steps
+            .get(codename)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Distros the built-in database has flashing data for. Which distros a
+    /// device can actually get comes from its distros.yml, see
+    /// [`DeviceDistroConfig::availability`](crate::models::distro_config::DeviceDistroConfig::availability).
+    pub fn get_distros(&self, codename: &str) -> Vec<Distro> {
+        self.distros.get(codename).cloned().unwrap_or_default()
+    }
+
+    /// Disk space installing `distro_name` on `codename` needs, for the
+    /// distros whose download size the database knows
+    pub fn space_needed(&self, codename: &str, distro_name: &str) -> Option<u64> {
+        self.distros
+            .get(codename)?
+            .iter()
+            .find(|d| d.name.eq_ignore_ascii_case(distro_name))?
+            .space_needed()
+    }
+
+    /// Get all supported device codenames
+    pub fn all_codenames(&self) -> Vec<String> {
+        self.devices.keys().cloned().collect()
+    }
+
+    /// Get all supported devices
+    pub fn all_devices(&self) -> Vec<Device> {
+        self.devices.values().cloned().collect()
+    }
+
+    /// Supported devices, leaving out experimental ones unless
+    /// `include_experimental`
+    pub fn all_devices_filtered(&self, include_experimental: bool) -> Vec<Device> {
+        self.devices
+            .values()
+            .filter(|device| include_experimental || !device.experimental)
+            .cloned()
+            .collect()
+    }
+
+    /// How many devices are supported
+    pub fn device_count(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// Supported devices by manufacturer, each group sorted by name. Makers
+    /// spelled differently across configs ("F(x)tec", "Fxtec") share a
+    /// group, named after the first device's spelling.
+    pub fn devices_by_maker(&self) -> BTreeMap<String, Vec<Device>> {
+        self.devices_by_maker_filtered(true, None)
+    }
+
+    /// [`devices_by_maker`](Self::devices_by_maker), leaving out
+    /// experimental devices unless `include_experimental`. The `connected`
+    /// device is always kept so the phone on the cable can't vanish from
+    /// the list.
+    pub fn devices_by_maker_filtered(
+        &self,
+        include_experimental: bool,
+        connected: Option<&str>,
+    ) -> BTreeMap<String, Vec<Device>> {
+        let mut by_key: HashMap<String, Vec<Device>> = HashMap::new();
+        for device in self.devices.values() {
+            if !Self::listed(device, include_experimental, connected) {
+                continue;
+            }
+            by_key.entry(maker_key(&device.maker)).or_default().push(device.clone());
+        }
+
+        by_key
+            .into_values()
+            .map(|mut devices| {
+                devices.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.codename.cmp(&b.codename)));
+                (devices[0].maker.clone(), devices)
+            })
+            .collect()
+    }
+}
+
+impl Default for DeviceDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A [`Device`] from its info.yml, falling back to the built-in record for
+/// anything the file leaves out
+fn device_from_info(
+    info: crate::models::device_info::DeviceInfoData,
+    maker_dir: &str,
+    builtin: Option<Device>,
+) -> Device {
+    let maker = info
+        .maker
+        .or_else(|| builtin.as_ref().map(|d| d.maker.clone()))
+        .unwrap_or_else(|| maker_from_dir(maker_dir));
+    let mut device = builtin.unwrap_or_else(|| Device {
+        codename: info.codename.clone(),
+        name: info.name.clone(),
+        maker: maker.clone(),
+        experimental: false,
+        battery_min: 50,
+        warnings: vec![],
+        aliases: vec![],
+        variants: vec![],
+        is_locked: None,
+        serial: None,
+        android_version: None,
+        build_id: None,
+        battery_level: None,
+    });
+    device.name = info.name;
+    device.maker = maker;
+    if let Some(experimental) = info.experimental {
+        device.experimental = experimental;
+    }
+    if let Some(battery_min) = info.battery_min {
+        device.battery_min = battery_min;
+    }
+    if !info.aliases.is_empty() {
+        device.aliases = info.aliases;
+    }
+    if !info.variants.is_empty() {
+        device.variants = info.variants;
+    }
+    device
+}
+
+/// Display name for a maker directory; the reverse of the lowercasing the
+/// pages do to find it
+fn maker_from_dir(dir: &str) -> String {
+    match dir {
+        "asus" => "ASUS".to_string(),
+        "fxtec" => "F(x)tec".to_string(),
+        "oneplus" => "OnePlus".to_string(),
+        "shift" => "SHIFT".to_string(),
+        _ => {
+            let mut chars = dir.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+fn flatten_distros(nodes: Vec<DistroTreeNode>, out: &mut Vec<Distro>) {
+    for node in nodes {
+        match node {
+            DistroTreeNode::Group { children, .. } => flatten_distros(children, out),
+            DistroTreeNode::Item(distro) => out.push(distro),
+        }
+    }
+}
+
+/// A maker name with case and punctuation dropped, so spellings of the
+/// same manufacturer compare equal. It's also the name of the maker's
+/// directory under `devices/`.
+pub(crate) fn maker_key(maker: &str) -> String {
+    maker.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Lower case, with `_`, `-` and runs of whitespace as single spaces
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How well normalized `query` matches normalized `field`, higher being
+/// better, or `None` for no match
+fn match_score(query: &str, field: &str) -> Option<u32> {
+    if field == query {
+        return Some(100);
+    }
+    // Separators are optional between words: "fp 4" and "fp4" are the same
+    let compact = |s: &str| s.replace(' ', "");
+    let (query_compact, field_compact) = (compact(query), compact(field));
+    if field_compact == query_compact {
+        return Some(90);
+    }
+    if field.starts_with(query) || field_compact.starts_with(&query_compact) {
+        return Some(80);
+    }
+    if field.split(' ').any(|word| word.starts_with(query)) {
+        return Some(70);
+    }
+    if field_compact.contains(&query_compact) {
+        return Some(60);
+    }
+    if query.split(' ').all(|word| field.contains(word)) {
+        return Some(50);
+    }
+    // Every letter in order, fewer skipped letters scoring higher
+    let mut skipped = 0;
+    let mut field_chars = field_compact.chars();
+    for wanted in query_compact.chars() {
+        loop {
+            let c = field_chars.next()?;
+            if c == wanted {
+                break;
+            }
+            skipped += 1;
+        }
+    }
+    Some(40u32.saturating_sub(skipped).max(1))
+}
@@ -61,6 +61,15 @@ fn default_battery_min() -> u8 {
 }
 
 impl Device {
+    /// How to answer an on-device prompt (unlock confirmation, bootloader menus)
+    /// with the hardware buttons, which differs by manufacturer.
+    pub fn button_hint(&self) -> &'static str {
+        match self.maker.to_lowercase().as_str() {
+            "samsung" | "volla" => "Press Volume Up to confirm, Volume Down to cancel",
+            _ => "Use the Volume keys to select, Power to confirm",
+        }
+    }
+
     /// Check if a codename matches this device (including aliases)
     pub fn matches_codename(&self, codename: &str) -> bool {
         if self.codename == codename {
@@ -0,0 +1,175 @@
+// Device Info Model
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceInfo {
+    pub device: DeviceInfoData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceInfoData {
+    pub name: String,
+    pub codename: String,
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub specs: DeviceSpecs,
+    #[serde(default)]
+    pub display: DisplaySpecs,
+    #[serde(default)]
+    pub connectivity: ConnectivitySpecs,
+    #[serde(default)]
+    pub cameras: Vec<CameraSpecs>,
+    /// Partitions holding IMEI, calibration or DRM data that should be
+    /// copied off the device before wiping
+    #[serde(default)]
+    pub backup_partitions: Vec<String>,
+    /// Device-specific gotchas shown before installing (cables, firmware base, ...)
+    #[serde(default)]
+    pub known_issues: Vec<String>,
+    /// What genuine units report about themselves, for spotting clones
+    #[serde(default)]
+    pub identity: Option<HardwareIdentity>,
+    /// Manufacturer as shown to users; defaults to one derived from the
+    /// directory the device sits in
+    #[serde(default)]
+    pub maker: Option<String>,
+    #[serde(default)]
+    pub experimental: Option<bool>,
+    /// Minimum battery level required before installation
+    #[serde(default)]
+    pub battery_min: Option<u8>,
+    /// Other codenames the device reports
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Retail names sold under the same codename
+    #[serde(default)]
+    pub variants: Vec<String>,
+}
+
+/// Identifiers a genuine unit reports over fastboot or adb. Every list is
+/// optional; an empty one isn't checked.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HardwareIdentity {
+    /// `getvar product` / `ro.product.device` values
+    #[serde(default)]
+    pub products: Vec<String>,
+    /// `getvar hw-revision` / `ro.revision` values retail units ship with
+    #[serde(default)]
+    pub revisions: Vec<String>,
+    /// Lengths of a genuine serial number
+    #[serde(default)]
+    pub serial_lengths: Vec<usize>,
+    /// Genuine serial numbers are hexadecimal
+    #[serde(default)]
+    pub serial_hex: bool,
+}
+
+// Every spec is optional so a partially filled info.yml still loads;
+// pages skip the rows whose value is missing.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeviceSpecs {
+    pub soc: Option<String>,
+    pub ram: Option<String>,
+    pub cpu: Option<String>,
+    pub gpu: Option<String>,
+    pub storage: Option<String>,
+    pub battery: Option<String>,
+    pub arch: Option<String>,
+    pub dimensions: Option<Dimensions>,
+}
+
+impl DeviceSpecs {
+    /// Title and value of each spec the device page lists, leaving out
+    /// those the info.yml doesn't give or leaves empty
+    pub fn rows(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("SoC", self.soc.as_deref()),
+            ("CPU", self.cpu.as_deref()),
+            ("GPU", self.gpu.as_deref()),
+            ("RAM", self.ram.as_deref()),
+            ("Storage", self.storage.as_deref()),
+            ("Battery", self.battery.as_deref()),
+            ("Architecture", self.arch.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(title, value)| Some((title, value.filter(|v| !v.is_empty())?)))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Dimensions {
+    pub height: String,
+    pub width: String,
+    pub depth: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DisplaySpecs {
+    pub size: Option<String>,
+    pub resolution: Option<String>,
+    pub density: Option<String>,
+    pub panel_type: Option<String>,
+    pub refresh_rate: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConnectivitySpecs {
+    #[serde(default)]
+    pub network: Vec<String>,
+    pub bluetooth: Option<String>,
+    pub wifi: Option<String>,
+    #[serde(default)]
+    pub peripherals: Vec<String>,
+    #[serde(default)]
+    pub sensors: Vec<String>,
+    #[serde(default)]
+    pub location: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CameraSpecs {
+    pub label: String,
+    pub resolution: String,
+    pub features: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_info_yml_lists_only_what_it_gives() {
+        let yaml = "\
+device:
+  name: Example Phone
+  codename: example
+  specs:
+    soc: Snapdragon 670
+    ram: 4 GB
+";
+        let info: DeviceInfo = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            info.device.specs.rows(),
+            vec![("SoC", "Snapdragon 670"), ("RAM", "4 GB")]
+        );
+        assert!(info.device.display.size.is_none());
+        assert!(info.device.cameras.is_empty());
+    }
+
+    #[test]
+    fn empty_specs_are_skipped() {
+        let yaml = "\
+device:
+  name: Example Phone
+  codename: example
+  specs:
+    soc: ''
+    cpu: Octa-core
+";
+        let info: DeviceInfo = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(info.device.specs.rows(), vec![("CPU", "Octa-core")]);
+    }
+}
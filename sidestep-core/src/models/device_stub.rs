@@ -0,0 +1,100 @@
+// Device description skeleton for unsupported devices
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// What could be read from a device that has no database entry, used to
+/// pre-fill an `info.yml` for a support request.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceStub {
+    pub codename: String,
+    pub maker: Option<String>,
+    pub model: Option<String>,
+    pub android_version: Option<String>,
+    pub build_id: Option<String>,
+    pub soc: Option<String>,
+    pub arch: Option<String>,
+    pub ram: Option<String>,
+    pub resolution: Option<String>,
+}
+
+impl DeviceStub {
+    /// Detected values as (label, value) pairs, skipping the unknown ones.
+    pub fn detected(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Codename", Some(self.codename.as_str())),
+            ("Manufacturer", self.maker.as_deref()),
+            ("Model", self.model.as_deref()),
+            ("Android Version", self.android_version.as_deref()),
+            ("Build", self.build_id.as_deref()),
+            ("SoC", self.soc.as_deref()),
+            ("Architecture", self.arch.as_deref()),
+            ("RAM", self.ram.as_deref()),
+            ("Resolution", self.resolution.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| value.filter(|v| !v.is_empty()).map(|v| (label, v)))
+        .collect()
+    }
+
+    /// Where the file belongs in the repository, e.g. `data/devices/google/sargo/info.yml`.
+    pub fn repo_path(&self) -> String {
+        let maker = self
+            .maker
+            .as_deref()
+            .map(|m| {
+                m.chars()
+                    .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                    .collect::<String>()
+                    .to_lowercase()
+            })
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| "<manufacturer>".to_string());
+        format!("data/devices/{}/{}/info.yml", maker, self.codename)
+    }
+
+    /// An `info.yml` in the layout of the bundled ones, with detected values
+    /// filled in and everything else left as a commented placeholder.
+    pub fn to_info_yml(&self) -> String {
+        let name = match (self.maker.as_deref(), self.model.as_deref()) {
+            (Some(maker), Some(model)) if !model.starts_with(maker) => format!("{} {}", maker, model),
+            (_, Some(model)) => model.to_string(),
+            (Some(maker), None) => format!("{} {}", maker, self.codename),
+            (None, None) => self.codename.clone(),
+        };
+
+        let mut yml = String::new();
+        yml.push_str(&format!("# {}\n", self.repo_path()));
+        if let Some(ref android) = self.android_version {
+            let build = self.build_id.as_deref().unwrap_or("unknown build");
+            yml.push_str(&format!("# Detected running Android {} ({})\n", android, build));
+        }
+        yml.push_str("device:\n");
+        yml.push_str(&format!("  name: {}\n", quote(&name)));
+        yml.push_str(&format!("  codename: {}\n", quote(&self.codename)));
+        yml.push_str("  # release_date: \"YYYY-MM-DD\"\n");
+        yml.push_str("  specs:\n");
+        push_field(&mut yml, "    ", "soc", self.soc.as_deref());
+        push_field(&mut yml, "    ", "ram", self.ram.as_deref());
+        push_field(&mut yml, "    ", "arch", self.arch.as_deref());
+        push_field(&mut yml, "    ", "cpu", None);
+        push_field(&mut yml, "    ", "gpu", None);
+        push_field(&mut yml, "    ", "storage", None);
+        push_field(&mut yml, "    ", "battery", None);
+        yml.push_str("  display:\n");
+        push_field(&mut yml, "    ", "resolution", self.resolution.as_deref());
+        push_field(&mut yml, "    ", "size", None);
+        push_field(&mut yml, "    ", "panel_type", None);
+        yml.push_str("  # backup_partitions: [persist, modemst1, modemst2]\n");
+        yml
+    }
+}
+
+fn push_field(yml: &mut String, indent: &str, key: &str, value: Option<&str>) {
+    match value.filter(|v| !v.is_empty()) {
+        Some(value) => yml.push_str(&format!("{}{}: {}\n", indent, key, quote(value))),
+        None => yml.push_str(&format!("{}# {}: \"\"\n", indent, key)),
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -1,6 +1,7 @@
 // Distro model
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use crate::flashing::{ChecksumAlgorithm, Compression};
 use serde::Deserialize;
 
 /// A partition image to flash
@@ -12,9 +13,18 @@ pub struct PartitionImage {
     /// Relative path or URL to the image file
     pub image: String,
     
-    /// Whether this partition should be erased before flashing
+    /// Whether this partition should be erased before flashing.
+    ///
+    /// The erase only happens once the image is on disk, immediately before
+    /// its flash; the pair is retried together.
     #[serde(default)]
     pub erase_first: bool,
+
+    /// Other names the same partition goes by on some variants of the
+    /// device (eMMC vs UFS builds, for example), tried in order when the
+    /// primary name isn't found
+    #[serde(default)]
+    pub aliases: Vec<String>,
 }
 
 /// A mobile Linux distribution available for a device
@@ -37,6 +47,11 @@ pub struct Distro {
     #[serde(default)]
     pub checksum_url: Option<String>,
     
+    /// Hash function the checksum file uses, when neither its name nor
+    /// the digests' length gives it away
+    #[serde(default)]
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    
     /// List of partition images to flash
     pub partitions: Vec<PartitionImage>,
     
@@ -44,6 +59,11 @@ pub struct Distro {
     #[serde(default)]
     pub homepage: Option<String>,
     
+    /// Alternate base URLs serving the same files, tried in order when
+    /// `download_base_url` fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    
     /// Estimated download size in bytes
     #[serde(default)]
     pub download_size_bytes: Option<u64>,
@@ -57,6 +77,10 @@ pub struct Distro {
     pub post_install_notes: Option<String>,
 }
 
+/// How many times their size compressed images are assumed to need once
+/// unpacked next to the download; sparse system images shrink a lot
+const UNPACK_HEADROOM: u64 = 3;
+
 fn default_requires_unlock() -> bool {
     true
 }
@@ -65,16 +89,26 @@ impl Distro {
     /// Get formatted download size string
     pub fn download_size_string(&self) -> String {
         match self.download_size_bytes {
-            Some(bytes) if bytes >= 1_000_000_000 => {
-                format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
-            }
-            Some(bytes) if bytes >= 1_000_000 => {
-                format!("{:.0} MB", bytes as f64 / 1_000_000.0)
-            }
-            Some(bytes) => format!("{} KB", bytes / 1000),
+            Some(bytes) => crate::utils::format::format_size(bytes),
             None => "Unknown".to_string(),
         }
     }
+
+    /// Disk space an install needs in the download directory: the images
+    /// plus room to unpack the compressed ones. `None` when the download
+    /// size isn't known.
+    pub fn space_needed(&self) -> Option<u64> {
+        let download = self.download_size_bytes?;
+        let compressed = self
+            .partitions
+            .iter()
+            .any(|p| Compression::from_name(&p.image) != Compression::None);
+        Some(if compressed {
+            download + download * UNPACK_HEADROOM
+        } else {
+            download
+        })
+    }
 }
 
 /// A node in the distribution selection tree
@@ -0,0 +1,251 @@
+// Distro Config Models
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::ChecksumAlgorithm;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceDistroConfig {
+    pub device_codename: String,
+    pub last_updated: String,
+    pub available_distros: Vec<DistroConfig>,
+}
+
+/// Every distro Sidestep can install, by the id distros.yml uses for it.
+/// A device listing all of them is fully supported.
+pub const KNOWN_DISTROS: &[(&str, &str)] = &[
+    ("ubports", "Ubuntu Touch"),
+    ("postmarketos", "postmarketOS"),
+    ("droidian", "Droidian"),
+    ("mobian", "Mobian"),
+    ("lineageos", "LineageOS"),
+    ("eos", "/e/OS"),
+];
+
+/// Whether a device can get one of [`KNOWN_DISTROS`], and why not
+#[derive(Debug, Clone)]
+pub struct DistroAvailability {
+    pub id: String,
+    pub name: String,
+    /// `None` when the distro can be installed
+    pub unavailable_reason: Option<String>,
+}
+
+impl DistroAvailability {
+    pub fn is_available(&self) -> bool {
+        self.unavailable_reason.is_none()
+    }
+}
+
+impl DeviceDistroConfig {
+    /// Each known distro in [`KNOWN_DISTROS`] order, followed by any other
+    /// distro the file lists
+    pub fn availability(&self) -> Vec<DistroAvailability> {
+        let mut result: Vec<DistroAvailability> = KNOWN_DISTROS
+            .iter()
+            .map(|(id, name)| {
+                let listed = self
+                    .available_distros
+                    .iter()
+                    .find(|d| d.id == *id || (*id == "ubports" && d.id == "ubuntutouch"));
+                match listed {
+                    Some(distro) => distro.availability(),
+                    None => DistroAvailability {
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        unavailable_reason: Some(format!("No {} image for this device", name)),
+                    },
+                }
+            })
+            .collect();
+        for distro in &self.available_distros {
+            // Ubuntu Touch, listed under its older id, is already covered
+            let listed = distro.id == "ubuntutouch" || result.iter().any(|a| a.id == distro.id);
+            if !listed {
+                result.push(distro.availability());
+            }
+        }
+        result
+    }
+
+    /// True when every known distro can be installed
+    pub fn is_fully_supported(&self) -> bool {
+        self.availability()
+            .iter()
+            .take(KNOWN_DISTROS.len())
+            .all(DistroAvailability::is_available)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DistroConfig {
+    pub id: String,
+    pub name: String,
+    pub developer: String,
+    pub discovery_strategy: String,
+    pub base_url: Option<String>,
+    /// Other hosts serving the same tree as `base_url`, offered when a
+    /// download from it fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Hash function behind the digests this distro publishes, for when
+    /// their length and file names don't tell (BLAKE3 vs SHA256)
+    #[serde(default)]
+    pub checksum_algorithm: Option<ChecksumAlgorithm>,
+    pub api_root: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+    pub interfaces: Option<Vec<InterfaceConfig>>,
+    pub image_pattern: Option<String>,
+    pub flash_method: String,
+    pub firmware_requirement: Option<String>,
+    pub chipset: Option<String>,
+    pub device_model: Option<String>,
+    pub compatibility: Option<CompatibilityInfo>,
+    /// Present when the distro can be tried with `fastboot boot` before installing
+    pub temporary_boot: Option<TemporaryBootConfig>,
+    /// adb shell commands offered on the success page once the new OS is up.
+    /// Only entries on `flashing::post_install::ALLOWED_COMMANDS` are ever run.
+    #[serde(default)]
+    pub post_install_commands: Vec<String>,
+    /// Set when a build is published as one archive holding several images
+    /// rather than one compressed image per partition
+    #[serde(default)]
+    pub archive_partitions: Vec<ArchiveEntry>,
+}
+
+impl DistroConfig {
+    /// `base_url` followed by its mirrors: every host serving this tree
+    pub fn image_hosts(&self) -> Vec<String> {
+        self.base_url.iter().chain(&self.mirrors).cloned().collect()
+    }
+
+    fn availability(&self) -> DistroAvailability {
+        let unavailable_reason = if !crate::flashing::has_backend(&self.id) {
+            Some(format!("Sidestep can't install {} yet", self.name))
+        } else if self.channels.is_empty() && self.interfaces.as_ref().is_none_or(|i| i.is_empty()) {
+            Some(format!("No {} build has been published for this device yet", self.name))
+        } else {
+            None
+        };
+        DistroAvailability {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            unavailable_reason,
+        }
+    }
+}
+
+/// Which file inside a combined archive goes to which partition
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArchiveEntry {
+    /// File name inside the archive; `*` and `?` match like in `artifact_match`
+    pub file: String,
+    pub partition: String,
+    /// Flash it when the archive has it, but don't fail when it doesn't
+    #[serde(default)]
+    pub optional: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemporaryBootConfig {
+    pub url: String,
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct CompatibilityInfo {
+    #[serde(default)]
+    pub working: Vec<String>,
+    #[serde(default)]
+    pub partial: Vec<String>,
+    #[serde(default)]
+    pub not_working: Vec<String>,
+    #[serde(default)]
+    pub untested: Vec<String>,
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelConfig {
+    pub id: String,
+    pub label: String,
+    pub path: Option<String>,
+    pub artifact_match: Option<String>,
+    pub release_url: Option<String>,
+    /// GitHub `owner/repo` to pick `artifact_match` assets from, as an
+    /// alternative to a `release_url` API address
+    pub github_repo: Option<String>,
+    /// Also consider GitHub prereleases when looking for an asset
+    #[serde(default)]
+    pub prereleases: bool,
+}
+
+impl ChannelConfig {
+    /// Check that the fields the distro's installer needs are present.
+    ///
+    /// Returns the names of any missing fields so the UI can explain why a
+    /// channel cannot be picked.
+    pub fn validate(&self, distro_id: &str) -> Result<(), Vec<String>> {
+        let required = match distro_id {
+            "ubuntutouch" | "ubports" => vec![("path", self.path.is_some())],
+            "droidian" => vec![
+                ("release_url", self.release_url.is_some() || self.github_repo.is_some()),
+                ("artifact_match", self.artifact_match.is_some()),
+            ],
+            "lineageos" => vec![("release_url", self.release_url.is_some())],
+            _ => Vec::new(),
+        };
+
+        let missing: Vec<String> = required
+            .iter()
+            .filter(|(_, present)| !present)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InterfaceConfig {
+    pub id: String,
+    pub label: String,
+    /// One-line summary; well-known interfaces fall back to a built-in one
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Approximate image download size for this variant
+    #[serde(default)]
+    pub download_size_bytes: Option<u64>,
+}
+
+impl InterfaceConfig {
+    pub fn description(&self) -> Option<&str> {
+        if let Some(ref description) = self.description {
+            return Some(description);
+        }
+        match self.id.as_str() {
+            "phosh" => Some("Touch-first GTK shell from Purism, the most widely tested"),
+            "gnome-mobile" => Some("GNOME Shell adapted for phones"),
+            "plasma-mobile" => Some("KDE's mobile shell with Qt apps"),
+            "sxmo-de-sway" => Some("Minimal, gesture and script driven; for tinkerers"),
+            "console" => Some("No graphical interface, terminal only"),
+            _ => None,
+        }
+    }
+
+    /// "Description • 1.2 GB" from whatever metadata is available.
+    pub fn summary(&self) -> String {
+        let size = self.download_size_bytes.map(crate::utils::format::format_size);
+        [self.description().map(str::to_string), size]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" \u{2022} ")
+    }
+}
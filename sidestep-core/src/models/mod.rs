@@ -3,14 +3,17 @@
 
 pub mod device;
 pub mod device_info;
+pub mod device_stub;
 pub mod unlocking_step;
 pub mod distro;
 pub mod distro_config;
 pub mod installer;
 pub mod database;
 pub mod system_image;
+pub mod samsung;
 
 pub use device::Device;
+pub use device_stub::DeviceStub;
 pub use unlocking_step::{UnlockingStep, StepType};
 pub use distro::{Distro, PartitionImage, DistroTreeNode};
 pub use database::DeviceDatabase;
@@ -0,0 +1,93 @@
+// Samsung download-mode flashing model
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// The four archive slots of a Samsung firmware package, as Odin labels them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdinSection {
+    /// Bootloader: `sboot.bin`, `param.bin`, `cm.bin`
+    Bl,
+    /// PDA / Android: kernel, recovery and system
+    Ap,
+    /// Modem firmware
+    Cp,
+    /// Consumer software customisation: carrier data and the cache image
+    Csc,
+}
+
+impl OdinSection {
+    /// Which section a firmware archive belongs to, from the prefix Samsung
+    /// gives its files (`AP_G930FXXU8ETI2_….tar.md5`). `HOME_CSC` counts as
+    /// CSC.
+    pub fn from_archive_name(name: &str) -> Option<Self> {
+        let name = name.rsplit('/').next().unwrap_or(name);
+        let prefix = name.split('_').next()?;
+        match prefix {
+            "BL" => Some(Self::Bl),
+            "AP" => Some(Self::Ap),
+            "CP" => Some(Self::Cp),
+            "CSC" | "HOME" => Some(Self::Csc),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Bl => "BL",
+            Self::Ap => "AP",
+            Self::Cp => "CP",
+            Self::Csc => "CSC",
+        }
+    }
+}
+
+/// One image inside an Odin archive and the PIT partition Heimdall writes it to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeimdallImage {
+    pub section: OdinSection,
+    pub file: &'static str,
+    pub partition: &'static str,
+}
+
+/// Image-to-partition table for the Exynos 8890 Galaxy S7 family
+/// (herolte, hero2lte). Partition names are from the devices' PIT.
+pub const EXYNOS_8890_IMAGES: &[HeimdallImage] = &[
+    HeimdallImage { section: OdinSection::Bl, file: "sboot.bin", partition: "BOOTLOADER" },
+    HeimdallImage { section: OdinSection::Bl, file: "param.bin", partition: "PARAM" },
+    HeimdallImage { section: OdinSection::Bl, file: "cm.bin", partition: "CM" },
+    HeimdallImage { section: OdinSection::Ap, file: "boot.img", partition: "BOOT" },
+    HeimdallImage { section: OdinSection::Ap, file: "recovery.img", partition: "RECOVERY" },
+    HeimdallImage { section: OdinSection::Ap, file: "system.img", partition: "SYSTEM" },
+    HeimdallImage { section: OdinSection::Cp, file: "modem.bin", partition: "RADIO" },
+    HeimdallImage { section: OdinSection::Csc, file: "cache.img", partition: "CACHE" },
+    HeimdallImage { section: OdinSection::Csc, file: "hidden.img", partition: "HIDDEN" },
+];
+
+/// The image table for a device flashed with Heimdall, by codename
+pub fn images_for(codename: &str) -> Option<&'static [HeimdallImage]> {
+    match codename {
+        "herolte" | "hero2lte" => Some(EXYNOS_8890_IMAGES),
+        _ => None,
+    }
+}
+
+/// The PIT partition for an image file, if the table knows it.
+pub fn partition_for(images: &[HeimdallImage], file: &str) -> Option<&'static str> {
+    let file = file.rsplit('/').next().unwrap_or(file);
+    images.iter().find(|i| i.file == file).map(|i| i.partition)
+}
+
+/// Arguments for a single `heimdall flash` run writing `files` as
+/// `(partition, path)` pairs.
+///
+/// `--no-reboot` is always passed: a Samsung device left to reboot into
+/// stock Android straight after a recovery flash restores the stock
+/// recovery, so the user has to boot into recovery with the key combo.
+pub fn heimdall_flash_args(files: &[(&str, &str)]) -> Vec<String> {
+    let mut args = vec!["flash".to_string()];
+    for (partition, path) in files {
+        args.push(format!("--{}", partition));
+        args.push(path.to_string());
+    }
+    args.push("--no-reboot".to_string());
+    args
+}
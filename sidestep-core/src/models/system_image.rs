@@ -17,6 +17,8 @@ pub struct SystemImageEntry {
     pub image_type: String,
     pub version: u32,
     pub files: Vec<SystemImageFile>,
+    /// Component versions, e.g. `ubports=20240703-41234,device=...,version=3`
+    pub version_detail: Option<String>,
 }
 
 /// A file within a system-image entry
@@ -42,4 +44,30 @@ impl SystemImageIndex {
             .filter(|e| e.image_type == "full")
             .max_by_key(|e| e.version)
     }
+
+    /// The "full" image entry with exactly `version`
+    pub fn full(&self, version: u32) -> Option<&SystemImageEntry> {
+        self.images
+            .iter()
+            .find(|e| e.image_type == "full" && e.version == version)
+    }
+
+    /// Every "full" image entry still on the server, newest first
+    pub fn full_images(&self) -> Vec<&SystemImageEntry> {
+        let mut images: Vec<&SystemImageEntry> =
+            self.images.iter().filter(|e| e.image_type == "full").collect();
+        images.sort_by_key(|e| std::cmp::Reverse(e.version));
+        images
+    }
+}
+
+impl SystemImageEntry {
+    /// The build date from the `ubports=YYYYMMDD-...` part of
+    /// `version_detail`, as `YYYY-MM-DD`
+    pub fn build_date(&self) -> Option<String> {
+        let detail = self.version_detail.as_deref()?;
+        let build = detail.split(',').find_map(|part| part.strip_prefix("ubports="))?;
+        let date = build.get(0..8).filter(|d| d.bytes().all(|b| b.is_ascii_digit()))?;
+        Some(format!("{}-{}-{}", &date[0..4], &date[4..6], &date[6..8]))
+    }
 }
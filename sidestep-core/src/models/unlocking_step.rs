@@ -11,6 +11,8 @@ pub enum StepType {
     Manual,
     /// Automated step that runs a command
     Automated,
+    /// Samsung download-mode flash; `command` holds the `heimdall` invocation
+    Heimdall,
 }
 
 /// A step in the bootloader unlocking process
@@ -29,7 +31,7 @@ pub struct UnlockingStep {
     #[serde(rename = "type")]
     pub step_type: StepType,
     
-    /// Command to run for automated steps (e.g., "adb reboot bootloader")
+    /// Command to run for automated and Heimdall steps (e.g., "adb reboot bootloader")
     #[serde(default)]
     pub command: Option<String>,
     
@@ -54,4 +56,21 @@ impl UnlockingStep {
     pub fn is_automated(&self) -> bool {
         self.step_type == StepType::Automated
     }
+
+    /// Step that writes to the device with Heimdall from download mode
+    pub fn is_heimdall(&self) -> bool {
+        self.step_type == StepType::Heimdall
+    }
+
+    /// Manual step asking the user to flip "OEM unlocking" in developer options
+    pub fn is_oem_unlock_toggle(&self) -> bool {
+        self.is_manual() && self.title.to_lowercase().contains("oem unlocking")
+    }
+
+    /// Automated step that issues the bootloader unlock command
+    pub fn is_unlock_command(&self) -> bool {
+        self.command.as_deref().is_some_and(|c| {
+            c.starts_with("fastboot oem unlock") || c == "fastboot flashing unlock"
+        })
+    }
 }
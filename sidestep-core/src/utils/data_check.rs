@@ -0,0 +1,182 @@
+// Data directory integrity check
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::config;
+use crate::flashing;
+use crate::utils::data_update;
+use crate::utils::yaml_parser::YamlParser;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Where the device YAMLs are looked for, in order. A downloaded update
+/// comes first so it overrides the bundled data.
+pub fn devices_dir_candidates() -> Vec<PathBuf> {
+    vec![
+        data_update::user_devices_dir(),
+        PathBuf::from(config::PKGDATADIR).join("devices"),
+        PathBuf::from("/app/share/sidestep/devices"),
+        PathBuf::from("data/devices"),
+        PathBuf::from("devices"),
+    ]
+}
+
+/// What [`check_data_dir`] found
+#[derive(Debug, Clone, Default)]
+pub struct DataReport {
+    /// The devices directory in use, if any exists
+    pub devices_dir: Option<PathBuf>,
+    /// Device directories whose YAML files all parsed
+    pub devices_ok: usize,
+    /// Distro ids referenced by the device configs, for icon lookups
+    pub distro_ids: BTreeSet<String>,
+    /// Problems that leave pages empty or broken
+    pub errors: Vec<String>,
+    /// Problems that only degrade them
+    pub warnings: Vec<String>,
+}
+
+impl DataReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Write the findings to the log: one line when everything is fine,
+    /// otherwise every problem followed by a count.
+    pub fn log_summary(&self) {
+        for error in &self.errors {
+            log::error!("Data check: {}", error);
+        }
+        for warning in &self.warnings {
+            log::warn!("Data check: {}", warning);
+        }
+
+        let dir = self
+            .devices_dir
+            .as_ref()
+            .map(|d| d.display().to_string())
+            .unwrap_or_else(|| "(missing)".to_string());
+        if self.errors.is_empty() && self.warnings.is_empty() {
+            log::info!("Data check: {} devices OK in {}", self.devices_ok, dir);
+        } else {
+            log::warn!(
+                "Data check: {} devices OK in {}, {} errors, {} warnings",
+                self.devices_ok,
+                dir,
+                self.errors.len(),
+                self.warnings.len()
+            );
+        }
+    }
+}
+
+/// Confirm the devices directory exists and that every device's
+/// `info.yml`, `distros.yml` and any `steps.yml` parse.
+pub fn check_data_dir() -> DataReport {
+    match devices_dir_candidates().into_iter().find(|p| p.is_dir()) {
+        Some(dir) => check_devices_dir(&dir),
+        None => DataReport {
+            errors: vec![
+                "No devices directory found; device details and distro lists will be empty"
+                    .to_string(),
+            ],
+            ..Default::default()
+        },
+    }
+}
+
+/// Parse every device config under `dir`, a `devices/` tree.
+pub fn check_devices_dir(dir: &Path) -> DataReport {
+    let mut report = DataReport::default();
+
+    let parser = YamlParser::new(dir);
+    for (manufacturer, codename) in device_dirs(dir) {
+        let name = format!("{}/{}", manufacturer, codename);
+        let mut ok = true;
+
+        match parser.parse_device_config(&manufacturer, &codename) {
+            Ok(config) => {
+                if config.available_distros.is_empty() {
+                    report.warnings.push(format!("{} lists no distros", name));
+                }
+                for distro in &config.available_distros {
+                    if let Some(problem) = missing_backend(&distro.id) {
+                        report.errors.push(format!("{}: {}", name, problem));
+                    }
+                }
+                report
+                    .distro_ids
+                    .extend(config.available_distros.into_iter().map(|d| d.id));
+            }
+            Err(e) => {
+                report.errors.push(format!("{}: {:#}", name, e));
+                ok = false;
+            }
+        }
+
+        if let Err(e) = parser.parse_device_info(&manufacturer, &codename) {
+            report.warnings.push(format!("{}: {:#}", name, e));
+            ok = false;
+        }
+
+        if let Err(e) = parser.parse_unlocking_steps(&manufacturer, &codename) {
+            report.warnings.push(format!("{}: {:#}", name, e));
+            ok = false;
+        }
+
+        if ok {
+            report.devices_ok += 1;
+        }
+    }
+
+    if report.devices_ok == 0 && report.errors.is_empty() {
+        report.errors.push(format!("{} contains no device configs", dir.display()));
+    }
+    report.devices_dir = Some(dir.to_path_buf());
+    report
+}
+
+/// Why `distro_id` can't be installed, or `None` when an installer
+/// handles it. Ids that match a backend but for case are called out, since
+/// that's the usual typo.
+fn missing_backend(distro_id: &str) -> Option<String> {
+    if flashing::has_backend(distro_id) {
+        return None;
+    }
+    Some(
+        match flashing::INSTALLER_BACKENDS
+            .iter()
+            .find(|id| id.eq_ignore_ascii_case(distro_id))
+        {
+            Some(id) => format!("distro \"{}\" has no installer; did you mean \"{}\"?", distro_id, id),
+            None => format!(
+                "distro \"{}\" has no installer; known ids are {}",
+                distro_id,
+                flashing::INSTALLER_BACKENDS.join(", ")
+            ),
+        },
+    )
+}
+
+/// `(manufacturer, codename)` for every `<manufacturer>/<codename>/` directory
+pub(crate) fn device_dirs(dir: &Path) -> Vec<(String, String)> {
+    let subdirs = |path: &Path| -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        names
+    };
+
+    subdirs(dir)
+        .into_iter()
+        .flat_map(|manufacturer| {
+            subdirs(&dir.join(&manufacturer))
+                .into_iter()
+                .map(move |codename| (manufacturer.clone(), codename))
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+// Device data updates
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::{ChecksumVerifier, ImageDownloader};
+use crate::utils::data_check;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Holds the digest of the bundle a devices directory was unpacked from
+const STAMP_NAME: &str = ".bundle-sha256";
+
+/// Where downloaded device data is kept. `SIDESTEP_DATA_DIR` overrides it,
+/// as it does for [`DeviceDatabase`](crate::models::DeviceDatabase).
+pub fn user_devices_dir() -> PathBuf {
+    std::env::var("SIDESTEP_DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("/tmp"))
+                .join("sidestep")
+                .join("devices")
+        })
+}
+
+/// The data the last update replaced, kept for [`rollback`]
+pub fn previous_dir() -> PathBuf {
+    sibling_of_devices_dir("previous")
+}
+
+fn staging_dir() -> PathBuf {
+    sibling_of_devices_dir("new")
+}
+
+fn sibling_of_devices_dir(suffix: &str) -> PathBuf {
+    let dir = user_devices_dir();
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "devices".to_string());
+    dir.with_file_name(format!("{}.{}", name, suffix))
+}
+
+/// What [`update_from`] did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataUpdate {
+    /// The data in use already came from the offered bundle
+    UpToDate,
+    /// A new bundle was verified and put in place
+    Updated { devices: usize },
+}
+
+/// Fetch the device data bundle at `url` and install it over the current
+/// data when it differs.
+///
+/// The bundle is a `.tar.gz` of a `devices/` tree, published with a
+/// `<url>.sha256` file in `sha256sum` format. It's applied only when the
+/// download matches that digest and every device config in it parses; the
+/// data it replaces is moved to [`previous_dir`].
+pub async fn update_from(url: &str) -> Result<DataUpdate> {
+    let filename = url
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("devices.tar.gz")
+        .to_string();
+    let cache_dir = dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("sidestep");
+    let downloader = ImageDownloader::new(cache_dir);
+
+    let checksum_url = format!("{}.sha256", url);
+    let sums = downloader.download_sums(&checksum_url).await?;
+    let expected = ChecksumVerifier::from_sums_file(&sums, &filename).or_else(|e| {
+        // A lone entry is the bundle's, whatever name it was built under
+        let checksums = ChecksumVerifier::parse_sums(&sums);
+        match checksums.into_values().collect::<Vec<_>>().as_slice() {
+            [digest] => Ok(digest.clone()),
+            _ => Err(e.context(format!("{} has no checksum for {}", checksum_url, filename))),
+        }
+    })?;
+
+    let installed = std::fs::read_to_string(user_devices_dir().join(STAMP_NAME)).ok();
+    if installed.is_some_and(|digest| digest.trim().eq_ignore_ascii_case(&expected)) {
+        log::info!("Device data is up to date ({})", expected);
+        return Ok(DataUpdate::UpToDate);
+    }
+
+    let digest = expected.clone();
+    let (bundle, _) = downloader
+        .download_verified(url, &filename, async { Ok(Some(digest)) }, None, None)
+        .await?;
+
+    let staging = staging_dir();
+    let root = unpack(&bundle, &staging)?;
+    let report = data_check::check_devices_dir(&root);
+    if !report.is_ok() {
+        report.log_summary();
+        let _ = std::fs::remove_dir_all(&staging);
+        anyhow::bail!(
+            "The downloaded device data is broken: {}",
+            report.errors.join("; ")
+        );
+    }
+
+    std::fs::write(root.join(STAMP_NAME), &expected)
+        .context("Failed to record the device data version")?;
+    replace_devices_dir(&root)?;
+    let _ = std::fs::remove_dir_all(&staging);
+    let _ = std::fs::remove_file(&bundle);
+
+    log::info!(
+        "Device data updated to {} ({} devices) in {}",
+        expected,
+        report.devices_ok,
+        user_devices_dir().display()
+    );
+    Ok(DataUpdate::Updated {
+        devices: report.devices_ok,
+    })
+}
+
+/// Put back the data the last update replaced. When that was the bundled
+/// data, the download is removed so the bundled copy is used again.
+pub fn rollback() -> Result<()> {
+    let current = user_devices_dir();
+    let previous = previous_dir();
+
+    if current.exists() {
+        std::fs::remove_dir_all(&current).context("Failed to remove the updated device data")?;
+    }
+    if previous.exists() {
+        std::fs::rename(&previous, &current).context("Failed to restore the previous device data")?;
+        log::info!("Restored the previous device data in {}", current.display());
+    } else {
+        log::info!("Removed the downloaded device data; using the bundled copy");
+    }
+    Ok(())
+}
+
+/// Unpack `bundle` into `staging`, returning the directory that holds the
+/// manufacturer directories; bundles may or may not wrap them in `devices/`.
+fn unpack(bundle: &Path, staging: &Path) -> Result<PathBuf> {
+    if staging.exists() {
+        std::fs::remove_dir_all(staging).context("Failed to clean previous extraction")?;
+    }
+    std::fs::create_dir_all(staging).context("Failed to create extraction directory")?;
+
+    let file = std::fs::File::open(bundle).context("Failed to open device data bundle")?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    crate::flashing::archive::unpack_tar(&mut archive, staging)
+        .context("Failed to extract device data bundle")?;
+
+    let nested = staging.join("devices");
+    Ok(if nested.is_dir() { nested } else { staging.to_path_buf() })
+}
+
+/// Move `new` into place, keeping the current data as the rollback copy.
+fn replace_devices_dir(new: &Path) -> Result<()> {
+    let current = user_devices_dir();
+    let previous = previous_dir();
+
+    if let Some(parent) = current.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create the data directory")?;
+    }
+    // A copy left from before must not come back on rollback
+    if previous.exists() {
+        std::fs::remove_dir_all(&previous).context("Failed to remove old rollback copy")?;
+    }
+    if current.exists() {
+        std::fs::rename(&current, &previous).context("Failed to keep a rollback copy")?;
+    }
+
+    if let Err(e) = std::fs::rename(new, &current) {
+        if previous.exists() {
+            let _ = std::fs::rename(&previous, &current);
+        }
+        return Err(e).context("Failed to put the new device data in place");
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// Display formatting helpers
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gettextrs::{gettext, ngettext};
+use once_cell::sync::Lazy;
+
+/// Decimal separator of the current locale (LC_NUMERIC), the same one
+/// GLib's size formatting uses, e.g. "1.5 GB" or "1,5 GB".
+static DECIMAL_SEPARATOR: Lazy<String> = Lazy::new(|| {
+    // SAFETY: localeconv() returns a pointer to static storage that stays
+    // valid until the next setlocale(); the string is copied out right away.
+    let separator = unsafe {
+        let conv = libc::localeconv();
+        if conv.is_null() || (*conv).decimal_point.is_null() {
+            None
+        } else {
+            Some(std::ffi::CStr::from_ptr((*conv).decimal_point).to_string_lossy().into_owned())
+        }
+    };
+    separator
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| ".".to_string())
+});
+
+/// Format a byte count with decimal (SI) units, matching GNOME conventions:
+/// whole numbers up to MB ("523 MB") and one decimal from GB ("1.5 GB").
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1_000.0;
+    const MB: f64 = 1_000_000.0;
+    const GB: f64 = 1_000_000_000.0;
+
+    let value = bytes as f64;
+    // Thresholds sit just below each unit so that rounding never yields
+    // "1000 kB" or "1000 MB"
+    if value >= GB - MB / 2.0 {
+        let number = format!("{:.1}", value / GB).replace('.', &DECIMAL_SEPARATOR);
+        gettext("%s GB").replace("%s", &number)
+    } else if value >= MB - KB / 2.0 {
+        gettext("%s MB").replace("%s", &format!("{:.0}", value / MB))
+    } else if bytes >= 1_000 {
+        gettext("%s kB").replace("%s", &format!("{:.0}", value / KB))
+    } else {
+        gettext("%s bytes").replace("%s", &bytes.to_string())
+    }
+}
+
+/// "120 MB / 1.5 GB" style progress text for transfers.
+pub fn format_progress(done: u64, total: u64) -> String {
+    format!("{} / {}", format_size(done), format_size(total))
+}
+
+/// Rough step duration for estimates: "about 3 minutes", or "less than a
+/// minute" for short ones.
+pub fn format_estimate(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        return gettext("less than a minute");
+    }
+    let minutes = ((secs + 30) / 60) as u32;
+    ngettext("about %s minute", "about %s minutes", minutes).replace("%s", &minutes.to_string())
+}
+
+/// Time left in a phase: "about 3 minutes remaining", or "less than a
+/// minute remaining" near the end.
+pub fn format_remaining(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        return gettext("less than a minute remaining");
+    }
+    let minutes = ((secs + 30) / 60) as u32;
+    ngettext("about %s minute remaining", "about %s minutes remaining", minutes)
+        .replace("%s", &minutes.to_string())
+}
+
+/// Transfer speed from bytes per second: "12 MB/s"
+pub fn format_speed(bytes_per_sec: f64) -> String {
+    gettext("%s/s").replace("%s", &format_size(bytes_per_sec as u64))
+}
+
+/// Measured time for statistics, to the second below an hour: "45 s",
+/// "3 min 20 s", "1 h 5 min".
+pub fn format_elapsed(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        gettext("%s s").replace("%s", &secs.to_string())
+    } else if secs < 3600 {
+        gettext("%m min %s s")
+            .replace("%m", &(secs / 60).to_string())
+            .replace("%s", &(secs % 60).to_string())
+    } else {
+        gettext("%h h %m min")
+            .replace("%h", &(secs / 3600).to_string())
+            .replace("%m", &(secs % 3600 / 60).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_below_a_kilobyte() {
+        assert_eq!(format_size(0), "0 bytes");
+        assert_eq!(format_size(999), "999 bytes");
+    }
+
+    #[test]
+    fn kilobytes() {
+        assert_eq!(format_size(1_000), "1 kB");
+        assert_eq!(format_size(523_400), "523 kB");
+    }
+
+    #[test]
+    fn megabytes_start_where_kilobytes_would_round_to_1000() {
+        assert_eq!(format_size(999_499), "999 kB");
+        assert_eq!(format_size(999_500), "1 MB");
+        assert_eq!(format_size(523_000_000), "523 MB");
+    }
+
+    #[test]
+    fn gigabytes_have_one_decimal() {
+        assert_eq!(format_size(999_499_999), "999 MB");
+        assert_eq!(format_size(999_500_000), "1.0 GB");
+        assert_eq!(format_size(1_500_000_000), "1.5 GB");
+    }
+}
@@ -0,0 +1,8 @@
+// Utils
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod data_check;
+pub mod data_update;
+pub mod format;
+pub mod session_log;
+pub mod yaml_parser;
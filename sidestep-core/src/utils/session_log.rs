@@ -0,0 +1,92 @@
+// This session's log, kept in memory for install reports
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Oldest lines are dropped past this many
+const MAX_LINES: usize = 5000;
+
+static START: Lazy<Instant> = Lazy::new(Instant::now);
+static LINES: Lazy<Mutex<VecDeque<String>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+/// Where kept lines are copied as well, see [`tee_to`]
+static TEE: Lazy<Mutex<Option<File>>> = Lazy::new(|| Mutex::new(None));
+
+/// Hands every record to the logger writing to the terminal and keeps a
+/// copy. Sidestep's own debug lines are kept even when the terminal
+/// doesn't show them.
+struct SessionLogger {
+    inner: Box<dyn log::Log>,
+}
+
+impl log::Log for SessionLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        keeps(metadata) || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if keeps(record.metadata()) || self.inner.enabled(record.metadata()) {
+            let elapsed = START.elapsed();
+            let line = format!(
+                "[{:>5}.{:03}] {:<5} {}: {}",
+                elapsed.as_secs(),
+                elapsed.subsec_millis(),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            if let Some(file) = TEE.lock().unwrap().as_mut() {
+                let _ = writeln!(file, "{}", line);
+            }
+            let mut lines = LINES.lock().unwrap();
+            if lines.len() == MAX_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+fn keeps(metadata: &log::Metadata) -> bool {
+    metadata.level() <= log::Level::Debug && metadata.target().starts_with("sidestep")
+}
+
+/// Install `inner` as the logger, recording into the session log as well.
+/// `inner_level` is the most verbose level `inner` shows.
+pub fn install(inner: Box<dyn log::Log>, inner_level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+    Lazy::force(&START);
+    log::set_logger(Box::leak(Box::new(SessionLogger { inner })))?;
+    log::set_max_level(inner_level.max(log::LevelFilter::Debug));
+    Ok(())
+}
+
+/// Everything logged so far this session, oldest first
+pub fn lines() -> Vec<String> {
+    LINES.lock().unwrap().iter().cloned().collect()
+}
+
+/// Copy every line kept from now on into a new file at `path`, after
+/// `header`, until [`end_tee`]. Replaces any file being written already.
+pub fn tee_to(path: &Path, header: &str) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(header.as_bytes())?;
+    *TEE.lock().unwrap() = Some(file);
+    Ok(())
+}
+
+/// Stop copying lines to the file given to [`tee_to`]
+pub fn end_tee() {
+    if let Some(mut file) = TEE.lock().unwrap().take() {
+        let _ = file.flush();
+    }
+}
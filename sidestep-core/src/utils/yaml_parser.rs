@@ -3,10 +3,26 @@
 
 use crate::models::distro::{Distro, DistroTreeNode};
 use crate::models::distro_config::{DeviceDistroConfig, DistroConfig};
+use crate::models::UnlockingStep;
 use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Step lists shared between devices, in `devices/unlocking_templates.yml`
+const UNLOCKING_TEMPLATES: &str = "unlocking_templates.yml";
+
+/// A device's `steps.yml`: a shared template, its own steps, or both, the
+/// template's coming first
+#[derive(Debug, Deserialize)]
+struct StepsFile {
+    #[serde(default)]
+    template: Option<String>,
+    #[serde(default)]
+    steps: Vec<UnlockingStep>,
+}
+
 pub struct YamlParser {
     devices_dir: PathBuf,
 }
@@ -29,6 +45,41 @@ impl YamlParser {
         Ok(info)
     }
 
+    /// The device's bootloader unlocking steps from its `steps.yml`, or
+    /// `None` when it has none. Steps are renumbered in order once a
+    /// template's have been put in front.
+    pub fn parse_unlocking_steps(&self, manufacturer: &str, codename: &str) -> Result<Option<Vec<UnlockingStep>>> {
+        let path = self.devices_dir.join(manufacturer).join(codename).join("steps.yml");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read steps.yml at {:?}", path))?;
+        let file: StepsFile = serde_yaml::from_str(&content)
+            .with_context(|| "Failed to parse steps.yml")?;
+
+        let mut steps = match &file.template {
+            Some(name) => self.unlocking_template(name)?,
+            None => Vec::new(),
+        };
+        steps.extend(file.steps);
+        for (index, step) in steps.iter_mut().enumerate() {
+            step.order = index as u8 + 1;
+        }
+        Ok(Some(steps))
+    }
+
+    fn unlocking_template(&self, name: &str) -> Result<Vec<UnlockingStep>> {
+        let path = self.devices_dir.join(UNLOCKING_TEMPLATES);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        let mut templates: HashMap<String, Vec<UnlockingStep>> = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", UNLOCKING_TEMPLATES))?;
+        templates
+            .remove(name)
+            .with_context(|| format!("No unlocking template called {:?}", name))
+    }
+
     pub fn parse_device_config(&self, manufacturer: &str, codename: &str) -> Result<DeviceDistroConfig> {
         let path = self.devices_dir.join(manufacturer).join(codename).join("distros.yml");
         let content = fs::read_to_string(&path)
@@ -146,9 +197,11 @@ impl YamlParser {
             description,
             download_base_url: url,
             checksum_url: None, // Logic for checkusm url usually derived or separate
+            checksum_algorithm: distro_conf.checksum_algorithm,
             partitions: vec![], // Partitions are dynamic, parser shouldn't guess them yet?
                                 // Distro struct expects partitions. For now empty vector.
             homepage: None,
+            mirrors: distro_conf.mirrors.clone(),
             download_size_bytes: None,
             requires_unlock: true,
             post_install_notes: None,
@@ -0,0 +1,368 @@
+// Wizard controller - state machine for installation wizard
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::{ChecksumAlgorithm, ChecksumVerifier, FlashExecutor, ImageDownloader, StepTimer};
+use crate::hardware::{Adb, Fastboot, Heimdall};
+use crate::models::{Device, DeviceDatabase, Distro, UnlockingStep};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Current state of the wizard
+#[derive(Debug, Clone)]
+pub enum WizardState {
+    /// Displaying safety warnings
+    SafetyWarnings,
+    
+    /// Performing unlocking steps
+    Unlocking {
+        current_step: usize,
+        total_steps: usize,
+    },
+    
+    /// Blocked on a prompt the user must answer with the device's buttons
+    AwaitingDeviceConfirmation {
+        instructions: String,
+    },
+    
+    /// Selecting distribution
+    DistroSelection,
+    
+    /// Downloading images
+    Downloading {
+        file: String,
+        progress: f64,
+    },
+    
+    /// Decompressing images
+    Decompressing {
+        file: String,
+        progress: f64,
+    },
+    
+    /// Flashing partitions
+    Flashing {
+        partition: String,
+        current: usize,
+        total: usize,
+    },
+    
+    /// Verifying checksums
+    Verifying,
+    
+    /// Installation complete
+    Success,
+    
+    /// Error occurred
+    Error(String),
+}
+
+/// Progress update callback
+pub type ProgressCallback = Arc<dyn Fn(WizardState) + Send + Sync>;
+
+/// Controller for the installation wizard flow
+pub struct WizardController {
+    device: Device,
+    database: DeviceDatabase,
+    adb: Adb,
+    fastboot: Fastboot,
+    executor: FlashExecutor,
+    
+    state: Arc<Mutex<WizardState>>,
+    unlocking_steps: Vec<UnlockingStep>,
+    /// "OEM unlocking" toggle as read over ADB before rebooting to fastboot
+    oem_unlock_allowed: Mutex<Option<bool>>,
+    /// How long automated unlocking steps took on earlier runs
+    step_timer: Mutex<StepTimer>,
+    available_distros: Vec<Distro>,
+    selected_distro: Option<Distro>,
+    device_serial: String,
+    download_dir: PathBuf,
+}
+
+impl WizardController {
+    pub fn new(device: Device, serial: String) -> Self {
+        let database = DeviceDatabase::new();
+        let unlocking_steps = database.get_unlocking_steps(&device.codename);
+        let available_distros = database.get_distros(&device.codename);
+
+        let download_dir = dirs::download_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join("sidestep")
+            .join(&device.codename);
+        let executor = FlashExecutor::new()
+            .with_all_slots(true)
+            .confirmed_device(&serial, &device);
+        let step_timer = StepTimer::new(&format!("{}/unlock", device.codename));
+
+        Self {
+            device,
+            database,
+            adb: Adb::new(),
+            fastboot: Fastboot::new(),
+            executor,
+            state: Arc::new(Mutex::new(WizardState::SafetyWarnings)),
+            unlocking_steps,
+            oem_unlock_allowed: Mutex::new(None),
+            step_timer: Mutex::new(step_timer),
+            available_distros,
+            selected_distro: None,
+            device_serial: serial,
+            download_dir,
+        }
+    }
+
+    /// Get current state
+    pub async fn state(&self) -> WizardState {
+        self.state.lock().await.clone()
+    }
+
+    /// Set state
+    async fn set_state(&self, state: WizardState) {
+        *self.state.lock().await = state;
+    }
+
+    /// Get device info
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Get unlocking steps
+    pub fn unlocking_steps(&self) -> &[UnlockingStep] {
+        &self.unlocking_steps
+    }
+
+    /// How long a step is likely to take: the average of its earlier runs on
+    /// this model, or the `duration_secs` from the device data before then.
+    pub async fn step_estimate(&self, step_index: usize) -> Option<Duration> {
+        let step = self.unlocking_steps.get(step_index)?;
+        let fallback = step.duration_secs.map(|secs| Duration::from_secs(secs.into()));
+        self.step_timer.lock().await.estimate(&step.title, fallback)
+    }
+
+    /// Get available distros
+    pub fn available_distros(&self) -> &[Distro] {
+        &self.available_distros
+    }
+
+    /// Move to unlocking phase.
+    ///
+    /// If the device already reports "OEM unlocking" as enabled, the manual
+    /// steps up to and including that toggle are skipped.
+    pub async fn start_unlocking(&self) {
+        let allowed = match self.adb.oem_unlock_allowed(&self.device_serial).await {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                log::warn!("Could not read OEM unlock state: {}", e);
+                None
+            }
+        };
+        *self.oem_unlock_allowed.lock().await = allowed;
+
+        let mut first_step = 0;
+        if allowed == Some(true)
+            && let Some(index) = self.unlocking_steps.iter().position(|s| s.is_oem_unlock_toggle())
+        {
+            log::info!("OEM unlocking already enabled, skipping to step {}", index + 2);
+            first_step = index + 1;
+        }
+
+        self.set_state(WizardState::Unlocking {
+            current_step: first_step + 1,
+            total_steps: self.unlocking_steps.len(),
+        }).await;
+    }
+
+    /// Refuse to send the unlock command when the device says OEM unlocking is off.
+    /// Devices that expose neither property are let through.
+    async fn ensure_oem_unlock_allowed(&self) -> Result<()> {
+        let allowed = match self.fastboot.unlock_ability(&self.device_serial).await {
+            Ok(Some(allowed)) => Some(allowed),
+            _ => *self.oem_unlock_allowed.lock().await,
+        };
+
+        if allowed == Some(false) {
+            anyhow::bail!("OEM unlocking is not enabled — toggle it first");
+        }
+        Ok(())
+    }
+
+    /// Execute an automated unlocking step, timing it for later estimates
+    pub async fn execute_step(&self, step_index: usize) -> Result<()> {
+        let step = &self.unlocking_steps[step_index];
+        self.step_timer.lock().await.begin(&step.title, None);
+        let result = self.run_step(step).await;
+        let mut timer = self.step_timer.lock().await;
+        if result.is_ok() {
+            timer.finish();
+        } else {
+            timer.abandon();
+        }
+        drop(timer);
+        result?;
+
+        // Move to next step
+        let next_step = step_index + 1;
+        if next_step < self.unlocking_steps.len() {
+            self.set_state(WizardState::Unlocking {
+                current_step: next_step + 1,
+                total_steps: self.unlocking_steps.len(),
+            }).await;
+        } else {
+            self.set_state(WizardState::DistroSelection).await;
+        }
+
+        Ok(())
+    }
+
+    async fn run_step(&self, step: &UnlockingStep) -> Result<()> {
+
+        if step.is_unlock_command() {
+            self.ensure_oem_unlock_allowed().await?;
+        }
+
+        if step.is_heimdall() {
+            return self.run_heimdall_step(step).await;
+        }
+
+        if let Some(ref command) = step.command {
+            log::info!("Executing: {}", command);
+
+            // Parse and execute command
+            if command.starts_with("adb ") {
+                let args = command.strip_prefix("adb ").unwrap();
+                if args == "reboot bootloader" {
+                    self.adb.reboot_bootloader(&self.device_serial).await?;
+                } else {
+                    // Generic shell command
+                    self.adb.shell(&self.device_serial, args).await?;
+                }
+            } else if command.starts_with("fastboot ") {
+                let args = command.strip_prefix("fastboot ").unwrap();
+                if args == "oem unlock" || args == "flashing unlock" {
+                    // The command only returns once the prompt on the device is answered
+                    self.set_state(WizardState::AwaitingDeviceConfirmation {
+                        instructions: format!(
+                            "Confirm the unlock on your device. {}",
+                            self.device.button_hint()
+                        ),
+                    }).await;
+                    self.fastboot.oem_unlock(&self.device_serial).await?;
+                    self.fastboot
+                        .wait_for_unlocked(&self.device_serial, Duration::from_secs(120))
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the images a Heimdall step's `--PARTITION file` pairs name,
+    /// looked up in the download directory
+    async fn run_heimdall_step(&self, step: &UnlockingStep) -> Result<()> {
+        let command = step.command.as_deref().unwrap_or("heimdall flash");
+        let mut files = Vec::new();
+        let mut args = command.split_whitespace().skip(2);
+        while let Some(arg) = args.next() {
+            let Some(partition) = arg.strip_prefix("--").filter(|p| *p != "no-reboot") else {
+                continue;
+            };
+            let Some(file) = args.next() else {
+                anyhow::bail!("\"{}\" names no image for {}", command, partition);
+            };
+            let path = self.download_dir.join(file);
+            if !path.is_file() {
+                anyhow::bail!(
+                    "Put {} in {} first, or with the device in Download Mode run: {}",
+                    file,
+                    self.download_dir.display(),
+                    command
+                );
+            }
+            files.push((partition, path));
+        }
+
+        let heimdall = Heimdall::new();
+        heimdall.wait_for_device(Duration::from_secs(60)).await?;
+        let files: Vec<(&str, &std::path::Path)> =
+            files.iter().map(|(partition, path)| (*partition, path.as_path())).collect();
+        heimdall.flash(&files, |partition, percent| {
+            log::debug!("Heimdall: {} {}%", partition, percent);
+        }).await
+    }
+
+    /// Select a distro
+    pub fn select_distro(&mut self, distro: Distro) {
+        self.selected_distro = Some(distro);
+    }
+
+    /// Start the installation process
+    pub async fn start_installation(&self) -> Result<()> {
+        let distro = self.selected_distro.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No distro selected"))?;
+
+        let downloader = ImageDownloader::new(self.download_dir.clone())
+            .with_mirrors(&distro.download_base_url, &distro.mirrors);
+        if let Some(needed) = distro.space_needed() {
+            downloader.ensure_space(needed)?;
+        }
+
+        // Download images while the checksum file is fetched, so a bad
+        // checksum_url fails before the images have all arrived
+        let downloads = async {
+            for partition in &distro.partitions {
+                let url = format!("{}{}", distro.download_base_url, partition.image);
+
+                self.set_state(WizardState::Downloading {
+                    file: partition.image.clone(),
+                    progress: 0.0,
+                }).await;
+
+                downloader.download(&url, &partition.image, None).await?;
+            }
+            Ok::<(), anyhow::Error>(())
+        };
+        let checksums = async {
+            match distro.checksum_url {
+                Some(ref checksum_url) => downloader.download_checksums(checksum_url).await.map(Some),
+                None => Ok(None),
+            }
+        };
+        let ((), checksums) = futures_util::future::try_join(downloads, checksums).await?;
+
+        // Images stay compressed; the executor unpacks each one as it
+        // flashes it
+
+        // Verify checksums if available
+        if let Some(ref checksums) = checksums {
+            self.set_state(WizardState::Verifying).await;
+            let algorithm = distro.checksum_algorithm.or_else(|| {
+                distro.checksum_url.as_deref().and_then(ChecksumAlgorithm::from_file_name)
+            });
+            ChecksumVerifier::verify_all(&self.download_dir, checksums, algorithm)?;
+        }
+
+        // Flash images
+        self.set_state(WizardState::Flashing {
+            partition: "starting".to_string(),
+            current: 0,
+            total: distro.partitions.len(),
+        }).await;
+
+        self.executor.flash_distro(
+            &self.device_serial,
+            distro,
+            &self.download_dir,
+            None,
+        ).await?;
+
+        // Reboot
+        self.executor.reboot(&self.device_serial).await?;
+
+        self.set_state(WizardState::Success).await;
+
+        Ok(())
+    }
+}
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::config;
+use crate::flashing;
+use crate::preferences_dialog::PreferencesDialog;
+use crate::utils::{data_check, data_update};
 use crate::window::SidestepWindow;
 use gettextrs::gettext;
 use gtk::{gio, glib, prelude::*, subclass::prelude::*};
@@ -13,7 +16,10 @@ mod imp {
     use super::*;
 
     #[derive(Debug, Default)]
-    pub struct SidestepApplication {}
+    pub struct SidestepApplication {
+        /// Kept so the change handler for the download limit stays connected
+        pub download_settings: once_cell::sync::OnceCell<gio::Settings>,
+    }
 
     #[glib::object_subclass]
     impl ObjectSubclass for SidestepApplication {
@@ -28,10 +34,25 @@ mod imp {
             let obj = self.obj();
             obj.setup_actions();
             obj.setup_accels();
+            obj.add_main_option(
+                "validate-data",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::None,
+                &gettext("Check the device data for problems and exit"),
+                None,
+            );
         }
     }
 
     impl ApplicationImpl for SidestepApplication {
+        fn handle_local_options(&self, options: &glib::VariantDict) -> std::ops::ControlFlow<glib::ExitCode> {
+            if options.contains("validate-data") {
+                return std::ops::ControlFlow::Break(self.obj().validate_data());
+            }
+            self.parent_handle_local_options(options)
+        }
+
         fn activate(&self) {
             log::debug!("Application activate");
             let application = self.obj();
@@ -41,6 +62,8 @@ mod imp {
                 window
             } else {
                 let window = SidestepWindow::new(&*application);
+                application.check_data();
+                application.apply_download_limits();
                 window.upcast()
             };
 
@@ -88,7 +111,25 @@ impl SidestepApplication {
             })
             .build();
 
-        self.add_action_entries([quit_action, about_action, preferences_action]);
+        let data_updates_action = gio::ActionEntry::builder("check-data-updates")
+            .activate(|app: &Self, _, _| {
+                app.check_data_updates();
+            })
+            .build();
+
+        let export_logs_action = gio::ActionEntry::builder("export-logs")
+            .activate(|app: &Self, _, _| {
+                app.export_logs();
+            })
+            .build();
+
+        self.add_action_entries([
+            quit_action,
+            about_action,
+            preferences_action,
+            data_updates_action,
+            export_logs_action,
+        ]);
     }
 
     fn setup_accels(&self) {
@@ -116,14 +157,204 @@ impl SidestepApplication {
         about.present(Some(&window));
     }
 
+    /// Log whether the device data and the icons it refers to are all in
+    /// place, so a broken install shows up in the log rather than as empty
+    /// pages.
+    fn check_data(&self) {
+        let mut report = data_check::check_data_dir();
+
+        let ui_path = "/io/github/tobagin/Sidestep/ui/pages";
+        if gio::resources_enumerate_children(ui_path, gio::ResourceLookupFlags::NONE).is_err() {
+            report.errors.push(format!("{} is missing from sidestep.gresource", ui_path));
+        }
+
+        if let Some(display) = gtk::gdk::Display::default() {
+            let theme = gtk::IconTheme::for_display(&display);
+            for id in &report.distro_ids {
+                let icon = format!("{}-symbolic", id);
+                if !theme.has_icon(&icon) {
+                    report.warnings.push(format!("Icon {} is missing; {} is shown without it", icon, id));
+                }
+            }
+        }
+
+        report.log_summary();
+    }
+
+    /// `--validate-data`: check the device data without opening a window,
+    /// print every problem and fail when any would break a page or an
+    /// install.
+    fn validate_data(&self) -> glib::ExitCode {
+        let report = data_check::check_data_dir();
+        if let Some(ref dir) = report.devices_dir {
+            println!("Checking {}", dir.display());
+        }
+        for error in &report.errors {
+            println!("error: {}", error);
+        }
+        for warning in &report.warnings {
+            println!("warning: {}", warning);
+        }
+        println!(
+            "{} devices OK, {} errors, {} warnings",
+            report.devices_ok,
+            report.errors.len(),
+            report.warnings.len()
+        );
+
+        if report.is_ok() {
+            glib::ExitCode::SUCCESS
+        } else {
+            glib::ExitCode::FAILURE
+        }
+    }
+
+    /// Hand the connection cap and speed limit from the settings to the
+    /// downloader, and keep them in sync when the settings change.
+    fn apply_download_limits(&self) {
+        let settings = gio::Settings::new(config::APP_ID);
+        let apply = |settings: &gio::Settings| {
+            flashing::set_max_connections(settings.int("max-download-connections").max(1) as usize);
+            let speed_limit = settings
+                .boolean("limit-download-speed")
+                .then(|| settings.int("download-speed-limit").max(1) as u64 * 1000);
+            flashing::set_max_bytes_per_sec(speed_limit);
+        };
+        apply(&settings);
+        for key in ["max-download-connections", "limit-download-speed", "download-speed-limit"] {
+            settings.connect_changed(Some(key), move |settings, _| apply(settings));
+        }
+        let _ = self.imp().download_settings.set(settings);
+    }
+
+    /// Fetch the device data bundle from the configured URL on a worker
+    /// thread and report what happened.
+    fn check_data_updates(&self) {
+        let Some(window) = self.active_window() else {
+            return;
+        };
+
+        let url = gio::Settings::new(config::APP_ID).string("device-data-url").to_string();
+        if url.trim().is_empty() {
+            let dialog = adw::AlertDialog::new(
+                Some("No Update Source"),
+                Some("Set the device-data-url setting to the address of a device data bundle to receive updates between releases."),
+            );
+            dialog.add_response("close", "OK");
+            dialog.present(Some(&window));
+            return;
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+            let result = rt.block_on(data_update::update_from(url.trim()));
+            let _ = sender.send(result.map_err(|e| format!("{:#}", e)));
+        });
+
+        let window = window.downgrade();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            };
+            if let Some(window) = window.upgrade() {
+                show_data_update_result(&window, result);
+            }
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Save a copy of the most recent install log where the user picks,
+    /// for attaching to a bug report
+    fn export_logs(&self) {
+        let Some(window) = self.active_window() else {
+            return;
+        };
+
+        let Some(log_path) = flashing::install_log::latest() else {
+            let dialog = adw::AlertDialog::new(
+                Some("No Install Logs"),
+                Some("A log is written each time an install runs. Start one, then export its log from here."),
+            );
+            dialog.add_response("close", "OK");
+            dialog.present(Some(&window));
+            return;
+        };
+
+        let initial_name = log_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "sidestep-install.log".to_string());
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Install Log")
+            .initial_name(initial_name)
+            .modal(true)
+            .build();
+
+        let window_weak = window.downgrade();
+        dialog.save(Some(&window), gio::Cancellable::NONE, move |result| {
+            let Some(dest) = result.ok().and_then(|file| file.path()) else {
+                return;
+            };
+            let message = match std::fs::copy(&log_path, &dest) {
+                Ok(_) => format!("Install log saved to {}", dest.display()),
+                Err(e) => {
+                    log::error!("Failed to export {}: {}", log_path.display(), e);
+                    format!("Couldn't save the install log: {}", e)
+                }
+            };
+            if let Some(window) = window_weak
+                .upgrade()
+                .and_then(|w| w.downcast::<SidestepWindow>().ok())
+            {
+                window.show_toast(&message);
+            }
+        });
+    }
+
     fn show_preferences(&self) {
-        let _window = self.active_window().unwrap();
-        
-        // TODO: Implement preferences dialog
-        log::info!("Preferences dialog not yet implemented");
+        let window = self.active_window().unwrap();
+        PreferencesDialog::new().present(Some(&window));
     }
 }
 
+fn show_data_update_result(window: &gtk::Window, result: Result<data_update::DataUpdate, String>) {
+    let dialog = match result {
+        Ok(data_update::DataUpdate::UpToDate) => adw::AlertDialog::new(
+            Some("Device Database Up to Date"),
+            Some("You already have the latest device data."),
+        ),
+        Ok(data_update::DataUpdate::Updated { devices }) => {
+            let dialog = adw::AlertDialog::new(
+                Some("Device Database Updated"),
+                Some(&format!(
+                    "Data for {} devices was downloaded and checked. Open a device again to see it.",
+                    devices
+                )),
+            );
+            dialog.add_response("rollback", "Restore Previous");
+            dialog.connect_response(Some("rollback"), |_, _| {
+                if let Err(e) = data_update::rollback() {
+                    log::error!("Failed to restore the previous device data: {:#}", e);
+                }
+            });
+            dialog
+        }
+        Err(message) => adw::AlertDialog::new(
+            Some("Device Database Update Failed"),
+            Some(&format!("{}\n\nThe current device data is unchanged.", message)),
+        ),
+    };
+    dialog.add_response("close", "OK");
+    dialog.set_default_response(Some("close"));
+    dialog.present(Some(window));
+}
+
 impl Default for SidestepApplication {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,206 @@
+// `sidestep install`: run an install without the GUI
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::flashing::headless::{self, HeadlessOptions};
+use crate::flashing::{EventBus, InstallEvent, InstallOutcome, InstallProgress, InstallReport, SessionStats};
+use gtk::glib;
+
+const USAGE: &str = "\
+Usage: sidestep install --device CODENAME --distro ID [OPTIONS]
+
+Install a distro on the connected device without opening a window.
+
+Options:
+  --device CODENAME         Device to install on, e.g. sargo
+  --distro ID               Distro id from the device's list, e.g. postmarketos
+  --channel ID              Release channel; needed when there are several
+  --interface ID            Desktop interface, for distros that offer a choice
+  --serial SERIAL           Device serial; needed when several are connected
+  --download-only           Only download and verify the images
+  --confirm-prerequisites   Prerequisites the device can't report have been
+                            checked by hand
+  --allow-unverified        Install files that have no published checksum
+  -h, --help                Show this help";
+
+/// Options for a command-line install, parsed from the arguments after
+/// `install`
+struct InstallArgs {
+    options: HeadlessOptions,
+    allow_unverified: bool,
+}
+
+impl InstallArgs {
+    fn parse(args: &[String]) -> Result<Option<Self>, String> {
+        let mut options = HeadlessOptions::default();
+        let mut allow_unverified = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| format!("{} needs a value", arg))
+            };
+            match arg.as_str() {
+                "--device" => options.device = value()?,
+                "--distro" => options.distro = value()?,
+                "--channel" => options.channel = Some(value()?),
+                "--interface" => options.interface = Some(value()?),
+                "--serial" => options.serial = Some(value()?),
+                "--download-only" => options.download_only = true,
+                "--confirm-prerequisites" => options.prerequisites_confirmed = true,
+                "--allow-unverified" => allow_unverified = true,
+                "-h" | "--help" => return Ok(None),
+                other => return Err(format!("Unknown option {}", other)),
+            }
+        }
+        if options.device.is_empty() || options.distro.is_empty() {
+            return Err("--device and --distro are required".to_string());
+        }
+        Ok(Some(Self {
+            options,
+            allow_unverified,
+        }))
+    }
+}
+
+/// Run `sidestep install` with `args`, printing progress to stdout. Fails
+/// when anything stops the install, including a prerequisite the device
+/// doesn't meet.
+pub fn install(args: &[String]) -> glib::ExitCode {
+    let args = match InstallArgs::parse(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return glib::ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return glib::ExitCode::FAILURE;
+        }
+    };
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create tokio runtime");
+    let run = match rt.block_on(headless::start(&args.options)) {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            return glib::ExitCode::FAILURE;
+        }
+    };
+    println!("Installing {} on {} ({})", run.distro.name, run.device.name, run.serial);
+
+    let bus = EventBus::new();
+    let events = bus.subscribe();
+    bus.log_events();
+    let report = InstallReport {
+        distro: run.distro.name.clone(),
+        options: [("Channel", &args.options.channel), ("Interface", &args.options.interface)]
+            .into_iter()
+            .filter_map(|(label, value)| value.clone().map(|v| (label.to_string(), v)))
+            .collect(),
+        outcome: None,
+        serial: Some(run.serial.clone()),
+        device: Some(run.device.clone()),
+        stats: None,
+    };
+    match crate::flashing::install_log::start(&report, &bus) {
+        Ok(path) => println!("Logging to {}", path.display()),
+        Err(e) => log::warn!("Not writing an install log: {:#}", e),
+    }
+    bus.forward(run.progress);
+
+    let mut stats = SessionStats::new();
+    let mut last_line = String::new();
+    for event in events.iter() {
+        match event {
+            InstallEvent::Progress(InstallProgress::ChecksumUnavailable { file_name, reply }) => {
+                if args.allow_unverified {
+                    println!("{} has no published checksum; installing it anyway", file_name);
+                } else {
+                    eprintln!("{} has no published checksum; pass --allow-unverified to install it", file_name);
+                }
+                let _ = reply.send(args.allow_unverified);
+            }
+            InstallEvent::Progress(progress) => {
+                stats.observe(&progress);
+                if let Some(line) = describe(&progress)
+                    && line != last_line
+                {
+                    println!("{}", line);
+                    last_line = line;
+                }
+            }
+            InstallEvent::Finished(outcome) => {
+                stats.finish();
+                return finish(outcome, &stats);
+            }
+        }
+    }
+    glib::ExitCode::FAILURE
+}
+
+fn finish(outcome: InstallOutcome, stats: &SessionStats) -> glib::ExitCode {
+    for (label, value) in stats.rows() {
+        println!("{}: {}", label, value);
+    }
+    match outcome {
+        InstallOutcome::Installed => println!("Done"),
+        InstallOutcome::Downloaded(dir) => println!("Images downloaded to {}", dir.display()),
+        InstallOutcome::BootedTemporarily => println!("Booted without installing"),
+        InstallOutcome::Cancelled => {
+            eprintln!("Cancelled");
+            return glib::ExitCode::FAILURE;
+        }
+        InstallOutcome::Failed(message) => {
+            eprintln!("error: {}", message);
+            return glib::ExitCode::FAILURE;
+        }
+    }
+    glib::ExitCode::SUCCESS
+}
+
+/// One line for `progress`, repeated lines being dropped by the caller so
+/// byte counts only print when the percentage moves
+fn describe(progress: &InstallProgress) -> Option<String> {
+    Some(match progress {
+        InstallProgress::DownloadProgress { downloaded, total, file_name } if *total > 0 => {
+            format!("Downloading {}: {}%", file_name, downloaded * 100 / total)
+        }
+        InstallProgress::VerifyProgress { verified, total, file_name } => {
+            format!("Verifying {} ({}/{})", file_name, verified, total)
+        }
+        InstallProgress::DecompressProgress { percent, file_name } => {
+            format!("Unpacking {}: {}%", file_name, percent)
+        }
+        InstallProgress::SideloadProgress { percent, file_name } => {
+            format!("Sideloading {}: {}%", file_name, percent)
+        }
+        InstallProgress::FlashProgress { current, total, description } => {
+            format!("[{}/{}] {}", current, total, description)
+        }
+        InstallProgress::PartitionProgress { partition, percent, .. } => {
+            format!("Flashing {}: {}%", partition, percent)
+        }
+        InstallProgress::TransferStalled { partition, silent_secs } => format!(
+            "Warning: no progress flashing {} for {}s; try another USB port or cable",
+            partition, silent_secs
+        ),
+        InstallProgress::FlashRetrying { partition, attempt, attempts, reason } => {
+            format!("Flashing {} failed ({}); attempt {} of {}", partition, reason, attempt, attempts)
+        }
+        InstallProgress::StatusChanged(status) => status.clone(),
+        InstallProgress::AwaitingDeviceConfirmation(instructions)
+        | InstallProgress::WaitingForUserAction(instructions) => {
+            format!("On the device: {}", instructions)
+        }
+        InstallProgress::ModeTransitionSlow { mode, .. } => {
+            format!("Still waiting for the device to reach {} mode", mode.label())
+        }
+        InstallProgress::WaitingForRecovery => "On the device: choose Recovery mode in the bootloader menu".to_string(),
+        InstallProgress::RecoveryDetected => "Recovery detected".to_string(),
+        _ => return None,
+    })
+}
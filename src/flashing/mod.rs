@@ -1,28 +0,0 @@
-// Flashing engine module
-// SPDX-License-Identifier: GPL-3.0-or-later
-
-pub mod downloader;
-pub mod decompressor;
-pub mod executor;
-pub mod checksum;
-pub mod progress;
-pub mod ubports;
-pub mod droidian;
-pub mod mobian;
-pub mod postmarketos;
-pub mod lineageos;
-pub mod eos;
-pub mod factory_image;
-
-pub use downloader::ImageDownloader;
-pub use decompressor::Decompressor;
-pub use executor::FlashExecutor;
-pub use checksum::ChecksumVerifier;
-pub use progress::InstallProgress;
-pub use ubports::UbportsInstaller;
-pub use droidian::DroidianInstaller;
-pub use mobian::MobianInstaller;
-pub use postmarketos::PostmarketosInstaller;
-pub use lineageos::LineageosInstaller;
-pub use eos::EosInstaller;
-pub use factory_image::FactoryImageInstaller;
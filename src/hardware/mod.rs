@@ -1,11 +0,0 @@
-// Hardware bridge module
-// SPDX-License-Identifier: GPL-3.0-or-later
-
-pub mod adb;
-pub mod fastboot;
-pub mod device_detector;
-
-pub use adb::Adb;
-pub use fastboot::Fastboot;
-pub use device_detector::{DeviceDetector, DeviceEvent};
-
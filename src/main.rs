@@ -2,27 +2,35 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 mod application;
-mod config;
-mod flashing;
-mod hardware;
-mod models;
+mod cli;
 mod window;
 mod pages;
-mod wizard;
-mod utils;
+mod preferences_dialog;
+
+// Everything below the UI lives in sidestep-core; re-imported so pages keep
+// their `crate::` paths
+use sidestep_core::{config, flashing, hardware, models, utils};
 
 use application::SidestepApplication;
 use gettextrs::LocaleCategory;
 use gtk::{gio, glib, prelude::*};
 
 fn main() -> glib::ExitCode {
-    // Initialize logging
-    env_logger::Builder::from_env(
+    // Initialize logging, keeping a copy for install reports
+    let logger = env_logger::Builder::from_env(
         env_logger::Env::default().default_filter_or("sidestep=info")
-    ).init();
+    ).build();
+    let level = logger.filter();
+    utils::session_log::install(Box::new(logger), level).expect("Unable to set up logging");
 
     log::info!("Starting Sidestep v{}", config::VERSION);
 
+    // `sidestep install ...` runs headless; nothing below is needed for it
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("install") {
+        return cli::install(&args[2..]);
+    }
+
     // Set up gettext translations
     gettextrs::setlocale(LocaleCategory::LcAll, "");
     gettextrs::bindtextdomain(config::GETTEXT_PACKAGE, config::LOCALEDIR)
@@ -0,0 +1,46 @@
+// Accessibility helpers shared by the pages
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::prelude::*;
+
+/// False when the user has turned animations off, e.g. with GNOME's
+/// "Reduce Animation" setting.
+pub fn animations_enabled() -> bool {
+    gtk::Settings::default().is_none_or(|settings| settings.is_gtk_enable_animations())
+}
+
+/// A spinner, or with animations off a static icon, for rows whose title
+/// already says what is being waited for.
+pub fn busy_indicator() -> gtk::Widget {
+    if animations_enabled() {
+        return gtk::Spinner::builder().spinning(true).build().upcast();
+    }
+    status_icon("content-loading-symbolic", None, "In progress").upcast()
+}
+
+/// Keep `spinner` still and hidden while animations are off, following
+/// the setting when it changes. Whatever it sits next to must say in
+/// text that something is in progress.
+pub fn follow_reduced_motion(spinner: &gtk::Spinner) {
+    spinner.set_visible(animations_enabled());
+    if let Some(settings) = gtk::Settings::default() {
+        let spinner = spinner.downgrade();
+        settings.connect_gtk_enable_animations_notify(move |settings| {
+            if let Some(spinner) = spinner.upgrade() {
+                spinner.set_visible(settings.is_gtk_enable_animations());
+            }
+        });
+    }
+}
+
+/// Icon for a status that colour alone would otherwise convey; `label` is
+/// read out by screen readers and shown as a tooltip.
+pub fn status_icon(icon_name: &str, style: Option<&str>, label: &str) -> gtk::Image {
+    let icon = gtk::Image::from_icon_name(icon_name);
+    if let Some(style) = style {
+        icon.add_css_class(style);
+    }
+    icon.set_tooltip_text(Some(label));
+    icon.update_property(&[gtk::accessible::Property::Label(label)]);
+    icon
+}
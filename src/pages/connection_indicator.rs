@@ -0,0 +1,118 @@
+// Connection status indicator
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::window::SidestepWindow;
+use gtk::{glib, prelude::*, subclass::prelude::*};
+use std::cell::RefCell;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct ConnectionIndicator {
+        pub dot: gtk::Image,
+        pub label: gtk::Label,
+        /// Subscription to the window's "connection-changed" while realized
+        pub handler: RefCell<Option<(glib::WeakRef<SidestepWindow>, glib::SignalHandlerId)>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ConnectionIndicator {
+        const NAME: &'static str = "ConnectionIndicator";
+        type Type = super::ConnectionIndicator;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for ConnectionIndicator {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_spacing(6);
+            obj.set_valign(gtk::Align::Center);
+
+            self.dot.set_icon_name(Some("media-record-symbolic"));
+            self.dot.set_pixel_size(10);
+            self.label.add_css_class("caption");
+            obj.append(&self.dot);
+            obj.append(&self.label);
+
+            obj.update(None);
+        }
+    }
+
+    impl WidgetImpl for ConnectionIndicator {
+        fn realize(&self) {
+            self.parent_realize();
+            self.obj().attach();
+        }
+
+        fn unrealize(&self) {
+            self.obj().detach();
+            self.parent_unrealize();
+        }
+    }
+
+    impl BoxImpl for ConnectionIndicator {}
+}
+
+glib::wrapper! {
+    /// Header bar badge showing the connected device and its mode,
+    /// e.g. "Connected: sargo (fastboot)", or "No device".
+    pub struct ConnectionIndicator(ObjectSubclass<imp::ConnectionIndicator>)
+        @extends gtk::Widget, gtk::Box,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl ConnectionIndicator {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Follow the window's connection status for as long as we're on screen.
+    fn attach(&self) {
+        let Some(window) = self.root().and_downcast::<SidestepWindow>() else {
+            return;
+        };
+        self.update(window.connection_status().as_deref());
+
+        let indicator = self.downgrade();
+        let id = window.connect_connection_changed(move |window| {
+            if let Some(indicator) = indicator.upgrade() {
+                indicator.update(window.connection_status().as_deref());
+            }
+        });
+        self.imp().handler.replace(Some((window.downgrade(), id)));
+    }
+
+    fn detach(&self) {
+        if let Some((window, id)) = self.imp().handler.take() {
+            if let Some(window) = window.upgrade() {
+                window.disconnect(id);
+            }
+        }
+    }
+
+    fn update(&self, status: Option<&str>) {
+        let imp = self.imp();
+        match status {
+            Some(status) => {
+                imp.label.set_label(&format!("Connected: {}", status));
+                imp.label.remove_css_class("dim-label");
+                imp.dot.remove_css_class("dim-label");
+                imp.dot.add_css_class("success");
+            }
+            None => {
+                imp.label.set_label("No device");
+                imp.label.add_css_class("dim-label");
+                imp.dot.remove_css_class("success");
+                imp.dot.add_css_class("dim-label");
+            }
+        }
+    }
+}
+
+impl Default for ConnectionIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,18 +1,26 @@
 // Device Browser Page
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use crate::config;
 use crate::models::DeviceDatabase;
-use gtk::{glib, prelude::*, subclass::prelude::*};
+use crate::pages::connection_indicator::ConnectionIndicator;
+use gettextrs::ngettext;
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use std::collections::BTreeMap;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 mod imp {
     use super::*;
 
     #[derive(Debug, Default)]
-    pub struct DeviceBrowserPage;
+    pub struct DeviceBrowserPage {
+        /// Codename of the connected device, listed even when experimental
+        /// devices are hidden
+        pub connected: RefCell<Option<String>>,
+    }
 
     #[glib::object_subclass]
     impl ObjectSubclass for DeviceBrowserPage {
@@ -32,11 +40,6 @@ mod imp {
                 ]
             })
         }
-
-        fn constructed(&self) {
-            self.parent_constructed();
-            self.obj().build_ui();
-        }
     }
 
     impl WidgetImpl for DeviceBrowserPage {}
@@ -50,16 +53,22 @@ glib::wrapper! {
 }
 
 impl DeviceBrowserPage {
-    pub fn new() -> Self {
-        glib::Object::builder()
+    /// `connected` is the codename of the device on the cable, if any
+    pub fn new(connected: Option<&str>) -> Self {
+        let page: Self = glib::Object::builder()
             .property("title", "Browse Devices")
             .property("tag", "device_browser")
-            .build()
+            .build();
+        page.imp().connected.replace(connected.map(str::to_string));
+        page.build_ui();
+        page
     }
 
     fn build_ui(&self) {
         let toolbar_view = adw::ToolbarView::new();
-        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        let header_bar = adw::HeaderBar::new();
+        header_bar.pack_start(&ConnectionIndicator::new());
+        toolbar_view.add_top_bar(&header_bar);
 
         let scrolled = gtk::ScrolledWindow::builder()
             .hscrollbar_policy(gtk::PolicyType::Never)
@@ -78,6 +87,8 @@ impl DeviceBrowserPage {
             .spacing(24)
             .build();
 
+        let db = DeviceDatabase::new();
+
         // Title area
         let title_box = gtk::Box::builder()
             .orientation(gtk::Orientation::Vertical)
@@ -91,7 +102,10 @@ impl DeviceBrowserPage {
             .build();
 
         let subtitle_label = gtk::Label::builder()
-            .label("Select your device to see setup instructions")
+            .label(format!(
+                "{} devices. Select yours to see setup instructions",
+                db.device_count()
+            ))
             .css_classes(vec!["dim-label".to_string()])
             .wrap(true)
             .justify(gtk::Justification::Center)
@@ -101,22 +115,77 @@ impl DeviceBrowserPage {
         title_box.append(&subtitle_label);
         content_box.append(&title_box);
 
-        // Load devices and group by manufacturer
-        let db = DeviceDatabase::new();
-        let devices = db.all_devices();
-
-        let mut grouped: BTreeMap<String, Vec<_>> = BTreeMap::new();
-        for device in &devices {
-            grouped
-                .entry(device.maker.clone())
-                .or_default()
-                .push(device.clone());
-        }
+        let search_entry = gtk::SearchEntry::builder()
+            .placeholder_text("Search by name or codename")
+            .build();
+        content_box.append(&search_entry);
+
+        let experimental_row = adw::SwitchRow::builder()
+            .title("Show Experimental Devices")
+            .subtitle("Devices whose support is still being tested")
+            .build();
+        let settings_group = adw::PreferencesGroup::new();
+        settings_group.add(&experimental_row);
+        content_box.append(&settings_group);
+
+        let settings = gio::Settings::new(config::APP_ID);
+        settings
+            .bind("show-experimental-devices", &experimental_row, "active")
+            .build();
+
+        // Rebuilt whenever the query or the switch changes
+        let list_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .build();
+        content_box.append(&list_box);
+
+        let refresh: Rc<dyn Fn()> = {
+            let page = self.downgrade();
+            let search_entry = search_entry.downgrade();
+            let experimental_row = experimental_row.downgrade();
+            Rc::new(move || {
+                let (Some(page), Some(search_entry), Some(experimental_row)) =
+                    (page.upgrade(), search_entry.upgrade(), experimental_row.upgrade())
+                else {
+                    return;
+                };
+                while let Some(child) = list_box.first_child() {
+                    list_box.remove(&child);
+                }
+                let include_experimental = experimental_row.is_active();
+                let query = search_entry.text();
+                let view = if query.trim().is_empty() {
+                    page.maker_sections(&db, include_experimental)
+                } else {
+                    page.search_results(&db, &query, include_experimental)
+                };
+                list_box.append(&view);
+            })
+        };
+        refresh();
+
+        let on_search = refresh.clone();
+        search_entry.connect_search_changed(move |_| on_search());
+        experimental_row.connect_active_notify(move |_| refresh());
+
+        clamp.set_child(Some(&content_box));
+        scrolled.set_child(Some(&clamp));
+        toolbar_view.set_content(Some(&scrolled));
+
+        self.set_child(Some(&toolbar_view));
+    }
 
-        // Create a PreferencesGroup per manufacturer
-        for (maker, devices) in &grouped {
-            let group = adw::PreferencesGroup::builder()
+    /// One collapsible section per manufacturer
+    fn maker_sections(&self, db: &DeviceDatabase, include_experimental: bool) -> gtk::Widget {
+        let connected = self.imp().connected.borrow().clone();
+        let group = adw::PreferencesGroup::new();
+        for (maker, devices) in &db.devices_by_maker_filtered(include_experimental, connected.as_deref()) {
+            let count = devices.len();
+            let subtitle = ngettext("{} device", "{} devices", count as u32)
+                .replace("{}", &count.to_string());
+            let expander = adw::ExpanderRow::builder()
                 .title(maker)
+                .subtitle(&subtitle)
                 .build();
 
             // Flatten all display names (primary + variants) into a single sorted list
@@ -130,42 +199,71 @@ impl DeviceBrowserPage {
             rows.sort_by(|a, b| a.0.cmp(&b.0));
 
             for (display_name, codename, experimental) in &rows {
-                let subtitle = if *experimental {
-                    format!("{} (Experimental)", codename)
-                } else {
-                    codename.clone()
-                };
+                expander.add_row(&self.device_row(display_name, codename, *experimental));
+            }
+
+            group.add(&expander);
+        }
+        group.upcast()
+    }
 
-                let row = adw::ActionRow::builder()
-                    .title(display_name)
-                    .subtitle(&subtitle)
-                    .activatable(true)
-                    .build();
+    /// Devices matching `query`, best match first
+    fn search_results(&self, db: &DeviceDatabase, query: &str, include_experimental: bool) -> gtk::Widget {
+        let connected = self.imp().connected.borrow().clone();
+        let matches: Vec<_> = db
+            .search(query)
+            .into_iter()
+            .filter(|device| DeviceDatabase::listed(device, include_experimental, connected.as_deref()))
+            .collect();
 
-                let icon = gtk::Image::from_icon_name("phone-symbolic");
-                icon.set_pixel_size(32);
-                row.add_prefix(&icon);
+        if matches.is_empty() {
+            return adw::StatusPage::builder()
+                .icon_name("system-search-symbolic")
+                .title("No Matching Devices")
+                .description(if include_experimental {
+                    "Try the device's codename, or fewer words"
+                } else {
+                    "Try the device's codename, or show experimental devices"
+                })
+                .build()
+                .upcast();
+        }
 
-                let chevron = gtk::Image::from_icon_name("go-next-symbolic");
-                row.add_suffix(&chevron);
+        let group = adw::PreferencesGroup::new();
+        for device in &matches {
+            group.add(&self.device_row(&device.name, &device.codename, device.experimental));
+        }
+        group.upcast()
+    }
 
-                let page_clone = self.clone();
-                let codename = codename.clone();
-                row.connect_activated(move |_| {
-                    page_clone.emit_by_name::<()>("device-selected", &[&codename]);
-                });
+    /// A row that emits `device-selected` with `codename` when activated
+    fn device_row(&self, title: &str, codename: &str, experimental: bool) -> adw::ActionRow {
+        let subtitle = if experimental {
+            format!("{} (Experimental)", codename)
+        } else {
+            codename.to_string()
+        };
 
-                group.add(&row);
-            }
+        let row = adw::ActionRow::builder()
+            .title(title)
+            .subtitle(&subtitle)
+            .activatable(true)
+            .build();
 
-            content_box.append(&group);
-        }
+        let icon = gtk::Image::from_icon_name("phone-symbolic");
+        icon.set_pixel_size(32);
+        row.add_prefix(&icon);
 
-        clamp.set_child(Some(&content_box));
-        scrolled.set_child(Some(&clamp));
-        toolbar_view.set_content(Some(&scrolled));
+        let chevron = gtk::Image::from_icon_name("go-next-symbolic");
+        row.add_suffix(&chevron);
 
-        self.set_child(Some(&toolbar_view));
+        let page_clone = self.clone();
+        let codename = codename.to_string();
+        row.connect_activated(move |_| {
+            page_clone.emit_by_name::<()>("device-selected", &[&codename]);
+        });
+
+        row
     }
 
     pub fn connect_device_selected<F: Fn(&Self, String) + 'static>(&self, f: F) -> glib::SignalHandlerId {
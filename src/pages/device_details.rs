@@ -2,18 +2,28 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::config;
-use crate::models::Device;
-use crate::models::device_info::DeviceInfo;
-use crate::models::distro_config::{ChannelConfig, CompatibilityInfo, DistroConfig, InterfaceConfig};
-use crate::models::installer::{InstallerConfig, Step};
+use crate::flashing::{channel_info, postmarketos, upstream_probe, ChannelBuild, DroidianInstaller, EosInstaller, FactoryImageInstaller, GithubReleaseSource, LastInstall, LineageosInstaller, LocalImageInstaller, MobianInstaller, PartitionBackup, PostInstallSetup, PostmarketosInstaller, TemporaryBootInstaller, UbportsInstaller, UpstreamCheck};
+use crate::flashing::local_images::{self, COMMON_PARTITIONS, LocalImageMapping};
+use crate::hardware::{device_report, heimdall, identity_check, DeviceMode};
+use crate::hardware::mode_guard::{self, StartMode};
+use crate::hardware::prerequisites::{self, CheckOutcome};
+use crate::models::{Device, DeviceDatabase, DeviceStub, PartitionImage};
+use crate::models::device_info::{DeviceInfo, HardwareIdentity};
+use crate::models::samsung;
+use crate::models::distro_config::{ChannelConfig, CompatibilityInfo, DeviceDistroConfig, DistroAvailability, DistroConfig, InterfaceConfig, TemporaryBootConfig};
+use crate::models::installer::{InstallerConfig, Prerequisite, Step};
+use crate::pages::accessibility::{busy_indicator, status_icon};
+use crate::pages::connection_indicator::ConnectionIndicator;
 use crate::pages::flashing::FlashingPage;
 use crate::pages::safety::SafetyPage;
+use crate::utils::data_check;
 use crate::utils::yaml_parser::YamlParser;
 use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 
 mod imp {
     use super::*;
@@ -26,20 +36,37 @@ mod imp {
         #[template_child]
         pub main_menu_button: TemplateChild<gtk::MenuButton>,
         #[template_child]
+        pub header_bar: TemplateChild<adw::HeaderBar>,
+        #[template_child]
         pub device_codename_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub status_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
         pub specs_group: TemplateChild<adw::PreferencesGroup>,
         #[template_child]
+        pub known_issues_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub contribute_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
         pub install_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub unsupported_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub flash_local_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub recovery_banner: TemplateChild<adw::Banner>,
 
         pub device: RefCell<Option<Device>>,
         pub supported: Cell<bool>,
+        pub download_only: Cell<bool>,
+        /// `post_install_commands` of the distro being installed
+        pub post_install_commands: RefCell<Vec<String>>,
+        /// Build the install was pinned to on the build selection page
+        pub pinned_build: RefCell<Option<ChannelBuild>>,
         pub status_rows: RefCell<Vec<adw::ActionRow>>,
         pub specs_rows: RefCell<Vec<adw::ActionRow>>,
+        pub known_issue_rows: RefCell<Vec<adw::ActionRow>>,
+        pub contribute_rows: RefCell<Vec<adw::ActionRow>>,
     }
 
     #[glib::object_subclass]
@@ -56,6 +83,12 @@ mod imp {
             klass.install_action("page.unlock", None, move |page, _, _| {
                 page.on_unlock_clicked();
             });
+            klass.install_action("page.flash-local", None, move |page, _, _| {
+                page.on_flash_local_clicked();
+            });
+            klass.install_action("page.recover", None, move |page, _, _| {
+                page.show_recovery_page();
+            });
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -107,6 +140,14 @@ impl DeviceDetailsPage {
             imp.unsupported_label.set_visible(true);
         }
 
+        // Local images only need a connected device, not a supported one
+        imp.flash_local_button.set_visible(!browse_mode);
+
+        imp.recovery_banner.set_revealed(false);
+        if supported {
+            self.offer_recovery_in_fastboot(device);
+        }
+
         // Adjust status group for browse mode
         if browse_mode {
             imp.status_group.set_title("Setup Instructions");
@@ -119,6 +160,8 @@ impl DeviceDetailsPage {
 
         // Populate specs group from info.yml
         self.load_and_populate_specs(device);
+
+        self.populate_contribute_group(device, supported);
     }
 
     pub fn set_menu_model(&self, model: &gio::MenuModel) {
@@ -151,6 +194,15 @@ impl DeviceDetailsPage {
             rows.push(info_row);
 
             rows.push(self.make_action_row("Bootloader", "Connect device to check"));
+
+            if !gio::NetworkMonitor::default().is_network_available() {
+                let offline_row = adw::ActionRow::builder()
+                    .title("Offline")
+                    .subtitle("Device and distro details still work; current versions and download sizes are unavailable offline")
+                    .build();
+                offline_row.add_prefix(&status_icon("network-offline-symbolic", None, "Offline"));
+                rows.push(offline_row);
+            }
         } else {
             // Live mode: show all runtime info
             if let Some(ref serial) = device.serial {
@@ -191,12 +243,49 @@ impl DeviceDetailsPage {
             rows.push(self.make_action_row("Bootloader", lock_text));
         }
 
+        if self.imp().supported.get()
+            && let Some(row) = self.distro_support_row(device)
+        {
+            rows.push(row);
+        }
+
         for row in &rows {
             imp.status_group.add(row);
         }
         *imp.status_rows.borrow_mut() = rows;
     }
 
+    /// "Fully supported", or which distros the device is limited to
+    fn distro_support_row(&self, device: &Device) -> Option<adw::ActionRow> {
+        let config = self.load_distro_file(device)?;
+        let availability = config.availability();
+        let names = |available: bool| {
+            availability
+                .iter()
+                .filter(|a| a.is_available() == available)
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let row = if config.is_fully_supported() {
+            let row = adw::ActionRow::builder()
+                .title("Fully Supported")
+                .subtitle(names(true))
+                .build();
+            row.add_prefix(&status_icon("emblem-ok-symbolic", Some("success"), "Fully supported"));
+            row
+        } else {
+            let row = adw::ActionRow::builder()
+                .title("Supported for These Distros Only")
+                .subtitle(format!("{}\nNot available: {}", names(true), names(false)))
+                .build();
+            row.add_prefix(&status_icon("dialog-information-symbolic", None, "Partially supported"));
+            row
+        };
+        Some(row)
+    }
+
     fn load_and_populate_specs(&self, device: &Device) {
         let imp = self.imp();
 
@@ -210,38 +299,191 @@ impl DeviceDetailsPage {
             Some(info) => info,
             None => {
                 imp.specs_group.set_visible(false);
+                self.populate_known_issues(&[]);
                 return;
             }
         };
 
+        self.populate_known_issues(&info.device.known_issues);
+
         let mut rows = Vec::new();
         let specs = &info.device.specs;
         let display = &info.device.display;
 
-        rows.push(self.make_action_row("SoC", &specs.soc));
-        rows.push(self.make_action_row("CPU", &specs.cpu));
-        rows.push(self.make_action_row("GPU", &specs.gpu));
-        rows.push(self.make_action_row("RAM", &specs.ram));
-        rows.push(self.make_action_row("Storage", &specs.storage));
-        rows.push(self.make_action_row("Battery", &specs.battery));
-        rows.push(self.make_action_row("Display", &format!(
-            "{} {} ({})", display.size, display.panel_type, display.resolution
-        )));
+        // Display summary from whichever parts are known, e.g. "6.1 in OLED (2520x1080)"
+        let mut display_summary = [display.size.as_deref(), display.panel_type.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Some(ref resolution) = display.resolution {
+            if display_summary.is_empty() {
+                display_summary = resolution.clone();
+            } else {
+                display_summary = format!("{} ({})", display_summary, resolution);
+            }
+        }
+
+        let values = [
+            ("SoC", specs.soc.as_deref()),
+            ("CPU", specs.cpu.as_deref()),
+            ("GPU", specs.gpu.as_deref()),
+            ("RAM", specs.ram.as_deref()),
+            ("Storage", specs.storage.as_deref()),
+            ("Battery", specs.battery.as_deref()),
+            ("Display", Some(display_summary.as_str())),
+        ];
+        for (title, value) in values {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                rows.push(self.make_action_row(title, value));
+            }
+        }
 
         for row in &rows {
             imp.specs_group.add(row);
         }
+        imp.specs_group.set_visible(!rows.is_empty());
         *imp.specs_rows.borrow_mut() = rows;
-        imp.specs_group.set_visible(true);
+    }
+
+    fn populate_known_issues(&self, issues: &[String]) {
+        let imp = self.imp();
+
+        for row in imp.known_issue_rows.borrow().iter() {
+            imp.known_issues_group.remove(row);
+        }
+        imp.known_issue_rows.borrow_mut().clear();
+
+        let mut rows = Vec::new();
+        for issue in issues {
+            let row = adw::ActionRow::builder()
+                .title(issue)
+                .title_lines(0)
+                .build();
+            let icon = status_icon("dialog-warning-symbolic", Some("warning"), "Known issue");
+            row.add_prefix(&icon);
+            imp.known_issues_group.add(&row);
+            rows.push(row);
+        }
+        imp.known_issues_group.set_visible(!rows.is_empty());
+        *imp.known_issue_rows.borrow_mut() = rows;
+    }
+
+    /// For a connected device with no database entry, read what we can from
+    /// it and offer a pre-filled info.yml for a support request.
+    fn populate_contribute_group(&self, device: &Device, supported: bool) {
+        let imp = self.imp();
+
+        for row in imp.contribute_rows.borrow().iter() {
+            imp.contribute_group.remove(row);
+        }
+        imp.contribute_rows.borrow_mut().clear();
+
+        let Some(serial) = device.serial.clone().filter(|_| !supported) else {
+            imp.contribute_group.set_visible(false);
+            return;
+        };
+        imp.contribute_group.set_visible(true);
+
+        let reading_row = adw::ActionRow::builder()
+            .title("Reading device details…")
+            .build();
+        reading_row.add_prefix(&busy_indicator());
+        imp.contribute_group.add(&reading_row);
+        imp.contribute_rows.borrow_mut().push(reading_row);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let device_clone = device.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let stub = rt.block_on(device_report::probe_stub(&serial, &device_clone));
+            let _ = sender.send(stub);
+        });
+
+        let page = self.downgrade();
+        let codename = device.codename.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let Some(page) = page.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let stub = match receiver.try_recv() {
+                Ok(stub) => stub,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(_) => return glib::ControlFlow::Break,
+            };
+            // The page may have moved on to another device meanwhile
+            let current = page.imp().device.borrow().as_ref().map(|d| d.codename.clone());
+            if current.as_deref() == Some(codename.as_str()) {
+                page.show_device_stub(&stub);
+            }
+            glib::ControlFlow::Break
+        });
+    }
+
+    fn show_device_stub(&self, stub: &DeviceStub) {
+        let imp = self.imp();
+
+        for row in imp.contribute_rows.borrow().iter() {
+            imp.contribute_group.remove(row);
+        }
+
+        let mut rows = Vec::new();
+        for (title, value) in stub.detected() {
+            rows.push(self.make_action_row(title, value));
+        }
+
+        let yml = stub.to_info_yml();
+        let yml_row = adw::ActionRow::builder()
+            .title("Device Description")
+            .subtitle(format!("Skeleton for {}", stub.repo_path()))
+            .build();
+
+        let copy_btn = gtk::Button::builder()
+            .icon_name("edit-copy-symbolic")
+            .tooltip_text("Copy info.yml")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["flat"])
+            .build();
+        let page = self.downgrade();
+        copy_btn.connect_clicked(move |btn| {
+            btn.clipboard().set_text(&yml);
+            let window = page
+                .upgrade()
+                .and_then(|p| p.root())
+                .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok());
+            if let Some(window) = window {
+                window.show_toast("Device description copied to clipboard");
+            }
+        });
+        yml_row.add_suffix(&copy_btn);
+
+        let report_btn = gtk::Button::builder()
+            .label("Request Support")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["suggested-action"])
+            .build();
+        report_btn.connect_clicked(move |_| {
+            let launcher = gtk::UriLauncher::new(NEW_ISSUE_URL);
+            glib::spawn_future_local(async move {
+                if let Err(e) = launcher.launch_future(None::<&gtk::Window>).await {
+                    log::warn!("Failed to launch URI: {}", e);
+                }
+            });
+        });
+        yml_row.add_suffix(&report_btn);
+        rows.push(yml_row);
+
+        for row in &rows {
+            imp.contribute_group.add(row);
+        }
+        *imp.contribute_rows.borrow_mut() = rows;
     }
 
     fn load_device_info(&self, device: &Device) -> Option<DeviceInfo> {
-        let possible_dirs = vec![
-            std::path::PathBuf::from(config::PKGDATADIR).join("devices"),
-            std::path::PathBuf::from("/app/share/sidestep/devices"),
-            std::path::PathBuf::from("data/devices"),
-            std::path::PathBuf::from("devices"),
-        ];
+        let possible_dirs = data_check::devices_dir_candidates();
 
         let manufacturer = maker_to_dir(&device.maker);
 
@@ -285,9 +527,17 @@ impl DeviceDetailsPage {
             return;
         };
 
-        let distros = self.load_all_distros(&device);
+        let Some(distro_file) = self.load_distro_file(&device) else {
+            return;
+        };
+        let availability = distro_file.availability();
+        let distros: Vec<DistroConfig> = distro_file
+            .available_distros
+            .into_iter()
+            .filter(|d| availability.iter().any(|a| a.id == d.id && a.is_available()))
+            .collect();
         if distros.is_empty() {
-            log::error!("No distros found for {}", device.codename);
+            log::error!("No installable distros found for {}", device.codename);
             return;
         }
 
@@ -338,13 +588,23 @@ impl DeviceDetailsPage {
         title_box.append(&subtitle_label);
         content_box.append(&title_box);
 
+        // Download sizes come from the built-in database where it knows the distro
+        let known_sizes = DeviceDatabase::new().get_distros(&device.codename);
+
         // Distro list group
         let distro_group = adw::PreferencesGroup::new();
 
         for distro in &distros {
+            let subtitle = match known_sizes
+                .iter()
+                .find(|d| d.name.eq_ignore_ascii_case(&distro.name) && d.download_size_bytes.is_some())
+            {
+                Some(known) => format!("{} · {}", distro.developer, known.download_size_string()),
+                None => distro.developer.clone(),
+            };
             let row = adw::ActionRow::builder()
                 .title(&distro.name)
-                .subtitle(&distro.developer)
+                .subtitle(&subtitle)
                 .activatable(true)
                 .build();
 
@@ -384,6 +644,26 @@ impl DeviceDetailsPage {
         }
 
         content_box.append(&distro_group);
+
+        let unavailable: Vec<&DistroAvailability> =
+            availability.iter().filter(|a| !a.is_available()).collect();
+        if !unavailable.is_empty() {
+            let unavailable_group = adw::PreferencesGroup::builder()
+                .title("Not Available for This Device")
+                .build();
+            for distro in unavailable {
+                let row = adw::ActionRow::builder()
+                    .title(&distro.name)
+                    .subtitle(distro.unavailable_reason.as_deref().unwrap_or_default())
+                    .sensitive(false)
+                    .build();
+                let icon = gtk::Image::from_icon_name(&format!("{}-symbolic", distro.id));
+                icon.set_pixel_size(32);
+                row.add_prefix(&icon);
+                unavailable_group.add(&row);
+            }
+            content_box.append(&unavailable_group);
+        }
         clamp.set_child(Some(&content_box));
         scrolled.set_child(Some(&clamp));
         toolbar_view.set_content(Some(&scrolled));
@@ -397,12 +677,13 @@ impl DeviceDetailsPage {
     }
 
     fn load_all_distros(&self, device: &Device) -> Vec<DistroConfig> {
-        let possible_paths = vec![
-            std::path::PathBuf::from(config::PKGDATADIR).join("devices"),
-            std::path::PathBuf::from("/app/share/sidestep/devices"),
-            std::path::PathBuf::from("data/devices"),
-            std::path::PathBuf::from("devices"),
-        ];
+        self.load_distro_file(device)
+            .map(|config| config.available_distros)
+            .unwrap_or_default()
+    }
+
+    fn load_distro_file(&self, device: &Device) -> Option<DeviceDistroConfig> {
+        let possible_paths = data_check::devices_dir_candidates();
         let devices_path = possible_paths
             .into_iter()
             .find(|p| p.exists())
@@ -412,10 +693,10 @@ impl DeviceDetailsPage {
 
         let parser = YamlParser::new(devices_path);
         match parser.parse_device_config(&manufacturer, &device.codename) {
-            Ok(config) => config.available_distros,
+            Ok(config) => Some(config),
             Err(e) => {
                 log::error!("Failed to load distros.yml for {}: {:#}", device.codename, e);
-                Vec::new()
+                None
             }
         }
     }
@@ -427,7 +708,7 @@ impl DeviceDetailsPage {
     fn show_compatibility_page(
         &self,
         nav_view: &adw::NavigationView,
-        _device: &Device,
+        device: &Device,
         distro_id: &str,
         distro_name: &str,
         compat: &CompatibilityInfo,
@@ -481,8 +762,7 @@ impl DeviceDetailsPage {
                 let row = adw::ActionRow::builder()
                     .title(item)
                     .build();
-                let icon = gtk::Image::from_icon_name("object-select-symbolic");
-                icon.add_css_class("success");
+                let icon = status_icon("object-select-symbolic", Some("success"), "Working");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -498,8 +778,7 @@ impl DeviceDetailsPage {
                 let row = adw::ActionRow::builder()
                     .title(item)
                     .build();
-                let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
-                icon.add_css_class("warning");
+                let icon = status_icon("dialog-warning-symbolic", Some("warning"), "Partially working");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -515,8 +794,7 @@ impl DeviceDetailsPage {
                 let row = adw::ActionRow::builder()
                     .title(item)
                     .build();
-                let icon = gtk::Image::from_icon_name("process-stop-symbolic");
-                icon.add_css_class("error");
+                let icon = status_icon("process-stop-symbolic", Some("error"), "Not working");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -532,8 +810,7 @@ impl DeviceDetailsPage {
                 let row = adw::ActionRow::builder()
                     .title(item)
                     .build();
-                let icon = gtk::Image::from_icon_name("dialog-question-symbolic");
-                icon.add_css_class("dim-label");
+                let icon = status_icon("dialog-question-symbolic", Some("dim-label"), "Untested");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -549,7 +826,7 @@ impl DeviceDetailsPage {
                 let row = adw::ActionRow::builder()
                     .title(note)
                     .build();
-                let icon = gtk::Image::from_icon_name("dialog-information-symbolic");
+                let icon = status_icon("dialog-information-symbolic", None, "Note");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -577,7 +854,56 @@ impl DeviceDetailsPage {
             self_clone.on_install_clicked(&distro_id_owned);
         });
 
+        let download_btn = gtk::Button::builder()
+            .label("Download Only")
+            .tooltip_text("Fetch and verify the images now, flash them later")
+            .css_classes(vec!["flat".to_string(), "pill".to_string()])
+            .width_request(250)
+            .height_request(50)
+            .build();
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        download_btn.connect_clicked(move |_| {
+            self_clone.on_download_only_clicked(&nav_clone, &device_clone, &distro_id_owned);
+        });
+
+        button_box.set_spacing(12);
         button_box.append(&continue_btn);
+        button_box.append(&download_btn);
+
+        // Distros that publish a standalone boot image can be tried first
+        if let Some(boot) = self
+            .load_distro_config(device, distro_id)
+            .and_then(|d| d.temporary_boot)
+        {
+            let try_btn = gtk::Button::builder()
+                .label("Try Without Installing")
+                .tooltip_text("Boot the system once with fastboot boot; nothing is written")
+                .css_classes(vec!["flat".to_string(), "pill".to_string()])
+                .width_request(250)
+                .height_request(50)
+                .build();
+
+            let self_clone = self.clone();
+            let nav_clone = nav_view.clone();
+            let device_clone = device.clone();
+            let distro_id_owned = distro_id.to_string();
+            let distro_name_owned = distro_name.to_string();
+            try_btn.connect_clicked(move |_| {
+                let nav = nav_clone.clone();
+                let device = device_clone.clone();
+                let distro_id = distro_id_owned.clone();
+                let distro_name = distro_name_owned.clone();
+                let boot = boot.clone();
+                self_clone.with_device_in_mode(&device_clone, TemporaryBootInstaller::REQUIRED_MODE, move |page| {
+                    page.launch_temporary_boot(&nav, &device, &distro_id, &distro_name, &boot);
+                });
+            });
+            button_box.append(&try_btn);
+        }
         content_box.append(&button_box);
 
         clamp.set_child(Some(&content_box));
@@ -601,6 +927,7 @@ impl DeviceDetailsPage {
             Some(d) => d,
             None => return,
         };
+        self.imp().download_only.set(false);
 
         let Some(nav_view) = self.ancestor(adw::NavigationView::static_type())
             .and_then(|w| w.downcast::<adw::NavigationView>().ok())
@@ -609,15 +936,26 @@ impl DeviceDetailsPage {
             return;
         };
 
-        // If bootloader is confirmed unlocked, skip the confirmation prompt
-        if device.is_locked == Some(false) {
-            self.show_safety_page(&nav_view, &device, distro_id);
-        } else {
-            // Locked or unknown — ask the user to confirm
-            self.show_unlock_check_page(&nav_view, &device, distro_id);
+        // Only ask about the bootloader when the device couldn't say
+        match device.is_locked {
+            Some(false) => self.show_safety_page(&nav_view, &device, distro_id),
+            Some(true) => self.on_unlock_clicked(),
+            None => self.show_unlock_check_page(&nav_view, &device, distro_id),
         }
     }
 
+    /// Stage images in the cache without flashing. Nothing is written to the
+    /// device, so the unlock, safety and firmware prerequisite pages are skipped.
+    fn on_download_only_clicked(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+    ) {
+        self.imp().download_only.set(true);
+        self.proceed_after_prerequisites(nav_view, device, distro_id);
+    }
+
     fn show_unlock_check_page(
         &self,
         nav_view: &adw::NavigationView,
@@ -696,190 +1034,548 @@ impl DeviceDetailsPage {
         let device_clone = device.clone();
         let distro_id_owned = distro_id.to_string();
         safety_page.connect_confirmed(move |_| {
-            self_clone.proceed_to_installer(&nav_clone, &device_clone, &distro_id_owned);
+            self_clone.check_battery(&nav_clone, &device_clone, &distro_id_owned);
         });
 
         nav_view.push(&safety_page);
     }
 
-    /// Continue the install flow after bootloader is confirmed unlocked.
-    fn proceed_to_installer(
+    /// Hold the install while the charge is under the device's
+    /// `battery_min`. Devices that can't report a level carry on.
+    fn check_battery(
         &self,
         nav_view: &adw::NavigationView,
         device: &Device,
         distro_id: &str,
     ) {
-        // Try loading installer YAML config for prerequisites
-        if let Some(config) = self.load_installer_config(device, distro_id) {
-            if let Some(prereq) = config.prerequisites.first() {
-                self.show_prerequisite_page(nav_view, device, distro_id, &config, prereq);
-                return;
-            }
-        }
-
-        // No prerequisites — go straight to channel selection / install
-        self.proceed_after_prerequisites(nav_view, device, distro_id);
-    }
-
-    fn load_installer_config(&self, device: &Device, distro_id: &str) -> Option<InstallerConfig> {
-        let possible_dirs = vec![
-            std::path::PathBuf::from(config::SIDESTEP_DATA_DIR),
-            std::path::PathBuf::from(config::PKGDATADIR),
-            std::path::PathBuf::from("/app/share/sidestep"),
-            std::path::PathBuf::from("data"),
-        ];
+        let Some(serial) = device.serial.clone().filter(|_| device.battery_min > 0) else {
+            self.show_backup_page_if_needed(nav_view, device, distro_id);
+            return;
+        };
 
-        for dir in possible_dirs {
-            let config_path = dir
-                .join("devices")
-                .join(maker_to_dir(&device.maker))
-                .join(device.codename.to_lowercase())
-                .join("installers")
-                .join(format!("{}.yml", distro_id));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let minimum = device.battery_min;
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+            let _ = sender.send(rt.block_on(prerequisites::battery(&serial, minimum)));
+        });
 
-            if let Ok(content) = std::fs::read_to_string(&config_path) {
-                match serde_yaml::from_str::<InstallerConfig>(&content) {
-                    Ok(config) => return Some(config),
-                    Err(e) => {
-                        log::error!("Failed to parse {}: {}", config_path.display(), e);
-                        return None;
-                    }
+        let page = self.downgrade();
+        let nav_view = nav_view.clone();
+        let device = device.clone();
+        let distro_id = distro_id.to_string();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let Some(page) = page.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let outcome = match receiver.try_recv() {
+                Ok(outcome) => outcome,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(_) => CheckOutcome::Manual,
+            };
+            match outcome {
+                CheckOutcome::Unsatisfied(found) => {
+                    page.show_low_battery_page(&nav_view, &device, &distro_id, &found);
+                }
+                CheckOutcome::Satisfied(_) | CheckOutcome::Manual => {
+                    page.show_backup_page_if_needed(&nav_view, &device, &distro_id);
                 }
             }
-        }
-
-        log::debug!("No installer config found for {}/{}", device.codename, distro_id);
-        None
+            glib::ControlFlow::Break
+        });
     }
 
-    fn show_prerequisite_page(
+    fn show_low_battery_page(
         &self,
         nav_view: &adw::NavigationView,
         device: &Device,
         distro_id: &str,
-        config: &InstallerConfig,
-        prereq: &crate::models::installer::Prerequisite,
+        found: &str,
     ) {
         let toolbar_view = adw::ToolbarView::new();
         toolbar_view.add_top_bar(&adw::HeaderBar::new());
 
         let status_page = adw::StatusPage::builder()
-            .title(&prereq.title)
-            .description(&prereq.message)
-            .icon_name("system-help-symbolic")
-            .build();
-
-        let buttons_box = gtk::Box::builder()
-            .orientation(gtk::Orientation::Vertical)
-            .spacing(12)
-            .halign(gtk::Align::Center)
+            .title("Battery Too Low")
+            .description(format!(
+                "{}. A phone that runs out of charge while flashing can be left unable to boot. Charge it to at least {}% and check again.",
+                found, device.battery_min
+            ))
+            .icon_name("battery-level-10-symbolic")
             .build();
 
-        let yes_btn = gtk::Button::builder()
-            .label("Yes, I have it")
+        let check_btn = gtk::Button::builder()
+            .label("Check Again")
             .css_classes(vec!["suggested-action", "pill"])
+            .halign(gtk::Align::Center)
             .width_request(200)
             .height_request(50)
             .build();
-
-        let no_btn = gtk::Button::builder()
-            .label("No, I don't")
-            .css_classes(vec!["pill"])
-            .width_request(200)
-            .height_request(50)
-            .build();
-
-        buttons_box.append(&yes_btn);
-        buttons_box.append(&no_btn);
-        status_page.set_child(Some(&buttons_box));
+        status_page.set_child(Some(&check_btn));
         toolbar_view.set_content(Some(&status_page));
 
         let page = adw::NavigationPage::builder()
-            .title(&prereq.title)
+            .title("Battery Check")
             .child(&toolbar_view)
             .build();
 
-        // "Yes" → proceed to channel selection / install
         let self_clone = self.clone();
         let nav_clone = nav_view.clone();
         let device_clone = device.clone();
         let distro_id_owned = distro_id.to_string();
-        let on_success = prereq.on_success.clone();
-        let config_clone = config.clone();
-        yes_btn.connect_clicked(move |_| {
-            // Check if "on_success" points to a step or directly to flash
-            if let Some(step) = config_clone.steps.get(&on_success) {
-                match step {
-                    Step::Flash { .. } => {
-                        self_clone.proceed_after_prerequisites(&nav_clone, &device_clone, &distro_id_owned);
-                    }
-                    Step::Instruction { message, link, action_label } => {
-                        self_clone.show_instruction_page(&nav_clone, message, link.as_deref(), action_label.as_deref());
-                    }
-                    Step::FlashAndroid { android_version, url, sha256, message } => {
-                        self_clone.show_flash_android_confirmation(
-                            &nav_clone, &device_clone, android_version, url, sha256, message,
-                        );
-                    }
-                }
-            } else {
-                self_clone.proceed_after_prerequisites(&nav_clone, &device_clone, &distro_id_owned);
-            }
-        });
-
-        // "No" → show instruction page
-        let self_clone2 = self.clone();
-        let nav_clone2 = nav_view.clone();
-        let on_failure = prereq.on_failure.clone();
-        let config_clone2 = config.clone();
-        let device_clone2 = device.clone();
-        no_btn.connect_clicked(move |_| {
-            if let Some(step) = config_clone2.steps.get(&on_failure) {
-                match step {
-                    Step::Instruction { message, link, action_label } => {
-                        self_clone2.show_instruction_page(&nav_clone2, message, link.as_deref(), action_label.as_deref());
-                    }
-                    Step::Flash { .. } => {
-                        log::warn!("on_failure pointed to a flash step, ignoring");
-                    }
-                    Step::FlashAndroid { android_version, url, sha256, message } => {
-                        self_clone2.show_flash_android_confirmation(
-                            &nav_clone2, &device_clone2, android_version, url, sha256, message,
-                        );
-                    }
-                }
-            }
+        check_btn.connect_clicked(move |_| {
+            nav_clone.pop();
+            self_clone.check_battery(&nav_clone, &device_clone, &distro_id_owned);
         });
 
         nav_view.push(&page);
     }
 
-    fn show_instruction_page(
+    /// Offer to copy IMEI/DRM-critical partitions off the device before anything
+    /// is wiped. Devices without such partitions in info.yml skip straight on.
+    fn show_backup_page_if_needed(
         &self,
         nav_view: &adw::NavigationView,
-        message: &str,
-        link: Option<&str>,
-        action_label: Option<&str>,
+        device: &Device,
+        distro_id: &str,
     ) {
+        let partitions = self
+            .load_device_info(device)
+            .map(|info| info.device.backup_partitions)
+            .unwrap_or_default();
+        let Some(serial) = device.serial.clone().filter(|_| !partitions.is_empty()) else {
+            self.proceed_to_installer(nav_view, device, distro_id);
+            return;
+        };
+
+        let previous = PartitionBackup::existing(&device.codename, &serial);
+
         let toolbar_view = adw::ToolbarView::new();
         toolbar_view.add_top_bar(&adw::HeaderBar::new());
 
         let status_page = adw::StatusPage::builder()
-            .title("Additional Steps Required")
-            .description(message)
-            .icon_name("dialog-information-symbolic")
+            .title("Back Up Critical Partitions")
+            .description("This device keeps IMEI, calibration or DRM data on partitions that cannot be restored once lost. Save a copy to this computer before anything is wiped. Reading them requires root access on the device.")
+            .icon_name("drive-harddisk-symbolic")
             .build();
 
-        if let (Some(url), Some(label)) = (link, action_label) {
-            let buttons_box = gtk::Box::builder()
-                .orientation(gtk::Orientation::Vertical)
-                .spacing(12)
-                .halign(gtk::Align::Center)
-                .build();
+        let content_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(24)
+            .build();
 
-            let link_btn = gtk::Button::builder()
-                .label(label)
-                .css_classes(vec!["suggested-action", "pill"])
+        let clamp = adw::Clamp::builder()
+            .maximum_size(600)
+            .build();
+
+        let group = adw::PreferencesGroup::builder()
+            .title("Partitions")
+            .build();
+        for partition in &partitions {
+            let row = adw::ActionRow::builder()
+                .title(partition)
+                .build();
+            group.add(&row);
+        }
+        content_box.append(&group);
+
+        if let Some(ref manifest) = previous {
+            let saved = PartitionBackup::backup_dir(&manifest.codename, &manifest.serial);
+            let row = adw::ActionRow::builder()
+                .title("Backup already taken")
+                .subtitle(&saved.display().to_string())
+                .build();
+            let icon = status_icon("object-select-symbolic", Some("success"), "Done");
+            row.add_prefix(&icon);
+            let done_group = adw::PreferencesGroup::new();
+            done_group.add(&row);
+            content_box.append(&done_group);
+        }
+
+        let buttons_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .halign(gtk::Align::Center)
+            .build();
+
+        let backup_btn = gtk::Button::builder()
+            .label(if previous.is_some() { "Back Up Again" } else { "Back Up Now" })
+            .css_classes(vec!["suggested-action", "pill"])
+            .width_request(250)
+            .height_request(50)
+            .build();
+
+        let skip_btn = gtk::Button::builder()
+            .label(if previous.is_some() { "Continue" } else { "Continue Without Backup" })
+            .css_classes(vec!["pill"])
+            .width_request(250)
+            .height_request(50)
+            .build();
+
+        buttons_box.append(&backup_btn);
+        buttons_box.append(&skip_btn);
+        content_box.append(&buttons_box);
+        clamp.set_child(Some(&content_box));
+        status_page.set_child(Some(&clamp));
+        toolbar_view.set_content(Some(&status_page));
+
+        let page = adw::NavigationPage::builder()
+            .title("Backup")
+            .child(&toolbar_view)
+            .build();
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        skip_btn.connect_clicked(move |_| {
+            self_clone.proceed_to_installer(&nav_clone, &device_clone, &distro_id_owned);
+        });
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        let skip_btn_clone = skip_btn.clone();
+        let status_clone = status_page.clone();
+        backup_btn.connect_clicked(move |btn| {
+            btn.set_sensitive(false);
+            btn.set_label("Backing Up...");
+            skip_btn_clone.set_sensitive(false);
+
+            let backup = PartitionBackup::new(
+                serial.clone(),
+                device_clone.codename.clone(),
+                partitions.clone(),
+            );
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create tokio runtime");
+                let _ = sender.send(rt.block_on(backup.run()));
+            });
+
+            let self_clone = self_clone.clone();
+            let nav_clone = nav_clone.clone();
+            let device_clone = device_clone.clone();
+            let distro_id_owned = distro_id_owned.clone();
+            let btn = btn.clone();
+            let skip_btn = skip_btn_clone.clone();
+            let status_page = status_clone.clone();
+            glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+                let Ok(result) = receiver.try_recv() else {
+                    return glib::ControlFlow::Continue;
+                };
+                let window = self_clone.root()
+                    .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok());
+                match result {
+                    Ok(dir) => {
+                        log::info!("Partition backup saved to {}", dir.display());
+                        if let Some(window) = window {
+                            window.show_toast(&format!("Backup saved to {}", dir.display()));
+                        }
+                        self_clone.proceed_to_installer(&nav_clone, &device_clone, &distro_id_owned);
+                    }
+                    Err(e) => {
+                        log::error!("Partition backup failed: {:#}", e);
+                        status_page.set_description(Some(&format!("Backup failed: {:#}", e)));
+                        btn.set_label("Try Again");
+                        btn.set_sensitive(true);
+                        skip_btn.set_sensitive(true);
+                    }
+                }
+                glib::ControlFlow::Break
+            });
+        });
+
+        nav_view.push(&page);
+    }
+
+    /// Continue the install flow after bootloader is confirmed unlocked.
+    fn proceed_to_installer(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+    ) {
+        // Try loading installer YAML config for prerequisites
+        if let Some(config) = self.load_installer_config(device, distro_id) {
+            if let Some(prereq) = config.prerequisites.first() {
+                self.check_prerequisite(nav_view, device, distro_id, &config, prereq);
+                return;
+            }
+        }
+
+        // No prerequisites — go straight to channel selection / install
+        self.proceed_after_prerequisites(nav_view, device, distro_id);
+    }
+
+    fn load_installer_config(&self, device: &Device, distro_id: &str) -> Option<InstallerConfig> {
+        for dir in data_check::devices_dir_candidates() {
+            let config_path = dir
+                .join(maker_to_dir(&device.maker))
+                .join(device.codename.to_lowercase())
+                .join("installers")
+                .join(format!("{}.yml", distro_id));
+
+            if let Ok(content) = std::fs::read_to_string(&config_path) {
+                match serde_yaml::from_str::<InstallerConfig>(&content) {
+                    Ok(config) => return Some(config),
+                    Err(e) => {
+                        log::error!("Failed to parse {}: {}", config_path.display(), e);
+                        return None;
+                    }
+                }
+            }
+        }
+
+        log::debug!("No installer config found for {}/{}", device.codename, distro_id);
+        None
+    }
+
+    /// Check a prerequisite against the device where it can be queried, and
+    /// only ask the user about the ones it can't answer.
+    fn check_prerequisite(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        config: &InstallerConfig,
+        prereq: &Prerequisite,
+    ) {
+        let Some(serial) = device.serial.clone() else {
+            self.show_prerequisite_page(nav_view, device, distro_id, config, prereq);
+            return;
+        };
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let check = prereq.check.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create tokio runtime");
+            let _ = sender.send(rt.block_on(prerequisites::verify(&check, &serial)));
+        });
+
+        let page = self.downgrade();
+        let nav_view = nav_view.clone();
+        let device = device.clone();
+        let distro_id = distro_id.to_string();
+        let config = config.clone();
+        let prereq = prereq.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let Some(page) = page.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let outcome = match receiver.try_recv() {
+                Ok(outcome) => outcome,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(_) => CheckOutcome::Manual,
+            };
+            match outcome {
+                CheckOutcome::Satisfied(found) => {
+                    if let Some(window) = page.root()
+                        .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                    {
+                        window.show_toast(&found);
+                    }
+                    page.follow_prerequisite(&nav_view, &device, &distro_id, &config, &prereq.on_success, true);
+                }
+                CheckOutcome::Unsatisfied(found) => {
+                    page.show_unmet_prerequisite_page(&nav_view, &device, &distro_id, &config, &prereq, &found);
+                }
+                CheckOutcome::Manual => {
+                    page.show_prerequisite_page(&nav_view, &device, &distro_id, &config, &prereq);
+                }
+            }
+            glib::ControlFlow::Break
+        });
+    }
+
+    fn show_prerequisite_page(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        config: &InstallerConfig,
+        prereq: &Prerequisite,
+    ) {
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title(&prereq.title)
+            .description(&prereq.message)
+            .icon_name("system-help-symbolic")
+            .build();
+
+        let buttons_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .halign(gtk::Align::Center)
+            .build();
+
+        let yes_btn = gtk::Button::builder()
+            .label("Yes, I have it")
+            .css_classes(vec!["suggested-action", "pill"])
+            .width_request(200)
+            .height_request(50)
+            .build();
+
+        let no_btn = gtk::Button::builder()
+            .label("No, I don't")
+            .css_classes(vec!["pill"])
+            .width_request(200)
+            .height_request(50)
+            .build();
+
+        buttons_box.append(&yes_btn);
+        buttons_box.append(&no_btn);
+        status_page.set_child(Some(&buttons_box));
+        toolbar_view.set_content(Some(&status_page));
+
+        let page = adw::NavigationPage::builder()
+            .title(&prereq.title)
+            .child(&toolbar_view)
+            .build();
+
+        // "Yes" → proceed to channel selection / install
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        let config_clone = config.clone();
+        let on_success = prereq.on_success.clone();
+        yes_btn.connect_clicked(move |_| {
+            self_clone.follow_prerequisite(&nav_clone, &device_clone, &distro_id_owned, &config_clone, &on_success, true);
+        });
+
+        // "No" → show instruction page
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        let config_clone = config.clone();
+        let on_failure = prereq.on_failure.clone();
+        no_btn.connect_clicked(move |_| {
+            self_clone.follow_prerequisite(&nav_clone, &device_clone, &distro_id_owned, &config_clone, &on_failure, false);
+        });
+
+        nav_view.push(&page);
+    }
+
+    /// Go to the step a prerequisite answer points at. A met prerequisite
+    /// without a matching step carries on to the install.
+    fn follow_prerequisite(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        config: &InstallerConfig,
+        step_id: &str,
+        met: bool,
+    ) {
+        match config.steps.get(step_id) {
+            Some(Step::Flash { .. }) if met => {
+                self.proceed_after_prerequisites(nav_view, device, distro_id);
+            }
+            Some(Step::Flash { .. }) => {
+                log::warn!("on_failure pointed to a flash step, ignoring");
+            }
+            Some(Step::Instruction { message, link, action_label }) => {
+                self.show_instruction_page(nav_view, message, link.as_deref(), action_label.as_deref());
+            }
+            Some(Step::FlashAndroid { android_version, url, sha256, message }) => {
+                self.show_flash_android_confirmation(
+                    nav_view, device, android_version, url, sha256, message,
+                );
+            }
+            None if met => self.proceed_after_prerequisites(nav_view, device, distro_id),
+            None => {}
+        }
+    }
+
+    /// The device was checked and doesn't meet a prerequisite: say what was
+    /// found rather than asking, and offer the config's way to fix it.
+    fn show_unmet_prerequisite_page(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        config: &InstallerConfig,
+        prereq: &Prerequisite,
+        found: &str,
+    ) {
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title(&prereq.title)
+            .description(format!("{}.\n\nThis install can't continue until that is fixed.", found))
+            .icon_name("dialog-warning-symbolic")
+            .build();
+
+        if config.steps.contains_key(&prereq.on_failure) {
+            let fix_btn = gtk::Button::builder()
+                .label("What Should I Do?")
+                .css_classes(vec!["suggested-action", "pill"])
+                .halign(gtk::Align::Center)
+                .width_request(200)
+                .height_request(50)
+                .build();
+
+            let self_clone = self.clone();
+            let nav_clone = nav_view.clone();
+            let device_clone = device.clone();
+            let distro_id_owned = distro_id.to_string();
+            let config_clone = config.clone();
+            let on_failure = prereq.on_failure.clone();
+            fix_btn.connect_clicked(move |_| {
+                self_clone.follow_prerequisite(&nav_clone, &device_clone, &distro_id_owned, &config_clone, &on_failure, false);
+            });
+            status_page.set_child(Some(&fix_btn));
+        }
+        toolbar_view.set_content(Some(&status_page));
+
+        let page = adw::NavigationPage::builder()
+            .title(&prereq.title)
+            .child(&toolbar_view)
+            .build();
+        nav_view.push(&page);
+    }
+
+    fn show_instruction_page(
+        &self,
+        nav_view: &adw::NavigationView,
+        message: &str,
+        link: Option<&str>,
+        action_label: Option<&str>,
+    ) {
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title("Additional Steps Required")
+            .description(message)
+            .icon_name("dialog-information-symbolic")
+            .build();
+
+        if let (Some(url), Some(label)) = (link, action_label) {
+            let buttons_box = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .spacing(12)
+                .halign(gtk::Align::Center)
+                .build();
+
+            let link_btn = gtk::Button::builder()
+                .label(label)
+                .css_classes(vec!["suggested-action", "pill"])
                 .width_request(200)
                 .height_request(50)
                 .build();
@@ -901,64 +1597,328 @@ impl DeviceDetailsPage {
         toolbar_view.set_content(Some(&status_page));
 
         let page = adw::NavigationPage::builder()
-            .title("Instructions")
+            .title("Instructions")
+            .child(&toolbar_view)
+            .build();
+
+        nav_view.push(&page);
+    }
+
+    fn show_channel_selection_page(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        channels: &[ChannelConfig],
+    ) {
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title("Select Channel")
+            .description("Choose which channel to install:")
+            .icon_name("emblem-system-symbolic")
+            .build();
+
+        let buttons_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .halign(gtk::Align::Center)
+            .build();
+
+        // Picking a channel installs its newest build unless asked otherwise
+        let choose_build = channel_info::can_choose_build(distro_id).then(|| {
+            gtk::CheckButton::builder()
+                .label("Choose a specific build")
+                .halign(gtk::Align::Center)
+                .build()
+        });
+
+        for channel in channels {
+            buttons_box.append(&self.channel_button(nav_view, device, distro_id, channel, choose_build.as_ref()));
+        }
+        if let Some(ref choose_build) = choose_build {
+            buttons_box.append(choose_build);
+        }
+
+        // postmarketOS keeps older releases on its image server
+        if distro_id == "postmarketos" {
+            self.append_other_versions_button(&buttons_box, nav_view, device, channels);
+        }
+
+        status_page.set_child(Some(&buttons_box));
+        toolbar_view.set_content(Some(&status_page));
+
+        let page = adw::NavigationPage::builder()
+            .title("Select Channel")
+            .child(&toolbar_view)
+            .build();
+
+        nav_view.push(&page);
+    }
+
+    fn channel_button(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        channel: &ChannelConfig,
+        choose_build: Option<&gtk::CheckButton>,
+    ) -> gtk::Button {
+        let btn = gtk::Button::builder()
+            .label(&channel.label)
+            .css_classes(vec!["suggested-action", "pill"])
+            .width_request(250)
+            .height_request(50)
+            .build();
+
+        // Incomplete channels stay visible but can't be picked
+        if let Err(missing) = channel.validate(distro_id) {
+            log::warn!(
+                "Channel {} of {} is missing {}",
+                channel.id,
+                distro_id,
+                missing.join(", ")
+            );
+            btn.set_sensitive(false);
+            btn.set_tooltip_text(Some(&incomplete_channel_message(&missing)));
+        }
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        let channel_clone = channel.clone();
+        let choose_build = choose_build.cloned();
+        btn.connect_clicked(move |_| {
+            if choose_build.as_ref().is_some_and(|c| c.is_active()) {
+                self_clone.show_build_selection_page(&nav_clone, &device_clone, &distro_id_owned, &channel_clone);
+            } else {
+                self_clone.launch_install(&nav_clone, &device_clone, &distro_id_owned, &channel_clone);
+            }
+        });
+
+        btn
+    }
+
+    /// Recent builds of `channel` with their dates, newest first, so an
+    /// install can be pinned to a known-good build instead of the latest.
+    fn show_build_selection_page(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        channel: &ChannelConfig,
+    ) {
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title("Select Build")
+            .description(format!("Choose which {} build to install:", channel.label))
+            .icon_name("emblem-system-symbolic")
+            .build();
+
+        let group = adw::PreferencesGroup::new();
+        let latest_row = self.build_row(nav_view, device, distro_id, channel, None);
+        group.add(&latest_row);
+
+        let loading_row = adw::ActionRow::builder()
+            .title("Looking for builds…")
+            .build();
+        loading_row.add_prefix(&busy_indicator());
+        group.add(&loading_row);
+
+        let clamp = adw::Clamp::builder()
+            .maximum_size(500)
+            .child(&group)
+            .build();
+        status_page.set_child(Some(&clamp));
+        toolbar_view.set_content(Some(&status_page));
+
+        let page = adw::NavigationPage::builder()
+            .title("Select Build")
             .child(&toolbar_view)
             .build();
-
         nav_view.push(&page);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let id = distro_id.to_string();
+        let chosen = channel.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let _ = sender.send(rt.block_on(channel_info::list_builds(&id, &chosen)));
+        });
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id = distro_id.to_string();
+        let channel = channel.clone();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(_) => return glib::ControlFlow::Break,
+            };
+            match result {
+                Ok(builds) if builds.is_empty() => {
+                    loading_row.set_title("No other builds listed");
+                    loading_row.set_subtitle("The latest build is the only one available");
+                }
+                Ok(builds) => {
+                    group.remove(&loading_row);
+                    for build in builds {
+                        group.add(&self_clone.build_row(&nav_clone, &device_clone, &distro_id, &channel, Some(build)));
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to list builds of {}/{}: {:#}", distro_id, channel.id, e);
+                    let title = if channel_info::is_unreachable(&e) {
+                        "Couldn't reach the build server"
+                    } else {
+                        "Couldn't list builds"
+                    };
+                    loading_row.set_title(title);
+                    loading_row.set_subtitle("The latest build can still be installed");
+                }
+            }
+            glib::ControlFlow::Break
+        });
     }
 
-    fn show_channel_selection_page(
+    /// A row installing `build` of `channel`, or its newest build for `None`
+    fn build_row(
         &self,
         nav_view: &adw::NavigationView,
         device: &Device,
         distro_id: &str,
+        channel: &ChannelConfig,
+        build: Option<ChannelBuild>,
+    ) -> adw::ActionRow {
+        let row = match build {
+            Some(ref build) => adw::ActionRow::builder()
+                .title(&build.label)
+                .subtitle(build.summary())
+                .activatable(true)
+                .build(),
+            None => adw::ActionRow::builder()
+                .title("Latest")
+                .subtitle("Whatever the channel offers when the install starts")
+                .activatable(true)
+                .build(),
+        };
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id = distro_id.to_string();
+        let channel = channel.clone();
+        row.connect_activated(move |_| {
+            self_clone.launch_pinned_install(&nav_clone, &device_clone, &distro_id, &channel, build.clone());
+        });
+
+        row
+    }
+
+    /// "Other Versions…" lists the releases on the image server that carry
+    /// this device and adds a button for each one not already configured.
+    fn append_other_versions_button(
+        &self,
+        buttons_box: &gtk::Box,
+        nav_view: &adw::NavigationView,
+        device: &Device,
         channels: &[ChannelConfig],
     ) {
-        let toolbar_view = adw::ToolbarView::new();
-        toolbar_view.add_top_bar(&adw::HeaderBar::new());
-
-        let status_page = adw::StatusPage::builder()
-            .title("Select Channel")
-            .description("Choose which channel to install:")
-            .icon_name("emblem-system-symbolic")
-            .build();
+        let Some(base_url) = self
+            .load_distro_config(device, "postmarketos")
+            .and_then(|c| c.base_url)
+        else {
+            return;
+        };
 
-        let buttons_box = gtk::Box::builder()
-            .orientation(gtk::Orientation::Vertical)
-            .spacing(12)
-            .halign(gtk::Align::Center)
+        let more_btn = gtk::Button::builder()
+            .label("Other Versions…")
+            .css_classes(vec!["flat", "pill"])
+            .width_request(250)
             .build();
+        buttons_box.append(&more_btn);
 
-        for channel in channels {
-            let btn = gtk::Button::builder()
-                .label(&channel.label)
-                .css_classes(vec!["suggested-action", "pill"])
-                .width_request(250)
-                .height_request(50)
-                .build();
-
-            let self_clone = self.clone();
-            let nav_clone = nav_view.clone();
-            let device_clone = device.clone();
-            let distro_id_owned = distro_id.to_string();
-            let channel_clone = channel.clone();
-            btn.connect_clicked(move |_| {
-                self_clone.launch_install(&nav_clone, &device_clone, &distro_id_owned, &channel_clone);
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let box_clone = buttons_box.clone();
+        let configured: Vec<String> = channels.iter().map(|c| c.id.clone()).collect();
+        more_btn.connect_clicked(move |btn| {
+            btn.set_sensitive(false);
+            btn.set_label("Looking for versions…");
+
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let base_url = base_url.clone();
+            let device_name = postmarketos::device_name(&device_clone);
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+                let _ = sender.send(rt.block_on(postmarketos::list_versions(&base_url, &device_name)));
             });
 
-            buttons_box.append(&btn);
-        }
-
-        status_page.set_child(Some(&buttons_box));
-        toolbar_view.set_content(Some(&status_page));
-
-        let page = adw::NavigationPage::builder()
-            .title("Select Channel")
-            .child(&toolbar_view)
-            .build();
-
-        nav_view.push(&page);
+            let self_clone = self_clone.clone();
+            let nav_clone = nav_clone.clone();
+            let device_clone = device_clone.clone();
+            let box_clone = box_clone.clone();
+            let configured = configured.clone();
+            let btn = btn.clone();
+            glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+                let result = match receiver.try_recv() {
+                    Ok(result) => result,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(_) => return glib::ControlFlow::Break,
+                };
+                match result {
+                    Ok(versions) => {
+                        let extra: Vec<String> = versions
+                            .into_iter()
+                            .filter(|v| !configured.contains(v))
+                            .collect();
+                        if extra.is_empty() {
+                            btn.set_label("No other versions available");
+                            return glib::ControlFlow::Break;
+                        }
+                        box_clone.remove(&btn);
+                        for version in extra {
+                            let channel = ChannelConfig {
+                                label: version.clone(),
+                                id: version,
+                                path: None,
+                                artifact_match: None,
+                                release_url: None,
+                                github_repo: None,
+                                prereleases: false,
+                            };
+                            box_clone.append(&self_clone.channel_button(
+                                &nav_clone,
+                                &device_clone,
+                                "postmarketos",
+                                &channel,
+                                None,
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to list postmarketOS versions: {:#}", e);
+                        btn.set_label("Couldn't list versions — try again");
+                        btn.set_sensitive(true);
+                    }
+                }
+                glib::ControlFlow::Break
+            });
+        });
     }
 
     fn proceed_after_prerequisites(
@@ -973,6 +1933,16 @@ impl DeviceDetailsPage {
         if channels.is_empty() {
             let interfaces = self.load_interfaces(device, distro_id);
             if !interfaces.is_empty() {
+                if let Some(iface) = self.preferred_interface(distro_id, &interfaces) {
+                    let nav = nav_view.clone();
+                    let target = device.clone();
+                    let distro_id = distro_id.to_string();
+                    self.with_device_in_mode(device, MobianInstaller::REQUIRED_MODE, move |page| {
+                        page.remember_install(&target, LastInstall::new(&distro_id, None, Some(iface.id.as_str())));
+                        page.launch_mobian_install(&nav, &target, &distro_id, &iface.id);
+                    });
+                    return;
+                }
                 self.show_interface_selection_page(nav_view, device, distro_id, &interfaces);
                 return;
             }
@@ -981,6 +1951,20 @@ impl DeviceDetailsPage {
         }
 
         if channels.len() == 1 {
+            if let Err(missing) = channels[0].validate(distro_id) {
+                log::error!(
+                    "Channel {} of {} is missing {}",
+                    channels[0].id,
+                    distro_id,
+                    missing.join(", ")
+                );
+                if let Some(window) = self.root()
+                    .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                {
+                    window.show_toast(&incomplete_channel_message(&missing));
+                }
+                return;
+            }
             self.launch_install(nav_view, device, distro_id, &channels[0]);
         } else {
             self.show_channel_selection_page(nav_view, device, distro_id, &channels);
@@ -1009,32 +1993,41 @@ impl DeviceDetailsPage {
 
     /// Load the full DistroConfig for a given distro_id from distros.yml.
     fn load_distro_config(&self, device: &Device, distro_id: &str) -> Option<DistroConfig> {
-        let possible_paths = vec![
-            std::path::PathBuf::from(config::PKGDATADIR).join("devices"),
-            std::path::PathBuf::from("/app/share/sidestep/devices"),
-            std::path::PathBuf::from("data/devices"),
-            std::path::PathBuf::from("devices"),
-        ];
-        let devices_path = possible_paths
+        self.load_distro_file(device)?
+            .available_distros
             .into_iter()
-            .find(|p| p.exists())
-            .unwrap_or_else(|| std::path::PathBuf::from("devices"));
+            .find(|d| d.id == distro_id)
+    }
 
-        let manufacturer = maker_to_dir(&device.maker);
+    /// The interface the user always installs with `distro_id`, when this
+    /// device offers it. Holding Shift while choosing the distro or channel
+    /// asks anyway.
+    fn preferred_interface(&self, distro_id: &str, interfaces: &[InterfaceConfig]) -> Option<InterfaceConfig> {
+        let preferred: HashMap<String, String> =
+            gio::Settings::new(config::APP_ID).get("preferred-interfaces");
+        let id = preferred.get(distro_id)?;
+
+        let shift_held = self
+            .display()
+            .default_seat()
+            .and_then(|seat| seat.keyboard())
+            .is_some_and(|keyboard| keyboard.modifier_state().contains(gtk::gdk::ModifierType::SHIFT_MASK));
+        if shift_held {
+            log::info!("Shift held; asking for the {} interface", distro_id);
+            return None;
+        }
 
-        let parser = YamlParser::new(devices_path);
-        let config = match parser.parse_device_config(&manufacturer, &device.codename) {
-            Ok(c) => c,
-            Err(e) => {
-                log::error!("Failed to load distros.yml for {}: {:#}", device.codename, e);
-                return None;
-            }
+        let Some(iface) = interfaces.iter().find(|i| &i.id == id) else {
+            log::info!("Preferred interface {} isn't offered here; asking", id);
+            return None;
         };
-
-        config
-            .available_distros
-            .into_iter()
-            .find(|d| d.id == distro_id)
+        log::info!("Installing {} with the preferred interface {}", distro_id, iface.id);
+        if let Some(window) = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+        {
+            window.show_toast(&format!("Installing with {}; hold Shift to choose another", iface.label));
+        }
+        Some(iface.clone())
     }
 
     fn show_interface_selection_page(
@@ -1049,37 +2042,26 @@ impl DeviceDetailsPage {
 
         let status_page = adw::StatusPage::builder()
             .title("Select Interface")
-            .description("Choose which desktop interface to install:")
+            .description("Choose which desktop interface to install. A preferred interface set in Preferences skips this step.")
             .icon_name("emblem-system-symbolic")
             .build();
 
-        let buttons_box = gtk::Box::builder()
-            .orientation(gtk::Orientation::Vertical)
-            .spacing(12)
-            .halign(gtk::Align::Center)
-            .build();
-
-        for iface in interfaces {
-            let btn = gtk::Button::builder()
-                .label(&iface.label)
-                .css_classes(vec!["suggested-action", "pill"])
-                .width_request(250)
-                .height_request(50)
-                .build();
-
-            let self_clone = self.clone();
-            let nav_clone = nav_view.clone();
-            let device_clone = device.clone();
-            let distro_id_owned = distro_id.to_string();
-            let iface_id = iface.id.clone();
-            btn.connect_clicked(move |_| {
-                self_clone.launch_mobian_install(&nav_clone, &device_clone, &distro_id_owned, &iface_id);
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let distro_id_owned = distro_id.to_string();
+        let group = interface_choice_group(interfaces, move |iface_id| {
+            let nav = nav_clone.clone();
+            let device = device_clone.clone();
+            let distro_id = distro_id_owned.clone();
+            let iface_id = iface_id.to_string();
+            self_clone.with_device_in_mode(&device_clone, MobianInstaller::REQUIRED_MODE, move |page| {
+                page.remember_install(&device, LastInstall::new(&distro_id, None, Some(iface_id.as_str())));
+                page.launch_mobian_install(&nav, &device, &distro_id, &iface_id);
             });
+        });
 
-            buttons_box.append(&btn);
-        }
-
-        status_page.set_child(Some(&buttons_box));
+        status_page.set_child(Some(&group));
         toolbar_view.set_content(Some(&status_page));
 
         let page = adw::NavigationPage::builder()
@@ -1087,66 +2069,401 @@ impl DeviceDetailsPage {
             .child(&toolbar_view)
             .build();
 
-        nav_view.push(&page);
+        nav_view.push(&page);
+    }
+
+    /// Connect completion and failure signals on a FlashingPage,
+    /// then push it onto the NavigationView.
+    fn push_flashing_page(
+        &self,
+        nav_view: &adw::NavigationView,
+        progress_page: &FlashingPage,
+    ) {
+        if let Some(ref device) = *self.imp().device.borrow() {
+            progress_page.set_button_hint(device.button_hint());
+            progress_page.set_device_codename(&device.codename);
+            progress_page.set_report_device(device);
+        }
+
+        // On success: show the success page
+        let nav_view_weak = nav_view.downgrade();
+        let menu_model = self.imp().main_menu_button.menu_model();
+        let commands = self.imp().post_install_commands.take();
+        let post_install = self
+            .imp()
+            .device
+            .borrow()
+            .as_ref()
+            .and_then(|device| device.serial.clone())
+            .map(|serial| PostInstallSetup::new(&serial, &commands))
+            .filter(|setup| !setup.is_empty());
+        let codename = self.imp().device.borrow().as_ref().map(|d| d.codename.clone());
+        progress_page.connect_installation_complete(move |page| {
+            if let Some(ref codename) = codename
+                && let Err(e) = LastInstall::mark_completed(codename)
+            {
+                log::warn!("Could not mark the install finished: {:#}", e);
+            }
+            if let Some(nav) = nav_view_weak.upgrade() {
+                if let Some(ref model) = menu_model {
+                    let name = page.distro_name();
+                    let image_dir = page.image_dir();
+                    crate::window::SidestepWindow::show_success(
+                        &nav,
+                        model,
+                        &name,
+                        image_dir.as_deref(),
+                        post_install.clone(),
+                        &page.session_stats(),
+                    );
+                }
+            }
+        });
+
+        // Stopped download: go back to the channel choice with everything else intact
+        let nav_view_weak = nav_view.downgrade();
+        progress_page.connect_download_cancelled(move |page| {
+            if let Some(window) = page.root()
+                .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+            {
+                window.resume_detection();
+                window.show_toast("Download stopped");
+            }
+            if let Some(nav) = nav_view_weak.upgrade() {
+                nav.pop();
+            }
+        });
+
+        // Download-only: nothing was flashed, just tell the user where the files went
+        progress_page.connect_download_complete(move |page, location| {
+            if let Some(window) = page.root()
+                .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+            {
+                window.show_toast(&format!("Images saved to {}", location));
+            }
+        });
+
+        // On failure: "Start Over" button resets to the waiting page
+        progress_page.connect_installation_failed(move |page| {
+            if let Some(window) = page.root()
+                .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+            {
+                window.reset_to_waiting();
+            }
+        });
+
+        nav_view.push(progress_page);
+    }
+
+    // ────────────────────────────────────────────────────────────────
+    // Install launchers
+    // ────────────────────────────────────────────────────────────────
+
+    fn launch_install(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        channel: &ChannelConfig,
+    ) {
+        self.launch_pinned_install(nav_view, device, distro_id, channel, None);
+    }
+
+    /// [`launch_install`](Self::launch_install) for one particular build of
+    /// `channel`, or its newest for `None`
+    fn launch_pinned_install(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        channel: &ChannelConfig,
+        build: Option<ChannelBuild>,
+    ) {
+        let nav_view = nav_view.clone();
+        let target = device.clone();
+        let id = distro_id.to_string();
+        let chosen = channel.clone();
+        self.check_upstream(device, distro_id, Some(channel), move |page| {
+            page.imp().pinned_build.replace(build.clone());
+            page.start_install(&nav_view, &target, &id, &chosen);
+        });
+    }
+
+    fn start_install(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        channel: &ChannelConfig,
+    ) {
+        // Pause device detection to prevent disconnect events during install
+        if let Some(window) = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+        {
+            window.pause_detection();
+        }
+
+        let commands = self
+            .load_distro_config(device, distro_id)
+            .map(|config| config.post_install_commands)
+            .unwrap_or_default();
+        self.imp().post_install_commands.replace(commands);
+
+        type Start = fn(&DeviceDetailsPage, &adw::NavigationView, &Device, &ChannelConfig);
+        let (needed, start): (StartMode, Start) = match distro_id {
+            "ubuntutouch" | "ubports" => (UbportsInstaller::REQUIRED_MODE, Self::launch_ubports_install),
+            "droidian" => (DroidianInstaller::REQUIRED_MODE, Self::launch_droidian_install),
+            "postmarketos" => (
+                PostmarketosInstaller::REQUIRED_MODE,
+                Self::show_postmarketos_interface_selection,
+            ),
+            "lineageos" => (LineageosInstaller::REQUIRED_MODE, Self::launch_lineageos_install),
+            "eos" => (EosInstaller::REQUIRED_MODE, Self::launch_eos_install),
+            _ => {
+                log::warn!("No installer backend for '{}'", distro_id);
+                return;
+            }
+        };
+
+        let nav_view = nav_view.clone();
+        let target = device.clone();
+        let channel = channel.clone();
+        let distro_id = distro_id.to_string();
+        self.with_device_in_mode(device, needed, move |page| {
+            page.remember_install(&target, LastInstall::new(&distro_id, Some(channel.id.as_str()), None));
+            start(page, &nav_view, &target, &channel);
+        });
+    }
+
+    /// Check that the server `distro_id` installs from still looks the way
+    /// its backend expects, then call `proceed`. When it doesn't, the user is
+    /// told this Sidestep may be too old and can still go ahead.
+    fn check_upstream<F>(&self, device: &Device, distro_id: &str, channel: Option<&ChannelConfig>, proceed: F)
+    where
+        F: Fn(&Self) + 'static,
+    {
+        let Some(distro) = self.load_distro_config(device, distro_id) else {
+            proceed(self);
+            return;
+        };
+
+        let proceed = std::rc::Rc::new(proceed);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        {
+            let device = device.clone();
+            let distro = distro.clone();
+            let channel = channel.cloned();
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create tokio runtime");
+                let check = rt.block_on(upstream_probe::check(&device, &distro, channel.as_ref()));
+                let _ = sender.send(check);
+            });
+        }
+
+        let page_weak = self.downgrade();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let check = match receiver.try_recv() {
+                Ok(check) => check,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            };
+            let Some(page) = page_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let UpstreamCheck::Changed { ref detail } = check else {
+                proceed(&page);
+                return glib::ControlFlow::Break;
+            };
+
+            let dialog = adw::AlertDialog::new(
+                Some("Sidestep May Need an Update"),
+                Some(&format!(
+                    "The {} server doesn't look the way this version of Sidestep expects:\n\n{}\n\nThe distro has probably changed how it publishes builds, and the install is likely to fail. Update Sidestep before installing.",
+                    distro.name, detail
+                )),
+            );
+            dialog.add_responses(&[("cancel", "Cancel"), ("continue", "Install Anyway")]);
+            dialog.set_response_appearance("continue", adw::ResponseAppearance::Destructive);
+            dialog.set_default_response(Some("cancel"));
+            dialog.set_close_response("cancel");
+
+            let page_weak = page.downgrade();
+            let proceed = proceed.clone();
+            dialog.connect_response(None, move |_, response| {
+                if response != "continue" {
+                    return;
+                }
+                if let Some(page) = page_weak.upgrade() {
+                    log::warn!("Installing although the upstream server looks different");
+                    proceed(&page);
+                }
+            });
+            dialog.present(Some(&page));
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Make sure the device is in the mode an installer starts from,
+    /// rebooting it there when that can be done from its current mode, then
+    /// call `launch`. Otherwise tell the user how to get it there and let
+    /// them try again.
+    ///
+    /// Once it's in the right mode, what it reports about itself is checked
+    /// against the model's `identity` in info.yml, and the user is warned
+    /// before going on when it looks like a clone.
+    fn with_device_in_mode<F>(&self, device: &Device, needed: StartMode, launch: F)
+    where
+        F: Fn(&Self) + 'static,
+    {
+        let Some(serial) = device.serial.clone() else {
+            // The launchers report the missing serial themselves
+            launch(self);
+            return;
+        };
+        let identity = self
+            .load_device_info(device)
+            .and_then(|info| info.device.identity);
+        self.run_mode_guard(serial, needed, identity, std::rc::Rc::new(launch));
+    }
+
+    fn run_mode_guard(
+        &self,
+        serial: String,
+        needed: StartMode,
+        identity: Option<HardwareIdentity>,
+        launch: std::rc::Rc<dyn Fn(&Self)>,
+    ) {
+        // A reboot into another mode must not look like the device leaving
+        let window = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok());
+        if let Some(ref window) = window {
+            window.pause_detection();
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        {
+            let serial = serial.clone();
+            let identity = identity.clone();
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create tokio runtime");
+                let result = rt.block_on(async {
+                    let mode = mode_guard::ensure(&serial, needed).await?;
+                    let mismatches = match identity {
+                        Some(ref identity) => identity_check::inconsistencies(&serial, mode, identity).await,
+                        None => Vec::new(),
+                    };
+                    Ok::<_, anyhow::Error>((mode, mismatches))
+                });
+                let _ = sender.send(result.map_err(|e| format!("{:#}", e)));
+            });
+        }
+
+        let page_weak = self.downgrade();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+            };
+            let Some(page) = page_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            match result {
+                Ok((mode, mismatches)) if mismatches.is_empty() => {
+                    log::info!("Device is in {} mode, starting the installer", mode.label());
+                    launch(&page);
+                }
+                Ok((_, mismatches)) => {
+                    page.show_identity_warning(&mismatches, window.clone(), launch.clone());
+                }
+                Err(message) => {
+                    if let Some(ref window) = window {
+                        window.resume_detection();
+                    }
+                    page.show_wrong_mode(&message, serial.clone(), needed, identity.clone(), launch.clone());
+                }
+            }
+            glib::ControlFlow::Break
+        });
     }
 
-    /// Connect completion and failure signals on a FlashingPage,
-    /// then push it onto the NavigationView.
-    fn push_flashing_page(
+    /// Best-effort warning: clones can't be told apart reliably, so the
+    /// user may still go ahead.
+    fn show_identity_warning(
         &self,
-        nav_view: &adw::NavigationView,
-        progress_page: &FlashingPage,
+        mismatches: &[String],
+        window: Option<crate::window::SidestepWindow>,
+        launch: std::rc::Rc<dyn Fn(&Self)>,
     ) {
-        // On success: show the success page
-        let nav_view_weak = nav_view.downgrade();
-        let menu_model = self.imp().main_menu_button.menu_model();
-        progress_page.connect_installation_complete(move |page| {
-            if let Some(nav) = nav_view_weak.upgrade() {
-                if let Some(ref model) = menu_model {
-                    let name = page.distro_name();
-                    crate::window::SidestepWindow::show_success(&nav, model, &name);
+        let model = self
+            .imp()
+            .device
+            .borrow()
+            .as_ref()
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| "this model".to_string());
+        let dialog = adw::AlertDialog::new(
+            Some("This May Not Be a Genuine Device"),
+            Some(&format!(
+                "The connected device doesn't look like a genuine {}:\n\n• {}\n\nClones with different hardware can be bricked by an install meant for the real model. Only continue if you're sure of what you have.",
+                model,
+                mismatches.join("\n• ")
+            )),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("continue", "Install Anyway")]);
+        dialog.set_response_appearance("continue", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        let page = self.downgrade();
+        dialog.connect_response(None, move |_, response| {
+            if response != "continue" {
+                if let Some(ref window) = window {
+                    window.resume_detection();
                 }
+                return;
             }
-        });
-
-        // On failure: "Start Over" button resets to the waiting page
-        progress_page.connect_installation_failed(move |page| {
-            if let Some(window) = page.root()
-                .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
-            {
-                window.reset_to_waiting();
+            if let Some(page) = page.upgrade() {
+                log::warn!("Installing despite identity mismatches");
+                launch(&page);
             }
         });
-
-        nav_view.push(progress_page);
+        dialog.present(Some(self));
     }
 
-    // ────────────────────────────────────────────────────────────────
-    // Install launchers
-    // ────────────────────────────────────────────────────────────────
-
-    fn launch_install(
+    fn show_wrong_mode(
         &self,
-        nav_view: &adw::NavigationView,
-        device: &Device,
-        distro_id: &str,
-        channel: &ChannelConfig,
+        message: &str,
+        serial: String,
+        needed: StartMode,
+        identity: Option<HardwareIdentity>,
+        launch: std::rc::Rc<dyn Fn(&Self)>,
     ) {
-        // Pause device detection to prevent disconnect events during install
-        if let Some(window) = self.root()
-            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
-        {
-            window.pause_detection();
-        }
-
-        match distro_id {
-            "ubuntutouch" | "ubports" => self.launch_ubports_install(nav_view, device, channel),
-            "droidian" => self.launch_droidian_install(nav_view, device, channel),
-            "postmarketos" => self.show_postmarketos_interface_selection(nav_view, device, channel),
-            "lineageos" => self.launch_lineageos_install(nav_view, device, channel),
-            "eos" => self.launch_eos_install(nav_view, device, channel),
-            _ => log::warn!("No installer backend for '{}'", distro_id),
-        }
+        let dialog = adw::AlertDialog::new(
+            Some("Device in the Wrong Mode"),
+            Some(&format!("{}.\n\n{}", message, needed.instructions())),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("retry", "Try Again")]);
+        dialog.set_response_appearance("retry", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("retry"));
+        dialog.set_close_response("cancel");
+
+        let page = self.downgrade();
+        dialog.connect_response(None, move |_, response| {
+            if response != "retry" {
+                return;
+            }
+            if let Some(page) = page.upgrade() {
+                page.run_mode_guard(serial.clone(), needed, identity.clone(), launch.clone());
+            }
+        });
+        dialog.present(Some(self));
     }
 
     fn launch_ubports_install(
@@ -1166,14 +2483,30 @@ impl DeviceDetailsPage {
         };
         let channel_path = channel_path.trim_end_matches('/');
 
-        log::info!("Installing Ubuntu Touch from channel: {} ({})", channel.label, channel_path);
+        let version = self
+            .imp()
+            .pinned_build
+            .borrow()
+            .as_ref()
+            .and_then(|build| build.id.parse().ok());
+        match version {
+            Some(version) => log::info!(
+                "Installing Ubuntu Touch version {} from channel: {} ({})",
+                version,
+                channel.label,
+                channel_path
+            ),
+            None => log::info!("Installing Ubuntu Touch from channel: {} ({})", channel.label, channel_path),
+        }
 
         let progress_page = FlashingPage::new();
         if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
             progress_page.set_menu_model(&menu_model);
         }
+        progress_page.set_download_only(self.imp().download_only.get());
+        progress_page.set_space_needed(DeviceDatabase::new().space_needed(&device.codename, "Ubuntu Touch"));
 
-        progress_page.start_ubports_installation("Ubuntu Touch", serial, channel_path);
+        progress_page.start_ubports_installation("Ubuntu Touch", serial, channel_path, version);
 
         self.push_flashing_page(nav_view, &progress_page);
     }
@@ -1189,10 +2522,12 @@ impl DeviceDetailsPage {
             return;
         };
 
-        let Some(ref release_url) = channel.release_url else {
-            log::error!("No release_url defined for Droidian channel {}", channel.id);
+        let Some(source) = GithubReleaseSource::for_channel(channel) else {
+            log::error!("No GitHub release source defined for Droidian channel {}", channel.id);
             return;
         };
+        let pinned = self.imp().pinned_build.borrow().as_ref().map(|build| build.id.clone());
+        let source = source.pin_asset(pinned);
 
         let Some(ref artifact_match) = channel.artifact_match else {
             log::error!("No artifact_match defined for Droidian channel {}", channel.id);
@@ -1209,8 +2544,14 @@ impl DeviceDetailsPage {
         if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
             progress_page.set_menu_model(&menu_model);
         }
+        progress_page.set_download_only(self.imp().download_only.get());
+        progress_page.set_space_needed(DeviceDatabase::new().space_needed(&device.codename, "Droidian"));
+        progress_page.set_checksum_algorithm(
+            self.load_distro_config(device, "droidian")
+                .and_then(|config| config.checksum_algorithm),
+        );
 
-        progress_page.start_droidian_installation("Droidian", serial, release_url, artifact_match);
+        progress_page.start_droidian_installation("Droidian", serial, source, artifact_match);
 
         self.push_flashing_page(nav_view, &progress_page);
     }
@@ -1241,6 +2582,8 @@ impl DeviceDetailsPage {
         if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
             progress_page.set_menu_model(&menu_model);
         }
+        progress_page.set_download_only(self.imp().download_only.get());
+        progress_page.set_space_needed(DeviceDatabase::new().space_needed(&device.codename, "LineageOS"));
 
         progress_page.start_lineageos_installation("LineageOS", serial, release_url, false);
 
@@ -1285,8 +2628,17 @@ impl DeviceDetailsPage {
         if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
             progress_page.set_menu_model(&menu_model);
         }
+        progress_page.set_download_only(self.imp().download_only.get());
+        progress_page.set_space_needed(DeviceDatabase::new().space_needed(&device.codename, "/e/OS"));
+        progress_page.set_checksum_algorithm(distro_config.checksum_algorithm);
 
-        progress_page.start_eos_installation(serial, &base_url, &device.codename, &channel.id);
+        progress_page.start_eos_installation(
+            serial,
+            &base_url,
+            distro_config.image_hosts(),
+            &device.codename,
+            &channel.id,
+        );
 
         self.push_flashing_page(nav_view, &progress_page);
     }
@@ -1298,11 +2650,6 @@ impl DeviceDetailsPage {
         distro_id: &str,
         interface_id: &str,
     ) {
-        let Some(ref serial) = device.serial else {
-            log::error!("No device serial available for Mobian installation");
-            return;
-        };
-
         let distro_config = match self.load_distro_config(device, distro_id) {
             Some(c) => c,
             None => {
@@ -1319,12 +2666,37 @@ impl DeviceDetailsPage {
             }
         };
 
-        let chipset = distro_config.chipset.unwrap_or_else(|| "sdm670".to_string());
-        let device_model = distro_config.device_model.unwrap_or_else(|| device.codename.clone());
+        self.imp()
+            .post_install_commands
+            .replace(distro_config.post_install_commands.clone());
+        let nav_view = nav_view.clone();
+        let target = device.clone();
+        let interface_id = interface_id.to_string();
+        self.check_upstream(device, distro_id, None, move |page| {
+            page.start_mobian_flash(&nav_view, &target, &distro_config, &interface_id, &base_url);
+        });
+    }
+
+    /// Run the Mobian install from `base_url`, which is the config's own
+    /// or one of its mirrors.
+    fn start_mobian_flash(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_config: &DistroConfig,
+        interface_id: &str,
+        base_url: &str,
+    ) {
+        let Some(ref serial) = device.serial else {
+            log::error!("No device serial available for Mobian installation");
+            return;
+        };
+        let chipset = distro_config.chipset.clone().unwrap_or_else(|| "sdm670".to_string());
+        let device_model = distro_config.device_model.clone().unwrap_or_else(|| device.codename.clone());
 
         log::info!(
-            "Installing Mobian ({}) for {}/{}",
-            interface_id, chipset, device_model
+            "Installing Mobian ({}) for {}/{} from {}",
+            interface_id, chipset, device_model, base_url
         );
 
         // Pause device detection to prevent disconnect events during install
@@ -1338,11 +2710,21 @@ impl DeviceDetailsPage {
         if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
             progress_page.set_menu_model(&menu_model);
         }
+        progress_page.set_download_only(self.imp().download_only.get());
+        progress_page.set_space_needed(DeviceDatabase::new().space_needed(&device.codename, &distro_config.name));
+        progress_page.set_checksum_algorithm(distro_config.checksum_algorithm);
 
         progress_page.start_mobian_installation(
-            "Mobian", serial, &base_url, interface_id, &chipset, &device_model,
+            "Mobian", serial, base_url, distro_config.image_hosts(), interface_id, &chipset, &device_model,
         );
 
+        let device = device.clone();
+        let config = distro_config.clone();
+        let interface_id = interface_id.to_string();
+        self.offer_mirrors_on_failure(&progress_page, distro_config, base_url, move |page, nav, url| {
+            page.start_mobian_flash(nav, &device, &config, &interface_id, url);
+        });
+
         self.push_flashing_page(nav_view, &progress_page);
     }
 
@@ -1361,48 +2743,29 @@ impl DeviceDetailsPage {
             log::error!("No interfaces found for postmarketOS");
             return;
         }
+        if let Some(iface) = self.preferred_interface("postmarketos", &interfaces) {
+            self.launch_postmarketos_install(nav_view, device, channel, &iface.id);
+            return;
+        }
 
         let toolbar_view = adw::ToolbarView::new();
         toolbar_view.add_top_bar(&adw::HeaderBar::new());
 
         let status_page = adw::StatusPage::builder()
             .title("Select Interface")
-            .description("Choose which desktop interface to install:")
+            .description("Choose which desktop interface to install. A preferred interface set in Preferences skips this step.")
             .icon_name("emblem-system-symbolic")
             .build();
 
-        let buttons_box = gtk::Box::builder()
-            .orientation(gtk::Orientation::Vertical)
-            .spacing(12)
-            .halign(gtk::Align::Center)
-            .build();
-
-        for iface in &interfaces {
-            let btn = gtk::Button::builder()
-                .label(&iface.label)
-                .css_classes(vec!["suggested-action", "pill"])
-                .width_request(250)
-                .height_request(50)
-                .build();
-
-            let self_clone = self.clone();
-            let nav_clone = nav_view.clone();
-            let device_clone = device.clone();
-            let channel_clone = channel.clone();
-            let iface_id = iface.id.clone();
-            btn.connect_clicked(move |_| {
-                self_clone.launch_postmarketos_install(
-                    &nav_clone,
-                    &device_clone,
-                    &channel_clone,
-                    &iface_id,
-                );
-            });
-
-            buttons_box.append(&btn);
-        }
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        let device_clone = device.clone();
+        let channel_clone = channel.clone();
+        let group = interface_choice_group(&interfaces, move |iface_id| {
+            self_clone.launch_postmarketos_install(&nav_clone, &device_clone, &channel_clone, iface_id);
+        });
 
-        status_page.set_child(Some(&buttons_box));
+        status_page.set_child(Some(&group));
         toolbar_view.set_content(Some(&status_page));
 
         let page = adw::NavigationPage::builder()
@@ -1420,11 +2783,6 @@ impl DeviceDetailsPage {
         channel: &ChannelConfig,
         interface_id: &str,
     ) {
-        let Some(ref serial) = device.serial else {
-            log::error!("No device serial available for postmarketOS installation");
-            return;
-        };
-
         let distro_config = match self.load_distro_config(device, "postmarketos") {
             Some(c) => c,
             None => {
@@ -1441,38 +2799,423 @@ impl DeviceDetailsPage {
             }
         };
 
-        // Device name on the image server: manufacturer-codename (e.g. google-sargo)
-        let device_name = format!("{}-{}", device.maker.to_lowercase(), device.codename);
+        self.start_postmarketos_flash(nav_view, device, &distro_config, channel, interface_id, &base_url);
+    }
+
+    /// Run the postmarketOS install from `base_url`, which is the config's
+    /// own or one of its mirrors.
+    fn start_postmarketos_flash(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_config: &DistroConfig,
+        channel: &ChannelConfig,
+        interface_id: &str,
+        base_url: &str,
+    ) {
+        let Some(ref serial) = device.serial else {
+            log::error!("No device serial available for postmarketOS installation");
+            return;
+        };
+        let device_name = postmarketos::device_name(device);
 
         log::info!(
-            "Installing postmarketOS channel={} interface={} device={}",
+            "Installing postmarketOS channel={} interface={} device={} from {}",
             channel.id,
             interface_id,
-            device_name
+            device_name,
+            base_url
+        );
+
+        // Pause device detection to prevent disconnect events during install
+        if let Some(window) = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+        {
+            window.pause_detection();
+        }
+
+        let progress_page = FlashingPage::new();
+        if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
+            progress_page.set_menu_model(&menu_model);
+        }
+        progress_page.set_download_only(self.imp().download_only.get());
+        progress_page.set_space_needed(DeviceDatabase::new().space_needed(&device.codename, &distro_config.name));
+
+        progress_page.start_postmarketos_installation(
+            "postmarketOS",
+            serial,
+            base_url,
+            distro_config.image_hosts(),
+            &channel.id,
+            interface_id,
+            &device_name,
+            distro_config.archive_partitions.clone(),
         );
 
-        // Pause device detection to prevent disconnect events during install
-        if let Some(window) = self.root()
-            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
-        {
-            window.pause_detection();
+        let device = device.clone();
+        let config = distro_config.clone();
+        let channel = channel.clone();
+        let interface_id = interface_id.to_string();
+        let base_url = base_url.to_string();
+        {
+            let device = device.clone();
+            let config = config.clone();
+            let channel = channel.clone();
+            let interface_id = interface_id.clone();
+            let base_url = base_url.clone();
+            let self_weak = self.downgrade();
+            progress_page.connect_channel_unavailable(move |progress_page, old, suggested| {
+                let Some(page) = self_weak.upgrade() else {
+                    return;
+                };
+                let dialog = adw::AlertDialog::new(
+                    Some("Release No Longer Available"),
+                    Some(&format!("{} is no longer available; {} is — use it?", old, suggested)),
+                );
+                dialog.add_responses(&[("cancel", "Cancel"), ("use", &format!("Use {}", suggested))]);
+                dialog.set_response_appearance("use", adw::ResponseAppearance::Suggested);
+                dialog.set_default_response(Some("use"));
+                dialog.set_close_response("cancel");
+
+                let device = device.clone();
+                let config = config.clone();
+                let channel = ChannelConfig {
+                    id: suggested.clone(),
+                    label: suggested.clone(),
+                    ..channel.clone()
+                };
+                let interface_id = interface_id.clone();
+                let base_url = base_url.clone();
+                dialog.connect_response(None, move |_, response| {
+                    if response != "use" {
+                        return;
+                    }
+                    let Some(nav) = page.ancestor(adw::NavigationView::static_type())
+                        .and_downcast::<adw::NavigationView>()
+                    else {
+                        return;
+                    };
+                    // Replace the failed progress page with a fresh run
+                    nav.pop();
+                    page.start_postmarketos_flash(&nav, &device, &config, &channel, &interface_id, &base_url);
+                });
+                dialog.present(Some(progress_page));
+            });
+        }
+
+        self.offer_mirrors_on_failure(&progress_page, distro_config, &base_url, move |page, nav, url| {
+            page.start_postmarketos_flash(nav, &device, &config, &channel, &interface_id, url);
+        });
+
+        self.push_flashing_page(nav_view, &progress_page);
+    }
+
+    /// When a download fails and the distro lists mirrors, let the user pick
+    /// the original source or any mirror and start over with it.
+    fn offer_mirrors_on_failure<F>(
+        &self,
+        progress_page: &FlashingPage,
+        distro_config: &DistroConfig,
+        current: &str,
+        retry: F,
+    ) where
+        F: Fn(&Self, &adw::NavigationView, &str) + 'static,
+    {
+        if distro_config.mirrors.is_empty() {
+            return;
+        }
+
+        let mut sources = Vec::new();
+        if let Some(ref base) = distro_config.base_url {
+            sources.push((base.clone(), "Original source"));
+        }
+        sources.extend(distro_config.mirrors.iter().map(|m| (m.clone(), "Mirror")));
+
+        let self_weak = self.downgrade();
+        let current = current.to_string();
+        let retry = std::rc::Rc::new(retry);
+        progress_page.connect_download_failed(move |_, message| {
+            let Some(page) = self_weak.upgrade() else {
+                return;
+            };
+            let Some(nav_view) = page.ancestor(adw::NavigationView::static_type())
+                .and_downcast::<adw::NavigationView>()
+            else {
+                return;
+            };
+            page.show_mirror_page(&nav_view, &message, &sources, &current, retry.clone());
+        });
+    }
+
+    fn show_mirror_page<F>(
+        &self,
+        nav_view: &adw::NavigationView,
+        message: &str,
+        sources: &[(String, &'static str)],
+        current: &str,
+        retry: std::rc::Rc<F>,
+    ) where
+        F: Fn(&Self, &adw::NavigationView, &str) + 'static,
+    {
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title("Try Another Mirror")
+            .description(format!("{}\n\nPick a server to download from instead.", message))
+            .icon_name("network-server-symbolic")
+            .build();
+
+        let group = adw::PreferencesGroup::new();
+        for (url, kind) in sources {
+            let subtitle = if url == current {
+                format!("{} · just failed", kind)
+            } else {
+                kind.to_string()
+            };
+            let row = adw::ActionRow::builder()
+                .title(url.as_str())
+                .subtitle(subtitle)
+                .build();
+
+            let button = gtk::Button::builder()
+                .label("Retry with This Mirror")
+                .valign(gtk::Align::Center)
+                .build();
+            let self_weak = self.downgrade();
+            let nav_weak = nav_view.downgrade();
+            let url = url.clone();
+            let retry = retry.clone();
+            button.connect_clicked(move |_| {
+                let (Some(page), Some(nav)) = (self_weak.upgrade(), nav_weak.upgrade()) else {
+                    return;
+                };
+                // Drop this page and the failed progress page before starting over
+                nav.pop();
+                nav.pop();
+                retry(&page, &nav, &url);
+            });
+            row.add_suffix(&button);
+            group.add(&row);
+        }
+
+        status_page.set_child(Some(&group));
+        toolbar_view.set_content(Some(&status_page));
+
+        let page = adw::NavigationPage::builder()
+            .title("Try Another Mirror")
+            .child(&toolbar_view)
+            .build();
+        nav_view.push(&page);
+    }
+
+    // ────────────────────────────────────────────────────────────────
+    // Recovery of devices left without a bootable system
+    // ────────────────────────────────────────────────────────────────
+
+    /// Ask which mode the device is in off the main thread and reveal the
+    /// recovery banner when it's sitting in fastboot.
+    fn offer_recovery_in_fastboot(&self, device: &Device) {
+        let Some(serial) = device.serial.clone() else {
+            return;
+        };
+        let unfinished = LastInstall::load(&device.codename).is_some_and(|last| !last.completed);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let _ = sender.send(rt.block_on(mode_guard::current_mode(&serial)));
+        });
+
+        let page_weak = self.downgrade();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let mode = match receiver.try_recv() {
+                Ok(mode) => mode,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                Err(_) => return glib::ControlFlow::Break,
+            };
+            let Some(page) = page_weak.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            if matches!(mode, Some(DeviceMode::Fastboot | DeviceMode::Fastbootd)) {
+                let banner = &page.imp().recovery_banner;
+                if unfinished {
+                    banner.set_title("The last install didn't finish. Recover the device here");
+                }
+                banner.set_revealed(true);
+            }
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Ways back to a bootable system: the install that was interrupted,
+    /// run again from scratch, and any stock factory image the installers
+    /// for this device know about.
+    fn show_recovery_page(&self) {
+        let Some(device) = self.imp().device.borrow().clone() else {
+            return;
+        };
+        let Some(nav_view) = self.ancestor(adw::NavigationView::static_type())
+            .and_then(|w| w.downcast::<adw::NavigationView>().ok())
+        else {
+            log::error!("Could not find ancestor NavigationView");
+            return;
+        };
+        self.imp().download_only.set(false);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let status_page = adw::StatusPage::builder()
+            .title("Recover Your Device")
+            .description(
+                "If an install was interrupted, the phone has no working system to start and \
+                 stays in fastboot. Put one back with either option below. Keep the cable \
+                 connected until it finishes.",
+            )
+            .icon_name("emblem-synchronizing-symbolic")
+            .build();
+
+        let group = adw::PreferencesGroup::new();
+        let mut options = 0;
+        let distros = self.load_all_distros(&device);
+
+        let last = LastInstall::load(&device.codename)
+            .and_then(|last| distros.iter().find(|d| d.id == last.distro_id).map(|d| (last, d.name.clone())));
+        if let Some((last, distro_name)) = last {
+            let channel_label = last.channel.as_ref().and_then(|id| {
+                self.load_channels(&device, &last.distro_id)
+                    .into_iter()
+                    .find(|c| &c.id == id)
+                    .map(|c| c.label)
+            });
+            let subtitle = match (channel_label, last.completed) {
+                (Some(label), false) => format!("{} ({}) — didn't finish", distro_name, label),
+                (Some(label), true) => format!("{} ({})", distro_name, label),
+                (None, false) => format!("{} — didn't finish", distro_name),
+                (None, true) => distro_name.clone(),
+            };
+            let row = adw::ActionRow::builder()
+                .title("Run the Last Install Again")
+                .subtitle(&subtitle)
+                .activatable(true)
+                .build();
+            row.add_prefix(&gtk::Image::from_icon_name("view-refresh-symbolic"));
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            let self_clone = self.clone();
+            let nav_clone = nav_view.clone();
+            let device_clone = device.clone();
+            row.connect_activated(move |_| {
+                self_clone.rerun_install(&nav_clone, &device_clone, &last);
+            });
+            group.add(&row);
+            options += 1;
+        }
+
+        // The same stock image is often a prerequisite of several distros
+        let mut factory_images: Vec<(String, String, String)> = Vec::new();
+        for distro in &distros {
+            let Some(config) = self.load_installer_config(&device, &distro.id) else {
+                continue;
+            };
+            for step in config.steps.values() {
+                if let Step::FlashAndroid { android_version, url, sha256, .. } = step
+                    && !factory_images.iter().any(|(_, u, _)| u == url)
+                {
+                    factory_images.push((android_version.clone(), url.clone(), sha256.clone()));
+                }
+            }
+        }
+        factory_images.sort();
+
+        for (version, url, sha256) in factory_images {
+            let row = adw::ActionRow::builder()
+                .title(format!("Reflash {}", version))
+                .subtitle("Stock factory image from the manufacturer")
+                .activatable(true)
+                .build();
+            row.add_prefix(&gtk::Image::from_icon_name("android-symbolic"));
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            let self_clone = self.clone();
+            let nav_clone = nav_view.clone();
+            let device_clone = device.clone();
+            row.connect_activated(move |_| {
+                self_clone.show_flash_android_confirmation(
+                    &nav_clone,
+                    &device_clone,
+                    &version,
+                    &url,
+                    &sha256,
+                    "This puts the manufacturer's software back on the device and erases \
+                     everything on it. Afterwards it starts like a new phone.",
+                );
+            });
+            group.add(&row);
+            options += 1;
         }
 
-        let progress_page = FlashingPage::new();
-        if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
-            progress_page.set_menu_model(&menu_model);
+        if options == 0 {
+            status_page.set_description(Some(
+                "Sidestep has no record of an install on this device and knows no factory \
+                 image for it. Flashing images you downloaded yourself with \
+                 \u{201c}Flash Local Images\u{201d} can still bring it back.",
+            ));
+        } else {
+            status_page.set_child(Some(&group));
         }
+        toolbar_view.set_content(Some(&status_page));
 
-        progress_page.start_postmarketos_installation(
-            "postmarketOS",
-            serial,
-            &base_url,
-            &channel.id,
-            interface_id,
-            &device_name,
-        );
+        let page = adw::NavigationPage::builder()
+            .title("Recover")
+            .child(&toolbar_view)
+            .build();
+        nav_view.push(&page);
+    }
 
-        self.push_flashing_page(nav_view, &progress_page);
+    /// Flash `last` again without going back through the distro, unlock and
+    /// prerequisite pages: those were all passed the first time.
+    fn rerun_install(&self, nav_view: &adw::NavigationView, device: &Device, last: &LastInstall) {
+        if let Some(ref channel_id) = last.channel {
+            let Some(channel) = self
+                .load_channels(device, &last.distro_id)
+                .into_iter()
+                .find(|c| &c.id == channel_id)
+            else {
+                log::error!("{} no longer has a {} channel", last.distro_id, channel_id);
+                if let Some(window) = self.root()
+                    .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                {
+                    window.show_toast("That channel is no longer available");
+                }
+                return;
+            };
+            self.launch_install(nav_view, device, &last.distro_id, &channel);
+        } else if let Some(ref interface_id) = last.interface {
+            let nav = nav_view.clone();
+            let target = device.clone();
+            let distro_id = last.distro_id.clone();
+            let interface_id = interface_id.clone();
+            self.with_device_in_mode(device, MobianInstaller::REQUIRED_MODE, move |page| {
+                page.remember_install(&target, LastInstall::new(&distro_id, None, Some(interface_id.as_str())));
+                page.launch_mobian_install(&nav, &target, &distro_id, &interface_id);
+            });
+        } else {
+            self.proceed_after_prerequisites(nav_view, device, &last.distro_id);
+        }
+    }
+
+    /// Record the install about to flash `device`, so it can be run again
+    /// if it's interrupted. Download-only runs flash nothing and aren't kept.
+    fn remember_install(&self, device: &Device, install: LastInstall) {
+        if self.imp().download_only.get() {
+            return;
+        }
+        if let Err(e) = install.record(&device.codename) {
+            log::warn!("Could not record the install: {:#}", e);
+        }
     }
 
     // ────────────────────────────────────────────────────────────────
@@ -1534,13 +3277,14 @@ impl DeviceDetailsPage {
         let url_owned = url.to_string();
         let sha256_owned = sha256.to_string();
         flash_btn.connect_clicked(move |_| {
-            self_clone.launch_factory_image_flash(
-                &nav_clone,
-                &device_clone,
-                &version_owned,
-                &url_owned,
-                &sha256_owned,
-            );
+            let nav = nav_clone.clone();
+            let device = device_clone.clone();
+            let version = version_owned.clone();
+            let url = url_owned.clone();
+            let sha256 = sha256_owned.clone();
+            self_clone.with_device_in_mode(&device_clone, FactoryImageInstaller::REQUIRED_MODE, move |page| {
+                page.launch_factory_image_flash(&nav, &device, &version, &url, &sha256);
+            });
         });
 
         let nav_clone2 = nav_view.clone();
@@ -1597,11 +3341,398 @@ impl DeviceDetailsPage {
         nav_view.push(&progress_page);
     }
 
+    fn launch_temporary_boot(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        distro_id: &str,
+        distro_name: &str,
+        boot: &TemporaryBootConfig,
+    ) {
+        let Some(ref serial) = device.serial else {
+            log::error!("No device serial available for temporary boot");
+            return;
+        };
+
+        let window = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok());
+
+        // fastboot boot is refused by locked bootloaders just like flashing
+        if device.is_locked == Some(true) {
+            if let Some(ref window) = window {
+                window.show_toast("Unlock the bootloader to try a system without installing");
+            }
+            return;
+        }
+
+        log::info!("Temporarily booting {} on {}", distro_name, serial);
+
+        if let Some(ref window) = window {
+            window.pause_detection();
+        }
+
+        let progress_page = FlashingPage::new();
+        if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
+            progress_page.set_menu_model(&menu_model);
+        }
+
+        progress_page.start_temporary_boot(
+            distro_id,
+            distro_name,
+            serial,
+            &boot.url,
+            boot.sha256.as_deref(),
+        );
+        self.push_flashing_page(nav_view, &progress_page);
+    }
+
+    // ────────────────────────────────────────────────────────────────
+    // Local image flashing
+    // ────────────────────────────────────────────────────────────────
+
+    fn on_flash_local_clicked(&self) {
+        let heimdall_device = self
+            .imp()
+            .device
+            .borrow()
+            .clone()
+            .filter(|device| samsung::images_for(&device.codename).is_some());
+        if let Some(device) = heimdall_device {
+            self.choose_odin_archives(device);
+            return;
+        }
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Choose a Folder of Images")
+            .modal(true)
+            .build();
+
+        let window = self.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+        let self_clone = self.clone();
+        dialog.select_folder(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            let Ok(folder) = result else { return };
+            let Some(dir) = folder.path() else {
+                log::error!("Selected folder has no local path");
+                return;
+            };
+            self_clone.show_local_mapping_page(&dir);
+        });
+    }
+
+    /// Samsung devices take Odin archives rather than a folder of images
+    fn choose_odin_archives(&self, device: Device) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Odin archives"));
+        filter.add_suffix("tar");
+        filter.add_suffix("md5");
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Choose Firmware Archives")
+            .modal(true)
+            .filters(&filters)
+            .build();
+
+        let window = self.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+        let self_clone = self.clone();
+        dialog.open_multiple(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            let Ok(files) = result else { return };
+            let archives: Vec<std::path::PathBuf> = (0..files.n_items())
+                .filter_map(|i| files.item(i).and_downcast::<gio::File>())
+                .filter_map(|file| file.path())
+                .collect();
+            if archives.is_empty() {
+                return;
+            }
+            self_clone.launch_heimdall_flash(&device, archives);
+        });
+    }
+
+    fn launch_heimdall_flash(&self, device: &Device, archives: Vec<std::path::PathBuf>) {
+        let Some(nav_view) = self.ancestor(adw::NavigationView::static_type())
+            .and_then(|w| w.downcast::<adw::NavigationView>().ok())
+        else {
+            log::error!("Could not find ancestor NavigationView");
+            return;
+        };
+        let serial = device
+            .serial
+            .clone()
+            .unwrap_or_else(|| heimdall::DOWNLOAD_MODE_SERIAL.to_string());
+
+        log::info!("Flashing {} Odin archive(s) to {}", archives.len(), device.codename);
+
+        if let Some(window) = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+        {
+            window.pause_detection();
+        }
+
+        let progress_page = FlashingPage::new();
+        if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
+            progress_page.set_menu_model(&menu_model);
+        }
+
+        progress_page.start_heimdall_installation(device, &serial, archives);
+        self.push_flashing_page(&nav_view, &progress_page);
+    }
+
+    /// Partition layouts of every distro known for this device, used to
+    /// recognise distro-specific filenames like `rootfs-google-sargo.img`.
+    fn known_partition_images(&self, device: &Device) -> Vec<PartitionImage> {
+        let mut known: Vec<PartitionImage> = Vec::new();
+        for distro in DeviceDatabase::new().get_distros(&device.codename) {
+            for image in distro.partitions {
+                if !known.iter().any(|k| k.image == image.image) {
+                    known.push(image);
+                }
+            }
+        }
+        known
+    }
+
+    fn show_local_mapping_page(&self, dir: &std::path::Path) {
+        let device = match self.imp().device.borrow().clone() {
+            Some(d) => d,
+            None => return,
+        };
+
+        let Some(nav_view) = self.ancestor(adw::NavigationView::static_type())
+            .and_then(|w| w.downcast::<adw::NavigationView>().ok())
+        else {
+            log::error!("Could not find ancestor NavigationView");
+            return;
+        };
+
+        let known = self.known_partition_images(&device);
+        let mappings = match local_images::scan_directory(dir, &known) {
+            Ok(m) => m,
+            Err(e) => {
+                log::error!("Failed to scan {}: {:#}", dir.display(), e);
+                if let Some(window) = self.root()
+                    .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                {
+                    window.show_toast(&format!("{}", e));
+                }
+                return;
+            }
+        };
+
+        // Choices offered for every file: skip, then the layout's partitions, then common ones
+        let mut partitions: Vec<String> = Vec::new();
+        for name in known.iter().map(|k| k.partition.as_str()).chain(COMMON_PARTITIONS.iter().copied()) {
+            if !partitions.iter().any(|p| p == name) {
+                partitions.push(name.to_string());
+            }
+        }
+        let mut choices = vec!["Skip"];
+        choices.extend(partitions.iter().map(|p| p.as_str()));
+        let model = gtk::StringList::new(&choices);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+
+        let scrolled = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .build();
+
+        let clamp = adw::Clamp::builder()
+            .maximum_size(600)
+            .margin_top(24)
+            .margin_bottom(24)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+
+        let content_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(24)
+            .build();
+
+        let status = adw::StatusPage::builder()
+            .icon_name("folder-symbolic")
+            .title("Confirm Partitions")
+            .description(&format!(
+                "Check which partition each image in {} will be flashed to. Unmatched files are skipped unless you pick a partition.",
+                dir.display()
+            ))
+            .build();
+        content_box.append(&status);
+
+        let group = adw::PreferencesGroup::builder()
+            .title("Images")
+            .description("Set an image to Skip to leave its partition as it is, e.g. to update only the kernel")
+            .build();
+
+        let mut combos: Vec<adw::ComboRow> = Vec::new();
+        for mapping in &mappings {
+            let combo = adw::ComboRow::builder()
+                .title(&mapping.file_name)
+                .model(&model)
+                .build();
+            let selected = mapping
+                .partition
+                .as_ref()
+                .and_then(|p| partitions.iter().position(|c| c == p))
+                .map(|i| i as u32 + 1)
+                .unwrap_or(0);
+            combo.set_selected(selected);
+            if mapping.partition.is_none() {
+                combo.set_subtitle("No match — choose a partition or skip");
+            }
+            group.add(&combo);
+            combos.push(combo);
+        }
+        content_box.append(&group);
+
+        let flash_btn = gtk::Button::builder()
+            .label("Flash Images")
+            .css_classes(vec!["destructive-action".to_string(), "pill".to_string()])
+            .halign(gtk::Align::Center)
+            .width_request(250)
+            .height_request(50)
+            .build();
+
+        let self_clone = self.clone();
+        let nav_clone = nav_view.clone();
+        flash_btn.connect_clicked(move |_| {
+            let confirmed: Vec<LocalImageMapping> = mappings
+                .iter()
+                .zip(&combos)
+                .filter_map(|(mapping, combo)| {
+                    let index = combo.selected() as usize;
+                    let partition = partitions.get(index.checked_sub(1)?)?.clone();
+                    let erase_first = mapping.partition.as_deref() == Some(partition.as_str())
+                        && mapping.erase_first;
+                    Some(LocalImageMapping {
+                        partition: Some(partition),
+                        erase_first,
+                        ..mapping.clone()
+                    })
+                })
+                .collect();
+
+            if confirmed.is_empty() {
+                if let Some(window) = self_clone.root()
+                    .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                {
+                    window.show_toast("Assign at least one image to a partition");
+                }
+                return;
+            }
+
+            // Recognised images the user chose not to flash
+            let skipped: Vec<String> = mappings
+                .iter()
+                .zip(&combos)
+                .filter(|(_, combo)| combo.selected() == 0)
+                .filter_map(|(mapping, _)| mapping.partition.clone())
+                .collect();
+
+            let nav = nav_clone.clone();
+            let target = device.clone();
+            let flash = move |page: &Self| {
+                let nav = nav.clone();
+                let confirmed = confirmed.clone();
+                let launch_target = target.clone();
+                page.with_device_in_mode(&target, LocalImageInstaller::REQUIRED_MODE, move |page| {
+                    page.launch_local_image_flash(&nav, &launch_target, confirmed.clone());
+                });
+            };
+            if skipped.is_empty() {
+                flash(&self_clone);
+            } else {
+                self_clone.confirm_partial_flash(&skipped, flash);
+            }
+        });
+        content_box.append(&flash_btn);
+
+        clamp.set_child(Some(&content_box));
+        scrolled.set_child(Some(&clamp));
+        toolbar_view.set_content(Some(&scrolled));
+
+        let page = adw::NavigationPage::builder()
+            .title("Flash Local Images")
+            .child(&toolbar_view)
+            .build();
+
+        nav_view.push(&page);
+    }
+
+    /// Flashing a subset is for repairs and kernel-only updates; make sure
+    /// the user knows the rest of the system stays as it was.
+    fn confirm_partial_flash<F: Fn(&Self) + 'static>(&self, skipped: &[String], flash: F) {
+        let dialog = adw::AlertDialog::new(
+            Some("Flash Only Some Partitions?"),
+            Some(&format!(
+                "These partitions won't be flashed: {}.\n\nWhat is already on them stays. If it doesn't match the new images, for example an old system with a new kernel, the device may not boot until the full set is flashed.",
+                skipped.join(", ")
+            )),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("flash", "Flash Selected")]);
+        dialog.set_response_appearance("flash", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        let page = self.downgrade();
+        let skipped = skipped.join(", ");
+        dialog.connect_response(None, move |_, response| {
+            if response != "flash" {
+                return;
+            }
+            if let Some(page) = page.upgrade() {
+                log::warn!("Partial flash confirmed; leaving {} as they are", skipped);
+                flash(&page);
+            }
+        });
+        dialog.present(Some(self));
+    }
+
+    fn launch_local_image_flash(
+        &self,
+        nav_view: &adw::NavigationView,
+        device: &Device,
+        images: Vec<LocalImageMapping>,
+    ) {
+        let Some(ref serial) = device.serial else {
+            log::error!("No device serial available for local image flash");
+            return;
+        };
+
+        log::info!("Flashing {} local image(s) to {}", images.len(), serial);
+
+        if let Some(window) = self.root()
+            .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+        {
+            window.pause_detection();
+        }
+
+        let progress_page = FlashingPage::new();
+        if let Some(menu_model) = self.imp().main_menu_button.menu_model() {
+            progress_page.set_menu_model(&menu_model);
+        }
+
+        progress_page.start_local_image_installation(device, serial, images);
+        self.push_flashing_page(nav_view, &progress_page);
+    }
+
     fn on_unlock_clicked(&self) {
         self.emit_by_name::<()>("unlock-clicked", &[]);
     }
 }
 
+/// Where support requests for new devices are filed
+const NEW_ISSUE_URL: &str = "https://github.com/tobagin/Sidestep/issues/new";
+
+/// User-facing explanation for a channel that failed `ChannelConfig::validate`.
+fn incomplete_channel_message(missing: &[String]) -> String {
+    format!(
+        "This channel can't be installed: its configuration is missing {}",
+        missing.join(", ")
+    )
+}
+
 /// Sanitize a manufacturer name for use as a filesystem directory.
 /// Strips characters that aren't alphanumeric, hyphen, or underscore,
 /// then lowercases. e.g. "F(x)tec" → "fxtec".
@@ -1619,6 +3750,37 @@ impl Default for DeviceDetailsPage {
     }
 }
 
+/// One activatable row per interface with its description and download
+/// size, calling `on_selected` with the chosen interface id.
+fn interface_choice_group<F: Fn(&str) + 'static>(
+    interfaces: &[InterfaceConfig],
+    on_selected: F,
+) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    let on_selected = std::rc::Rc::new(on_selected);
+
+    for iface in interfaces {
+        let row = adw::ActionRow::builder()
+            .title(&iface.label)
+            .activatable(true)
+            .build();
+        let summary = iface.summary();
+        if !summary.is_empty() {
+            row.set_subtitle(&summary);
+        }
+        row.add_prefix(&gtk::Image::from_icon_name("desktop-symbolic"));
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+        let on_selected = on_selected.clone();
+        let iface_id = iface.id.clone();
+        row.connect_activated(move |_| on_selected(&iface_id));
+
+        group.add(&row);
+    }
+
+    group
+}
+
 impl DeviceDetailsPage {
     pub fn connect_unlock_clicked<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
         self.connect_closure(
@@ -1634,6 +3796,7 @@ impl DeviceDetailsPage {
 impl ObjectImpl for imp::DeviceDetailsPage {
     fn constructed(&self) {
         self.parent_constructed();
+        self.header_bar.pack_start(&ConnectionIndicator::new());
     }
 
     fn signals() -> &'static [glib::subclass::Signal] {
@@ -1,12 +1,15 @@
 // Device Info Page — read-only device information for the browse flow
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::config;
+use crate::flashing::channel_info;
 use crate::models::Device;
+use crate::pages::accessibility::status_icon;
+use crate::pages::connection_indicator::ConnectionIndicator;
 use crate::models::device_info::DeviceInfo;
 use crate::models::distro_config::{CompatibilityInfo, DistroConfig};
+use crate::utils::data_check;
 use crate::utils::yaml_parser::YamlParser;
-use gtk::{glib, prelude::*, subclass::prelude::*};
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
@@ -52,7 +55,9 @@ impl DeviceInfoPage {
 
     fn build_ui(&self, device: &Device) {
         let toolbar_view = adw::ToolbarView::new();
-        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        let header_bar = adw::HeaderBar::new();
+        header_bar.pack_start(&ConnectionIndicator::new());
+        toolbar_view.add_top_bar(&header_bar);
 
         let scrolled = gtk::ScrolledWindow::builder()
             .hscrollbar_policy(gtk::PolicyType::Never)
@@ -78,6 +83,7 @@ impl DeviceInfoPage {
         let info = self.load_device_info(device);
 
         if let Some(ref info) = info {
+            self.build_known_issues_section(&content_box, info);
             self.build_specs_section(&content_box, info);
             self.build_display_section(&content_box, info);
             self.build_connectivity_section(&content_box, info);
@@ -145,6 +151,32 @@ impl DeviceInfoPage {
         content_box.append(&header_box);
     }
 
+    // ────────────────────────────────────────────────────────────────
+    // Known issues (shown above the specs so they are read first)
+    // ────────────────────────────────────────────────────────────────
+
+    fn build_known_issues_section(&self, content_box: &gtk::Box, info: &DeviceInfo) {
+        let issues = &info.device.known_issues;
+        if issues.is_empty() {
+            return;
+        }
+
+        let group = adw::PreferencesGroup::builder()
+            .title("Known Issues")
+            .description("Read these before installing")
+            .build();
+
+        for issue in issues {
+            let row = adw::ActionRow::builder().title(issue).title_lines(0).build();
+            let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+            icon.add_css_class("warning");
+            row.add_prefix(&icon);
+            group.add(&row);
+        }
+
+        content_box.append(&group);
+    }
+
     // ────────────────────────────────────────────────────────────────
     // Specifications
     // ────────────────────────────────────────────────────────────────
@@ -156,18 +188,15 @@ impl DeviceInfoPage {
             .title("Specifications")
             .build();
 
-        group.add(&make_info_row("SoC", &specs.soc));
-        group.add(&make_info_row("CPU", &specs.cpu));
-        group.add(&make_info_row("GPU", &specs.gpu));
-        group.add(&make_info_row("RAM", &specs.ram));
-        group.add(&make_info_row("Storage", &specs.storage));
-        group.add(&make_info_row("Battery", &specs.battery));
+        let rows: Vec<(&str, Option<&str>)> = specs
+            .rows()
+            .into_iter()
+            .map(|(title, value)| (title, Some(value)))
+            .collect();
 
-        if let Some(ref arch) = specs.arch {
-            group.add(&make_info_row("Architecture", arch));
+        if add_info_rows(&group, &rows) {
+            content_box.append(&group);
         }
-
-        content_box.append(&group);
     }
 
     // ────────────────────────────────────────────────────────────────
@@ -181,13 +210,17 @@ impl DeviceInfoPage {
             .title("Display")
             .build();
 
-        group.add(&make_info_row("Size", &display.size));
-        group.add(&make_info_row("Resolution", &display.resolution));
-        group.add(&make_info_row("Panel Type", &display.panel_type));
-        group.add(&make_info_row("Density", &display.density));
-        group.add(&make_info_row("Refresh Rate", &display.refresh_rate));
+        let rows = [
+            ("Size", display.size.as_deref()),
+            ("Resolution", display.resolution.as_deref()),
+            ("Panel Type", display.panel_type.as_deref()),
+            ("Density", display.density.as_deref()),
+            ("Refresh Rate", display.refresh_rate.as_deref()),
+        ];
 
-        content_box.append(&group);
+        if add_info_rows(&group, &rows) {
+            content_box.append(&group);
+        }
     }
 
     // ────────────────────────────────────────────────────────────────
@@ -201,14 +234,23 @@ impl DeviceInfoPage {
             .title("Connectivity")
             .build();
 
-        group.add(&make_info_row("Network", &conn.network.join(", ")));
-        group.add(&make_info_row("Bluetooth", &conn.bluetooth));
-        group.add(&make_info_row("WiFi", &conn.wifi));
-        group.add(&make_info_row("Peripherals", &conn.peripherals.join(", ")));
-        group.add(&make_info_row("Sensors", &conn.sensors.join(", ")));
-        group.add(&make_info_row("Location", &conn.location.join(", ")));
+        let network = conn.network.join(", ");
+        let peripherals = conn.peripherals.join(", ");
+        let sensors = conn.sensors.join(", ");
+        let location = conn.location.join(", ");
+
+        let rows = [
+            ("Network", Some(network.as_str())),
+            ("Bluetooth", conn.bluetooth.as_deref()),
+            ("WiFi", conn.wifi.as_deref()),
+            ("Peripherals", Some(peripherals.as_str())),
+            ("Sensors", Some(sensors.as_str())),
+            ("Location", Some(location.as_str())),
+        ];
 
-        content_box.append(&group);
+        if add_info_rows(&group, &rows) {
+            content_box.append(&group);
+        }
     }
 
     // ────────────────────────────────────────────────────────────────
@@ -290,6 +332,63 @@ impl DeviceInfoPage {
     // Distro detail sub-page
     // ────────────────────────────────────────────────────────────────
 
+    /// Look up each channel's current version and download size off the
+    /// main thread and show them as the rows' subtitles. Offline, nothing
+    /// is looked up and the rows say so instead.
+    fn fill_channel_details(&self, device: &Device, distro: &DistroConfig, rows: Vec<adw::ActionRow>) {
+        if !gio::NetworkMonitor::default().is_network_available() {
+            for row in &rows {
+                row.set_subtitle(UNAVAILABLE_OFFLINE);
+            }
+            return;
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let device = device.clone();
+        let distro = distro.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let mut unreachable = false;
+            for (index, channel) in distro.channels.iter().enumerate() {
+                // Once one lookup can't reach anything, the rest won't either
+                let result = if unreachable {
+                    Err(None)
+                } else {
+                    rt.block_on(channel_info::channel_details(&device, &distro, channel))
+                        .map_err(|e| {
+                            unreachable = channel_info::is_unreachable(&e);
+                            (!unreachable).then_some(e)
+                        })
+                };
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            loop {
+                let (index, result) = match receiver.try_recv() {
+                    Ok(message) => message,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(_) => return glib::ControlFlow::Break,
+                };
+                let Some(row) = rows.get(index) else { continue };
+                match result {
+                    Ok(details) => row.set_subtitle(&details.summary()),
+                    Err(None) => row.set_subtitle(UNAVAILABLE_OFFLINE),
+                    Err(Some(e)) => {
+                        log::warn!("Could not look up {}: {:#}", row.title(), e);
+                        row.set_subtitle("");
+                    }
+                }
+            }
+        });
+    }
+
     fn show_distro_detail(&self, device: &Device, distro: &DistroConfig) {
         let Some(nav_view) = self.ancestor(adw::NavigationView::static_type())
             .and_then(|w| w.downcast::<adw::NavigationView>().ok())
@@ -362,16 +461,20 @@ impl DeviceInfoPage {
                 .title("Available Channels")
                 .build();
 
+            let mut rows = Vec::new();
             for channel in &distro.channels {
                 let row = adw::ActionRow::builder()
                     .title(&channel.label)
+                    .subtitle("Checking latest build…")
                     .build();
                 let icon = gtk::Image::from_icon_name("emblem-system-symbolic");
                 row.add_prefix(&icon);
                 channels_group.add(&row);
+                rows.push(row);
             }
 
             content_box.append(&channels_group);
+            self.fill_channel_details(device, distro, rows);
         }
 
         // Interfaces
@@ -385,6 +488,10 @@ impl DeviceInfoPage {
                     let row = adw::ActionRow::builder()
                         .title(&iface.label)
                         .build();
+                    let summary = iface.summary();
+                    if !summary.is_empty() {
+                        row.set_subtitle(&summary);
+                    }
                     let icon = gtk::Image::from_icon_name("desktop-symbolic");
                     row.add_prefix(&icon);
                     iface_group.add(&row);
@@ -452,8 +559,7 @@ impl DeviceInfoPage {
                 .build();
             for item in &compat.working {
                 let row = adw::ActionRow::builder().title(item).build();
-                let icon = gtk::Image::from_icon_name("object-select-symbolic");
-                icon.add_css_class("success");
+                let icon = status_icon("object-select-symbolic", Some("success"), "Working");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -467,8 +573,7 @@ impl DeviceInfoPage {
                 .build();
             for item in &compat.partial {
                 let row = adw::ActionRow::builder().title(item).build();
-                let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
-                icon.add_css_class("warning");
+                let icon = status_icon("dialog-warning-symbolic", Some("warning"), "Partially working");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -482,8 +587,7 @@ impl DeviceInfoPage {
                 .build();
             for item in &compat.not_working {
                 let row = adw::ActionRow::builder().title(item).build();
-                let icon = gtk::Image::from_icon_name("process-stop-symbolic");
-                icon.add_css_class("error");
+                let icon = status_icon("process-stop-symbolic", Some("error"), "Not working");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -497,8 +601,7 @@ impl DeviceInfoPage {
                 .build();
             for item in &compat.untested {
                 let row = adw::ActionRow::builder().title(item).build();
-                let icon = gtk::Image::from_icon_name("dialog-question-symbolic");
-                icon.add_css_class("dim-label");
+                let icon = status_icon("dialog-question-symbolic", Some("dim-label"), "Untested");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -512,7 +615,7 @@ impl DeviceInfoPage {
                 .build();
             for note in &compat.notes {
                 let row = adw::ActionRow::builder().title(note).build();
-                let icon = gtk::Image::from_icon_name("dialog-information-symbolic");
+                let icon = status_icon("dialog-information-symbolic", None, "Note");
                 row.add_prefix(&icon);
                 group.add(&row);
             }
@@ -525,12 +628,7 @@ impl DeviceInfoPage {
     // ────────────────────────────────────────────────────────────────
 
     fn load_device_info(&self, device: &Device) -> Option<DeviceInfo> {
-        let possible_dirs = vec![
-            std::path::PathBuf::from(config::PKGDATADIR).join("devices"),
-            std::path::PathBuf::from("/app/share/sidestep/devices"),
-            std::path::PathBuf::from("data/devices"),
-            std::path::PathBuf::from("devices"),
-        ];
+        let possible_dirs = data_check::devices_dir_candidates();
 
         let manufacturer = maker_to_dir(&device.maker);
 
@@ -546,12 +644,7 @@ impl DeviceInfoPage {
     }
 
     fn load_all_distros(&self, device: &Device) -> Vec<DistroConfig> {
-        let possible_paths = vec![
-            std::path::PathBuf::from(config::PKGDATADIR).join("devices"),
-            std::path::PathBuf::from("/app/share/sidestep/devices"),
-            std::path::PathBuf::from("data/devices"),
-            std::path::PathBuf::from("devices"),
-        ];
+        let possible_paths = data_check::devices_dir_candidates();
         let devices_path = possible_paths
             .into_iter()
             .find(|p| p.exists())
@@ -581,6 +674,19 @@ fn maker_to_dir(maker: &str) -> String {
         .to_lowercase()
 }
 
+/// Add a row for every value that is present. Returns whether any were added,
+/// so callers can drop sections that would otherwise be empty.
+fn add_info_rows(group: &adw::PreferencesGroup, rows: &[(&str, Option<&str>)]) -> bool {
+    let mut added = false;
+    for (title, value) in rows {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            group.add(&make_info_row(title, value));
+            added = true;
+        }
+    }
+    added
+}
+
 fn make_info_row(title: &str, value: &str) -> adw::ActionRow {
     let row = adw::ActionRow::builder()
         .title(title)
@@ -592,3 +698,6 @@ fn make_info_row(title: &str, value: &str) -> adw::ActionRow {
     row.add_suffix(&label);
     row
 }
+
+/// Subtitle of rows whose details come from a server that can't be reached
+const UNAVAILABLE_OFFLINE: &str = "Version and size unavailable offline";
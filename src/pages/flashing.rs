@@ -1,7 +1,13 @@
 // Flashing Progress Page
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::flashing::{DroidianInstaller, EosInstaller, FactoryImageInstaller, InstallProgress, LineageosInstaller, MobianInstaller, PostmarketosInstaller, UbportsInstaller};
+use crate::config;
+use crate::utils::format::{format_estimate, format_progress, format_remaining, format_size};
+use crate::flashing::mode_wait::TargetMode;
+use crate::hardware::CancelToken;
+use crate::models::Device;
+use crate::models::distro_config::ArchiveEntry;
+use crate::flashing::{ChecksumAlgorithm, DownloadCancelHandle, DroidianInstaller, EosInstaller, EventBus, FactoryImageInstaller, GithubReleaseSource, HeimdallInstaller, ImageDownloader, InstallEvent, InstallOutcome, InstallProgress, InstallReport, LineageosInstaller, LocalImageInstaller, LocalImageMapping, MobianInstaller, OverallProgress, PhaseWeights, PostmarketosInstaller, ProgressPhase, RemainingTime, SessionStats, StepTimer, TemporaryBootInstaller, UbportsInstaller};
 use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
@@ -14,9 +20,47 @@ mod imp {
     #[template(resource = "/io/github/tobagin/Sidestep/ui/pages/flashing.ui")]
     pub struct FlashingPage {
         pub distro_name: std::cell::RefCell<String>,
+        pub download_only: std::cell::Cell<bool>,
+        pub unverified: std::cell::Cell<bool>,
+        pub download_cancel: DownloadCancelHandle,
+        /// Stops the whole install, including a flash in progress
+        pub install_cancel: CancelToken,
+        /// Set once the user confirmed cancelling, so the download stopping
+        /// reads as a cancelled install and not a request to choose again
+        pub cancelling: std::cell::Cell<bool>,
+        pub stall_warning: std::cell::Cell<bool>,
+        /// `current` and `total` of the last flash step, for placing a
+        /// partition's own progress inside it
+        pub flash_step: std::cell::Cell<(usize, usize)>,
+        pub confirmation_prompt: std::cell::Cell<bool>,
+        pub button_hint: std::cell::RefCell<String>,
+        /// Cache directory the running installer downloads into
+        pub image_dir: std::cell::RefCell<Option<std::path::PathBuf>>,
+        /// Lets the user end a slow mode transition wait manually
+        pub mode_override: std::cell::RefCell<Option<std::sync::mpsc::Sender<()>>>,
+        /// Scopes recorded flash step durations to the model being installed
+        pub device_codename: std::cell::RefCell<String>,
+        /// Started by the first flash step
+        pub step_timer: std::cell::RefCell<Option<StepTimer>>,
+        /// Time left in the download, unpacking or flash under way
+        pub remaining: std::cell::RefCell<RemainingTime>,
+        /// Drives the bar for the install as a whole
+        pub overall: std::cell::RefCell<OverallProgress>,
+        /// Bytes, speed and timings for the success page and the report
+        pub stats: std::cell::RefCell<SessionStats>,
+        /// The distro's hint for what its published digests are made with
+        pub checksum_algorithm: std::cell::Cell<Option<ChecksumAlgorithm>>,
+        /// Room the install needs in its download directory, when known
+        pub space_needed: std::cell::Cell<Option<u64>>,
+        /// What "Save Report" writes out, filled in as the install runs
+        pub report: std::cell::RefCell<InstallReport>,
         #[template_child]
         pub status_page: TemplateChild<adw::StatusPage>,
         #[template_child]
+        pub overall_caption: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub overall_progress: TemplateChild<gtk::ProgressBar>,
+        #[template_child]
         pub download_row: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub download_progress: TemplateChild<gtk::ProgressBar>,
@@ -35,12 +79,24 @@ mod imp {
         #[template_child]
         pub error_banner: TemplateChild<adw::Banner>,
         #[template_child]
+        pub slow_transition_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub slow_transition_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub stop_download_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub stop_download_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub cancel_box: TemplateChild<gtk::Box>,
+        #[template_child]
         pub cancel_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub restart_box: TemplateChild<gtk::Box>,
         #[template_child]
         pub restart_button: TemplateChild<gtk::Button>,
         #[template_child]
+        pub report_button: TemplateChild<gtk::Button>,
+        #[template_child]
         pub main_menu_button: TemplateChild<gtk::MenuButton>,
     }
 
@@ -52,6 +108,20 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+            klass.install_action("flashing.stop-download", None, move |page, _, _| {
+                page.stop_download();
+            });
+            klass.install_action("flashing.cancel", None, move |page, _, _| {
+                page.confirm_cancel();
+            });
+            klass.install_action("flashing.continue-anyway", None, move |page, _, _| {
+                page.continue_anyway();
+            });
+            klass.add_binding_action(
+                gtk::gdk::Key::Escape,
+                gtk::gdk::ModifierType::empty(),
+                "flashing.stop-download",
+            );
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -66,6 +136,16 @@ mod imp {
                     vec![
                         glib::subclass::Signal::builder("installation-complete").build(),
                         glib::subclass::Signal::builder("installation-failed").build(),
+                        glib::subclass::Signal::builder("download-cancelled").build(),
+                        glib::subclass::Signal::builder("download-complete")
+                            .param_types([String::static_type()])
+                            .build(),
+                        glib::subclass::Signal::builder("download-failed")
+                            .param_types([String::static_type()])
+                            .build(),
+                        glib::subclass::Signal::builder("channel-unavailable")
+                            .param_types([String::static_type(), String::static_type()])
+                            .build(),
                     ]
                 });
             &SIGNALS
@@ -73,11 +153,20 @@ mod imp {
 
         fn constructed(&self) {
             self.parent_constructed();
+            self.obj().set_download_stoppable(false);
+            self.obj().set_cancellable(false);
 
             let obj = self.obj().clone();
             self.restart_button.connect_clicked(move |_| {
                 obj.emit_by_name::<()>("installation-failed", &[]);
             });
+
+            let obj = self.obj().downgrade();
+            self.report_button.connect_clicked(move |_| {
+                if let Some(obj) = obj.upgrade() {
+                    obj.save_report();
+                }
+            });
         }
     }
     impl WidgetImpl for FlashingPage {}
@@ -119,32 +208,145 @@ impl FlashingPage {
         )
     }
 
+    pub fn connect_download_cancelled<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "download-cancelled",
+            false,
+            glib::closure_local!(move |obj: Self| {
+                f(&obj);
+            }),
+        )
+    }
+
+    pub fn connect_download_complete<F: Fn(&Self, String) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "download-complete",
+            false,
+            glib::closure_local!(move |obj: Self, location: String| {
+                f(&obj, location);
+            }),
+        )
+    }
+
+    pub fn connect_download_failed<F: Fn(&Self, String) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "download-failed",
+            false,
+            glib::closure_local!(move |obj: Self, message: String| {
+                f(&obj, message);
+            }),
+        )
+    }
+
+    /// The release channel is gone from the server and another one still
+    /// has builds; called with the old channel and the suggested one.
+    pub fn connect_channel_unavailable<F: Fn(&Self, String, String) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "channel-unavailable",
+            false,
+            glib::closure_local!(move |obj: Self, channel: String, suggestion: String| {
+                f(&obj, channel, suggestion);
+            }),
+        )
+    }
+
+    /// Only download and verify images; the installer stops before touching the device.
+    /// Must be called before one of the `start_*_installation` methods.
+    pub fn set_download_only(&self, download_only: bool) {
+        self.imp().download_only.set(download_only);
+    }
+
+    /// Hash function the distro's digests are made with, from its
+    /// `checksum_algorithm`. Must be called before one of the
+    /// `start_*_installation` methods.
+    pub fn set_checksum_algorithm(&self, algorithm: Option<ChecksumAlgorithm>) {
+        self.imp().checksum_algorithm.set(algorithm);
+    }
+
+    /// Disk space the install needs for its downloads and unpacking; it
+    /// fails before downloading anything when there isn't that much free.
+    /// Must be called before one of the `start_*_installation` methods.
+    pub fn set_space_needed(&self, needed: Option<u64>) {
+        self.imp().space_needed.set(needed);
+    }
+
+    /// Manufacturer-specific wording for answering prompts with the hardware buttons.
+    pub fn set_button_hint(&self, hint: &str) {
+        *self.imp().button_hint.borrow_mut() = hint.to_string();
+    }
+
+    /// Model being installed, so flash steps are estimated from earlier
+    /// installs on the same one.
+    pub fn set_device_codename(&self, codename: &str) {
+        *self.imp().device_codename.borrow_mut() = codename.to_string();
+    }
+
     pub fn set_distro_name(&self, name: &str) {
         log::info!("Starting installation for: {}", name);
         self.imp().distro_name.replace(name.to_string());
+        self.imp().report.borrow_mut().distro = name.to_string();
+    }
+
+    /// The device being installed, for the failure report
+    pub fn set_report_device(&self, device: &Device) {
+        self.imp().report.borrow_mut().device = Some(device.clone());
+    }
+
+    /// Record the choices behind this run for the failure report.
+    fn note_options(&self, serial: &str, options: &[(&str, &str)]) {
+        let mut report = self.imp().report.borrow_mut();
+        report.serial = Some(serial.to_string());
+        report.options = options
+            .iter()
+            .map(|(label, value)| (label.to_string(), value.to_string()))
+            .collect();
+        if self.imp().download_only.get() {
+            report.options.push(("Download only".to_string(), "yes".to_string()));
+        }
     }
 
     pub fn distro_name(&self) -> String {
         self.imp().distro_name.borrow().clone()
     }
 
+    /// How much this run downloaded and how long it took
+    pub fn session_stats(&self) -> SessionStats {
+        self.imp().stats.borrow().clone()
+    }
+
+    /// Where the installer cached its downloads, for offering to reclaim the space.
+    pub fn image_dir(&self) -> Option<std::path::PathBuf> {
+        self.imp().image_dir.borrow().clone()
+    }
+
     /// Legacy mock-based installation (for non-UBports distros)
     pub fn start_installation(&self, distro_name: &str) {
         self.set_distro_name(distro_name);
 
         let imp = self.imp();
-        imp.status_page.set_title(&format!("Installing {}", distro_name));
+        self.set_running_title(distro_name);
         imp.status_page.set_description(Some("Preparing..."));
 
         self.mock_progress();
     }
 
     /// Start real UBports installation with progress from background thread
-    pub fn start_ubports_installation(&self, distro_name: &str, serial: &str, channel_path: &str) {
+    /// `version` pins the install to a system-image version rather than
+    /// the channel's newest
+    pub fn start_ubports_installation(
+        &self,
+        distro_name: &str,
+        serial: &str,
+        channel_path: &str,
+        version: Option<u32>,
+    ) {
         self.set_distro_name(distro_name);
 
         let imp = self.imp();
-        imp.status_page.set_title(&format!("Installing {}", distro_name));
+        self.set_running_title(distro_name);
         imp.status_page.set_description(Some("Preparing..."));
 
         // Repurpose the "Decompress" row for checksum verification
@@ -152,20 +354,22 @@ impl FlashingPage {
         #[allow(deprecated)]
         imp.decompress_row.set_icon_name(Some("channel-secure-symbolic"));
 
-        let installer = UbportsInstaller::new(serial.to_string(), channel_path.to_string());
-        let receiver = installer.spawn();
+        let version_label = version.map(|v| v.to_string());
+        let mut options = vec![("Channel", channel_path)];
+        if let Some(ref version) = version_label {
+            options.push(("Version", version.as_str()));
+        }
+        self.note_options(serial, &options);
+        let installer = UbportsInstaller::new(serial.to_string(), channel_path.to_string())
+            .version(version)
+            .download_only(imp.download_only.get())
+            .download_cancel_handle(imp.download_cancel.clone())
+            .cancel_handle(imp.install_cancel.clone());
+        let download_dir = installer.download_dir().to_path_buf();
+        let receiver = self.spawn_checked(&download_dir, move || installer.spawn());
 
         // Poll receiver on the main thread
-        let page = self.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
-                if should_stop {
-                    return glib::ControlFlow::Break;
-                }
-            }
-            glib::ControlFlow::Continue
-        });
+        self.watch_progress(receiver);
     }
 
     /// Start real Droidian installation with progress from background thread
@@ -173,13 +377,13 @@ impl FlashingPage {
         &self,
         distro_name: &str,
         serial: &str,
-        release_url: &str,
+        source: GithubReleaseSource,
         artifact_pattern: &str,
     ) {
         self.set_distro_name(distro_name);
 
         let imp = self.imp();
-        imp.status_page.set_title(&format!("Installing {}", distro_name));
+        self.set_running_title(distro_name);
         imp.status_page.set_description(Some("Preparing..."));
 
         // Repurpose the "Decompress" row for ZIP extraction
@@ -188,24 +392,25 @@ impl FlashingPage {
         imp.decompress_row
             .set_icon_name(Some("package-x-generic-symbolic"));
 
+        let mut options = vec![("Release source", source.repo()), ("Artifact", artifact_pattern)];
+        if let Some(pinned) = source.pinned_asset() {
+            options.push(("Build", pinned));
+        }
+        self.note_options(serial, &options);
         let installer = DroidianInstaller::new(
             serial.to_string(),
-            release_url.to_string(),
+            source,
             artifact_pattern.to_string(),
-        );
-        let receiver = installer.spawn();
+        )
+        .checksum_algorithm(imp.checksum_algorithm.get())
+        .download_only(imp.download_only.get())
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
+        let download_dir = installer.download_dir().to_path_buf();
+        let receiver = self.spawn_checked(&download_dir, move || installer.spawn());
 
         // Poll receiver on the main thread
-        let page = self.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
-                if should_stop {
-                    return glib::ControlFlow::Break;
-                }
-            }
-            glib::ControlFlow::Continue
-        });
+        self.watch_progress(receiver);
     }
 
     /// Start real Mobian installation with progress from background thread
@@ -214,6 +419,7 @@ impl FlashingPage {
         distro_name: &str,
         serial: &str,
         base_url: &str,
+        mirrors: Vec<String>,
         interface: &str,
         chipset: &str,
         device_model: &str,
@@ -221,7 +427,7 @@ impl FlashingPage {
         self.set_distro_name(distro_name);
 
         let imp = self.imp();
-        imp.status_page.set_title(&format!("Installing {}", distro_name));
+        self.set_running_title(distro_name);
         imp.status_page.set_description(Some("Preparing..."));
 
         // Repurpose the "Decompress" row for tar.xz extraction
@@ -230,26 +436,27 @@ impl FlashingPage {
         imp.decompress_row
             .set_icon_name(Some("package-x-generic-symbolic"));
 
+        self.note_options(
+            serial,
+            &[("Interface", interface), ("Chipset", chipset), ("Model", device_model), ("Image server", base_url)],
+        );
         let installer = MobianInstaller::new(
             serial.to_string(),
             base_url.to_string(),
             interface.to_string(),
             chipset.to_string(),
             device_model.to_string(),
-        );
-        let receiver = installer.spawn();
+        )
+        .mirrors(mirrors)
+        .checksum_algorithm(imp.checksum_algorithm.get())
+        .download_only(imp.download_only.get())
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
+        let download_dir = installer.download_dir().to_path_buf();
+        let receiver = self.spawn_checked(&download_dir, move || installer.spawn());
 
         // Poll receiver on the main thread
-        let page = self.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
-                if should_stop {
-                    return glib::ControlFlow::Break;
-                }
-            }
-            glib::ControlFlow::Continue
-        });
+        self.watch_progress(receiver);
     }
 
     /// Start real postmarketOS installation with progress from background thread
@@ -258,36 +465,39 @@ impl FlashingPage {
         distro_name: &str,
         serial: &str,
         base_url: &str,
+        mirrors: Vec<String>,
         channel: &str,
         interface: &str,
         device: &str,
+        archive_partitions: Vec<ArchiveEntry>,
     ) {
         self.set_distro_name(distro_name);
 
         let imp = self.imp();
-        imp.status_page.set_title(&format!("Installing {}", distro_name));
+        self.set_running_title(distro_name);
         imp.status_page.set_description(Some("Preparing..."));
 
+        self.note_options(
+            serial,
+            &[("Channel", channel), ("Interface", interface), ("Device", device), ("Image server", base_url)],
+        );
         let installer = PostmarketosInstaller::new(
             serial.to_string(),
             base_url.to_string(),
             channel.to_string(),
             interface.to_string(),
             device.to_string(),
-        );
-        let receiver = installer.spawn();
+        )
+        .archive_partitions(archive_partitions)
+        .mirrors(mirrors)
+        .download_only(imp.download_only.get())
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
+        let download_dir = installer.download_dir().to_path_buf();
+        let receiver = self.spawn_checked(&download_dir, move || installer.spawn());
 
         // Poll receiver on the main thread
-        let page = self.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
-                if should_stop {
-                    return glib::ControlFlow::Break;
-                }
-            }
-            glib::ControlFlow::Continue
-        });
+        self.watch_progress(receiver);
     }
 
     /// Start real LineageOS installation with progress from background thread
@@ -301,7 +511,7 @@ impl FlashingPage {
         self.set_distro_name(distro_name);
 
         let imp = self.imp();
-        imp.status_page.set_title(&format!("Installing {}", distro_name));
+        self.set_running_title(distro_name);
         imp.status_page.set_description(Some("Preparing..."));
         imp.status_page.set_icon_name(Some("lineageos-symbolic"));
 
@@ -310,24 +520,23 @@ impl FlashingPage {
         #[allow(deprecated)]
         imp.decompress_row.set_icon_name(Some("channel-secure-symbolic"));
 
+        self.note_options(
+            serial,
+            &[("Build API", api_url), ("Update only", if update_only { "yes" } else { "no" })],
+        );
         let installer = LineageosInstaller::new(
             serial.to_string(),
             api_url.to_string(),
             update_only,
-        );
-        let receiver = installer.spawn();
+        )
+        .download_only(imp.download_only.get())
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
+        let download_dir = installer.download_dir().to_path_buf();
+        let receiver = self.spawn_checked(&download_dir, move || installer.spawn());
 
         // Poll receiver on the main thread
-        let page = self.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
-                if should_stop {
-                    return glib::ControlFlow::Break;
-                }
-            }
-            glib::ControlFlow::Continue
-        });
+        self.watch_progress(receiver);
     }
 
     /// Start /e/OS installation with progress from background thread
@@ -335,13 +544,14 @@ impl FlashingPage {
         &self,
         serial: &str,
         base_url: &str,
+        mirrors: Vec<String>,
         codename: &str,
         channel: &str,
     ) {
         self.set_distro_name("/e/OS");
 
         let imp = self.imp();
-        imp.status_page.set_title("Installing /e/OS");
+        self.set_running_title("/e/OS");
         imp.status_page.set_description(Some("Preparing..."));
         imp.status_page.set_icon_name(Some("eos-symbolic"));
 
@@ -349,24 +559,22 @@ impl FlashingPage {
         #[allow(deprecated)]
         imp.decompress_row.set_icon_name(Some("channel-secure-symbolic"));
 
+        self.note_options(serial, &[("Channel", channel), ("Image server", base_url)]);
         let installer = EosInstaller::new(
             serial.to_string(),
             base_url.to_string(),
             codename.to_string(),
             channel.to_string(),
-        );
-        let receiver = installer.spawn();
-
-        let page = self.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
-                if should_stop {
-                    return glib::ControlFlow::Break;
-                }
-            }
-            glib::ControlFlow::Continue
-        });
+        )
+        .mirrors(mirrors)
+        .checksum_algorithm(imp.checksum_algorithm.get())
+        .download_only(imp.download_only.get())
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
+        let download_dir = installer.download_dir().to_path_buf();
+        let receiver = self.spawn_checked(&download_dir, move || installer.spawn());
+
+        self.watch_progress(receiver);
     }
 
     /// Start factory image (stock Android) installation with progress from background thread
@@ -390,19 +598,183 @@ impl FlashingPage {
         imp.decompress_row
             .set_icon_name(Some("package-x-generic-symbolic"));
 
+        self.note_options(serial, &[("Image", url)]);
         let installer = FactoryImageInstaller::new(
             serial.to_string(),
             url.to_string(),
             sha256.to_string(),
             android_version.to_string(),
-        );
+        )
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
+        let receiver = installer.spawn();
+
+        self.watch_progress(receiver);
+    }
+
+    /// Boot a distro once via `fastboot boot` so it can be tried without installing.
+    pub fn start_temporary_boot(
+        &self,
+        distro_id: &str,
+        distro_name: &str,
+        serial: &str,
+        url: &str,
+        sha256: Option<&str>,
+    ) {
+        self.set_distro_name(distro_name);
+
+        let imp = self.imp();
+        imp.status_page.set_title(&format!("Trying {}", distro_name));
+        imp.status_page.set_description(Some("Preparing..."));
+        imp.decompress_row.set_visible(false);
+        imp.flash_row.set_title("Booting");
+
+        self.note_options(serial, &[("Image", url)]);
+        let installer = TemporaryBootInstaller::new(
+            serial.to_string(),
+            distro_id,
+            distro_name.to_string(),
+            url.to_string(),
+            sha256.map(str::to_string),
+        )
+        .download_cancel_handle(imp.download_cancel.clone())
+        .cancel_handle(imp.install_cancel.clone());
         let receiver = installer.spawn();
 
+        self.watch_progress(receiver);
+    }
+
+    /// Flash images the user already has on disk; there is nothing to
+    /// download or decompress, so only the flash row is shown.
+    pub fn start_local_image_installation(&self, device: &Device, serial: &str, images: Vec<LocalImageMapping>) {
+        self.set_distro_name("Local Images");
+
+        let imp = self.imp();
+        imp.status_page.set_title("Flashing Local Images");
+        imp.status_page.set_description(Some("Preparing..."));
+        imp.download_row.set_visible(false);
+        imp.decompress_row.set_visible(false);
+        imp.verify_row.set_visible(false);
+
+        let mapping: Vec<String> = images
+            .iter()
+            .map(|image| format!("{} → {}", image.file_name, image.partition.as_deref().unwrap_or("(skipped)")))
+            .collect();
+        self.note_options(serial, &[("Images", mapping.join(", ").as_str())]);
+
+        let stall_secs = gio::Settings::new(config::APP_ID).int("flash-stall-timeout");
+        let installer = LocalImageInstaller::new(serial.to_string(), images)
+            .confirmed_device(device.clone())
+            .cancel_handle(imp.install_cancel.clone())
+            .stall_threshold(std::time::Duration::from_secs(stall_secs.max(1) as u64));
+        let receiver = installer.spawn();
+
+        self.watch_progress(receiver);
+    }
+
+    /// Write Samsung firmware archives with Heimdall from Download Mode.
+    /// Nothing is downloaded; the archives are checked and unpacked first.
+    pub fn start_heimdall_installation(&self, device: &Device, serial: &str, archives: Vec<std::path::PathBuf>) {
+        self.set_distro_name("Samsung Firmware");
+
+        let imp = self.imp();
+        imp.status_page.set_title("Flashing with Heimdall");
+        imp.status_page.set_description(Some("Preparing..."));
+        imp.download_row.set_visible(false);
+        imp.decompress_row.set_title("Unpacking");
+
+        let names: Vec<String> = archives
+            .iter()
+            .map(|path| path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned()))
+            .collect();
+        self.note_options(serial, &[("Archives", names.join(", ").as_str())]);
+
+        let installer = HeimdallInstaller::new(serial.to_string(), device.codename.clone(), archives)
+            .cancel_handle(imp.install_cancel.clone());
+        let receiver = installer.spawn();
+
+        self.watch_progress(receiver);
+    }
+
+    /// Relay installer messages through an event bus and poll the page's
+    /// subscription on the main loop until a terminal one arrives.
+    /// Spawn the installer, unless the download directory can't fit what
+    /// [`set_space_needed`](Self::set_space_needed) asked for. Then it
+    /// never starts, and the run fails the same way as a download that
+    /// runs out of space.
+    fn spawn_checked(
+        &self,
+        download_dir: &std::path::Path,
+        spawn: impl FnOnce() -> std::sync::mpsc::Receiver<InstallProgress>,
+    ) -> std::sync::mpsc::Receiver<InstallProgress> {
+        self.imp().image_dir.replace(Some(download_dir.to_path_buf()));
+        let Some(needed) = self.imp().space_needed.get() else {
+            return spawn();
+        };
+        match ImageDownloader::new(download_dir.to_path_buf()).ensure_space(needed) {
+            Ok(()) => spawn(),
+            Err(e) => {
+                log::error!("Not starting the install: {:#}", e);
+                let (sender, receiver) = std::sync::mpsc::channel();
+                let _ = sender.send(InstallProgress::from_error(&e));
+                receiver
+            }
+        }
+    }
+
+    fn watch_progress(&self, receiver: std::sync::mpsc::Receiver<InstallProgress>) {
+        let bus = EventBus::new();
+        let events = bus.subscribe();
+        bus.log_events();
+        let imp = self.imp();
+        match crate::flashing::install_log::start(&imp.report.borrow(), &bus) {
+            Ok(path) => log::info!("Writing the install log to {}", path.display()),
+            Err(e) => log::warn!("Not writing an install log: {:#}", e),
+        }
+        bus.forward(receiver);
+        self.set_cancellable(true);
+        imp.remaining.borrow_mut().reset();
+        imp.stats.replace(SessionStats::new());
+
+        // The phases whose rows the start_* method left showing
+        let mut weights = PhaseWeights::default();
+        if !imp.download_row.is_visible() {
+            weights = weights.without(ProgressPhase::Download);
+        }
+        if !imp.decompress_row.is_visible() {
+            weights = weights.without(ProgressPhase::Decompress);
+        }
+        if imp.download_only.get() {
+            weights = weights.without(ProgressPhase::Flash);
+        }
+        imp.overall.replace(OverallProgress::new(weights));
+
         let page = self.clone();
         glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            while let Ok(msg) = receiver.try_recv() {
-                let should_stop = page.handle_progress(msg);
+            while let Ok(event) = events.try_recv() {
+                let should_stop = match event {
+                    InstallEvent::Progress(msg) => {
+                        page.imp().stats.borrow_mut().observe(&msg);
+                        if let Some(outcome) = InstallOutcome::from_progress(&msg) {
+                            page.finish_run(outcome);
+                        }
+                        page.handle_progress(msg)
+                    }
+                    // Only reached when the installer ended without a terminal message
+                    InstallEvent::Finished(InstallOutcome::Failed(msg)) => {
+                        page.finish_run(InstallOutcome::Failed(msg.clone()));
+                        page.handle_progress(InstallProgress::Error(msg))
+                    }
+                    InstallEvent::Finished(_) => true,
+                };
                 if should_stop {
+                    page.set_cancellable(false);
+                    // Nothing is being written any more; let the computer sleep again
+                    if let Some(window) = page.root()
+                        .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                    {
+                        window.release_inhibit();
+                    }
                     return glib::ControlFlow::Break;
                 }
             }
@@ -410,9 +782,187 @@ impl FlashingPage {
         });
     }
 
+    /// Ask where to save the install report, then write it there. The
+    /// report stays on this computer until the user shares the file.
+    fn save_report(&self) {
+        let report = self.imp().report.borrow().clone();
+        let dialog = gtk::FileDialog::builder()
+            .title("Save Install Report")
+            .initial_name(report.file_name())
+            .modal(true)
+            .build();
+
+        let window = self.root().and_then(|r| r.downcast::<gtk::Window>().ok());
+        let page = self.downgrade();
+        dialog.save(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            let Some(path) = result.ok().and_then(|file| file.path()) else {
+                return;
+            };
+            let Some(page) = page.upgrade() else {
+                return;
+            };
+            page.imp().report_button.set_sensitive(false);
+
+            // Rendering probes the device again, which can take a few seconds
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create tokio runtime");
+                let text = rt.block_on(report.render());
+                let _ = sender.send(std::fs::write(&path, text).map(|_| path));
+            });
+
+            let page = page.downgrade();
+            glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+                let result = match receiver.try_recv() {
+                    Ok(result) => result,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => return glib::ControlFlow::Continue,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => return glib::ControlFlow::Break,
+                };
+                let Some(page) = page.upgrade() else {
+                    return glib::ControlFlow::Break;
+                };
+                page.imp().report_button.set_sensitive(true);
+                let message = match result {
+                    Ok(path) => format!("Report saved to {}. Nothing was sent; attach it to an issue to share it.", path.display()),
+                    Err(e) => {
+                        log::error!("Failed to save the install report: {}", e);
+                        format!("Couldn't save the report: {}", e)
+                    }
+                };
+                if let Some(window) = page.root()
+                    .and_then(|w| w.downcast::<crate::window::SidestepWindow>().ok())
+                {
+                    window.show_toast(&message);
+                }
+                glib::ControlFlow::Break
+            });
+        });
+    }
+
+    /// Abort the in-flight download or unpacking only; the installer reports
+    /// back with `DownloadCancelled` and the page hands control back to the
+    /// wizard.
+    fn stop_download(&self) {
+        let imp = self.imp();
+        log::info!("User requested to stop the download");
+        imp.download_cancel.cancel();
+        imp.stop_download_button.set_sensitive(false);
+        imp.status_page.set_description(Some("Stopping..."));
+    }
+
+    /// Start timing a flash step and return how long it took on earlier
+    /// installs of this distro on this model.
+    fn begin_step(&self, description: &str) -> Option<std::time::Duration> {
+        let imp = self.imp();
+        let mut timer = imp.step_timer.borrow_mut();
+        let timer = timer.get_or_insert_with(|| {
+            StepTimer::new(&format!(
+                "{}/{}",
+                imp.device_codename.borrow(),
+                imp.distro_name.borrow()
+            ))
+        });
+        timer.begin(description, None)
+    }
+
+    /// Note how the run ended in the report, with its figures
+    fn finish_run(&self, outcome: InstallOutcome) {
+        let imp = self.imp();
+        self.stop_step_timer(&outcome);
+        imp.stats.borrow_mut().finish();
+        let mut report = imp.report.borrow_mut();
+        report.outcome = Some(outcome);
+        report.stats = Some(imp.stats.borrow().clone());
+    }
+
+    /// Record the steps of a finished run; the last one only counts when
+    /// the run succeeded.
+    fn stop_step_timer(&self, outcome: &InstallOutcome) {
+        if let Some(mut timer) = self.imp().step_timer.take() {
+            match outcome {
+                InstallOutcome::Installed | InstallOutcome::BootedTemporarily => timer.finish(),
+                _ => timer.abandon(),
+            }
+        }
+    }
+
+    /// Move the overall bar and name the phase it's in
+    fn update_overall(&self, msg: &InstallProgress) {
+        let imp = self.imp();
+        let mut overall = imp.overall.borrow_mut();
+        let fraction = overall.observe(msg);
+        imp.overall_progress.set_fraction(fraction);
+        if let Some(phase) = overall.phase() {
+            imp.overall_caption
+                .set_label(&format!("{} — {:.0}%", phase.label(), fraction * 100.0));
+        }
+    }
+
+    /// Ask before cancelling: stopping part way through a flash can leave
+    /// the device unbootable until it is flashed again.
+    fn confirm_cancel(&self) {
+        let dialog = adw::AlertDialog::new(
+            Some("Cancel Installation?"),
+            Some(
+                "The running step is stopped immediately. If a partition is being written, \
+                 the device may not boot until the installation is run again.",
+            ),
+        );
+        dialog.add_responses(&[("continue", "Keep Installing"), ("cancel", "Cancel Installation")]);
+        dialog.set_response_appearance("cancel", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("continue"));
+        dialog.set_close_response("continue");
+
+        let page = self.downgrade();
+        dialog.connect_response(Some("cancel"), move |_, _| {
+            if let Some(page) = page.upgrade() {
+                page.cancel_install();
+            }
+        });
+
+        dialog.present(Some(self));
+    }
+
+    /// Stop the install; the installer ends with `Cancelled`, or with
+    /// `DownloadCancelled` when it was still downloading.
+    fn cancel_install(&self) {
+        let imp = self.imp();
+        log::info!("User cancelled the installation");
+        imp.cancelling.set(true);
+        imp.install_cancel.cancel();
+        imp.download_cancel.cancel();
+        imp.cancel_button.set_sensitive(false);
+        self.set_download_stoppable(false);
+        imp.status_page.set_description(Some("Cancelling..."));
+    }
+
+    fn set_cancellable(&self, cancellable: bool) {
+        self.imp().cancel_box.set_visible(cancellable);
+        self.action_set_enabled("flashing.cancel", cancellable);
+    }
+
+    fn set_download_stoppable(&self, stoppable: bool) {
+        self.imp().stop_download_box.set_visible(stoppable);
+        self.action_set_enabled("flashing.stop-download", stoppable);
+    }
+
+    fn set_running_title(&self, distro_name: &str) {
+        let verb = if self.imp().download_only.get() { "Downloading" } else { "Installing" };
+        self.imp().status_page.set_title(&format!("{} {}", verb, distro_name));
+    }
+
     /// Handle a progress message from the installer. Returns true if polling should stop.
     fn handle_progress(&self, msg: InstallProgress) -> bool {
         let imp = self.imp();
+        let remaining = imp.remaining.borrow_mut().observe(&msg);
+        self.update_overall(&msg);
+        let with_remaining = |text: String| match remaining {
+            Some(remaining) => format!("{} — {}", text, format_remaining(remaining)),
+            None => text,
+        };
 
         match msg {
             InstallProgress::DownloadProgress {
@@ -420,16 +970,17 @@ impl FlashingPage {
                 total,
                 file_name,
             } => {
+                self.set_download_stoppable(downloaded < total && !imp.cancelling.get());
+
                 if total > 0 {
                     let fraction = downloaded as f64 / total as f64;
                     imp.download_progress.set_fraction(fraction.min(1.0));
 
-                    let downloaded_mb = downloaded as f64 / 1_000_000.0;
-                    let total_mb = total as f64 / 1_000_000.0;
-                    imp.download_row.set_subtitle(&format!(
-                        "{} — {:.1} / {:.1} MB",
-                        file_name, downloaded_mb, total_mb
-                    ));
+                    imp.download_row.set_subtitle(&with_remaining(format!(
+                        "{} — {}",
+                        file_name,
+                        format_progress(downloaded, total)
+                    )));
                 }
             }
 
@@ -438,6 +989,7 @@ impl FlashingPage {
                 total,
                 file_name,
             } => {
+                self.set_download_stoppable(false);
                 if total > 0 {
                     let fraction = verified as f64 / total as f64;
                     imp.decompress_progress.set_fraction(fraction.min(1.0));
@@ -452,30 +1004,135 @@ impl FlashingPage {
                 }
             }
 
+            InstallProgress::DecompressProgress { percent, file_name } => {
+                // Unpacking a rootfs takes minutes and can still be stopped
+                self.set_download_stoppable(percent < 100 && !imp.cancelling.get());
+                imp.decompress_progress.set_fraction(percent.min(100) as f64 / 100.0);
+                imp.decompress_row
+                    .set_subtitle(&with_remaining(format!("{} ({}%)", file_name, percent)));
+                imp.status_page.set_description(Some(&format!(
+                    "Unpacking {} ({}%)",
+                    file_name.to_lowercase(),
+                    percent
+                )));
+            }
+
+            InstallProgress::SideloadProgress { percent, file_name } => {
+                if imp.stall_warning.replace(false) {
+                    imp.error_banner.set_revealed(false);
+                }
+                imp.flash_progress.set_fraction((percent as f64 / 100.0).min(1.0));
+                imp.flash_row.set_subtitle(&with_remaining(format!(
+                    "Sideloading {} ({}%)",
+                    file_name, percent
+                )));
+            }
+
             InstallProgress::FlashProgress {
                 current,
                 total,
                 description,
             } => {
+                self.set_download_stoppable(false);
+                // Any flash progress means the device is moving again
+                if imp.stall_warning.replace(false) | imp.confirmation_prompt.replace(false) {
+                    imp.error_banner.set_revealed(false);
+                }
+                let estimate = self.begin_step(&description);
+                imp.flash_step.set((current, total));
                 if total > 0 {
                     let fraction = current as f64 / total as f64;
                     imp.flash_progress.set_fraction(fraction.min(1.0));
-                    imp.flash_row.set_subtitle(&format!(
-                        "{} ({}/{})",
-                        description, current, total
-                    ));
+                    let subtitle = format!("{} ({}/{})", description, current, total);
+                    match estimate {
+                        Some(estimate) => imp.flash_row.set_subtitle(&format!(
+                            "{} — {}",
+                            subtitle,
+                            format_estimate(estimate)
+                        )),
+                        None => imp.flash_row.set_subtitle(&subtitle),
+                    }
                 }
             }
 
+            InstallProgress::PartitionProgress { partition, chunk, chunks, percent } => {
+                if imp.stall_warning.replace(false) {
+                    imp.error_banner.set_revealed(false);
+                }
+                let (current, total) = imp.flash_step.get();
+                if total > 0 {
+                    let fraction = (current.saturating_sub(1) as f64 + percent as f64 / 100.0) / total as f64;
+                    imp.flash_progress.set_fraction(fraction.min(1.0));
+                }
+                let subtitle = if chunks > 1 {
+                    format!("Flashing {}: chunk {}/{} ({}%)", partition, chunk, chunks, percent)
+                } else {
+                    format!("Flashing {} ({}%)", partition, percent)
+                };
+                imp.flash_row.set_subtitle(&with_remaining(subtitle));
+            }
+
+            InstallProgress::TransferStalled { partition, silent_secs } => {
+                log::warn!("Flash of {} silent for {}s", partition, silent_secs);
+                imp.stall_warning.set(true);
+                imp.error_banner.set_title(
+                    "Transfer appears stalled — try a different USB port/cable",
+                );
+                imp.error_banner.remove_css_class("error");
+                imp.error_banner.set_revealed(true);
+            }
+
+            InstallProgress::FlashRetrying { partition, attempt, attempts, reason } => {
+                log::warn!("Retrying {} (attempt {}/{}): {}", partition, attempt, attempts, reason);
+                imp.status_page.set_description(Some(&format!(
+                    "Flashing {} failed; trying again (attempt {} of {})",
+                    partition, attempt, attempts
+                )));
+            }
+
             InstallProgress::StatusChanged(status) => {
                 imp.status_page.set_description(Some(&status));
             }
 
+            InstallProgress::ChecksumUnavailable { file_name, reply } => {
+                self.confirm_unverified(&file_name, reply);
+            }
+
+            InstallProgress::AwaitingDeviceConfirmation(instructions) => {
+                imp.confirmation_prompt.set(true);
+                imp.status_page.set_description(Some("Waiting for confirmation on the device..."));
+                imp.status_page.set_icon_name(Some("phone-symbolic"));
+                imp.error_banner.set_title(&instructions);
+                imp.error_banner.remove_css_class("error");
+                imp.error_banner.set_revealed(true);
+            }
+
+            InstallProgress::ModeTransitionSlow { mode, proceed } => {
+                imp.status_page.set_description(Some(
+                    "Waiting for device... (taking longer than expected?)",
+                ));
+                imp.slow_transition_label.set_label(&self.transition_help(mode));
+                imp.slow_transition_box.set_visible(true);
+                imp.mode_override.replace(Some(proceed));
+            }
+
+            InstallProgress::ModeTransitionDone => {
+                self.hide_slow_transition();
+            }
+
             InstallProgress::WaitingForRecovery => {
                 imp.status_page.set_description(Some("Waiting for Recovery Mode..."));
-                imp.error_banner.set_title(
-                    "On your phone: use Volume buttons to select \"Recovery mode\", then press Power",
-                );
+                let hint = imp.button_hint.borrow().clone();
+                if hint.is_empty() {
+                    imp.error_banner.set_title(
+                        "On your phone: use Volume buttons to select \"Recovery mode\", then press Power",
+                    );
+                } else {
+                    imp.error_banner.set_title(&format!(
+                        "On your phone: select \"Recovery mode\". {}",
+                        hint
+                    ));
+                }
                 imp.error_banner.set_revealed(true);
                 // Remove the error styling — this is an info prompt, not an error
                 imp.error_banner.remove_css_class("error");
@@ -493,7 +1150,47 @@ impl FlashingPage {
                 imp.status_page.set_description(Some("Waiting for user action..."));
             }
 
+            InstallProgress::DownloadOnlyComplete(location) => {
+                self.set_download_stoppable(false);
+                let location = location.display().to_string();
+                imp.status_page.set_title("Download Complete");
+                imp.status_page.set_icon_name(Some("folder-download-symbolic"));
+                imp.status_page.set_description(Some(&format!(
+                    "Images saved to {}. Installing later will reuse them.",
+                    location
+                )));
+                imp.download_row.set_subtitle("Complete");
+                imp.download_progress.set_fraction(1.0);
+                imp.decompress_progress.set_fraction(1.0);
+                imp.flash_row.set_visible(false);
+                imp.verify_row.set_visible(false);
+
+                imp.restart_button.set_label("Done");
+                imp.restart_box.set_visible(true);
+
+                self.emit_by_name::<()>("download-complete", &[&location]);
+                return true;
+            }
+
+            InstallProgress::BootedTemporarily => {
+                self.set_download_stoppable(false);
+                let name = self.distro_name();
+                imp.status_page.set_title(&format!("{} Is Booting", name));
+                imp.status_page.set_icon_name(Some("media-playlist-repeat-symbolic"));
+                imp.status_page.set_description(Some(&format!(
+                    "Your device is starting {} from memory. Nothing was installed; restarting the device returns to the current system.",
+                    name
+                )));
+                imp.flash_progress.set_fraction(1.0);
+                imp.flash_row.set_subtitle("Booted");
+
+                imp.restart_button.set_label("Done");
+                imp.restart_box.set_visible(true);
+                return true;
+            }
+
             InstallProgress::Complete => {
+                self.set_download_stoppable(false);
                 imp.status_page.set_title("Installation Complete!");
                 imp.download_row.set_subtitle("Complete");
                 imp.decompress_row.set_subtitle("Complete");
@@ -503,15 +1200,140 @@ impl FlashingPage {
                 imp.decompress_progress.set_fraction(1.0);
                 imp.flash_progress.set_fraction(1.0);
 
-                imp.verify_icon.set_icon_name(Some("emblem-ok-symbolic"));
+                if imp.unverified.get() {
+                    log::warn!("Installation completed with unverified images");
+                    imp.verify_icon.set_icon_name(Some("dialog-warning-symbolic"));
+                    imp.verify_icon.set_tooltip_text(Some("Not verified"));
+                    imp.verify_row.set_subtitle("Not verified — no checksum was published");
+                } else {
+                    imp.verify_icon.set_icon_name(Some("emblem-ok-symbolic"));
+                    imp.verify_icon.set_tooltip_text(Some("Verified"));
+                    imp.verify_row.set_subtitle("Verified");
+                }
                 imp.verify_icon.set_visible(true);
-                imp.verify_row.set_subtitle("Verified");
 
                 self.emit_by_name::<()>("installation-complete", &[]);
                 return true;
             }
 
+            InstallProgress::DownloadCancelled if !imp.cancelling.get() => {
+                self.set_download_stoppable(false);
+                self.emit_by_name::<()>("download-cancelled", &[]);
+                return true;
+            }
+
+            InstallProgress::DownloadCancelled | InstallProgress::Cancelled => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
+                imp.status_page.set_title("Installation Cancelled");
+                imp.status_page.set_icon_name(Some("process-stop-symbolic"));
+                let description = if matches!(msg, InstallProgress::Cancelled) {
+                    "The installation was stopped. If flashing had started, run the installation again before rebooting the device."
+                } else {
+                    "The installation was stopped before anything was written to the device."
+                };
+                imp.status_page.set_description(Some(description));
+                imp.restart_box.set_visible(true);
+                return true;
+            }
+
+            InstallProgress::DeviceDisconnected(operation) => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
+                log::error!("Device disconnected during: {}", operation);
+                imp.status_page.set_title("Device Disconnected");
+                imp.status_page.set_icon_name(Some("phone-disabled-symbolic"));
+                imp.status_page.set_description(Some(&format!(
+                    "The USB connection was lost during \"{}\". The device may be only partly flashed.",
+                    operation
+                )));
+                imp.error_banner.set_title(
+                    "Device disconnected during flashing — do NOT unplug; reconnect and retry the failed step",
+                );
+                imp.error_banner.add_css_class("error");
+                imp.error_banner.set_revealed(true);
+                imp.restart_box.set_visible(true);
+                return true;
+            }
+
+            InstallProgress::ImageCorrupted(msg) => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
+                log::error!("{}", msg);
+                imp.status_page.set_title("Image Appears Corrupted");
+                imp.status_page.set_icon_name(Some("dialog-error-symbolic"));
+                imp.status_page.set_description(Some(
+                    "The download is damaged or incomplete. Nothing was flashed from it; retrying downloads it again.",
+                ));
+                imp.error_banner.set_title(&msg);
+                imp.error_banner.add_css_class("error");
+                imp.error_banner.set_revealed(true);
+                imp.restart_box.set_visible(true);
+                return true;
+            }
+
+            InstallProgress::DownloadFailed(msg) => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
+                log::error!("{}", msg);
+                imp.status_page.set_title("Download Failed");
+                imp.status_page.set_icon_name(Some("network-error-symbolic"));
+                imp.status_page.set_description(Some(
+                    "The server stopped sending the image. Nothing was flashed; another mirror may work.",
+                ));
+                imp.error_banner.set_title(&msg);
+                imp.error_banner.add_css_class("error");
+                imp.error_banner.set_revealed(true);
+                imp.restart_box.set_visible(true);
+
+                self.emit_by_name::<()>("download-failed", &[&msg]);
+                return true;
+            }
+
+            InstallProgress::InsufficientSpace { path, needed, available } => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
+                log::error!("Not enough space in {}: {} needed, {} free", path.display(), needed, available);
+                imp.status_page.set_title("Not Enough Disk Space");
+                imp.status_page.set_icon_name(Some("drive-harddisk-symbolic"));
+                imp.status_page.set_description(Some(&format!(
+                    "The images need {} in {}, but only {} is free. Free up space or choose another download folder in Preferences, then try again. Nothing was flashed.",
+                    format_size(needed),
+                    path.display(),
+                    format_size(available)
+                )));
+                imp.restart_box.set_visible(true);
+                return true;
+            }
+
+            InstallProgress::ChannelUnavailable { channel, suggestion } => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
+                log::error!("Channel {} is no longer on the server", channel);
+                imp.status_page.set_title("Release No Longer Available");
+                imp.status_page.set_icon_name(Some("network-error-symbolic"));
+                let description = match suggestion {
+                    Some(ref suggestion) => format!(
+                        "{} has been removed from the image server; {} is still available. Nothing was flashed.",
+                        channel, suggestion
+                    ),
+                    None => format!(
+                        "{} has been removed from the image server and no other release has builds for this device. Nothing was flashed.",
+                        channel
+                    ),
+                };
+                imp.status_page.set_description(Some(&description));
+                imp.restart_box.set_visible(true);
+
+                if let Some(suggestion) = suggestion {
+                    self.emit_by_name::<()>("channel-unavailable", &[&channel, &suggestion]);
+                }
+                return true;
+            }
+
             InstallProgress::Error(msg) => {
+                self.set_download_stoppable(false);
+                self.hide_slow_transition();
                 log::error!("Installation error: {}", msg);
                 imp.status_page.set_title("Installation Failed");
                 imp.status_page.set_icon_name(Some("dialog-error-symbolic"));
@@ -526,6 +1348,65 @@ impl FlashingPage {
         false
     }
 
+    /// Ask before using a file without a checksum; the installer thread waits on `reply`.
+    /// Troubleshooting shown once a mode switch is overdue.
+    fn transition_help(&self, mode: TargetMode) -> String {
+        let hint = self.imp().button_hint.borrow().clone();
+        match mode {
+            TargetMode::Fastboot => "If the phone is still running normally, power it off and hold \
+                 Power + Volume Down to open the bootloader. If it already shows the \
+                 bootloader, try another USB port or cable."
+                .to_string(),
+            TargetMode::Recovery => format!(
+                "On the phone, choose \"Recovery mode\" from the bootloader menu. {}",
+                if hint.is_empty() { "Use the Volume keys to select, Power to confirm" } else { hint.as_str() }
+            ),
+            TargetMode::Sideload => "In recovery, choose \"Apply update\" → \"Apply from ADB\". \
+                 If it is already open, unplug and reconnect the cable."
+                .to_string(),
+        }
+    }
+
+    /// The user says the device is ready even though it isn't detected.
+    fn continue_anyway(&self) {
+        if let Some(proceed) = self.imp().mode_override.take() {
+            let _ = proceed.send(());
+        }
+        self.hide_slow_transition();
+    }
+
+    fn hide_slow_transition(&self) {
+        let imp = self.imp();
+        imp.mode_override.replace(None);
+        imp.slow_transition_box.set_visible(false);
+    }
+
+    fn confirm_unverified(&self, file_name: &str, reply: std::sync::mpsc::Sender<bool>) {
+        let dialog = adw::AlertDialog::new(
+            Some("Image Cannot Be Verified"),
+            Some(&format!(
+                "{} has no checksum and cannot be verified — proceed anyway?\n\n\
+                 A corrupted or tampered image could leave your device unbootable.",
+                file_name
+            )),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("proceed", "Proceed Anyway")]);
+        dialog.set_response_appearance("proceed", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        let page = self.clone();
+        dialog.connect_response(None, move |_, response| {
+            let proceed = response == "proceed";
+            if proceed {
+                page.imp().unverified.set(true);
+            }
+            let _ = reply.send(proceed);
+        });
+
+        dialog.present(Some(self));
+    }
+
     fn mock_progress(&self) {
         let page = self.clone();
         glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
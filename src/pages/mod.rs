@@ -1,3 +1,5 @@
+pub mod accessibility;
+pub mod connection_indicator;
 pub mod device_browser;
 pub mod device_details;
 pub mod device_info;
@@ -1,10 +1,14 @@
 // Success Page
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use crate::config;
+use crate::flashing::{cache, PostInstallProgress, PostInstallSetup, SessionStats};
+use crate::utils::format::format_size;
 use gtk::{gio, glib, prelude::*, subclass::prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
+use std::path::{Path, PathBuf};
 
 mod imp {
     use super::*;
@@ -12,9 +16,29 @@ mod imp {
     #[derive(Debug, Default, gtk::CompositeTemplate)]
     #[template(resource = "/io/github/tobagin/Sidestep/ui/pages/success.ui")]
     pub struct SuccessPage {
+        /// Cached images from the install just finished
+        pub image_dir: std::cell::RefCell<Option<PathBuf>>,
+        /// Setup commands offered once the new OS is up
+        pub post_install: std::cell::RefCell<Option<PostInstallSetup>>,
         #[template_child]
         pub status_page: TemplateChild<adw::StatusPage>,
         #[template_child]
+        pub images_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub images_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub delete_images_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub keep_images_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub post_install_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub post_install_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub apply_post_install_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub stats_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
         pub main_menu_button: TemplateChild<gtk::MenuButton>,
     }
 
@@ -29,6 +53,12 @@ mod imp {
             klass.install_action("page.restart", None, move |page, _, _| {
                  page.emit_by_name::<()>("restart-clicked", &[]);
             });
+            klass.install_action("page.delete-images", None, move |page, _, _| {
+                page.delete_images();
+            });
+            klass.install_action("page.apply-post-install", None, move |page, _, _| {
+                page.apply_post_install();
+            });
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -61,6 +91,113 @@ impl SuccessPage {
         ));
     }
     
+    /// Show how much space the install's cached images take, with the choice
+    /// to delete them. When the user has turned off keeping images they are
+    /// removed straight away instead.
+    pub fn set_image_dir(&self, dir: &Path) {
+        let imp = self.imp();
+        let settings = gio::Settings::new(config::APP_ID);
+        settings
+            .bind("keep-downloaded-images", &*imp.keep_images_row, "active")
+            .build();
+
+        let size = cache::disk_usage(dir);
+        if size == 0 {
+            return;
+        }
+        imp.image_dir.replace(Some(dir.to_path_buf()));
+        imp.images_group.set_visible(true);
+
+        if settings.boolean("keep-downloaded-images") {
+            imp.images_row.set_subtitle(&format!("{} in {}", format_size(size), dir.display()));
+        } else {
+            self.delete_images();
+        }
+    }
+
+    fn delete_images(&self) {
+        let imp = self.imp();
+        let Some(dir) = imp.image_dir.take() else {
+            return;
+        };
+
+        match cache::remove(&dir) {
+            Ok(freed) => {
+                imp.images_row.set_subtitle(&format!("Deleted, {} freed", format_size(freed)));
+                imp.delete_images_button.set_visible(false);
+            }
+            Err(e) => {
+                log::error!("Failed to delete cached images: {:#}", e);
+                imp.images_row.set_subtitle(&format!("Could not delete: {}", e));
+                imp.image_dir.replace(Some(dir));
+            }
+        }
+    }
+
+    /// List what the install downloaded and how long it took, for
+    /// comparing mirrors and connections
+    pub fn set_session_stats(&self, stats: &SessionStats) {
+        let imp = self.imp();
+        let rows = stats.rows();
+        for (label, value) in &rows {
+            let row = adw::ActionRow::builder().title(label.as_str()).subtitle(value.as_str()).build();
+            row.add_css_class("property");
+            imp.stats_group.add(&row);
+        }
+        imp.stats_group.set_visible(!rows.is_empty());
+    }
+
+    /// Offer the distro's post-install commands, run only when asked.
+    pub fn set_post_install(&self, setup: PostInstallSetup) {
+        let imp = self.imp();
+        imp.post_install_row.set_subtitle(&format!(
+            "Once the device has booted, run over adb:\n{}",
+            setup.commands().join("\n")
+        ));
+        imp.post_install_group.set_visible(true);
+        imp.post_install.replace(Some(setup));
+    }
+
+    fn apply_post_install(&self) {
+        let imp = self.imp();
+        let Some(setup) = imp.post_install.take() else {
+            return;
+        };
+        imp.apply_post_install_button.set_sensitive(false);
+
+        let receiver = setup.spawn();
+        let page = self.downgrade();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let Some(page) = page.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            let row = &page.imp().post_install_row;
+            while let Ok(msg) = receiver.try_recv() {
+                match msg {
+                    PostInstallProgress::WaitingForDevice => {
+                        row.set_subtitle("Waiting for the device to finish booting…");
+                    }
+                    PostInstallProgress::Running(command) => {
+                        row.set_subtitle(&format!("Running {}", command));
+                    }
+                    PostInstallProgress::Done { applied, failed } => {
+                        let mut lines = vec![format!("Applied {} of {}", applied.len(), applied.len() + failed.len())];
+                        lines.extend(failed.iter().map(|(command, error)| format!("{} failed: {}", command, error)));
+                        row.set_subtitle(&lines.join("\n"));
+                        page.imp().apply_post_install_button.set_visible(false);
+                        return glib::ControlFlow::Break;
+                    }
+                    PostInstallProgress::Skipped(reason) => {
+                        row.set_subtitle(&format!("Skipped: {}", reason));
+                        page.imp().apply_post_install_button.set_visible(false);
+                        return glib::ControlFlow::Break;
+                    }
+                }
+            }
+            glib::ControlFlow::Continue
+        });
+    }
+
     pub fn connect_restart_clicked<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
         self.connect_closure(
             "restart-clicked",
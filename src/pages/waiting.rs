@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::models::Device;
+use crate::pages::accessibility;
+use crate::pages::connection_indicator::ConnectionIndicator;
 use gtk::{glib, prelude::*, subclass::prelude::*};
 use libadwaita as adw;
 use adw::prelude::*;
@@ -19,9 +21,13 @@ mod imp {
         #[template_child]
         pub stack: TemplateChild<gtk::Stack>,
         #[template_child]
+        pub connecting_status: TemplateChild<adw::StatusPage>,
+        #[template_child]
         pub device_list: TemplateChild<gtk::Box>,
         #[template_child]
         pub browse_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub header_bar: TemplateChild<adw::HeaderBar>,
 
         pub devices: RefCell<Vec<Device>>,
         pub device_buttons: RefCell<Vec<gtk::Button>>,
@@ -58,6 +64,12 @@ mod imp {
     }
 
     impl ObjectImpl for WaitingPage {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.header_bar.pack_start(&ConnectionIndicator::new());
+            accessibility::follow_reduced_motion(&self.waiting_spinner);
+        }
+
         fn signals() -> &'static [glib::subclass::Signal] {
             static SIGNALS: std::sync::OnceLock<Vec<glib::subclass::Signal>> = std::sync::OnceLock::new();
             SIGNALS.get_or_init(|| {
@@ -97,6 +109,7 @@ impl WaitingPage {
         // Store devices
         *imp.devices.borrow_mut() = devices.to_vec();
 
+        self.set_unauthorized(false);
         if devices.is_empty() {
             imp.stack.set_visible_child_name("connecting");
             imp.waiting_spinner.set_spinning(true);
@@ -129,6 +142,37 @@ impl WaitingPage {
         imp.waiting_spinner.set_spinning(false);
     }
 
+    /// Ask the user to accept the USB debugging prompt while a device sits
+    /// unauthorised in adb, or go back to the usual connect instructions
+    pub fn set_unauthorized(&self, unauthorized: bool) {
+        let status = &self.imp().connecting_status;
+        if unauthorized {
+            status.set_icon_name(Some("dialog-password-symbolic"));
+            status.set_title("Allow USB Debugging");
+            status.set_description(Some(
+                "Please accept the USB debugging prompt on your phone.\nTick \"Always allow from this computer\" to skip it next time.",
+            ));
+        } else {
+            status.set_icon_name(Some("phone-symbolic"));
+            status.set_title("Connect Your Device");
+            status.set_description(Some(
+                "Connect an Android device via USB to begin.\nMake sure USB debugging is enabled.",
+            ));
+        }
+    }
+
+    /// Explain why a phone adb or fastboot sees isn't being detected: it
+    /// can't be opened over USB. Cleared like [`set_unauthorized`](Self::set_unauthorized).
+    pub fn set_permission_denied(&self, tool: &str) {
+        let status = &self.imp().connecting_status;
+        status.set_icon_name(Some("dialog-warning-symbolic"));
+        status.set_title("No Permission to Use the Device");
+        status.set_description(Some(&format!(
+            "{} can see your phone but isn't allowed to open it.\nInstall the Android udev rules (often the android-udev-rules package) or add your user to the plugdev group, then reconnect the phone.",
+            tool
+        )));
+    }
+
     pub fn get_device(&self, index: u32) -> Option<Device> {
         let devices = self.imp().devices.borrow();
         devices.get(index as usize).cloned()
@@ -0,0 +1,208 @@
+// Preferences Dialog
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::config;
+use gtk::{gio, glib, prelude::*, subclass::prelude::*};
+use libadwaita as adw;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use std::collections::HashMap;
+
+/// Distros installed with a choice of interface, and the interfaces their
+/// device configs offer
+const INTERFACE_CHOICES: &[(&str, &str, &[(&str, &str)])] = &[
+    (
+        "postmarketos",
+        "postmarketOS",
+        &[
+            ("phosh", "Phosh"),
+            ("plasma-mobile", "Plasma Mobile"),
+            ("gnome-mobile", "GNOME Mobile"),
+            ("sxmo-de-sway", "Sxmo (Sway)"),
+        ],
+    ),
+    ("mobian", "Mobian", &[("phosh", "Phosh"), ("plasma-mobile", "Plasma Mobile")]),
+];
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/tobagin/Sidestep/ui/preferences_dialog.ui")]
+    pub struct PreferencesDialog {
+        #[template_child]
+        pub download_path_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub choose_folder_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub keep_images_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub max_connections_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub limit_speed_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub speed_limit_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub preferred_interfaces_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub sync_interval_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub sync_now_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub show_terminal_row: TemplateChild<adw::SwitchRow>,
+
+        pub settings: once_cell::sync::OnceCell<gio::Settings>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PreferencesDialog {
+        const NAME: &'static str = "PreferencesDialog";
+        type Type = super::PreferencesDialog;
+        type ParentType = adw::PreferencesDialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PreferencesDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for PreferencesDialog {}
+    impl AdwDialogImpl for PreferencesDialog {}
+    impl PreferencesDialogImpl for PreferencesDialog {}
+}
+
+glib::wrapper! {
+    pub struct PreferencesDialog(ObjectSubclass<imp::PreferencesDialog>)
+        @extends gtk::Widget, adw::Dialog, adw::PreferencesDialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl PreferencesDialog {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    fn setup(&self) {
+        let imp = self.imp();
+        let settings = gio::Settings::new(config::APP_ID);
+
+        settings
+            .bind("keep-downloaded-images", &*imp.keep_images_row, "active")
+            .build();
+        settings
+            .bind("max-download-connections", &*imp.max_connections_row, "value")
+            .build();
+        settings
+            .bind("limit-download-speed", &*imp.limit_speed_row, "enable-expansion")
+            .build();
+        settings
+            .bind("download-speed-limit", &*imp.speed_limit_row, "value")
+            .build();
+        settings
+            .bind("sync-interval-hours", &*imp.sync_interval_row, "value")
+            .build();
+        settings
+            .bind("show-terminal", &*imp.show_terminal_row, "active")
+            .build();
+
+        self.show_download_path(&settings.string("download-path"));
+        let dialog = self.downgrade();
+        settings.connect_changed(Some("download-path"), move |settings, key| {
+            if let Some(dialog) = dialog.upgrade() {
+                dialog.show_download_path(&settings.string(key));
+            }
+        });
+        let dialog = self.downgrade();
+        imp.choose_folder_button.connect_clicked(move |_| {
+            if let Some(dialog) = dialog.upgrade() {
+                dialog.choose_download_folder();
+            }
+        });
+
+        imp.sync_now_button.connect_clicked(|button| {
+            let _ = button.activate_action("app.check-data-updates", None);
+        });
+
+        for (distro_id, distro_name, interfaces) in INTERFACE_CHOICES {
+            imp.preferred_interfaces_group
+                .add(&self.interface_row(&settings, distro_id, distro_name, interfaces));
+        }
+
+        let _ = imp.settings.set(settings);
+    }
+
+    fn show_download_path(&self, path: &str) {
+        let subtitle = if path.is_empty() { "Downloads folder" } else { path };
+        self.imp().download_path_row.set_subtitle(subtitle);
+    }
+
+    fn choose_download_folder(&self) {
+        let Some(settings) = self.imp().settings.get().cloned() else {
+            return;
+        };
+        let file_dialog = gtk::FileDialog::builder()
+            .title("Choose Download Location")
+            .modal(true)
+            .build();
+        let window = self.root().and_then(|root| root.downcast::<gtk::Window>().ok());
+        file_dialog.select_folder(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            if let Some(path) = result.ok().and_then(|folder| folder.path()) {
+                let _ = settings.set_string("download-path", &path.to_string_lossy());
+            }
+        });
+    }
+
+    /// "Always Ask" followed by the distro's interfaces, kept in the
+    /// `preferred-interfaces` map.
+    fn interface_row(
+        &self,
+        settings: &gio::Settings,
+        distro_id: &'static str,
+        distro_name: &str,
+        interfaces: &'static [(&'static str, &'static str)],
+    ) -> adw::ComboRow {
+        let labels: Vec<&str> = std::iter::once("Always Ask")
+            .chain(interfaces.iter().map(|(_, label)| *label))
+            .collect();
+        let row = adw::ComboRow::builder()
+            .title(distro_name)
+            .model(&gtk::StringList::new(&labels))
+            .build();
+
+        let preferred: HashMap<String, String> = settings.get("preferred-interfaces");
+        let selected = preferred
+            .get(distro_id)
+            .and_then(|id| interfaces.iter().position(|(i, _)| i == id))
+            .map_or(0, |index| index + 1);
+        row.set_selected(selected as u32);
+
+        let settings = settings.clone();
+        row.connect_selected_notify(move |row| {
+            let mut preferred: HashMap<String, String> = settings.get("preferred-interfaces");
+            match (row.selected() as usize).checked_sub(1).and_then(|i| interfaces.get(i)) {
+                Some((id, _)) => preferred.insert(distro_id.to_string(), id.to_string()),
+                None => preferred.remove(distro_id),
+            };
+            if let Err(e) = settings.set("preferred-interfaces", preferred) {
+                log::error!("Failed to save the preferred interface: {}", e);
+            }
+        });
+        row
+    }
+}
+
+impl Default for PreferencesDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
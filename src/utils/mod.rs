@@ -1,4 +0,0 @@
-// Utils
-// SPDX-License-Identifier: GPL-3.0-or-later
-
-pub mod yaml_parser;
@@ -1,5 +1,5 @@
 use crate::config;
-use crate::hardware::{DeviceDetector, DeviceEvent};
+use crate::hardware::{device_report, DeviceDetector, DeviceEvent, DeviceMode};
 use crate::models::{Device, DeviceDatabase};
 use crate::pages::device_browser::DeviceBrowserPage;
 use crate::pages::device_details::DeviceDetailsPage;
@@ -31,6 +31,10 @@ mod imp {
         pub waiting_page: RefCell<Option<WaitingPage>>,
         pub terminal_visible: Cell<bool>,
         pub installing: Cell<bool>,
+        pub inhibit_cookie: Cell<u32>,
+        /// "codename (mode)" of the connected device, shown in page headers
+        pub connection_status: RefCell<Option<String>>,
+        pub device_mode: Cell<Option<DeviceMode>>,
 
         #[template_child]
         pub primary_menu: TemplateChild<gio::MenuModel>,
@@ -57,6 +61,14 @@ mod imp {
     }
 
     impl ObjectImpl for SidestepWindow {
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: once_cell::sync::Lazy<Vec<glib::subclass::Signal>> =
+                once_cell::sync::Lazy::new(|| {
+                    vec![glib::subclass::Signal::builder("connection-changed").build()]
+                });
+            &SIGNALS
+        }
+
         fn constructed(&self) {
             self.parent_constructed();
             let obj = self.obj();
@@ -131,6 +143,7 @@ mod imp {
             if let Some(detector) = self.device_detector.borrow_mut().take() {
                 detector.stop();
             }
+            obj.release_inhibit();
 
             glib::Propagation::Proceed
         }
@@ -158,12 +171,14 @@ impl SidestepWindow {
     }
 
     /// Pause device detection entirely (no events will be sent).
-    /// Also sets the installing flag to prevent any queued events from being handled.
+    /// Also sets the installing flag to prevent any queued events from being handled,
+    /// and keeps the computer awake until detection resumes.
     pub fn pause_detection(&self) {
         self.imp().installing.set(true);
         if let Some(ref detector) = *self.imp().device_detector.borrow() {
             detector.pause();
         }
+        self.inhibit_suspend();
     }
 
     /// Resume device detection and clear the installing flag.
@@ -172,6 +187,41 @@ impl SidestepWindow {
         if let Some(ref detector) = *self.imp().device_detector.borrow() {
             detector.resume();
         }
+        self.release_inhibit();
+    }
+
+    /// Stop the session from suspending or idling while an install runs;
+    /// a suspend mid-flash can leave the device unbootable.
+    fn inhibit_suspend(&self) {
+        let imp = self.imp();
+        if imp.inhibit_cookie.get() != 0 {
+            return;
+        }
+        let Some(app) = self.application() else { return };
+
+        let cookie = app.inhibit(
+            Some(self),
+            gtk::ApplicationInhibitFlags::SUSPEND | gtk::ApplicationInhibitFlags::IDLE,
+            Some("Installing an operating system on a connected device"),
+        );
+        if cookie == 0 {
+            log::warn!("Could not inhibit suspend; continuing without it");
+        } else {
+            log::debug!("Suspend inhibited (cookie {})", cookie);
+        }
+        imp.inhibit_cookie.set(cookie);
+    }
+
+    /// Drop the suspend inhibitor once the device is no longer being written to.
+    pub fn release_inhibit(&self) {
+        let cookie = self.imp().inhibit_cookie.replace(0);
+        if cookie == 0 {
+            return;
+        }
+        if let Some(app) = self.application() {
+            app.uninhibit(cookie);
+            log::debug!("Suspend inhibit released");
+        }
     }
 
     fn setup_actions(&self) {
@@ -199,8 +249,18 @@ impl SidestepWindow {
             // TODO: Show/hide terminal overlay
         });
 
+        // Copy a redacted device report for bug reports
+        let copy_report = gio::SimpleAction::new("copy-device-report", None);
+        let window_weak = self.downgrade();
+        copy_report.connect_activate(move |_, _| {
+            if let Some(window) = window_weak.upgrade() {
+                window.copy_device_report();
+            }
+        });
+
         // Register window actions
         self.add_action(&toggle_terminal);
+        self.add_action(&copy_report);
     }
 
     fn start_device_detection(&self) {
@@ -237,18 +297,52 @@ impl SidestepWindow {
     }
 
     fn handle_device_event(&self, event: DeviceEvent) {
+        // Keep the header indicator live even while the wizard ignores events
+        match event {
+            DeviceEvent::Connected(ref device, mode) => self.set_connection_status(Some(device), mode),
+            DeviceEvent::Disconnected => self.set_connection_status(None, DeviceMode::Adb),
+            DeviceEvent::Rebooting { ref serial, target } => {
+                self.imp().device_mode.set(None);
+                let name = self.rebooting_device_name(serial);
+                self.update_connection_status(Some(format!("{} (rebooting to {})", name, target)));
+            }
+            DeviceEvent::Reconnected { ref serial, mode } | DeviceEvent::ModeChanged { ref serial, mode, .. } => {
+                self.imp().device_mode.set(Some(mode));
+                let name = self.rebooting_device_name(serial);
+                self.update_connection_status(Some(format!("{} ({})", name, mode.label())));
+            }
+            DeviceEvent::Unauthorized { ref serial } => {
+                self.imp().device_mode.set(None);
+                self.update_connection_status(Some(format!("{} (unauthorized)", serial)));
+            }
+            DeviceEvent::PermissionDenied { ref serial, .. } => {
+                self.imp().device_mode.set(None);
+                let name = serial.as_deref().unwrap_or("Device");
+                self.update_connection_status(Some(format!("{} (no permissions)", name)));
+            }
+        }
+
         if self.imp().installing.get() {
             log::info!("Ignoring device event during installation: {:?}", event);
             return;
         }
 
         match event {
-            DeviceEvent::Connected(device) => {
+            DeviceEvent::Connected(device, _) => {
                 self.on_device_detected(device);
             }
             DeviceEvent::Disconnected => {
                 self.on_device_disconnected();
             }
+            DeviceEvent::Unauthorized { serial } => {
+                self.on_device_unauthorized(&serial);
+            }
+            DeviceEvent::PermissionDenied { serial, tool } => {
+                self.on_device_permission_denied(serial.as_deref(), tool);
+            }
+            // Part of a reboot an installer asked for, or the same device
+            // in another mode; it's still the one the wizard is working with
+            DeviceEvent::Rebooting { .. } | DeviceEvent::Reconnected { .. } | DeviceEvent::ModeChanged { .. } => {}
         }
     }
 
@@ -272,6 +366,43 @@ impl SidestepWindow {
         // Device is now shown on WaitingPage — user clicks to start wizard
     }
 
+    /// Every command fails until the phone trusts this computer, so hold
+    /// on the waiting page and say what's needed
+    fn on_device_unauthorized(&self, serial: &str) {
+        log::info!("Device {} hasn't authorised this computer for adb", serial);
+        let imp = self.imp();
+        let was_current = imp
+            .current_device
+            .borrow()
+            .as_ref()
+            .is_some_and(|d| d.serial.as_deref() == Some(serial));
+        if was_current {
+            self.reset_to_waiting();
+        }
+        if let Some(ref waiting_page) = *imp.waiting_page.borrow() {
+            waiting_page.set_unauthorized(true);
+        }
+    }
+
+    /// Nothing works until the tools can open the phone, so hold on the
+    /// waiting page and say how to allow it
+    fn on_device_permission_denied(&self, serial: Option<&str>, tool: &str) {
+        log::info!("{} isn't allowed to open device {}", tool, serial.unwrap_or("(unknown)"));
+        let imp = self.imp();
+        let was_current = serial.is_some_and(|serial| {
+            imp.current_device
+                .borrow()
+                .as_ref()
+                .is_some_and(|d| d.serial.as_deref() == Some(serial))
+        });
+        if was_current {
+            self.reset_to_waiting();
+        }
+        if let Some(ref waiting_page) = *imp.waiting_page.borrow() {
+            waiting_page.set_permission_denied(tool);
+        }
+    }
+
     fn on_device_disconnected(&self) {
         log::info!("Device disconnected");
         self.reset_to_waiting();
@@ -293,6 +424,46 @@ impl SidestepWindow {
         log::info!("Reset to waiting state");
     }
 
+    /// The connected device as "codename (mode)", or `None` when nothing is attached.
+    pub fn connection_status(&self) -> Option<String> {
+        self.imp().connection_status.borrow().clone()
+    }
+
+    fn set_connection_status(&self, device: Option<&Device>, mode: DeviceMode) {
+        self.imp().device_mode.set(device.map(|_| mode));
+        self.update_connection_status(device.map(|d| format!("{} ({})", d.codename, mode.label())));
+    }
+
+    /// The codename of the device going through an expected reboot, or its
+    /// serial when the wizard isn't tracking it.
+    fn rebooting_device_name(&self, serial: &str) -> String {
+        self.imp()
+            .current_device
+            .borrow()
+            .as_ref()
+            .filter(|d| d.serial.as_deref() == Some(serial))
+            .map(|d| d.codename.clone())
+            .unwrap_or_else(|| serial.to_string())
+    }
+
+    fn update_connection_status(&self, status: Option<String>) {
+        if *self.imp().connection_status.borrow() == status {
+            return;
+        }
+        *self.imp().connection_status.borrow_mut() = status;
+        self.emit_by_name::<()>("connection-changed", &[]);
+    }
+
+    pub fn connect_connection_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "connection-changed",
+            false,
+            glib::closure_local!(move |window: Self| {
+                f(&window);
+            }),
+        )
+    }
+
     fn update_waiting_page(&self) {
         let imp = self.imp();
         if let Some(ref waiting_page) = *imp.waiting_page.borrow() {
@@ -315,10 +486,24 @@ impl SidestepWindow {
         imp.main_nav.push(&details_page);
     }
 
-    pub fn show_success(nav_view: &adw::NavigationView, menu_model: &gio::MenuModel, distro_name: &str) {
+    pub fn show_success(
+        nav_view: &adw::NavigationView,
+        menu_model: &gio::MenuModel,
+        distro_name: &str,
+        image_dir: Option<&std::path::Path>,
+        post_install: Option<crate::flashing::PostInstallSetup>,
+        stats: &crate::flashing::SessionStats,
+    ) {
         let success_page = SuccessPage::new();
         success_page.set_menu_model(menu_model);
         success_page.set_distro_name(distro_name);
+        success_page.set_session_stats(stats);
+        if let Some(dir) = image_dir {
+            success_page.set_image_dir(dir);
+        }
+        if let Some(setup) = post_install {
+            success_page.set_post_install(setup);
+        }
 
         success_page.connect_restart_clicked(move |page| {
             // Reset fully: resume detection, clear stale devices, pop to waiting
@@ -335,7 +520,8 @@ impl SidestepWindow {
     fn show_device_browser(&self) {
         let imp = self.imp();
 
-        let browser_page = DeviceBrowserPage::new();
+        let connected = imp.current_device.borrow().as_ref().map(|d| d.codename.clone());
+        let browser_page = DeviceBrowserPage::new(connected.as_deref());
 
         let window_weak = self.downgrade();
         browser_page.connect_device_selected(move |_, codename| {
@@ -355,6 +541,44 @@ impl SidestepWindow {
         imp.main_nav.push(&info_page);
     }
 
+    /// Collect the device report off the main thread and put it on the clipboard.
+    fn copy_device_report(&self) {
+        let imp = self.imp();
+        let device = imp.current_device.borrow().clone();
+        let serial = imp
+            .device_serial
+            .borrow()
+            .clone()
+            .or_else(|| device.as_ref().and_then(|d| d.serial.clone()));
+        let mode = imp.device_mode.get();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let report = rt.block_on(device_report::collect(serial, mode, device));
+            let _ = tx.send(report);
+        });
+
+        let window = self.downgrade();
+        glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+            let Some(window) = window.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            match rx.try_recv() {
+                Ok(report) => {
+                    window.clipboard().set_text(&report);
+                    window.show_toast("Device report copied to clipboard");
+                    glib::ControlFlow::Break
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => glib::ControlFlow::Continue,
+                Err(_) => glib::ControlFlow::Break,
+            }
+        });
+    }
+
     pub fn show_toast(&self, message: &str) {
         let imp = self.imp();
         let toast = adw::Toast::new(message);